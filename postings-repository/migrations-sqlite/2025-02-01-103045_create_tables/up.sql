@@ -0,0 +1,263 @@
+-- SQLite counterpart of migrations/2025-02-01-103045_create_tables.
+--
+-- SQLite has no enum types: enums are stored as TEXT restricted by CHECK
+-- constraints listing the labels of the Postgres types. It has no exact
+-- decimal type either: amounts are stored as TEXT, as a REAL would lose
+-- precision. Timestamps are stored as TEXT ('YYYY-MM-DD HH:MM:SS.SSS'), which
+-- orders like the time it represents.
+--
+-- Foreign keys are only enforced with `PRAGMA foreign_keys = ON`.
+
+-- ===============================================
+--  CHART_OF_ACCOUNT
+-- ===============================================
+CREATE TABLE chart_of_account (
+    id             TEXT PRIMARY KEY NOT NULL,
+    created        TIMESTAMP NOT NULL,
+    user_details   TEXT NOT NULL,
+    short_desc     TEXT,
+    long_desc      TEXT,
+    name           TEXT NOT NULL,
+
+    CONSTRAINT chart_of_account_name_unique UNIQUE (name)
+);
+
+-- ===============================================
+--  LEDGER
+-- ===============================================
+CREATE TABLE ledger (
+    id             TEXT PRIMARY KEY NOT NULL,
+    created        TIMESTAMP NOT NULL,
+    user_details   TEXT NOT NULL,
+    short_desc     TEXT,
+    long_desc      TEXT,
+    name           TEXT NOT NULL,
+
+    coa_id         TEXT NOT NULL,
+
+    CONSTRAINT ledger_name_unique UNIQUE (name),
+    CONSTRAINT fk_ledger_coa
+        FOREIGN KEY (coa_id)
+        REFERENCES chart_of_account (id)
+);
+
+-- ===============================================
+--  LEDGER_ACCOUNT
+-- ===============================================
+CREATE TABLE ledger_account (
+    id             TEXT PRIMARY KEY NOT NULL,
+    created        TIMESTAMP NOT NULL,
+    user_details   TEXT NOT NULL,
+    short_desc     TEXT,
+    long_desc      TEXT,
+    name           TEXT NOT NULL,
+
+    ledger_id      TEXT NOT NULL,
+    parent_id      TEXT,
+    coa_id         TEXT NOT NULL,
+
+    balance_side   TEXT NOT NULL
+        CONSTRAINT ledger_account_balance_side_check
+        CHECK (balance_side IN ('Dr', 'Cr', 'DrCr')),
+    category       TEXT NOT NULL
+        CONSTRAINT ledger_account_category_check
+        CHECK (category IN ('RE', 'EX', 'AS', 'LI', 'EQ', 'NOOP', 'NORE', 'NOEX')),
+
+    CONSTRAINT LedgerAccount_ledger_id_name_unique
+        UNIQUE (ledger_id, name),
+    CONSTRAINT fk_ledger_account_ledger
+        FOREIGN KEY (ledger_id)
+        REFERENCES ledger (id),
+    CONSTRAINT fk_ledger_account_parent
+        FOREIGN KEY (parent_id)
+        REFERENCES ledger_account (id),
+    CONSTRAINT fk_ledger_account_coa
+        FOREIGN KEY (coa_id)
+        REFERENCES chart_of_account (id)
+);
+
+-- ===============================================
+--  OPERATION_DETAILS
+-- ===============================================
+CREATE TABLE operation_details (
+    id         TEXT PRIMARY KEY NOT NULL,
+    op_details TEXT
+);
+
+-- ===============================================
+--  POSTING
+-- ===============================================
+CREATE TABLE posting (
+    id                TEXT PRIMARY KEY NOT NULL,
+
+    antecedent_id     TEXT,
+    antecedent_hash   TEXT,
+    hash              TEXT,
+    hash_alg          TEXT,
+
+    record_user       TEXT NOT NULL,
+    record_time       TIMESTAMP NOT NULL,
+    opr_id            TEXT NOT NULL,
+    opr_time          TIMESTAMP,
+    opr_type          TEXT,
+    opr_src           TEXT,
+    pst_time          TIMESTAMP NOT NULL,
+    pst_type          TEXT NOT NULL
+        CONSTRAINT posting_pst_type_check
+        CHECK (pst_type IN ('BUSI_TX', 'ADJ_TX', 'BAL_STMT', 'PnL_STMT', 'BS_STMT', 'LDG_CLSNG')),
+    pst_status        TEXT NOT NULL
+        CONSTRAINT posting_pst_status_check
+        CHECK (pst_status IN ('DEFERRED', 'POSTED', 'PROPOSED', 'SIMULATED', 'TAX', 'UNPOSTED', 'CANCELLED', 'OTHER')),
+    ledger_id         TEXT NOT NULL,
+    val_time          TIMESTAMP,
+    discarded_id      TEXT,
+    discarded_time    TIMESTAMP,
+    discarding_id     TEXT,
+
+    opr_details_id    TEXT,
+
+    CONSTRAINT posting_opr_id_discarding_id_unique
+        UNIQUE (opr_id, discarding_id),
+    CONSTRAINT fk_posting_ledger
+        FOREIGN KEY (ledger_id)
+        REFERENCES ledger (id),
+    CONSTRAINT fk_posting_opr_details
+        FOREIGN KEY (opr_details_id)
+        REFERENCES operation_details (id)
+);
+
+-- ===============================================
+--  POSTING_TRACE
+-- ===============================================
+CREATE TABLE posting_trace (
+    id              TEXT PRIMARY KEY NOT NULL,
+    tgt_pst_id      TEXT NOT NULL,
+    src_pst_id      TEXT NOT NULL,
+    src_pst_time    TIMESTAMP,
+    src_opr_id      TEXT NOT NULL,
+    account_id      TEXT NOT NULL,
+    debit_amount    TEXT NOT NULL,
+    credit_amount   TEXT NOT NULL,
+    src_pst_hash    TEXT,
+
+    CONSTRAINT fk_posting_trace_account
+        FOREIGN KEY (account_id)
+        REFERENCES ledger_account (id)
+);
+
+-- ===============================================
+--  LEDGER_STMT
+-- ===============================================
+CREATE TABLE ledger_stmt (
+    id             TEXT PRIMARY KEY NOT NULL,
+    posting_id     TEXT,
+    pst_time       TIMESTAMP NOT NULL,
+    stmt_status    TEXT NOT NULL
+        CONSTRAINT ledger_stmt_stmt_status_check
+        CHECK (stmt_status IN ('SIMULATED', 'CLOSED')),
+    latest_pst_id  TEXT,
+    stmt_seq_nbr   INTEGER NOT NULL,
+
+    created        TIMESTAMP,
+    user_details   TEXT,
+    short_desc     TEXT,
+    long_desc      TEXT,
+
+    ledger_id      TEXT NOT NULL,
+
+    CONSTRAINT fk_ledger_stmt_posting
+        FOREIGN KEY (posting_id)
+        REFERENCES posting (id),
+    CONSTRAINT fk_ledger_stmt_latest_pst
+        FOREIGN KEY (latest_pst_id)
+        REFERENCES posting_trace (id),
+    CONSTRAINT fk_ledger_stmt_ledger
+        FOREIGN KEY (ledger_id)
+        REFERENCES ledger (id)
+);
+
+-- ===============================================
+--  ACCOUNT_STMT
+-- ===============================================
+CREATE TABLE account_stmt (
+    id              TEXT PRIMARY KEY NOT NULL,
+    posting_id      TEXT,
+    pst_time        TIMESTAMP NOT NULL,
+    stmt_status     TEXT NOT NULL
+        CONSTRAINT account_stmt_stmt_status_check
+        CHECK (stmt_status IN ('SIMULATED', 'CLOSED')),
+    latest_pst_id   TEXT,
+    stmt_seq_nbr    INTEGER NOT NULL,
+
+    created         TIMESTAMP,
+    user_details    TEXT,
+    short_desc      TEXT,
+    long_desc       TEXT,
+
+    account_id      TEXT NOT NULL,
+    youngest_pst_id TEXT,
+    total_debit     TEXT NOT NULL,
+    total_credit    TEXT NOT NULL,
+
+    CONSTRAINT fk_account_stmt_posting
+        FOREIGN KEY (posting_id)
+        REFERENCES posting (id),
+    CONSTRAINT fk_account_stmt_latest_pst
+        FOREIGN KEY (latest_pst_id)
+        REFERENCES posting_trace (id),
+    CONSTRAINT fk_account_stmt_account
+        FOREIGN KEY (account_id)
+        REFERENCES ledger_account (id),
+    CONSTRAINT fk_account_stmt_youngest_pst
+        FOREIGN KEY (youngest_pst_id)
+        REFERENCES posting_trace (id)
+);
+
+-- ===============================================
+--  POSTING_LINE
+-- ===============================================
+CREATE TABLE posting_line (
+    id             TEXT PRIMARY KEY NOT NULL,
+    account_id     TEXT NOT NULL,
+    debit_amount   TEXT NOT NULL,
+    credit_amount  TEXT NOT NULL,
+    details_id     TEXT,
+    src_account    TEXT,
+    base_line      TEXT,
+    sub_opr_src_id TEXT,
+
+    record_time    TIMESTAMP NOT NULL,
+    opr_id         TEXT NOT NULL,
+    opr_src        TEXT,
+    pst_time       TIMESTAMP NOT NULL,
+    pst_type       TEXT NOT NULL
+        CONSTRAINT posting_line_pst_type_check
+        CHECK (pst_type IN ('BUSI_TX', 'ADJ_TX', 'BAL_STMT', 'PnL_STMT', 'BS_STMT', 'LDG_CLSNG')),
+    pst_status     TEXT NOT NULL
+        CONSTRAINT posting_line_pst_status_check
+        CHECK (pst_status IN ('DEFERRED', 'POSTED', 'PROPOSED', 'SIMULATED', 'TAX', 'UNPOSTED', 'CANCELLED', 'OTHER')),
+    hash           TEXT NOT NULL,
+    discarded_time TIMESTAMP,
+
+    CONSTRAINT fk_posting_line_account
+        FOREIGN KEY (account_id)
+        REFERENCES ledger_account (id),
+    CONSTRAINT fk_posting_line_details
+        FOREIGN KEY (details_id)
+        REFERENCES operation_details (id)
+);
+
+-- ===============================================
+--  OP_NOTE
+-- ===============================================
+CREATE TABLE op_note (
+    id            TEXT PRIMARY KEY NOT NULL,
+    rec_id        TEXT NOT NULL,
+    type          TEXT,
+    content       TEXT,
+    rec_time      TIMESTAMP NOT NULL,
+    exec_time     TIMESTAMP,
+    premature_exc BOOLEAN,
+    repeated_exec BOOLEAN,
+    exec_status   TEXT
+);
@@ -0,0 +1,4 @@
+-- Serves the keyset pagination of the lines of an account, which filters on
+-- the account and orders by (pst_time, record_time, id).
+CREATE INDEX posting_line_account_keyset_idx
+    ON posting_line (account_id, pst_time, record_time, id);
@@ -4,5 +4,6 @@
  */
 
 pub mod models;
+pub mod pool;
 pub mod repository;
 mod schema;
\ No newline at end of file
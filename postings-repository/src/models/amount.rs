@@ -0,0 +1,77 @@
+// src/models/amount.rs
+/*
+ * Copyright (c) 2018-2024 adorsys GmbH and Co. KG
+ * All rights are reserved.
+ */
+
+//! Maps `Decimal` amounts to the `Amount` SQL type.
+//!
+//! Postgres stores amounts as `NUMERIC`, delegating to the `rust_decimal`
+//! support. SQLite has no exact decimal type: amounts are stored as `TEXT`,
+//! so that they read back with the scale they were written with.
+//!
+//! Models keep plain `Decimal` fields and convert through `DbAmount` with the
+//! `serialize_as` and `deserialize_as` attributes.
+
+use diesel::backend::Backend;
+use diesel::deserialize::{self, FromSql, FromSqlRow};
+use diesel::expression::AsExpression;
+use diesel::pg::Pg;
+use diesel::serialize::{self, Output, ToSql};
+use diesel::sql_types::Numeric;
+use rust_decimal::Decimal;
+
+use crate::schema::sql_types::Amount;
+
+#[derive(Debug, Clone, Copy, AsExpression, FromSqlRow)]
+#[diesel(sql_type = Amount)]
+pub struct DbAmount(Decimal);
+
+impl From<Decimal> for DbAmount {
+    fn from(amount: Decimal) -> Self {
+        DbAmount(amount)
+    }
+}
+
+impl From<DbAmount> for Decimal {
+    fn from(amount: DbAmount) -> Self {
+        amount.0
+    }
+}
+
+impl ToSql<Amount, Pg> for DbAmount {
+    fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, Pg>) -> serialize::Result {
+        <Decimal as ToSql<Numeric, Pg>>::to_sql(&self.0, out)
+    }
+}
+
+impl FromSql<Amount, Pg> for DbAmount {
+    fn from_sql(bytes: <Pg as Backend>::RawValue<'_>) -> deserialize::Result<Self> {
+        <Decimal as FromSql<Numeric, Pg>>::from_sql(bytes).map(DbAmount)
+    }
+}
+
+#[cfg(feature = "sqlite")]
+mod sqlite {
+    use super::*;
+    use diesel::sql_types::Text;
+    use diesel::sqlite::Sqlite;
+    use std::str::FromStr;
+
+    impl ToSql<Amount, Sqlite> for DbAmount {
+        fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, Sqlite>) -> serialize::Result {
+            out.set_value(self.0.to_string());
+            Ok(serialize::IsNull::No)
+        }
+    }
+
+    impl FromSql<Amount, Sqlite> for DbAmount {
+        fn from_sql(value: <Sqlite as Backend>::RawValue<'_>) -> deserialize::Result<Self> {
+            // Numbers written by hand into a TEXT column, e.g. by fixtures, may
+            // come back in scientific notation.
+            let text = <String as FromSql<Text, Sqlite>>::from_sql(value)?;
+            let amount = Decimal::from_str(&text).or_else(|_| Decimal::from_scientific(&text))?;
+            Ok(DbAmount(amount))
+        }
+    }
+}
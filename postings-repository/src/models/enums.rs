@@ -14,6 +14,7 @@ use diesel_derive_enum::DbEnum;
 /// - Asset
 /// - Liability
 /// - Equity
+///
 /// This matches the `CREATE TYPE account_category AS ENUM (...)` in up.sql:
 /// ('RE','EX','AS','LI','EQ','NOOP','NORE','NOEX')
 #[derive(DbEnum, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -76,6 +77,23 @@ pub enum PostingStatus {
     OTHER,
 }
 
+impl PostingStatus {
+    /// Postings in these statuses are recorded without taking effect: they are
+    /// not chained in the journal and their lines do not count toward balances.
+    /// Simulated lines only count in what-if views, deferred postings take
+    /// effect once promoted at their posting time.
+    pub const INEFFECTIVE: [PostingStatus; 4] = [
+        PostingStatus::PROPOSED,
+        PostingStatus::CANCELLED,
+        PostingStatus::SIMULATED,
+        PostingStatus::DEFERRED,
+    ];
+
+    pub fn is_effective(self) -> bool {
+        !Self::INEFFECTIVE.contains(&self)
+    }
+}
+
 /// Matches `CREATE TYPE posting_type AS ENUM (...)`:
 /// ('BUSI_TX','ADJ_TX','BAL_STMT','PnL_STMT','BS_STMT','LDG_CLSNG')
 #[derive(DbEnum, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -108,3 +126,82 @@ pub enum StmtStatus {
     #[db_rename = "CLOSED"]
     CLOSED,
 }
+
+/// SQLite has no enum types: the enums are stored as `TEXT` holding the
+/// Postgres labels, restricted by CHECK constraints in `migrations-sqlite`.
+#[cfg(feature = "sqlite")]
+mod sqlite {
+    use super::*;
+    use diesel::backend::Backend;
+    use diesel::deserialize::{self, FromSql, Queryable};
+    use diesel::serialize::{self, Output, ToSql};
+    use diesel::sql_types::Text;
+    use diesel::sqlite::Sqlite;
+
+    use crate::schema::sql_types;
+
+    macro_rules! text_enum {
+        ($enum_ty:ident { $($variant:ident => $label:literal),+ $(,)? }) => {
+            impl ToSql<sql_types::$enum_ty, Sqlite> for $enum_ty {
+                fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, Sqlite>) -> serialize::Result {
+                    let label = match self {
+                        $($enum_ty::$variant => $label,)+
+                    };
+                    <str as ToSql<Text, Sqlite>>::to_sql(label, out)
+                }
+            }
+
+            impl FromSql<sql_types::$enum_ty, Sqlite> for $enum_ty {
+                fn from_sql(value: <Sqlite as Backend>::RawValue<'_>) -> deserialize::Result<Self> {
+                    match <String as FromSql<Text, Sqlite>>::from_sql(value)?.as_str() {
+                        $($label => Ok($enum_ty::$variant),)+
+                        other => Err(format!("Unrecognized enum variant: '{}'", other).into()),
+                    }
+                }
+            }
+
+            impl Queryable<sql_types::$enum_ty, Sqlite> for $enum_ty {
+                type Row = Self;
+
+                fn build(row: Self) -> deserialize::Result<Self> {
+                    Ok(row)
+                }
+            }
+        };
+    }
+
+    text_enum!(AccountCategory {
+        RE => "RE",
+        EX => "EX",
+        AS => "AS",
+        LI => "LI",
+        EQ => "EQ",
+        NOOP => "NOOP",
+        NORE => "NORE",
+        NOEX => "NOEX",
+    });
+
+    text_enum!(BalanceSide { Dr => "Dr", Cr => "Cr", DrCr => "DrCr" });
+
+    text_enum!(PostingStatus {
+        DEFERRED => "DEFERRED",
+        POSTED => "POSTED",
+        PROPOSED => "PROPOSED",
+        SIMULATED => "SIMULATED",
+        TAX => "TAX",
+        UNPOSTED => "UNPOSTED",
+        CANCELLED => "CANCELLED",
+        OTHER => "OTHER",
+    });
+
+    text_enum!(PostingType {
+        BusiTx => "BUSI_TX",
+        AdjTx => "ADJ_TX",
+        BalStmt => "BAL_STMT",
+        PnLStmt => "PnL_STMT",
+        BsStmt => "BS_STMT",
+        LdgClsng => "LDG_CLSNG",
+    });
+
+    text_enum!(StmtStatus { SIMULATED => "SIMULATED", CLOSED => "CLOSED" });
+}
@@ -3,6 +3,7 @@
  * All rights are reserved.
  */
 
+mod amount;
 pub mod enums;
 use serde::{Deserialize, Serialize};
 use chrono::NaiveDateTime;
@@ -11,6 +12,7 @@ use diesel::prelude::*;
 
 // Pull in your Diesel table definitions (macro-generated by `diesel print-schema`)
 use crate::schema::*;
+use amount::DbAmount;
 
 // Pull in your custom enums (defined via diesel-derive-enum)
 use crate::models::enums::{
@@ -21,9 +23,10 @@ use crate::models::enums::{
 //
 // 1) chart_of_account
 //
-#[derive(Debug, Clone, Serialize, Deserialize, Queryable, Identifiable)]
+#[derive(Debug, Clone, Serialize, Deserialize, Queryable, Identifiable, AsChangeset)]
 #[diesel(table_name = chart_of_account)]
 #[diesel(primary_key(id))]
+#[diesel(treat_none_as_null = true)]
 pub struct ChartOfAccount {
     pub id: String,
     pub created: NaiveDateTime,
@@ -48,9 +51,10 @@ pub struct NewChartOfAccount {
 //
 // 2) ledger
 //
-#[derive(Debug, Clone, Serialize, Deserialize, Queryable, Identifiable)]
+#[derive(Debug, Clone, Serialize, Deserialize, Queryable, Identifiable, AsChangeset)]
 #[diesel(table_name = ledger)]
 #[diesel(primary_key(id))]
+#[diesel(treat_none_as_null = true)]
 pub struct Ledger {
     pub id: String,
     pub created: NaiveDateTime,
@@ -77,9 +81,10 @@ pub struct NewLedger {
 //
 // 3) ledger_account
 //
-#[derive(Debug, Clone, Serialize, Deserialize, Queryable, Identifiable)]
+#[derive(Debug, Clone, Serialize, Deserialize, Queryable, QueryableByName, Identifiable, AsChangeset)]
 #[diesel(table_name = ledger_account)]
 #[diesel(primary_key(id))]
+#[diesel(treat_none_as_null = true)]
 pub struct LedgerAccount {
     pub id: String,
     pub created: NaiveDateTime,
@@ -211,6 +216,9 @@ pub struct Posting {
     pub discarded_id: Option<String>,
     /// The record time of the discarded posting
     pub discarded_time: Option<NaiveDateTime>,
+    /// The id of the posting discarding this posting. Only the posting of an
+    /// operation that has not been discarded is effective.
+    pub discarding_id: Option<String>,
 
     /// Details associated with this operation.
     pub opr_details_id: Option<String>,
@@ -239,6 +247,7 @@ pub struct NewPosting {
     pub val_time: Option<NaiveDateTime>,
     pub discarded_id: Option<String>,
     pub discarded_time: Option<NaiveDateTime>,
+    pub discarding_id: Option<String>,
 
     pub opr_details_id: Option<String>,
 }
@@ -259,7 +268,9 @@ pub struct PostingTrace {
     pub src_pst_time: Option<NaiveDateTime>,
     pub src_opr_id: String,
     pub account_id: String,
+    #[diesel(deserialize_as = DbAmount)]
     pub debit_amount: Decimal,
+    #[diesel(deserialize_as = DbAmount)]
     pub credit_amount: Decimal,
     pub src_pst_hash: Option<String>,
 }
@@ -273,7 +284,9 @@ pub struct NewPostingTrace {
     pub src_pst_time: Option<NaiveDateTime>,
     pub src_opr_id: String,
     pub account_id: String,
+    #[diesel(serialize_as = DbAmount)]
     pub debit_amount: Decimal,
+    #[diesel(serialize_as = DbAmount)]
     pub credit_amount: Decimal,
     pub src_pst_hash: Option<String>,
 }
@@ -357,7 +370,9 @@ pub struct AccountStmt {
     // custom fields
     pub account_id: String,
     pub youngest_pst_id: Option<String>,
+    #[diesel(deserialize_as = DbAmount)]
     pub total_debit: Decimal,
+    #[diesel(deserialize_as = DbAmount)]
     pub total_credit: Decimal,
 }
 
@@ -378,7 +393,9 @@ pub struct NewAccountStmt {
 
     pub account_id: String,
     pub youngest_pst_id: Option<String>,
+    #[diesel(serialize_as = DbAmount)]
     pub total_debit: Decimal,
+    #[diesel(serialize_as = DbAmount)]
     pub total_credit: Decimal,
 }
 
@@ -392,7 +409,9 @@ pub struct PostingLine {
     pub id: String,
     /// The associated ledger account
     pub account_id: String,
+    #[diesel(deserialize_as = DbAmount)]
     pub debit_amount: Decimal,
+    #[diesel(deserialize_as = DbAmount)]
     pub credit_amount: Decimal,
     /// This is the JSON representation of the transaction as posted for the product module.
     pub details_id: Option<String>,
@@ -436,7 +455,9 @@ pub struct PostingLine {
 pub struct NewPostingLine {
     pub id: String,
     pub account_id: String,
+    #[diesel(serialize_as = DbAmount)]
     pub debit_amount: Decimal,
+    #[diesel(serialize_as = DbAmount)]
     pub credit_amount: Decimal,
     pub details_id: Option<String>,
     pub src_account: Option<String>,
@@ -456,9 +477,10 @@ pub struct NewPostingLine {
 //
 // 10) op_note
 //
-#[derive(Debug, Clone, Serialize, Deserialize, Queryable, Identifiable)]
+#[derive(Debug, Clone, Serialize, Deserialize, Queryable, Identifiable, AsChangeset)]
 #[diesel(table_name = op_note)]
 #[diesel(primary_key(id))]
+#[diesel(treat_none_as_null = true)]
 pub struct OpNote {
     pub id: String,
     pub rec_id: String,
@@ -478,6 +500,13 @@ pub struct OpNote {
     pub exec_status: Option<String>,
 }
 
+impl OpNote {
+    /// The execution status of a note executed successfully.
+    pub const EXEC_DONE: &'static str = "DONE";
+    /// The execution status of a note whose execution failed.
+    pub const EXEC_FAILED: &'static str = "FAILED";
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, Insertable)]
 #[diesel(table_name = op_note)]
 pub struct NewOpNote {
@@ -0,0 +1,128 @@
+// src/pool.rs
+/*
+ * Copyright (c) 2018-2024 adorsys GmbH and Co. KG
+ * All rights are reserved.
+ */
+
+//! Connection pool settings shared by the blocking and the async pools.
+//!
+//! The settings are read from the environment:
+//!
+//! | Variable                          | Default |
+//! |-----------------------------------|---------|
+//! | `DB_POOL_MAX_SIZE`                | 10      |
+//! | `DB_POOL_MIN_IDLE`                | none    |
+//! | `DB_POOL_CONNECTION_TIMEOUT_SECS` | 30      |
+//! | `DB_POOL_IDLE_TIMEOUT_SECS`       | 600     |
+//! | `DB_POOL_MAX_LIFETIME_SECS`       | 1800    |
+
+use std::env;
+use std::fmt;
+use std::str::FromStr;
+use std::time::Duration;
+
+/// Sizes and timeouts of a connection pool.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PoolConfig {
+    /// The maximum number of open connections.
+    pub max_size: u32,
+    /// The number of idle connections to keep open, the pool default if `None`.
+    pub min_idle: Option<u32>,
+    /// How long a caller waits for a connection before giving up.
+    pub connection_timeout: Duration,
+    /// How long a connection may stay idle before it is closed.
+    pub idle_timeout: Duration,
+    /// How long a connection is used before it is replaced.
+    pub max_lifetime: Duration,
+}
+
+impl Default for PoolConfig {
+    fn default() -> Self {
+        Self {
+            max_size: 10,
+            min_idle: None,
+            connection_timeout: Duration::from_secs(30),
+            idle_timeout: Duration::from_secs(600),
+            max_lifetime: Duration::from_secs(1800),
+        }
+    }
+}
+
+/// A pool setting that could not be parsed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PoolConfigError {
+    pub variable: String,
+    pub value: String,
+}
+
+impl fmt::Display for PoolConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid value {:?} for {}", self.value, self.variable)
+    }
+}
+
+impl std::error::Error for PoolConfigError {}
+
+impl PoolConfig {
+    /// Reads the settings from the environment, using the defaults for the
+    /// variables that are not set.
+    pub fn from_env() -> Result<Self, PoolConfigError> {
+        Self::from_lookup(|variable| env::var(variable).ok())
+    }
+
+    /// Reads the settings through `lookup`, which returns the value of a
+    /// variable if it is set.
+    pub fn from_lookup(lookup: impl Fn(&str) -> Option<String>) -> Result<Self, PoolConfigError> {
+        let defaults = Self::default();
+        let seconds = |variable: &str, default: Duration| {
+            Ok(parse::<u64>(&lookup, variable)?.map(Duration::from_secs).unwrap_or(default))
+        };
+        Ok(Self {
+            max_size: parse(&lookup, "DB_POOL_MAX_SIZE")?.unwrap_or(defaults.max_size),
+            min_idle: parse(&lookup, "DB_POOL_MIN_IDLE")?,
+            connection_timeout: seconds("DB_POOL_CONNECTION_TIMEOUT_SECS", defaults.connection_timeout)?,
+            idle_timeout: seconds("DB_POOL_IDLE_TIMEOUT_SECS", defaults.idle_timeout)?,
+            max_lifetime: seconds("DB_POOL_MAX_LIFETIME_SECS", defaults.max_lifetime)?,
+        })
+    }
+}
+
+fn parse<T: FromStr>(lookup: &impl Fn(&str) -> Option<String>, variable: &str) -> Result<Option<T>, PoolConfigError> {
+    lookup(variable)
+        .map(|value| {
+            value.trim().parse().map_err(|_| PoolConfigError {
+                variable: variable.to_string(),
+                value,
+            })
+        })
+        .transpose()
+}
+
+#[cfg(feature = "async")]
+pub use self::async_pool::*;
+
+#[cfg(feature = "async")]
+mod async_pool {
+    use diesel_async::pooled_connection::bb8::Pool;
+    use diesel_async::pooled_connection::{AsyncDieselConnectionManager, PoolError};
+    use diesel_async::AsyncPgConnection;
+
+    use super::PoolConfig;
+
+    /// A pool of async Postgres connections.
+    pub type AsyncDbPool = Pool<AsyncPgConnection>;
+
+    /// Builds an async pool with the given settings. Connections are opened
+    /// lazily, except for the `min_idle` ones.
+    pub async fn build_async_pool(database_url: &str, config: &PoolConfig) -> Result<AsyncDbPool, PoolError> {
+        let manager = AsyncDieselConnectionManager::<AsyncPgConnection>::new(database_url);
+        Pool::builder()
+            .max_size(config.max_size)
+            .min_idle(config.min_idle)
+            .connection_timeout(config.connection_timeout)
+            .idle_timeout(Some(config.idle_timeout))
+            .max_lifetime(Some(config.max_lifetime))
+            .build(manager)
+            .await
+    }
+}
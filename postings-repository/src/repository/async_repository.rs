@@ -0,0 +1,191 @@
+// src/repository/async_repository.rs
+/*
+ * Copyright (c) 2018-2024 adorsys GmbH and Co. KG
+ * All rights are reserved.
+ */
+
+//! Async variants of the posting line and account statement repositories,
+//! available with the `async` feature. They run the queries of their
+//! blocking counterparts on an `AsyncPgConnection`, typically taken from an
+//! [`AsyncDbPool`](crate::pool::AsyncDbPool).
+
+use chrono::NaiveDateTime;
+use diesel::{ExpressionMethods, OptionalExtension, QueryDsl};
+use diesel_async::{AsyncPgConnection, RunQueryDsl};
+
+use crate::models::enums::{PostingStatus, StmtStatus};
+use crate::models::PostingLine;
+use crate::repository::{RepositoryError, RepositoryResult};
+
+/// Async PostingLineRepository-like functions, see
+/// [`posting_line_repository`](super::posting_line_repository).
+pub mod posting_line_repository {
+    use super::*;
+    use crate::models::NewPostingLine;
+    use crate::schema::posting_line::dsl::*;
+
+    pub async fn save(conn: &mut AsyncPgConnection, new_line: NewPostingLine) -> RepositoryResult<PostingLine> {
+        diesel::insert_into(posting_line)
+            .values(new_line)
+            .get_result(conn)
+            .await
+            .map_err(RepositoryError::from)
+    }
+
+    pub async fn save_all(
+        conn: &mut AsyncPgConnection,
+        new_lines: Vec<NewPostingLine>,
+    ) -> RepositoryResult<Vec<PostingLine>> {
+        diesel::insert_into(posting_line)
+            .values(new_lines)
+            .get_results(conn)
+            .await
+            .map_err(RepositoryError::from)
+    }
+
+    pub async fn update_discarded_time_by_opr_id_and_record_time(
+        conn: &mut AsyncPgConnection,
+        opr_id_val: &str,
+        record_time_val: NaiveDateTime,
+        discarded_time_val: NaiveDateTime,
+    ) -> RepositoryResult<usize> {
+        diesel::update(
+            posting_line
+                .filter(opr_id.eq(opr_id_val))
+                .filter(record_time.eq(record_time_val))
+                .filter(discarded_time.is_null()),
+        )
+        .set(discarded_time.eq(discarded_time_val))
+        .execute(conn)
+        .await
+        .map_err(RepositoryError::from)
+    }
+
+    pub async fn find_by_opr_id_and_record_time(
+        conn: &mut AsyncPgConnection,
+        opr_id_val: &str,
+        record_time_val: NaiveDateTime,
+    ) -> RepositoryResult<Vec<PostingLine>> {
+        posting_line
+            .filter(opr_id.eq(opr_id_val))
+            .filter(record_time.eq(record_time_val))
+            .order_by(id.asc())
+            .load::<PostingLine>(conn)
+            .await
+            .map_err(RepositoryError::from)
+    }
+
+    pub async fn find_postings_by_account_and_dates(
+        conn: &mut AsyncPgConnection,
+        account_id_val: &str,
+        from_dt: NaiveDateTime,
+        to_dt: NaiveDateTime,
+    ) -> RepositoryResult<Vec<PostingLine>> {
+        posting_line
+            .filter(account_id.eq(account_id_val))
+            .filter(pst_time.gt(from_dt))
+            .filter(pst_time.le(to_dt))
+            .filter(discarded_time.is_null())
+            .filter(pst_status.ne_all(PostingStatus::INEFFECTIVE))
+            .order_by(pst_time.desc())
+            .load::<PostingLine>(conn)
+            .await
+            .map_err(RepositoryError::from)
+    }
+
+    pub async fn find_first_by_id_and_account(
+        conn: &mut AsyncPgConnection,
+        transaction_id: &str,
+        account_id_val: &str,
+    ) -> RepositoryResult<Option<PostingLine>> {
+        posting_line
+            .filter(id.eq(transaction_id))
+            .filter(account_id.eq(account_id_val))
+            .first::<PostingLine>(conn)
+            .await
+            .optional()
+            .map_err(RepositoryError::from)
+    }
+
+    pub async fn find_by_account_and_pst_time_lte_and_discarded_is_null_order_by_record_time_desc(
+        conn: &mut AsyncPgConnection,
+        account_id_val: &str,
+        ref_time_val: NaiveDateTime,
+    ) -> RepositoryResult<Vec<PostingLine>> {
+        posting_line
+            .filter(account_id.eq(account_id_val))
+            .filter(pst_time.le(ref_time_val))
+            .filter(discarded_time.is_null())
+            .filter(pst_status.ne_all(PostingStatus::INEFFECTIVE))
+            .order_by(record_time.desc())
+            .load::<PostingLine>(conn)
+            .await
+            .map_err(RepositoryError::from)
+    }
+
+    pub async fn find_by_base_line_and_pst_time_lte_and_discarded_is_null_order_by_record_time_desc(
+        conn: &mut AsyncPgConnection,
+        base_line_val: &str,
+        ref_time_val: NaiveDateTime,
+    ) -> RepositoryResult<Vec<PostingLine>> {
+        posting_line
+            .filter(base_line.eq(base_line_val))
+            .filter(pst_time.le(ref_time_val))
+            .filter(discarded_time.is_null())
+            .filter(pst_status.ne_all(PostingStatus::INEFFECTIVE))
+            .order_by(record_time.desc())
+            .load::<PostingLine>(conn)
+            .await
+            .map_err(RepositoryError::from)
+    }
+}
+
+/// Async AccountStmtRepository-like functions, see
+/// [`account_stmt_repository`](super::account_stmt_repository).
+pub mod account_stmt_repository {
+    use super::*;
+    use crate::models::{AccountStmt, NewAccountStmt};
+    use crate::schema::account_stmt::dsl::*;
+
+    pub async fn save(conn: &mut AsyncPgConnection, new_stmt: NewAccountStmt) -> RepositoryResult<AccountStmt> {
+        diesel::insert_into(account_stmt)
+            .values(new_stmt)
+            .get_result(conn)
+            .await
+            .map_err(RepositoryError::from)
+    }
+
+    pub async fn find_first_by_account_and_stmt_status_and_pst_time_less_than_order_by_pst_time_desc_stmt_seq_nbr_desc(
+        conn: &mut AsyncPgConnection,
+        account_id_val: &str,
+        stmt_status_val: StmtStatus,
+        ref_time_val: NaiveDateTime,
+    ) -> RepositoryResult<Option<AccountStmt>> {
+        account_stmt
+            .filter(account_id.eq(account_id_val))
+            .filter(stmt_status.eq(stmt_status_val))
+            .filter(pst_time.lt(ref_time_val))
+            .order_by((pst_time.desc(), stmt_seq_nbr.desc()))
+            .first::<AccountStmt>(conn)
+            .await
+            .optional()
+            .map_err(RepositoryError::from)
+    }
+
+    pub async fn find_first_by_account_and_stmt_status_and_pst_time_gte(
+        conn: &mut AsyncPgConnection,
+        account_id_val: &str,
+        stmt_status_val: StmtStatus,
+        ref_time_val: NaiveDateTime,
+    ) -> RepositoryResult<Option<AccountStmt>> {
+        account_stmt
+            .filter(account_id.eq(account_id_val))
+            .filter(stmt_status.eq(stmt_status_val))
+            .filter(pst_time.ge(ref_time_val))
+            .order_by((pst_time.asc(), stmt_seq_nbr.asc()))
+            .first::<AccountStmt>(conn)
+            .await
+            .optional()
+            .map_err(RepositoryError::from)
+    }
+}
@@ -0,0 +1,176 @@
+// src/repository/error.rs
+/*
+ * Copyright (c) 2018-2024 adorsys GmbH and Co. KG
+ * All rights are reserved.
+ */
+
+//! The errors of the repository functions.
+//!
+//! Diesel errors are classified so that callers can react to them without
+//! looking into Diesel: a duplicate ledger name is a
+//! `UniqueViolation { constraint: "ledger_name_unique" }` on every backend.
+//! The names of the constraints of the migrations are listed in
+//! [`constraints`].
+
+use std::fmt;
+
+use diesel::result::{ConnectionError, DatabaseErrorInformation, DatabaseErrorKind, Error};
+
+/// The names of the constraints declared by the migrations, as reported by
+/// [`RepositoryError`]. Primary keys are named `<table>_pkey`.
+pub mod constraints {
+    pub const CHART_OF_ACCOUNT_NAME_UNIQUE: &str = "chart_of_account_name_unique";
+    pub const LEDGER_NAME_UNIQUE: &str = "ledger_name_unique";
+    pub const LEDGER_ACCOUNT_LEDGER_ID_NAME_UNIQUE: &str = "ledgeraccount_ledger_id_name_unique";
+    pub const POSTING_OPR_ID_DISCARDING_ID_UNIQUE: &str = "posting_opr_id_discarding_id_unique";
+
+    pub const FK_LEDGER_COA: &str = "fk_ledger_coa";
+    pub const FK_LEDGER_ACCOUNT_LEDGER: &str = "fk_ledger_account_ledger";
+    pub const FK_LEDGER_ACCOUNT_PARENT: &str = "fk_ledger_account_parent";
+    pub const FK_LEDGER_ACCOUNT_COA: &str = "fk_ledger_account_coa";
+    pub const FK_POSTING_LEDGER: &str = "fk_posting_ledger";
+    pub const FK_POSTING_OPR_DETAILS: &str = "fk_posting_opr_details";
+    pub const FK_POSTING_TRACE_ACCOUNT: &str = "fk_posting_trace_account";
+    pub const FK_LEDGER_STMT_POSTING: &str = "fk_ledger_stmt_posting";
+    pub const FK_LEDGER_STMT_LATEST_PST: &str = "fk_ledger_stmt_latest_pst";
+    pub const FK_LEDGER_STMT_LEDGER: &str = "fk_ledger_stmt_ledger";
+    pub const FK_ACCOUNT_STMT_POSTING: &str = "fk_account_stmt_posting";
+    pub const FK_ACCOUNT_STMT_LATEST_PST: &str = "fk_account_stmt_latest_pst";
+    pub const FK_ACCOUNT_STMT_ACCOUNT: &str = "fk_account_stmt_account";
+    pub const FK_ACCOUNT_STMT_YOUNGEST_PST: &str = "fk_account_stmt_youngest_pst";
+    pub const FK_POSTING_LINE_ACCOUNT: &str = "fk_posting_line_account";
+    pub const FK_POSTING_LINE_DETAILS: &str = "fk_posting_line_details";
+
+    /// The unique constraints by table and columns, for SQLite which reports
+    /// the columns of a violated unique constraint instead of its name.
+    pub(super) const UNIQUE_COLUMNS: [(&str, &str, &str); 4] = [
+        ("chart_of_account", "name", CHART_OF_ACCOUNT_NAME_UNIQUE),
+        ("ledger", "name", LEDGER_NAME_UNIQUE),
+        ("ledger_account", "ledger_id, name", LEDGER_ACCOUNT_LEDGER_ID_NAME_UNIQUE),
+        ("posting", "opr_id, discarding_id", POSTING_OPR_ID_DISCARDING_ID_UNIQUE),
+    ];
+}
+
+/// An error of a repository function.
+#[derive(Debug)]
+pub enum RepositoryError {
+    /// The row to read or update does not exist.
+    NotFound,
+    /// The row would duplicate the key of the named unique constraint.
+    UniqueViolation { constraint: String },
+    /// The row references a missing row, or a deleted row is still
+    /// referenced. SQLite does not report which foreign key is violated.
+    ForeignKeyViolation { constraint: Option<String> },
+    /// The row violates a check or not null constraint, e.g. with an unknown
+    /// enum value.
+    CheckViolation { constraint: Option<String> },
+    /// The transaction conflicted with a concurrent one and may be retried.
+    SerializationFailure,
+    /// The database could not be reached or the connection was lost.
+    Connection(String),
+    /// Any other error of the database or of Diesel.
+    Other(Error),
+}
+
+pub type RepositoryResult<T> = Result<T, RepositoryError>;
+
+impl RepositoryError {
+    /// The name of the violated constraint, if known.
+    pub fn constraint(&self) -> Option<&str> {
+        match self {
+            RepositoryError::UniqueViolation { constraint } => Some(constraint),
+            RepositoryError::ForeignKeyViolation { constraint } | RepositoryError::CheckViolation { constraint } => {
+                constraint.as_deref()
+            }
+            _ => None,
+        }
+    }
+
+    /// Whether the error violates the named constraint.
+    pub fn violates(&self, constraint: &str) -> bool {
+        self.constraint() == Some(constraint)
+    }
+}
+
+impl fmt::Display for RepositoryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RepositoryError::NotFound => write!(f, "record not found"),
+            RepositoryError::UniqueViolation { constraint } => {
+                write!(f, "duplicate key violates unique constraint {}", constraint)
+            }
+            RepositoryError::ForeignKeyViolation { constraint: Some(constraint) } => {
+                write!(f, "foreign key constraint {} violated", constraint)
+            }
+            RepositoryError::ForeignKeyViolation { constraint: None } => write!(f, "foreign key constraint violated"),
+            RepositoryError::CheckViolation { constraint: Some(constraint) } => {
+                write!(f, "check constraint {} violated", constraint)
+            }
+            RepositoryError::CheckViolation { constraint: None } => write!(f, "check constraint violated"),
+            RepositoryError::SerializationFailure => write!(f, "transaction conflicted with a concurrent transaction"),
+            RepositoryError::Connection(message) => write!(f, "database connection failed: {}", message),
+            RepositoryError::Other(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+impl std::error::Error for RepositoryError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            RepositoryError::Other(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<Error> for RepositoryError {
+    fn from(err: Error) -> Self {
+        match err {
+            Error::NotFound => RepositoryError::NotFound,
+            Error::DatabaseError(kind, info) => match kind {
+                DatabaseErrorKind::UniqueViolation => RepositoryError::UniqueViolation {
+                    constraint: unique_constraint(info.as_ref()),
+                },
+                DatabaseErrorKind::ForeignKeyViolation => RepositoryError::ForeignKeyViolation {
+                    constraint: info.constraint_name().map(str::to_string),
+                },
+                DatabaseErrorKind::CheckViolation | DatabaseErrorKind::NotNullViolation => {
+                    RepositoryError::CheckViolation {
+                        constraint: info.constraint_name().map(str::to_string),
+                    }
+                }
+                DatabaseErrorKind::SerializationFailure => RepositoryError::SerializationFailure,
+                DatabaseErrorKind::ClosedConnection => RepositoryError::Connection(info.message().to_string()),
+                _ => RepositoryError::Other(Error::DatabaseError(kind, info)),
+            },
+            err => RepositoryError::Other(err),
+        }
+    }
+}
+
+impl From<ConnectionError> for RepositoryError {
+    fn from(err: ConnectionError) -> Self {
+        RepositoryError::Connection(err.to_string())
+    }
+}
+
+/// The name of a violated unique constraint. SQLite only reports the columns,
+/// as in `UNIQUE constraint failed: ledger.name`.
+fn unique_constraint(info: &dyn DatabaseErrorInformation) -> String {
+    if let Some(name) = info.constraint_name() {
+        return name.to_string();
+    }
+    let message = info.message();
+    let columns = message.strip_prefix("UNIQUE constraint failed: ").unwrap_or(message);
+    let table = columns.split('.').next().unwrap_or_default();
+    let column_names: Vec<&str> =
+        columns.split(", ").map(|column| column.split_once('.').map_or(column, |(_, name)| name)).collect();
+    let column_names = column_names.join(", ");
+    if column_names == "id" {
+        return format!("{}_pkey", table);
+    }
+    constraints::UNIQUE_COLUMNS
+        .iter()
+        .find(|(unique_table, unique_columns, _)| *unique_table == table && *unique_columns == column_names)
+        .map_or_else(|| message.to_string(), |(_, _, name)| name.to_string())
+}
@@ -0,0 +1,1295 @@
+// src/repository/memory.rs
+/*
+ * Copyright (c) 2018-2024 adorsys GmbH and Co. KG
+ * All rights are reserved.
+ */
+
+//! An in-memory implementation of the repository traits, for tests and
+//! simulations that must run without a database.
+//!
+//! The finders apply the filters and orderings of the Diesel queries of the
+//! repository modules. The store also enforces the primary keys, unique and
+//! foreign key constraints of the migration, reporting violations with the
+//! constraint names Postgres would use. Timestamps are truncated to
+//! microseconds like the `TIMESTAMP` columns.
+//!
+//! Two differences remain: names are ordered bytewise (like the `C`
+//! collation), and rows tied on the ordering columns keep their insertion
+//! order.
+
+use chrono::{NaiveDateTime, Timelike};
+
+use super::error::constraints;
+use super::*;
+use crate::repository::pagination::LineCursor;
+use crate::models::enums::{PostingStatus, StmtStatus};
+use crate::models::{
+    AccountStmt, ChartOfAccount, Ledger, LedgerAccount, LedgerStmt, NewAccountStmt, NewChartOfAccount, NewLedger,
+    NewLedgerAccount, NewLedgerStmt, NewOpNote, NewOperationDetails, NewPosting, NewPostingLine, NewPostingTrace,
+    OpNote, OperationDetails, Posting, PostingLine, PostingTrace,
+};
+
+/// Holds all tables in memory. Transactions work on a snapshot of the tables,
+/// restored when the transaction fails.
+#[derive(Debug, Clone, Default)]
+pub struct InMemoryRepositories {
+    tables: Tables,
+}
+
+impl InMemoryRepositories {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+struct Tables {
+    chart_of_account: Vec<ChartOfAccount>,
+    ledger: Vec<Ledger>,
+    ledger_account: Vec<LedgerAccount>,
+    operation_details: Vec<OperationDetails>,
+    posting: Vec<Posting>,
+    posting_trace: Vec<PostingTrace>,
+    ledger_stmt: Vec<LedgerStmt>,
+    account_stmt: Vec<AccountStmt>,
+    posting_line: Vec<PostingLine>,
+    op_note: Vec<OpNote>,
+}
+
+fn unique_violation(constraint: &str) -> RepositoryError {
+    RepositoryError::UniqueViolation {
+        constraint: constraint.to_string(),
+    }
+}
+
+/// A row references a missing row, or a deleted row is still referenced.
+fn foreign_key_violation(constraint: &str) -> RepositoryError {
+    RepositoryError::ForeignKeyViolation {
+        constraint: Some(constraint.to_string()),
+    }
+}
+
+/// The depth at which the recursive account queries stop, like their SQL.
+const MAX_ACCOUNT_DEPTH: usize = 64;
+
+/// Timestamps are stored with microsecond precision.
+fn micros(time: NaiveDateTime) -> NaiveDateTime {
+    time.with_nanosecond(time.nanosecond() / 1_000 * 1_000).unwrap_or(time)
+}
+
+/// Whether a line counted at `as_of`: recorded by then, and not discarded yet.
+fn effective_as_of(line: &PostingLine, as_of: NaiveDateTime) -> bool {
+    line.record_time <= as_of
+        && line.discarded_time.is_none_or(|discarded_time| discarded_time > as_of)
+        && line.pst_status.is_effective()
+}
+
+/// A row of a table, identified by its primary key.
+trait Row: Clone {
+    const TABLE: &'static str;
+
+    fn id(&self) -> &str;
+
+    /// The row as stored by the database.
+    fn stored(self) -> Self;
+}
+
+macro_rules! row {
+    ($model:ty, $table:literal, |$row:ident| $stored:block) => {
+        impl Row for $model {
+            const TABLE: &'static str = $table;
+
+            fn id(&self) -> &str {
+                &self.id
+            }
+
+            fn stored(self) -> Self {
+                let $row = self;
+                $stored
+            }
+        }
+    };
+}
+
+row!(ChartOfAccount, "chart_of_account", |row| {
+    ChartOfAccount { created: micros(row.created), ..row }
+});
+row!(Ledger, "ledger", |row| { Ledger { created: micros(row.created), ..row } });
+row!(LedgerAccount, "ledger_account", |row| {
+    LedgerAccount { created: micros(row.created), ..row }
+});
+row!(OperationDetails, "operation_details", |row| { row });
+row!(Posting, "posting", |row| {
+    Posting {
+        record_time: micros(row.record_time),
+        opr_time: row.opr_time.map(micros),
+        pst_time: micros(row.pst_time),
+        val_time: row.val_time.map(micros),
+        discarded_time: row.discarded_time.map(micros),
+        ..row
+    }
+});
+row!(PostingTrace, "posting_trace", |row| {
+    PostingTrace { src_pst_time: row.src_pst_time.map(micros), ..row }
+});
+row!(LedgerStmt, "ledger_stmt", |row| {
+    LedgerStmt { pst_time: micros(row.pst_time), created: row.created.map(micros), ..row }
+});
+row!(AccountStmt, "account_stmt", |row| {
+    AccountStmt { pst_time: micros(row.pst_time), created: row.created.map(micros), ..row }
+});
+row!(PostingLine, "posting_line", |row| {
+    PostingLine {
+        record_time: micros(row.record_time),
+        pst_time: micros(row.pst_time),
+        discarded_time: row.discarded_time.map(micros),
+        ..row
+    }
+});
+row!(OpNote, "op_note", |row| {
+    OpNote { rec_time: micros(row.rec_time), exec_time: row.exec_time.map(micros), ..row }
+});
+
+fn find<R: Row>(rows: &[R], id: &str) -> Option<R> {
+    rows.iter().find(|row| row.id() == id).cloned()
+}
+
+fn exists<R: Row>(rows: &[R], id: &str) -> bool {
+    rows.iter().any(|row| row.id() == id)
+}
+
+/// Fails unless the optional reference is null or designates a row of `rows`.
+fn check_reference<R: Row>(
+    rows: &[R],
+    reference: Option<&str>,
+    constraint: &str,
+) -> RepositoryResult<()> {
+    match reference {
+        Some(id) if !exists(rows, id) => Err(foreign_key_violation(constraint)),
+        _ => Ok(()),
+    }
+}
+
+/// Fails if a row of the referencing table still points to the deleted row.
+fn check_unreferenced<R: Row>(
+    rows: &[R],
+    reference: impl Fn(&R) -> Option<&str>,
+    id: &str,
+    constraint: &str,
+) -> RepositoryResult<()> {
+    if rows.iter().any(|row| reference(row) == Some(id)) {
+        Err(foreign_key_violation(constraint))
+    } else {
+        Ok(())
+    }
+}
+
+fn insert<R: Row>(rows: &mut Vec<R>, row: R) -> RepositoryResult<R> {
+    if exists(rows, row.id()) {
+        return Err(unique_violation(&format!("{}_pkey", R::TABLE)));
+    }
+    let row = row.stored();
+    rows.push(row.clone());
+    Ok(row)
+}
+
+/// Replaces the row with the same primary key, failing with `NotFound` like
+/// `get_result` on an update matching no row.
+fn replace<R: Row>(rows: &mut [R], row: R) -> RepositoryResult<R> {
+    let existing = rows.iter_mut().find(|existing| existing.id() == row.id()).ok_or(RepositoryError::NotFound)?;
+    *existing = row.stored();
+    Ok(existing.clone())
+}
+
+fn delete<R: Row>(rows: &mut Vec<R>, id: &str) -> usize {
+    let len = rows.len();
+    rows.retain(|row| row.id() != id);
+    len - rows.len()
+}
+
+impl Tables {
+    fn check_chart_of_account(&self, coa: &ChartOfAccount) -> RepositoryResult<()> {
+        if self.chart_of_account.iter().any(|other| other.id != coa.id && other.name == coa.name) {
+            return Err(unique_violation(constraints::CHART_OF_ACCOUNT_NAME_UNIQUE));
+        }
+        Ok(())
+    }
+
+    fn check_ledger(&self, ledger: &Ledger) -> RepositoryResult<()> {
+        if self.ledger.iter().any(|other| other.id != ledger.id && other.name == ledger.name) {
+            return Err(unique_violation(constraints::LEDGER_NAME_UNIQUE));
+        }
+        check_reference(&self.chart_of_account, Some(&ledger.coa_id), constraints::FK_LEDGER_COA)
+    }
+
+    fn check_ledger_account(&self, account: &LedgerAccount) -> RepositoryResult<()> {
+        if self.ledger_account.iter().any(|other| {
+            other.id != account.id && other.ledger_id == account.ledger_id && other.name == account.name
+        }) {
+            return Err(unique_violation(constraints::LEDGER_ACCOUNT_LEDGER_ID_NAME_UNIQUE));
+        }
+        check_reference(&self.ledger, Some(&account.ledger_id), constraints::FK_LEDGER_ACCOUNT_LEDGER)?;
+        // An account may reference itself as parent, as the row then exists.
+        if account.parent_id.as_deref() != Some(account.id.as_str()) {
+            check_reference(&self.ledger_account, account.parent_id.as_deref(), constraints::FK_LEDGER_ACCOUNT_PARENT)?;
+        }
+        check_reference(&self.chart_of_account, Some(&account.coa_id), constraints::FK_LEDGER_ACCOUNT_COA)
+    }
+
+    fn check_posting(&self, posting: &Posting) -> RepositoryResult<()> {
+        // Nulls are distinct: only discarded postings take part in the constraint.
+        if posting.discarding_id.is_some()
+            && self.posting.iter().any(|other| {
+                other.id != posting.id && other.opr_id == posting.opr_id && other.discarding_id == posting.discarding_id
+            })
+        {
+            return Err(unique_violation(constraints::POSTING_OPR_ID_DISCARDING_ID_UNIQUE));
+        }
+        check_reference(&self.ledger, Some(&posting.ledger_id), constraints::FK_POSTING_LEDGER)?;
+        check_reference(&self.operation_details, posting.opr_details_id.as_deref(), constraints::FK_POSTING_OPR_DETAILS)
+    }
+
+    fn check_posting_trace(&self, trace: &PostingTrace) -> RepositoryResult<()> {
+        check_reference(&self.ledger_account, Some(&trace.account_id), constraints::FK_POSTING_TRACE_ACCOUNT)
+    }
+
+    fn check_ledger_stmt(&self, stmt: &LedgerStmt) -> RepositoryResult<()> {
+        check_reference(&self.posting, stmt.posting_id.as_deref(), constraints::FK_LEDGER_STMT_POSTING)?;
+        check_reference(&self.posting_trace, stmt.latest_pst_id.as_deref(), constraints::FK_LEDGER_STMT_LATEST_PST)?;
+        check_reference(&self.ledger, Some(&stmt.ledger_id), constraints::FK_LEDGER_STMT_LEDGER)
+    }
+
+    fn check_account_stmt(&self, stmt: &AccountStmt) -> RepositoryResult<()> {
+        check_reference(&self.posting, stmt.posting_id.as_deref(), constraints::FK_ACCOUNT_STMT_POSTING)?;
+        check_reference(
+            &self.posting_trace,
+            stmt.latest_pst_id.as_deref(),
+            constraints::FK_ACCOUNT_STMT_LATEST_PST,
+        )?;
+        check_reference(&self.ledger_account, Some(&stmt.account_id), constraints::FK_ACCOUNT_STMT_ACCOUNT)?;
+        check_reference(
+            &self.posting_trace,
+            stmt.youngest_pst_id.as_deref(),
+            constraints::FK_ACCOUNT_STMT_YOUNGEST_PST,
+        )
+    }
+
+    fn check_posting_line(&self, line: &PostingLine) -> RepositoryResult<()> {
+        check_reference(&self.ledger_account, Some(&line.account_id), constraints::FK_POSTING_LINE_ACCOUNT)?;
+        check_reference(&self.operation_details, line.details_id.as_deref(), constraints::FK_POSTING_LINE_DETAILS)
+    }
+
+    fn account_ids_of_ledger(&self, ledger_id: &str) -> Vec<String> {
+        self.ledger_account
+            .iter()
+            .filter(|account| account.ledger_id == ledger_id)
+            .map(|account| account.id.clone())
+            .collect()
+    }
+}
+
+impl Transactional for InMemoryRepositories {
+    fn transaction<T, E, F>(&mut self, f: F) -> Result<T, E>
+    where
+        F: FnOnce(&mut Self) -> Result<T, E>,
+        E: From<diesel::result::Error>,
+    {
+        let snapshot = self.tables.clone();
+        let result = f(self);
+        if result.is_err() {
+            self.tables = snapshot;
+        }
+        result
+    }
+}
+
+/// Saves the rows one after the other, keeping none of them if one fails, like
+/// a multi-row insert statement.
+fn save_all<S, N, R>(store: &mut S, rows: Vec<N>, save: impl Fn(&mut S, N) -> RepositoryResult<R>) -> RepositoryResult<Vec<R>>
+where
+    S: Transactional,
+{
+    store.transaction(|store| rows.into_iter().map(|row| save(store, row)).collect())
+}
+
+impl ChartOfAccountRepository for InMemoryRepositories {
+    fn save(&mut self, new_coa: NewChartOfAccount) -> RepositoryResult<ChartOfAccount> {
+        let coa = ChartOfAccount {
+            id: new_coa.id,
+            created: new_coa.created,
+            user_details: new_coa.user_details,
+            short_desc: new_coa.short_desc,
+            long_desc: new_coa.long_desc,
+            name: new_coa.name,
+        };
+        self.tables.check_chart_of_account(&coa)?;
+        insert(&mut self.tables.chart_of_account, coa)
+    }
+
+    fn find_by_id(&mut self, id: &str) -> RepositoryResult<Option<ChartOfAccount>> {
+        Ok(find(&self.tables.chart_of_account, id))
+    }
+
+    fn find_all(&mut self) -> RepositoryResult<Vec<ChartOfAccount>> {
+        let mut coas = self.tables.chart_of_account.clone();
+        coas.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(coas)
+    }
+
+    fn update(&mut self, coa: &ChartOfAccount) -> RepositoryResult<ChartOfAccount> {
+        self.tables.check_chart_of_account(coa)?;
+        replace(&mut self.tables.chart_of_account, coa.clone())
+    }
+
+    fn delete_by_id(&mut self, id: &str) -> RepositoryResult<usize> {
+        let tables = &self.tables;
+        check_unreferenced(&tables.ledger, |l| Some(&l.coa_id), id, constraints::FK_LEDGER_COA)?;
+        check_unreferenced(
+            &tables.ledger_account,
+            |a| Some(&a.coa_id),
+            id,
+            constraints::FK_LEDGER_ACCOUNT_COA,
+        )?;
+        Ok(delete(&mut self.tables.chart_of_account, id))
+    }
+}
+
+impl LedgerRepository for InMemoryRepositories {
+    fn find_by_id(&mut self, ledger_id: &str) -> RepositoryResult<Option<Ledger>> {
+        Ok(find(&self.tables.ledger, ledger_id))
+    }
+
+    /// The store is borrowed mutably for the whole transaction, so no lock is
+    /// needed.
+    fn find_by_id_for_update(&mut self, ledger_id: &str) -> RepositoryResult<Option<Ledger>> {
+        Ok(find(&self.tables.ledger, ledger_id))
+    }
+
+    fn find_all(&mut self) -> RepositoryResult<Vec<Ledger>> {
+        let mut ledgers = self.tables.ledger.clone();
+        ledgers.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(ledgers)
+    }
+
+    fn find_optional_by_name(&mut self, name: &str) -> RepositoryResult<Option<Ledger>> {
+        Ok(self.tables.ledger.iter().find(|ledger| ledger.name == name).cloned())
+    }
+
+    fn save(&mut self, new_ledger: NewLedger) -> RepositoryResult<Ledger> {
+        let ledger = Ledger {
+            id: new_ledger.id,
+            created: new_ledger.created,
+            user_details: new_ledger.user_details,
+            short_desc: new_ledger.short_desc,
+            long_desc: new_ledger.long_desc,
+            name: new_ledger.name,
+            coa_id: new_ledger.coa_id,
+        };
+        self.tables.check_ledger(&ledger)?;
+        insert(&mut self.tables.ledger, ledger)
+    }
+
+    fn save_all(&mut self, new_ledgers: Vec<NewLedger>) -> RepositoryResult<Vec<Ledger>> {
+        save_all(self, new_ledgers, LedgerRepository::save)
+    }
+
+    fn update(&mut self, ledger: &Ledger) -> RepositoryResult<Ledger> {
+        self.tables.check_ledger(ledger)?;
+        replace(&mut self.tables.ledger, ledger.clone())
+    }
+
+    fn delete_by_id(&mut self, ledger_id: &str) -> RepositoryResult<usize> {
+        let tables = &self.tables;
+        check_unreferenced(
+            &tables.ledger_account,
+            |a| Some(&a.ledger_id),
+            ledger_id,
+            constraints::FK_LEDGER_ACCOUNT_LEDGER,
+        )?;
+        check_unreferenced(&tables.posting, |p| Some(&p.ledger_id), ledger_id, constraints::FK_POSTING_LEDGER)?;
+        check_unreferenced(&tables.ledger_stmt, |s| Some(&s.ledger_id), ledger_id, constraints::FK_LEDGER_STMT_LEDGER)?;
+        Ok(delete(&mut self.tables.ledger, ledger_id))
+    }
+}
+
+impl LedgerAccountRepository for InMemoryRepositories {
+    fn find_by_id(&mut self, account_id: &str) -> RepositoryResult<Option<LedgerAccount>> {
+        Ok(find(&self.tables.ledger_account, account_id))
+    }
+
+    fn find_optional_by_ledger_and_name(&mut self, ledger_id: &str, name: &str) -> RepositoryResult<Option<LedgerAccount>> {
+        Ok(self
+            .tables
+            .ledger_account
+            .iter()
+            .find(|account| account.ledger_id == ledger_id && account.name == name)
+            .cloned())
+    }
+
+    fn find_by_ledger(&mut self, ledger_id: &str) -> RepositoryResult<Vec<LedgerAccount>> {
+        let mut accounts: Vec<LedgerAccount> =
+            self.tables.ledger_account.iter().filter(|account| account.ledger_id == ledger_id).cloned().collect();
+        accounts.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(accounts)
+    }
+
+    fn find_by_coa(&mut self, coa_id: &str) -> RepositoryResult<Vec<LedgerAccount>> {
+        let mut accounts: Vec<LedgerAccount> =
+            self.tables.ledger_account.iter().filter(|account| account.coa_id == coa_id).cloned().collect();
+        accounts.sort_by(|a, b| a.name.cmp(&b.name).then_with(|| a.ledger_id.cmp(&b.ledger_id)));
+        Ok(accounts)
+    }
+
+    fn find_children(&mut self, account_id: &str) -> RepositoryResult<Vec<LedgerAccount>> {
+        let mut children: Vec<LedgerAccount> = self
+            .tables
+            .ledger_account
+            .iter()
+            .filter(|account| account.parent_id.as_deref() == Some(account_id) && account.id != account_id)
+            .cloned()
+            .collect();
+        children.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(children)
+    }
+
+    fn find_ancestors(&mut self, account_id: &str) -> RepositoryResult<Vec<LedgerAccount>> {
+        let accounts = &self.tables.ledger_account;
+        let mut ancestors: Vec<LedgerAccount> = Vec::new();
+        let mut current = find(accounts, account_id);
+        while let Some(account) = current {
+            current = match account.parent_id.as_deref() {
+                Some(parent_id) if parent_id != account.id && ancestors.len() < MAX_ACCOUNT_DEPTH => {
+                    find(accounts, parent_id)
+                }
+                _ => None,
+            };
+            ancestors.extend(current.clone());
+        }
+        Ok(ancestors)
+    }
+
+    fn find_subtree(&mut self, account_id: &str) -> RepositoryResult<Vec<LedgerAccount>> {
+        let mut level: Vec<LedgerAccount> = find(&self.tables.ledger_account, account_id).into_iter().collect();
+        let mut subtree = Vec::new();
+        for _ in 0..=MAX_ACCOUNT_DEPTH {
+            if level.is_empty() {
+                break;
+            }
+            let mut next = Vec::new();
+            for account in &level {
+                next.extend(LedgerAccountRepository::find_children(self, &account.id)?);
+            }
+            next.sort_by(|a, b| a.name.cmp(&b.name));
+            subtree.append(&mut level);
+            level = next;
+        }
+        Ok(subtree)
+    }
+
+    fn save(&mut self, new_account: NewLedgerAccount) -> RepositoryResult<LedgerAccount> {
+        let account = LedgerAccount {
+            id: new_account.id,
+            created: new_account.created,
+            user_details: new_account.user_details,
+            short_desc: new_account.short_desc,
+            long_desc: new_account.long_desc,
+            name: new_account.name,
+            ledger_id: new_account.ledger_id,
+            parent_id: new_account.parent_id,
+            coa_id: new_account.coa_id,
+            balance_side: new_account.balance_side,
+            category: new_account.category,
+        };
+        self.tables.check_ledger_account(&account)?;
+        insert(&mut self.tables.ledger_account, account)
+    }
+
+    fn update(&mut self, account: &LedgerAccount) -> RepositoryResult<LedgerAccount> {
+        self.tables.check_ledger_account(account)?;
+        replace(&mut self.tables.ledger_account, account.clone())
+    }
+
+    fn delete_by_id(&mut self, account_id: &str) -> RepositoryResult<usize> {
+        let tables = &self.tables;
+        check_unreferenced(
+            &tables.ledger_account,
+            |a| a.parent_id.as_deref().filter(|parent_id| *parent_id != a.id),
+            account_id,
+            constraints::FK_LEDGER_ACCOUNT_PARENT,
+        )?;
+        check_unreferenced(
+            &tables.posting_trace,
+            |t| Some(&t.account_id),
+            account_id,
+            constraints::FK_POSTING_TRACE_ACCOUNT,
+        )?;
+        check_unreferenced(
+            &tables.account_stmt,
+            |s| Some(&s.account_id),
+            account_id,
+            constraints::FK_ACCOUNT_STMT_ACCOUNT,
+        )?;
+        check_unreferenced(
+            &tables.posting_line,
+            |l| Some(&l.account_id),
+            account_id,
+            constraints::FK_POSTING_LINE_ACCOUNT,
+        )?;
+        Ok(delete(&mut self.tables.ledger_account, account_id))
+    }
+}
+
+impl PostingRepository for InMemoryRepositories {
+    fn save(&mut self, new_posting: NewPosting) -> RepositoryResult<Posting> {
+        let posting = Posting {
+            id: new_posting.id,
+            antecedent_id: new_posting.antecedent_id,
+            antecedent_hash: new_posting.antecedent_hash,
+            hash: new_posting.hash,
+            hash_alg: new_posting.hash_alg,
+            record_user: new_posting.record_user,
+            record_time: new_posting.record_time,
+            opr_id: new_posting.opr_id,
+            opr_time: new_posting.opr_time,
+            opr_type: new_posting.opr_type,
+            opr_src: new_posting.opr_src,
+            pst_time: new_posting.pst_time,
+            pst_type: new_posting.pst_type,
+            pst_status: new_posting.pst_status,
+            ledger_id: new_posting.ledger_id,
+            val_time: new_posting.val_time,
+            discarded_id: new_posting.discarded_id,
+            discarded_time: new_posting.discarded_time,
+            discarding_id: new_posting.discarding_id,
+            opr_details_id: new_posting.opr_details_id,
+        };
+        self.tables.check_posting(&posting)?;
+        insert(&mut self.tables.posting, posting)
+    }
+
+    fn save_all(&mut self, new_postings: Vec<NewPosting>) -> RepositoryResult<Vec<Posting>> {
+        save_all(self, new_postings, PostingRepository::save)
+    }
+
+    fn find_by_id(&mut self, pst_id: &str) -> RepositoryResult<Option<Posting>> {
+        Ok(find(&self.tables.posting, pst_id))
+    }
+
+    fn find_by_opr_id(&mut self, opr_id: &str) -> RepositoryResult<Vec<Posting>> {
+        Ok(self.tables.posting.iter().filter(|posting| posting.opr_id == opr_id).cloned().collect())
+    }
+
+    fn find_by_opr_id_and_discarding_id_is_null(&mut self, opr_id: &str) -> RepositoryResult<Option<Posting>> {
+        Ok(self
+            .tables
+            .posting
+            .iter()
+            .find(|posting| {
+                posting.opr_id == opr_id && posting.discarding_id.is_none() && posting.pst_status.is_effective()
+            })
+            .cloned())
+    }
+
+    fn update_discarding_id(&mut self, pst_id: &str, discarding_id: &str) -> RepositoryResult<usize> {
+        let Some(mut posting) =
+            self.tables.posting.iter().find(|posting| posting.id == pst_id && posting.discarding_id.is_none()).cloned()
+        else {
+            return Ok(0);
+        };
+        posting.discarding_id = Some(discarding_id.to_string());
+        self.tables.check_posting(&posting)?;
+        replace(&mut self.tables.posting, posting)?;
+        Ok(1)
+    }
+
+    fn update_pending(&mut self, record: &NewPosting, pending_status: PostingStatus) -> RepositoryResult<usize> {
+        let Some(mut posting) = self
+            .tables
+            .posting
+            .iter()
+            .find(|posting| posting.id == record.id && posting.pst_status == pending_status)
+            .cloned()
+        else {
+            return Ok(0);
+        };
+        posting.antecedent_id = record.antecedent_id.clone();
+        posting.antecedent_hash = record.antecedent_hash.clone();
+        posting.hash = record.hash.clone();
+        posting.hash_alg = record.hash_alg.clone();
+        posting.record_time = record.record_time;
+        posting.pst_status = record.pst_status;
+        posting.discarded_id = record.discarded_id.clone();
+        posting.discarded_time = record.discarded_time;
+        replace(&mut self.tables.posting, posting)?;
+        Ok(1)
+    }
+
+    fn find_by_pst_status_and_pst_time_lte(
+        &mut self,
+        pst_status: PostingStatus,
+        ref_time: NaiveDateTime,
+    ) -> RepositoryResult<Vec<Posting>> {
+        let ref_time = micros(ref_time);
+        let mut postings: Vec<Posting> = self
+            .tables
+            .posting
+            .iter()
+            .filter(|posting| posting.pst_status == pst_status && posting.pst_time <= ref_time)
+            .cloned()
+            .collect();
+        postings.sort_by(|a, b| (a.pst_time, a.record_time, &a.id).cmp(&(b.pst_time, b.record_time, &b.id)));
+        Ok(postings)
+    }
+
+    fn find_by_ledger_order_by_record_time_asc(&mut self, ledger_id: &str) -> RepositoryResult<Vec<Posting>> {
+        let mut postings: Vec<Posting> = self
+            .tables
+            .posting
+            .iter()
+            .filter(|posting| posting.ledger_id == ledger_id && posting.pst_status.is_effective())
+            .cloned()
+            .collect();
+        postings.sort_by(|a, b| (a.record_time, &a.id).cmp(&(b.record_time, &b.id)));
+        Ok(postings)
+    }
+
+    fn find_first_by_ledger_order_by_record_time_desc(&mut self, ledger_id: &str) -> RepositoryResult<Option<Posting>> {
+        Ok(self
+            .tables
+            .posting
+            .iter()
+            .filter(|posting| posting.ledger_id == ledger_id && posting.pst_status.is_effective())
+            .max_by_key(|posting| posting.record_time)
+            .cloned())
+    }
+
+    fn delete_simulated_by_ledger(&mut self, ledger_id: &str) -> RepositoryResult<usize> {
+        let tables = &self.tables;
+        let simulated: Vec<String> = tables
+            .posting
+            .iter()
+            .filter(|posting| posting.ledger_id == ledger_id && posting.pst_status == PostingStatus::SIMULATED)
+            .map(|posting| posting.id.clone())
+            .collect();
+        for pst_id in &simulated {
+            check_unreferenced(
+                &tables.account_stmt,
+                |s| s.posting_id.as_deref(),
+                pst_id,
+                constraints::FK_ACCOUNT_STMT_POSTING,
+            )?;
+            check_unreferenced(
+                &tables.ledger_stmt,
+                |s| s.posting_id.as_deref(),
+                pst_id,
+                constraints::FK_LEDGER_STMT_POSTING,
+            )?;
+        }
+        Ok(simulated.iter().map(|pst_id| delete(&mut self.tables.posting, pst_id)).sum())
+    }
+}
+
+impl PostingLineRepository for InMemoryRepositories {
+    fn save(&mut self, new_line: NewPostingLine) -> RepositoryResult<PostingLine> {
+        let line = PostingLine {
+            id: new_line.id,
+            account_id: new_line.account_id,
+            debit_amount: new_line.debit_amount,
+            credit_amount: new_line.credit_amount,
+            details_id: new_line.details_id,
+            src_account: new_line.src_account,
+            base_line: new_line.base_line,
+            sub_opr_src_id: new_line.sub_opr_src_id,
+            record_time: new_line.record_time,
+            opr_id: new_line.opr_id,
+            opr_src: new_line.opr_src,
+            pst_time: new_line.pst_time,
+            pst_type: new_line.pst_type,
+            pst_status: new_line.pst_status,
+            hash: new_line.hash,
+            discarded_time: new_line.discarded_time,
+        };
+        self.tables.check_posting_line(&line)?;
+        insert(&mut self.tables.posting_line, line)
+    }
+
+    fn save_all(&mut self, new_lines: Vec<NewPostingLine>) -> RepositoryResult<Vec<PostingLine>> {
+        save_all(self, new_lines, PostingLineRepository::save)
+    }
+
+    fn update_discarded_time_by_opr_id_and_record_time(
+        &mut self,
+        opr_id: &str,
+        record_time: NaiveDateTime,
+        discarded_time: NaiveDateTime,
+    ) -> RepositoryResult<usize> {
+        let record_time = micros(record_time);
+        let mut updated = 0;
+        for line in self.tables.posting_line.iter_mut() {
+            if line.opr_id == opr_id && line.record_time == record_time && line.discarded_time.is_none() {
+                line.discarded_time = Some(micros(discarded_time));
+                updated += 1;
+            }
+        }
+        Ok(updated)
+    }
+
+    fn update_pending(&mut self, record: &NewPostingLine, pending_status: PostingStatus) -> RepositoryResult<usize> {
+        let Some(line) = self
+            .tables
+            .posting_line
+            .iter_mut()
+            .find(|line| line.id == record.id && line.pst_status == pending_status)
+        else {
+            return Ok(0);
+        };
+        line.record_time = micros(record.record_time);
+        line.pst_status = record.pst_status;
+        line.hash = record.hash.clone();
+        Ok(1)
+    }
+
+    fn find_by_opr_id_and_record_time(
+        &mut self,
+        opr_id: &str,
+        record_time: NaiveDateTime,
+    ) -> RepositoryResult<Vec<PostingLine>> {
+        let record_time = micros(record_time);
+        let mut lines: Vec<PostingLine> = self
+            .tables
+            .posting_line
+            .iter()
+            .filter(|line| line.opr_id == opr_id && line.record_time == record_time)
+            .cloned()
+            .collect();
+        lines.sort_by(|a, b| a.id.cmp(&b.id));
+        Ok(lines)
+    }
+
+    fn find_postings_by_account_and_dates(
+        &mut self,
+        account_id: &str,
+        from: NaiveDateTime,
+        to: NaiveDateTime,
+    ) -> RepositoryResult<Vec<PostingLine>> {
+        let (from, to) = (micros(from), micros(to));
+        let mut lines: Vec<PostingLine> = self
+            .tables
+            .posting_line
+            .iter()
+            .filter(|line| line.account_id == account_id)
+            .filter(|line| line.pst_time > from && line.pst_time <= to)
+            .filter(|line| line.discarded_time.is_none() && line.pst_status.is_effective())
+            .cloned()
+            .collect();
+        lines.sort_by_key(|line| std::cmp::Reverse(line.pst_time));
+        Ok(lines)
+    }
+
+    fn find_by_account_and_dates_after(
+        &mut self,
+        account_id: &str,
+        from: NaiveDateTime,
+        to: NaiveDateTime,
+        after: Option<&LineCursor>,
+        limit: i64,
+    ) -> RepositoryResult<Vec<PostingLine>> {
+        let (from, to) = (micros(from), micros(to));
+        let after = after.map(|cursor| (micros(cursor.pst_time), micros(cursor.record_time), cursor.id.as_str()));
+        let mut lines: Vec<&PostingLine> = self
+            .tables
+            .posting_line
+            .iter()
+            .filter(|line| line.account_id == account_id)
+            .filter(|line| line.pst_time > from && line.pst_time <= to)
+            .filter(|line| line.discarded_time.is_none() && line.pst_status.is_effective())
+            .filter(|line| after.is_none_or(|after| (line.pst_time, line.record_time, line.id.as_str()) > after))
+            .collect();
+        lines.sort_by_key(|line| (line.pst_time, line.record_time, line.id.as_str()));
+        Ok(lines.into_iter().take(limit.max(0) as usize).cloned().collect())
+    }
+
+    fn find_first_by_id_and_account(&mut self, line_id: &str, account_id: &str) -> RepositoryResult<Option<PostingLine>> {
+        Ok(self
+            .tables
+            .posting_line
+            .iter()
+            .find(|line| line.id == line_id && line.account_id == account_id)
+            .cloned())
+    }
+
+    fn exists_by_account(&mut self, account_id: &str) -> RepositoryResult<bool> {
+        Ok(self.tables.posting_line.iter().any(|line| line.account_id == account_id))
+    }
+
+    fn find_by_account_and_pst_time_lte_and_discarded_is_null_order_by_record_time_desc(
+        &mut self,
+        account_id: &str,
+        ref_time: NaiveDateTime,
+    ) -> RepositoryResult<Vec<PostingLine>> {
+        let ref_time = micros(ref_time);
+        let mut lines: Vec<PostingLine> = self
+            .tables
+            .posting_line
+            .iter()
+            .filter(|line| line.account_id == account_id)
+            .filter(|line| line.pst_time <= ref_time && line.discarded_time.is_none() && line.pst_status.is_effective())
+            .cloned()
+            .collect();
+        lines.sort_by_key(|line| std::cmp::Reverse(line.record_time));
+        Ok(lines)
+    }
+
+    fn find_by_account_and_dates_as_of(
+        &mut self,
+        account_id: &str,
+        from: NaiveDateTime,
+        to: NaiveDateTime,
+        as_of: NaiveDateTime,
+    ) -> RepositoryResult<Vec<PostingLine>> {
+        let (from, to, as_of) = (micros(from), micros(to), micros(as_of));
+        let mut lines: Vec<PostingLine> = self
+            .tables
+            .posting_line
+            .iter()
+            .filter(|line| line.account_id == account_id)
+            .filter(|line| line.pst_time > from && line.pst_time <= to)
+            .filter(|line| effective_as_of(line, as_of))
+            .cloned()
+            .collect();
+        lines.sort_by_key(|line| std::cmp::Reverse(line.pst_time));
+        Ok(lines)
+    }
+
+    fn find_by_account_and_dates_as_of_after(
+        &mut self,
+        account_id: &str,
+        from: NaiveDateTime,
+        to: NaiveDateTime,
+        as_of: NaiveDateTime,
+        after: Option<&LineCursor>,
+        limit: i64,
+    ) -> RepositoryResult<Vec<PostingLine>> {
+        let (from, to, as_of) = (micros(from), micros(to), micros(as_of));
+        let after = after.map(|cursor| (micros(cursor.pst_time), micros(cursor.record_time), cursor.id.as_str()));
+        let mut lines: Vec<&PostingLine> = self
+            .tables
+            .posting_line
+            .iter()
+            .filter(|line| line.account_id == account_id)
+            .filter(|line| line.pst_time > from && line.pst_time <= to)
+            .filter(|line| effective_as_of(line, as_of))
+            .filter(|line| after.is_none_or(|after| (line.pst_time, line.record_time, line.id.as_str()) > after))
+            .collect();
+        lines.sort_by_key(|line| (line.pst_time, line.record_time, line.id.as_str()));
+        Ok(lines.into_iter().take(limit.max(0) as usize).cloned().collect())
+    }
+
+    fn find_by_account_and_pst_time_lte_as_of(
+        &mut self,
+        account_id: &str,
+        ref_time: NaiveDateTime,
+        as_of: NaiveDateTime,
+    ) -> RepositoryResult<Vec<PostingLine>> {
+        let (ref_time, as_of) = (micros(ref_time), micros(as_of));
+        let mut lines: Vec<PostingLine> = self
+            .tables
+            .posting_line
+            .iter()
+            .filter(|line| line.account_id == account_id)
+            .filter(|line| line.pst_time <= ref_time && effective_as_of(line, as_of))
+            .cloned()
+            .collect();
+        lines.sort_by_key(|line| std::cmp::Reverse(line.record_time));
+        Ok(lines)
+    }
+
+    fn find_by_base_line_and_pst_time_lte_and_discarded_is_null_order_by_record_time_desc(
+        &mut self,
+        base_line: &str,
+        ref_time: NaiveDateTime,
+    ) -> RepositoryResult<Vec<PostingLine>> {
+        let ref_time = micros(ref_time);
+        let mut lines: Vec<PostingLine> = self
+            .tables
+            .posting_line
+            .iter()
+            .filter(|line| line.base_line.as_deref() == Some(base_line))
+            .filter(|line| line.pst_time <= ref_time && line.discarded_time.is_none() && line.pst_status.is_effective())
+            .cloned()
+            .collect();
+        lines.sort_by_key(|line| std::cmp::Reverse(line.record_time));
+        Ok(lines)
+    }
+
+    fn find_simulated_by_account_and_pst_time_lte(
+        &mut self,
+        account_id: &str,
+        ref_time: NaiveDateTime,
+    ) -> RepositoryResult<Vec<PostingLine>> {
+        let ref_time = micros(ref_time);
+        let mut lines: Vec<PostingLine> = self
+            .tables
+            .posting_line
+            .iter()
+            .filter(|line| line.account_id == account_id)
+            .filter(|line| line.pst_time <= ref_time && line.discarded_time.is_none())
+            .filter(|line| line.pst_status == PostingStatus::SIMULATED)
+            .cloned()
+            .collect();
+        lines.sort_by_key(|line| std::cmp::Reverse(line.record_time));
+        Ok(lines)
+    }
+
+    fn delete_simulated_by_ledger(&mut self, ledger_id: &str) -> RepositoryResult<usize> {
+        let accounts = self.tables.account_ids_of_ledger(ledger_id);
+        let len = self.tables.posting_line.len();
+        self.tables
+            .posting_line
+            .retain(|line| line.pst_status != PostingStatus::SIMULATED || !accounts.contains(&line.account_id));
+        Ok(len - self.tables.posting_line.len())
+    }
+}
+
+impl AccountStmtRepository for InMemoryRepositories {
+    fn save(&mut self, new_stmt: NewAccountStmt) -> RepositoryResult<AccountStmt> {
+        let stmt = AccountStmt {
+            id: new_stmt.id,
+            posting_id: new_stmt.posting_id,
+            pst_time: new_stmt.pst_time,
+            stmt_status: new_stmt.stmt_status,
+            latest_pst_id: new_stmt.latest_pst_id,
+            stmt_seq_nbr: new_stmt.stmt_seq_nbr,
+            created: new_stmt.created,
+            user_details: new_stmt.user_details,
+            short_desc: new_stmt.short_desc,
+            long_desc: new_stmt.long_desc,
+            account_id: new_stmt.account_id,
+            youngest_pst_id: new_stmt.youngest_pst_id,
+            total_debit: new_stmt.total_debit,
+            total_credit: new_stmt.total_credit,
+        };
+        self.tables.check_account_stmt(&stmt)?;
+        insert(&mut self.tables.account_stmt, stmt)
+    }
+
+    fn find_first_by_account_and_stmt_status_and_pst_time_less_than_order_by_pst_time_desc_stmt_seq_nbr_desc(
+        &mut self,
+        account_id: &str,
+        stmt_status: StmtStatus,
+        ref_time: NaiveDateTime,
+    ) -> RepositoryResult<Option<AccountStmt>> {
+        let ref_time = micros(ref_time);
+        Ok(self
+            .tables
+            .account_stmt
+            .iter()
+            .filter(|stmt| stmt.account_id == account_id && stmt.stmt_status == stmt_status)
+            .filter(|stmt| stmt.pst_time < ref_time)
+            .max_by_key(|stmt| (stmt.pst_time, stmt.stmt_seq_nbr))
+            .cloned())
+    }
+
+    fn find_first_by_account_and_stmt_status_and_pst_time_less_than_as_of(
+        &mut self,
+        account_id: &str,
+        stmt_status: StmtStatus,
+        ref_time: NaiveDateTime,
+        as_of: NaiveDateTime,
+    ) -> RepositoryResult<Option<AccountStmt>> {
+        let (ref_time, as_of) = (micros(ref_time), micros(as_of));
+        Ok(self
+            .tables
+            .account_stmt
+            .iter()
+            .filter(|stmt| stmt.account_id == account_id && stmt.stmt_status == stmt_status)
+            .filter(|stmt| stmt.pst_time < ref_time && stmt.created.is_some_and(|created| created <= as_of))
+            .max_by_key(|stmt| (stmt.pst_time, stmt.stmt_seq_nbr))
+            .cloned())
+    }
+
+    fn find_first_by_account_and_stmt_status_and_pst_time_gte(
+        &mut self,
+        account_id: &str,
+        stmt_status: StmtStatus,
+        ref_time: NaiveDateTime,
+    ) -> RepositoryResult<Option<AccountStmt>> {
+        let ref_time = micros(ref_time);
+        Ok(self
+            .tables
+            .account_stmt
+            .iter()
+            .filter(|stmt| stmt.account_id == account_id && stmt.stmt_status == stmt_status)
+            .filter(|stmt| stmt.pst_time >= ref_time)
+            .min_by_key(|stmt| (stmt.pst_time, stmt.stmt_seq_nbr))
+            .cloned())
+    }
+
+    fn find_by_account_and_stmt_status_and_pst_time_gte(
+        &mut self,
+        account_id: &str,
+        stmt_status: StmtStatus,
+        ref_time: NaiveDateTime,
+    ) -> RepositoryResult<Vec<AccountStmt>> {
+        let ref_time = micros(ref_time);
+        let mut stmts: Vec<AccountStmt> = self
+            .tables
+            .account_stmt
+            .iter()
+            .filter(|stmt| stmt.account_id == account_id && stmt.stmt_status == stmt_status)
+            .filter(|stmt| stmt.pst_time >= ref_time)
+            .cloned()
+            .collect();
+        stmts.sort_by_key(|stmt| (stmt.pst_time, stmt.stmt_seq_nbr));
+        Ok(stmts)
+    }
+
+    fn delete_simulated_by_ledger(&mut self, ledger_id: &str) -> RepositoryResult<usize> {
+        let accounts = self.tables.account_ids_of_ledger(ledger_id);
+        let len = self.tables.account_stmt.len();
+        self.tables
+            .account_stmt
+            .retain(|stmt| stmt.stmt_status != StmtStatus::SIMULATED || !accounts.contains(&stmt.account_id));
+        Ok(len - self.tables.account_stmt.len())
+    }
+}
+
+impl LedgerStmtRepository for InMemoryRepositories {
+    fn save(&mut self, new_stmt: NewLedgerStmt) -> RepositoryResult<LedgerStmt> {
+        let stmt = LedgerStmt {
+            id: new_stmt.id,
+            posting_id: new_stmt.posting_id,
+            pst_time: new_stmt.pst_time,
+            stmt_status: new_stmt.stmt_status,
+            latest_pst_id: new_stmt.latest_pst_id,
+            stmt_seq_nbr: new_stmt.stmt_seq_nbr,
+            created: new_stmt.created,
+            user_details: new_stmt.user_details,
+            short_desc: new_stmt.short_desc,
+            long_desc: new_stmt.long_desc,
+            ledger_id: new_stmt.ledger_id,
+        };
+        self.tables.check_ledger_stmt(&stmt)?;
+        insert(&mut self.tables.ledger_stmt, stmt)
+    }
+
+    fn save_all(&mut self, new_stmts: Vec<NewLedgerStmt>) -> RepositoryResult<Vec<LedgerStmt>> {
+        save_all(self, new_stmts, LedgerStmtRepository::save)
+    }
+
+    fn find_by_id(&mut self, stmt_id: &str) -> RepositoryResult<Option<LedgerStmt>> {
+        Ok(find(&self.tables.ledger_stmt, stmt_id))
+    }
+
+    fn delete_simulated_by_id(&mut self, stmt_id: &str) -> RepositoryResult<usize> {
+        let len = self.tables.ledger_stmt.len();
+        self.tables.ledger_stmt.retain(|stmt| stmt.id != stmt_id || stmt.stmt_status != StmtStatus::SIMULATED);
+        Ok(len - self.tables.ledger_stmt.len())
+    }
+
+    fn delete_simulated_by_ledger(&mut self, ledger_id: &str) -> RepositoryResult<usize> {
+        let len = self.tables.ledger_stmt.len();
+        self.tables
+            .ledger_stmt
+            .retain(|stmt| stmt.ledger_id != ledger_id || stmt.stmt_status != StmtStatus::SIMULATED);
+        Ok(len - self.tables.ledger_stmt.len())
+    }
+
+    fn find_first_by_ledger_and_stmt_status_and_pst_time_gte(
+        &mut self,
+        ledger_id: &str,
+        stmt_status: StmtStatus,
+        ref_time: NaiveDateTime,
+    ) -> RepositoryResult<Option<LedgerStmt>> {
+        let ref_time = micros(ref_time);
+        Ok(self
+            .tables
+            .ledger_stmt
+            .iter()
+            .filter(|stmt| stmt.ledger_id == ledger_id && stmt.stmt_status == stmt_status)
+            .filter(|stmt| stmt.pst_time >= ref_time)
+            .min_by_key(|stmt| (stmt.pst_time, stmt.stmt_seq_nbr))
+            .cloned())
+    }
+
+    fn find_by_ledger_and_stmt_status_and_pst_time_gte(
+        &mut self,
+        ledger_id: &str,
+        stmt_status: StmtStatus,
+        ref_time: NaiveDateTime,
+    ) -> RepositoryResult<Vec<LedgerStmt>> {
+        let ref_time = micros(ref_time);
+        let mut stmts: Vec<LedgerStmt> = self
+            .tables
+            .ledger_stmt
+            .iter()
+            .filter(|stmt| stmt.ledger_id == ledger_id && stmt.stmt_status == stmt_status)
+            .filter(|stmt| stmt.pst_time >= ref_time)
+            .cloned()
+            .collect();
+        stmts.sort_by_key(|stmt| (stmt.pst_time, stmt.stmt_seq_nbr));
+        Ok(stmts)
+    }
+
+    fn find_first_by_ledger_and_stmt_status_and_pst_time_lte_order_by_pst_time_desc_stmt_seq_nbr_desc(
+        &mut self,
+        ledger_id: &str,
+        stmt_status: StmtStatus,
+        ref_time: NaiveDateTime,
+    ) -> RepositoryResult<Option<LedgerStmt>> {
+        let ref_time = micros(ref_time);
+        Ok(self
+            .tables
+            .ledger_stmt
+            .iter()
+            .filter(|stmt| stmt.ledger_id == ledger_id && stmt.stmt_status == stmt_status)
+            .filter(|stmt| stmt.pst_time <= ref_time)
+            .max_by_key(|stmt| (stmt.pst_time, stmt.stmt_seq_nbr))
+            .cloned())
+    }
+}
+
+impl PostingTraceRepository for InMemoryRepositories {
+    fn save(&mut self, new_trace: NewPostingTrace) -> RepositoryResult<PostingTrace> {
+        let trace = PostingTrace {
+            id: new_trace.id,
+            tgt_pst_id: new_trace.tgt_pst_id,
+            src_pst_id: new_trace.src_pst_id,
+            src_pst_time: new_trace.src_pst_time,
+            src_opr_id: new_trace.src_opr_id,
+            account_id: new_trace.account_id,
+            debit_amount: new_trace.debit_amount,
+            credit_amount: new_trace.credit_amount,
+            src_pst_hash: new_trace.src_pst_hash,
+        };
+        self.tables.check_posting_trace(&trace)?;
+        insert(&mut self.tables.posting_trace, trace)
+    }
+
+    fn save_all(&mut self, new_traces: Vec<NewPostingTrace>) -> RepositoryResult<Vec<PostingTrace>> {
+        save_all(self, new_traces, PostingTraceRepository::save)
+    }
+
+    fn find_by_tgt_pst_id(&mut self, tgt_pst_id: &str) -> RepositoryResult<Vec<PostingTrace>> {
+        let mut traces: Vec<PostingTrace> =
+            self.tables.posting_trace.iter().filter(|trace| trace.tgt_pst_id == tgt_pst_id).cloned().collect();
+        traces.sort_by(|a, b| a.id.cmp(&b.id));
+        Ok(traces)
+    }
+
+    fn find_by_id(&mut self, trace_id: &str) -> RepositoryResult<Option<PostingTrace>> {
+        Ok(find(&self.tables.posting_trace, trace_id))
+    }
+
+    fn delete_simulated_by_ledger(&mut self, ledger_id: &str) -> RepositoryResult<usize> {
+        let tables = &self.tables;
+        let traces: Vec<String> = tables
+            .posting_trace
+            .iter()
+            .filter(|trace| {
+                tables.posting.iter().any(|posting| {
+                    posting.id == trace.tgt_pst_id
+                        && posting.ledger_id == ledger_id
+                        && posting.pst_status == PostingStatus::SIMULATED
+                })
+            })
+            .map(|trace| trace.id.clone())
+            .collect();
+        for trace_id in &traces {
+            check_unreferenced(
+                &tables.account_stmt,
+                |s| s.latest_pst_id.as_deref(),
+                trace_id,
+                constraints::FK_ACCOUNT_STMT_LATEST_PST,
+            )?;
+            check_unreferenced(
+                &tables.account_stmt,
+                |s| s.youngest_pst_id.as_deref(),
+                trace_id,
+                constraints::FK_ACCOUNT_STMT_YOUNGEST_PST,
+            )?;
+            check_unreferenced(
+                &tables.ledger_stmt,
+                |s| s.latest_pst_id.as_deref(),
+                trace_id,
+                constraints::FK_LEDGER_STMT_LATEST_PST,
+            )?;
+        }
+        Ok(traces.iter().map(|trace_id| delete(&mut self.tables.posting_trace, trace_id)).sum())
+    }
+}
+
+impl OpNoteRepository for InMemoryRepositories {
+    fn save(&mut self, new_note: NewOpNote) -> RepositoryResult<OpNote> {
+        let note = OpNote {
+            id: new_note.id,
+            rec_id: new_note.rec_id,
+            note_type: new_note.note_type,
+            content: new_note.content,
+            rec_time: new_note.rec_time,
+            exec_time: new_note.exec_time,
+            premature_exc: new_note.premature_exc,
+            repeated_exec: new_note.repeated_exec,
+            exec_status: new_note.exec_status,
+        };
+        insert(&mut self.tables.op_note, note)
+    }
+
+    fn save_all(&mut self, new_notes: Vec<NewOpNote>) -> RepositoryResult<Vec<OpNote>> {
+        save_all(self, new_notes, OpNoteRepository::save)
+    }
+
+    fn update(&mut self, note: &OpNote) -> RepositoryResult<OpNote> {
+        replace(&mut self.tables.op_note, note.clone())
+    }
+
+    fn delete_by_id(&mut self, note_id: &str) -> RepositoryResult<usize> {
+        Ok(delete(&mut self.tables.op_note, note_id))
+    }
+
+    fn find_by_id(&mut self, note_id: &str) -> RepositoryResult<Option<OpNote>> {
+        Ok(find(&self.tables.op_note, note_id))
+    }
+
+    fn find_by_id_for_update_skip_locked(&mut self, note_id: &str) -> RepositoryResult<Option<OpNote>> {
+        Ok(find(&self.tables.op_note, note_id))
+    }
+
+    fn find_by_rec_id(&mut self, rec_id: &str) -> RepositoryResult<Vec<OpNote>> {
+        let mut notes: Vec<OpNote> = self.tables.op_note.iter().filter(|note| note.rec_id == rec_id).cloned().collect();
+        notes.sort_by(|a, b| (a.rec_time, &a.id).cmp(&(b.rec_time, &b.id)));
+        Ok(notes)
+    }
+
+    fn find_pending_by_exec_time_lte(&mut self, ref_time: NaiveDateTime) -> RepositoryResult<Vec<OpNote>> {
+        let ref_time = micros(ref_time);
+        let mut notes: Vec<OpNote> = self
+            .tables
+            .op_note
+            .iter()
+            .filter(|note| note.exec_time.is_some_and(|exec_time| exec_time <= ref_time))
+            .filter(|note| match note.exec_status.as_deref() {
+                None => true,
+                Some(exec_status) => exec_status == OpNote::EXEC_FAILED && note.repeated_exec == Some(true),
+            })
+            .cloned()
+            .collect();
+        notes.sort_by(|a, b| (a.exec_time, a.rec_time, &a.id).cmp(&(b.exec_time, b.rec_time, &b.id)));
+        Ok(notes)
+    }
+}
+
+impl OperationDetailsRepository for InMemoryRepositories {
+    fn save(&mut self, new_details: NewOperationDetails) -> RepositoryResult<OperationDetails> {
+        let details = OperationDetails { id: new_details.id, op_details: new_details.op_details };
+        insert(&mut self.tables.operation_details, details)
+    }
+
+    fn save_all(&mut self, new_details: Vec<NewOperationDetails>) -> RepositoryResult<Vec<OperationDetails>> {
+        save_all(self, new_details, OperationDetailsRepository::save)
+    }
+
+    fn find_by_id(&mut self, details_id: &str) -> RepositoryResult<Option<OperationDetails>> {
+        Ok(find(&self.tables.operation_details, details_id))
+    }
+}
@@ -7,27 +7,39 @@
 use chrono::NaiveDateTime;
 use diesel::prelude::*;
 use diesel::{OptionalExtension, QueryDsl, ExpressionMethods, RunQueryDsl};
-use rust_decimal::Decimal;
 
 use crate::models::{
-    AccountStmt, Ledger, LedgerAccount, LedgerStmt, Posting, PostingLine, PostingTrace, OpNote,
-    ChartOfAccount,
+    Ledger, LedgerStmt, Posting, PostingLine, PostingTrace, OpNote,
 };
-use crate::models::enums::{StmtStatus};
+use crate::models::enums::{PostingStatus, StmtStatus};
+
+#[cfg(feature = "async")]
+pub mod async_repository;
+pub mod error;
+mod memory;
+pub mod pagination;
+mod pg;
+#[cfg(feature = "sqlite")]
+pub mod sqlite;
+pub mod traits;
+
+pub use error::{RepositoryError, RepositoryResult};
+pub use memory::InMemoryRepositories;
+pub use traits::*;
 
 // AccountStmtRepository-like
 //
 pub mod account_stmt_repository {
     use super::*;
 
-    use diesel::prelude::*;
-use crate::models::{AccountStmt, NewAccountStmt};
+    use crate::models::{AccountStmt, NewAccountStmt};
 use crate::schema::account_stmt::dsl::*;
 
-    pub fn save(conn: &mut PgConnection, new_stmt: NewAccountStmt) -> QueryResult<AccountStmt> {
+    pub fn save(conn: &mut PgConnection, new_stmt: NewAccountStmt) -> RepositoryResult<AccountStmt> {
         diesel::insert_into(account_stmt)
-            .values(&new_stmt)
+            .values(new_stmt)
             .get_result(conn)
+            .map_err(RepositoryError::from)
     }
 
     /// findFirstByAccountAndStmtStatusAndPstTimeLessThanOrderByPstTimeDescStmtSeqNbrDesc(...)
@@ -36,15 +48,37 @@ use crate::schema::account_stmt::dsl::*;
         account_id_val: &str,
         stmt_status_val: StmtStatus,
         ref_time_val: NaiveDateTime,
-    ) -> QueryResult<Option<AccountStmt>> {
+    ) -> RepositoryResult<Option<AccountStmt>> {
+        use crate::schema::account_stmt::dsl::*;
+        account_stmt
+            .filter(account_id.eq(account_id_val))
+            .filter(stmt_status.eq(stmt_status_val))
+            .filter(pst_time.lt(ref_time_val))
+            .order_by((pst_time.desc(), stmt_seq_nbr.desc()))
+            .first::<AccountStmt>(conn)
+            .optional()
+            .map_err(RepositoryError::from)
+    }
+
+    /// The statement of `find_first_by_account_and_stmt_status_and_pst_time_less_than_order_by_pst_time_desc_stmt_seq_nbr_desc`
+    /// as recorded at `as_of`: statements created after it are left out.
+    pub fn find_first_by_account_and_stmt_status_and_pst_time_less_than_as_of(
+        conn: &mut PgConnection,
+        account_id_val: &str,
+        stmt_status_val: StmtStatus,
+        ref_time_val: NaiveDateTime,
+        as_of: NaiveDateTime,
+    ) -> RepositoryResult<Option<AccountStmt>> {
         use crate::schema::account_stmt::dsl::*;
         account_stmt
             .filter(account_id.eq(account_id_val))
             .filter(stmt_status.eq(stmt_status_val))
             .filter(pst_time.lt(ref_time_val))
+            .filter(created.le(as_of))
             .order_by((pst_time.desc(), stmt_seq_nbr.desc()))
             .first::<AccountStmt>(conn)
             .optional()
+            .map_err(RepositoryError::from)
     }
 
     /// findFirstByAccountAndStmtStatusAndPstTimeGreaterThanEqual(...)
@@ -53,7 +87,7 @@ use crate::schema::account_stmt::dsl::*;
         account_id_val: &str,
         stmt_status_val: StmtStatus,
         ref_time_val: NaiveDateTime,
-    ) -> QueryResult<Option<AccountStmt>> {
+    ) -> RepositoryResult<Option<AccountStmt>> {
         use crate::schema::account_stmt::dsl::*;
         account_stmt
             .filter(account_id.eq(account_id_val))
@@ -62,9 +96,64 @@ use crate::schema::account_stmt::dsl::*;
             .order_by((pst_time.asc(), stmt_seq_nbr.asc()))
             .first::<AccountStmt>(conn)
             .optional()
+            .map_err(RepositoryError::from)
+    }
+
+    /// findByAccountAndStmtStatusAndPstTimeGreaterThanEqualOrderByPstTimeAscStmtSeqNbrAsc(...)
+    pub fn find_by_account_and_stmt_status_and_pst_time_gte(
+        conn: &mut PgConnection,
+        account_id_val: &str,
+        stmt_status_val: StmtStatus,
+        ref_time_val: NaiveDateTime,
+    ) -> RepositoryResult<Vec<AccountStmt>> {
+        use crate::schema::account_stmt::dsl::*;
+        account_stmt
+            .filter(account_id.eq(account_id_val))
+            .filter(stmt_status.eq(stmt_status_val))
+            .filter(pst_time.ge(ref_time_val))
+            .order_by((pst_time.asc(), stmt_seq_nbr.asc()))
+            .load::<AccountStmt>(conn)
+            .map_err(RepositoryError::from)
+    }
+
+    /// Deletes the simulated statements of the accounts of a ledger. Closed
+    /// statements are left untouched.
+    pub fn delete_simulated_by_ledger(conn: &mut PgConnection, ledger_id_val: &str) -> RepositoryResult<usize> {
+        use crate::schema::ledger_account;
+        let accounts = ledger_account::table
+            .filter(ledger_account::ledger_id.eq(ledger_id_val))
+            .select(ledger_account::id);
+        diesel::delete(
+            account_stmt
+                .filter(account_id.eq_any(accounts))
+                .filter(stmt_status.eq(StmtStatus::SIMULATED)),
+        )
+        .execute(conn)
+        .map_err(RepositoryError::from)
     }
 }
 
+/// Walks up the `parent_id` chain of the account bound to `$1`. A root
+/// account may reference itself as parent. The recursive account queries stop
+/// 64 levels deep, which only matters for a cyclic `parent_id` chain. They are
+/// also run by SQLite, which binds `$1` as its first parameter.
+pub(crate) const ANCESTORS_QUERY: &str = "WITH RECURSIVE tree AS ( \
+    SELECT account.*, 0 AS depth FROM ledger_account account WHERE account.id = $1 \
+    UNION ALL \
+    SELECT parent.*, tree.depth + 1 FROM ledger_account parent \
+    JOIN tree ON parent.id = tree.parent_id \
+    WHERE tree.parent_id <> tree.id AND tree.depth < 64 \
+    ) SELECT * FROM tree WHERE depth > 0 ORDER BY depth";
+
+/// Walks down from the account bound to `$1` to all its descendants.
+pub(crate) const SUBTREE_QUERY: &str = "WITH RECURSIVE tree AS ( \
+    SELECT account.*, 0 AS depth FROM ledger_account account WHERE account.id = $1 \
+    UNION ALL \
+    SELECT child.*, tree.depth + 1 FROM ledger_account child \
+    JOIN tree ON child.parent_id = tree.id \
+    WHERE child.id <> child.parent_id AND tree.depth < 64 \
+    ) SELECT * FROM tree ORDER BY depth, name";
+
 //
 // LedgerAccountRepository-like
 //
@@ -77,12 +166,13 @@ pub mod ledger_account_repository {
     pub fn find_by_id(
         conn: &mut PgConnection,
         account_id_val: &str,
-    ) -> QueryResult<Option<LedgerAccount>> {
+    ) -> RepositoryResult<Option<LedgerAccount>> {
         use crate::schema::ledger_account::dsl::*;
         ledger_account
             .find(account_id_val)
             .first::<LedgerAccount>(conn)
             .optional()
+            .map_err(RepositoryError::from)
     }
 
     /// findOptionalByLedgerAndName(...)
@@ -90,16 +180,35 @@ pub mod ledger_account_repository {
         conn: &mut PgConnection,
         ledger_id_val: &str,
         account_name: &str,
-    ) -> QueryResult<Option<LedgerAccount>> {
+    ) -> RepositoryResult<Option<LedgerAccount>> {
         use crate::schema::ledger_account::dsl::*;
         ledger_account
             .filter(ledger_id.eq(ledger_id_val))
             .filter(name.eq(account_name))
             .first::<LedgerAccount>(conn)
             .optional()
+            .map_err(RepositoryError::from)
+    }
+
+    /// findByLedger(...): all accounts of a ledger ordered by name.
+    pub fn find_by_ledger(conn: &mut PgConnection, ledger_id_val: &str) -> RepositoryResult<Vec<LedgerAccount>> {
+        ledger_account
+            .filter(ledger_id.eq(ledger_id_val))
+            .order_by(name.asc())
+            .load::<LedgerAccount>(conn)
+            .map_err(RepositoryError::from)
+    }
+
+    /// findByCoa(...): all accounts of a chart of account, over all its
+    /// ledgers, ordered by name and ledger.
+    pub fn find_by_coa(conn: &mut PgConnection, coa_id_val: &str) -> RepositoryResult<Vec<LedgerAccount>> {
+        ledger_account
+            .filter(coa_id.eq(coa_id_val))
+            .order_by((name.asc(), ledger_id.asc()))
+            .load::<LedgerAccount>(conn)
+            .map_err(RepositoryError::from)
     }
 
-    
     /// Saves a new LedgerAccount into the database and returns the inserted record.
     ///
     /// # Arguments
@@ -109,13 +218,56 @@ pub mod ledger_account_repository {
     ///
     /// # Returns
     ///
-    /// A QueryResult wrapping the inserted LedgerAccount.
-    pub fn save(conn: &mut PgConnection, new_account: NewLedgerAccount) -> QueryResult<LedgerAccount> {
+    /// A RepositoryResult wrapping the inserted LedgerAccount.
+    pub fn save(conn: &mut PgConnection, new_account: NewLedgerAccount) -> RepositoryResult<LedgerAccount> {
         diesel::insert_into(ledger_account)
             .values(&new_account)
             .get_result(conn)
+            .map_err(RepositoryError::from)
+    }
+
+    /// Updates an existing LedgerAccount with the values of the given record.
+    pub fn update(conn: &mut PgConnection, account: &LedgerAccount) -> RepositoryResult<LedgerAccount> {
+        diesel::update(account)
+            .set(account)
+            .get_result(conn)
+            .map_err(RepositoryError::from)
+    }
+
+    /// findChildren(...): the accounts whose parent is the given account,
+    /// ordered by name. A root account referencing itself is not its own child.
+    pub fn find_children(conn: &mut PgConnection, account_id_val: &str) -> RepositoryResult<Vec<LedgerAccount>> {
+        ledger_account
+            .filter(parent_id.eq(account_id_val))
+            .filter(id.ne(account_id_val))
+            .order_by(name.asc())
+            .load::<LedgerAccount>(conn)
+            .map_err(RepositoryError::from)
+    }
+
+    /// findAncestors(...): the parent of the account, its parent and so on up
+    /// to the root, nearest first.
+    pub fn find_ancestors(conn: &mut PgConnection, account_id_val: &str) -> RepositoryResult<Vec<LedgerAccount>> {
+        diesel::sql_query(ANCESTORS_QUERY)
+            .bind::<diesel::sql_types::Varchar, _>(account_id_val)
+            .load::<LedgerAccount>(conn)
+            .map_err(RepositoryError::from)
+    }
+
+    /// findSubtree(...): the account and all its descendants, level by level
+    /// and by name within a level. Empty if the account does not exist.
+    pub fn find_subtree(conn: &mut PgConnection, account_id_val: &str) -> RepositoryResult<Vec<LedgerAccount>> {
+        diesel::sql_query(SUBTREE_QUERY)
+            .bind::<diesel::sql_types::Varchar, _>(account_id_val)
+            .load::<LedgerAccount>(conn)
+            .map_err(RepositoryError::from)
+    }
+
+    /// Deletes a LedgerAccount. Fails with a foreign key violation as long as
+    /// child accounts or statements still reference the account.
+    pub fn delete_by_id(conn: &mut PgConnection, account_id_val: &str) -> RepositoryResult<usize> {
+        diesel::delete(ledger_account.find(account_id_val)).execute(conn).map_err(RepositoryError::from)
     }
-    
 }
 
 //
@@ -123,26 +275,83 @@ pub mod ledger_account_repository {
 //
 pub mod ledger_repository {
     use super::*;
+    use crate::models::NewLedger;
 
     /// findById(...)
-    pub fn find_by_id(conn: &mut PgConnection, ledger_id_val: &str) -> QueryResult<Option<Ledger>> {
+    pub fn find_by_id(conn: &mut PgConnection, ledger_id_val: &str) -> RepositoryResult<Option<Ledger>> {
+        use crate::schema::ledger::dsl::*;
+        ledger
+            .find(ledger_id_val)
+            .first::<Ledger>(conn)
+            .optional()
+            .map_err(RepositoryError::from)
+    }
+
+    /// findById(...) locking the ledger row until the end of the transaction.
+    /// Used to serialize the recording of postings of a ledger.
+    pub fn find_by_id_for_update(conn: &mut PgConnection, ledger_id_val: &str) -> RepositoryResult<Option<Ledger>> {
         use crate::schema::ledger::dsl::*;
         ledger
             .find(ledger_id_val)
+            .for_update()
             .first::<Ledger>(conn)
             .optional()
+            .map_err(RepositoryError::from)
+    }
+
+    /// findAll(...): all ledgers ordered by name.
+    pub fn find_all(conn: &mut PgConnection) -> RepositoryResult<Vec<Ledger>> {
+        use crate::schema::ledger::dsl::*;
+        ledger
+            .order_by(name.asc())
+            .load::<Ledger>(conn)
+            .map_err(RepositoryError::from)
     }
 
     /// findOptionalByName(...)
     pub fn find_optional_by_name(
         conn: &mut PgConnection,
         ledger_name_val: &str,
-    ) -> QueryResult<Option<Ledger>> {
+    ) -> RepositoryResult<Option<Ledger>> {
         use crate::schema::ledger::dsl::*;
         ledger
             .filter(name.eq(ledger_name_val))
             .first::<Ledger>(conn)
             .optional()
+            .map_err(RepositoryError::from)
+    }
+
+    /// Saves a new Ledger into the database and returns the inserted record.
+    pub fn save(conn: &mut PgConnection, new_ledger: NewLedger) -> RepositoryResult<Ledger> {
+        use crate::schema::ledger::dsl::*;
+        diesel::insert_into(ledger)
+            .values(&new_ledger)
+            .get_result(conn)
+            .map_err(RepositoryError::from)
+    }
+
+    /// Saves a batch of new Ledgers in a single statement.
+    pub fn save_all(conn: &mut PgConnection, new_ledgers: Vec<NewLedger>) -> RepositoryResult<Vec<Ledger>> {
+        use crate::schema::ledger::dsl::*;
+        diesel::insert_into(ledger)
+            .values(&new_ledgers)
+            .get_results(conn)
+            .map_err(RepositoryError::from)
+    }
+
+    /// Updates an existing Ledger with the values of the given record.
+    pub fn update(conn: &mut PgConnection, ledger_val: &Ledger) -> RepositoryResult<Ledger> {
+        diesel::update(ledger_val)
+            .set(ledger_val)
+            .get_result(conn)
+            .map_err(RepositoryError::from)
+    }
+
+    /// Deletes a Ledger. Fails with a foreign key violation as long as accounts,
+    /// postings or statements still reference the ledger.
+    pub fn delete_by_id(conn: &mut PgConnection, ledger_id_val: &str) -> RepositoryResult<usize> {
+        use crate::schema::ledger::dsl::*;
+        diesel::delete(ledger.find(ledger_id_val)).execute(conn).map_err(RepositoryError::from)
     }
 }
 
@@ -151,71 +360,289 @@ pub mod ledger_repository {
 //
 pub mod posting_repository {
     use super::*;
+    use crate::models::NewPosting;
+
+    /// Saves a new Posting into the database and returns the inserted record.
+    ///
+    /// Postings are journal entries: once recorded they are neither updated nor
+    /// deleted. A changed operation is recorded with a new posting discarding
+    /// the previous one (see `update_discarding_id`). Only proposed and deferred
+    /// postings, which are not recorded in the journal yet, are updated once
+    /// decided on or due (see `update_pending`), and simulated postings are deleted
+    /// once the simulation is over (see `delete_simulated_by_ledger`).
+    pub fn save(conn: &mut PgConnection, new_posting: NewPosting) -> RepositoryResult<Posting> {
+        use crate::schema::posting::dsl::*;
+        diesel::insert_into(posting)
+            .values(&new_posting)
+            .get_result(conn)
+            .map_err(RepositoryError::from)
+    }
+
+    /// Saves a batch of new Postings in a single statement.
+    pub fn save_all(conn: &mut PgConnection, new_postings: Vec<NewPosting>) -> RepositoryResult<Vec<Posting>> {
+        use crate::schema::posting::dsl::*;
+        diesel::insert_into(posting)
+            .values(&new_postings)
+            .get_results(conn)
+            .map_err(RepositoryError::from)
+    }
 
     /// findById(...) if you need a direct "findById" for posting
-    pub fn find_by_id(conn: &mut PgConnection, pst_id: &str) -> QueryResult<Option<Posting>> {
+    pub fn find_by_id(conn: &mut PgConnection, pst_id: &str) -> RepositoryResult<Option<Posting>> {
         use crate::schema::posting::dsl::*;
         posting
             .find(pst_id)
             .first::<Posting>(conn)
             .optional()
+            .map_err(RepositoryError::from)
     }
 
     /// findByOprId(...)
-    pub fn find_by_opr_id(conn: &mut PgConnection, opr_id_val: &str) -> QueryResult<Vec<Posting>> {
+    pub fn find_by_opr_id(conn: &mut PgConnection, opr_id_val: &str) -> RepositoryResult<Vec<Posting>> {
         use crate::schema::posting::dsl::*;
         posting
             .filter(opr_id.eq(opr_id_val))
             .load::<Posting>(conn)
+            .map_err(RepositoryError::from)
     }
 
     /// findByOprIdAndDiscardingIdIsNull(...)
     pub fn find_by_opr_id_and_discarding_id_is_null(
         conn: &mut PgConnection,
         opr_id_val: &str,
-    ) -> QueryResult<Option<Posting>> {
+    ) -> RepositoryResult<Option<Posting>> {
         use crate::schema::posting::dsl::*;
         posting
             .filter(opr_id.eq(opr_id_val))
-            // TODO: Find another way to select the last record.
-            //.filter(discarding_id.is_null())
+            .filter(discarding_id.is_null())
+            .filter(pst_status.ne_all(PostingStatus::INEFFECTIVE))
             .first::<Posting>(conn)
             .optional()
+            .map_err(RepositoryError::from)
+    }
+
+    /// Records the posting discarding the given posting.
+    ///
+    /// This is the only change allowed on a recorded posting, and it can only
+    /// happen once: a posting that has already been discarded is left untouched
+    /// and 0 is returned.
+    pub fn update_discarding_id(
+        conn: &mut PgConnection,
+        pst_id: &str,
+        discarding_id_val: &str,
+    ) -> RepositoryResult<usize> {
+        use crate::schema::posting::dsl::*;
+        diesel::update(posting.filter(id.eq(pst_id)).filter(discarding_id.is_null()))
+            .set(discarding_id.eq(discarding_id_val))
+            .execute(conn)
+            .map_err(RepositoryError::from)
+    }
+
+    /// Records the outcome of a pending posting, such as the approval of a
+    /// proposed posting or the promotion of a deferred one: the fields set when
+    /// recording a posting are overwritten with those of `record`, if the posting
+    /// still has the `pending_status`. Returns 0 otherwise.
+    pub fn update_pending(
+        conn: &mut PgConnection,
+        record: &NewPosting,
+        pending_status: PostingStatus,
+    ) -> RepositoryResult<usize> {
+        use crate::schema::posting::dsl::*;
+        diesel::update(posting.filter(id.eq(&record.id)).filter(pst_status.eq(pending_status)))
+            .set((
+                antecedent_id.eq(&record.antecedent_id),
+                antecedent_hash.eq(&record.antecedent_hash),
+                hash.eq(&record.hash),
+                hash_alg.eq(&record.hash_alg),
+                record_time.eq(record.record_time),
+                pst_status.eq(record.pst_status),
+                discarded_id.eq(&record.discarded_id),
+                discarded_time.eq(record.discarded_time),
+            ))
+            .execute(conn)
+            .map_err(RepositoryError::from)
+    }
+
+    /// findByPstStatusAndPstTimeLessThanEqualOrderByPstTimeAsc(...): the postings
+    /// of all ledgers in a status, such as DEFERRED, that are due at `ref_time`.
+    pub fn find_by_pst_status_and_pst_time_lte(
+        conn: &mut PgConnection,
+        pst_status_val: PostingStatus,
+        ref_time_val: NaiveDateTime,
+    ) -> RepositoryResult<Vec<Posting>> {
+        use crate::schema::posting::dsl::*;
+        posting
+            .filter(pst_status.eq(pst_status_val))
+            .filter(pst_time.le(ref_time_val))
+            .order_by((pst_time.asc(), record_time.asc(), id.asc()))
+            .load::<Posting>(conn)
+            .map_err(RepositoryError::from)
+    }
+
+    /// findByLedgerOrderByRecordTimeAsc(...): the journal of a ledger. Proposed
+    /// and cancelled postings are not part of it.
+    pub fn find_by_ledger_order_by_record_time_asc(
+        conn: &mut PgConnection,
+        ledger_id_val: &str,
+    ) -> RepositoryResult<Vec<Posting>> {
+        use crate::schema::posting::dsl::*;
+        posting
+            .filter(ledger_id.eq(ledger_id_val))
+            .filter(pst_status.ne_all(PostingStatus::INEFFECTIVE))
+            .order_by((record_time.asc(), id.asc()))
+            .load::<Posting>(conn)
+            .map_err(RepositoryError::from)
     }
 
     /// findFirstByLedgerOrderByRecordTimeDesc(...)
     pub fn find_first_by_ledger_order_by_record_time_desc(
         conn: &mut PgConnection,
         ledger_id_val: &str,
-    ) -> QueryResult<Option<Posting>> {
+    ) -> RepositoryResult<Option<Posting>> {
         use crate::schema::posting::dsl::*;
         posting
             .filter(ledger_id.eq(ledger_id_val))
+            .filter(pst_status.ne_all(PostingStatus::INEFFECTIVE))
             .order_by(record_time.desc())
             .first::<Posting>(conn)
             .optional()
+            .map_err(RepositoryError::from)
+    }
+
+    /// Deletes the simulated postings of a ledger. Their lines, statements and
+    /// traces must be deleted first.
+    pub fn delete_simulated_by_ledger(conn: &mut PgConnection, ledger_id_val: &str) -> RepositoryResult<usize> {
+        use crate::schema::posting::dsl::*;
+        diesel::delete(posting.filter(ledger_id.eq(ledger_id_val)).filter(pst_status.eq(PostingStatus::SIMULATED)))
+            .execute(conn)
+            .map_err(RepositoryError::from)
     }
 }
 
 /// PostingLineRepository-like functions
 pub mod posting_line_repository {
     use super::*;
+    use crate::models::NewPostingLine;
+    use crate::repository::pagination::LineCursor;
     use crate::schema::posting_line::dsl::*;
 
-    /// findPostingsByAccountAndDates(...) ignoring pagination
+    /// Saves a new PostingLine into the database and returns the inserted record.
+    ///
+    /// Like postings, posting lines are never deleted, simulated ones aside.
+    /// The only change allowed is setting the discarded time once their posting
+    /// has been discarded.
+    pub fn save(conn: &mut PgConnection, new_line: NewPostingLine) -> RepositoryResult<PostingLine> {
+        diesel::insert_into(posting_line)
+            .values(new_line)
+            .get_result(conn)
+            .map_err(RepositoryError::from)
+    }
+
+    /// Saves a batch of new PostingLines in a single statement.
+    pub fn save_all(conn: &mut PgConnection, new_lines: Vec<NewPostingLine>) -> RepositoryResult<Vec<PostingLine>> {
+        diesel::insert_into(posting_line)
+            .values(new_lines)
+            .get_results(conn)
+            .map_err(RepositoryError::from)
+    }
+
+    /// Marks the lines recorded with a discarded posting as discarded at the
+    /// given time. Lines that are already discarded are left untouched.
+    pub fn update_discarded_time_by_opr_id_and_record_time(
+        conn: &mut PgConnection,
+        opr_id_val: &str,
+        record_time_val: NaiveDateTime,
+        discarded_time_val: NaiveDateTime,
+    ) -> RepositoryResult<usize> {
+        diesel::update(
+            posting_line
+                .filter(opr_id.eq(opr_id_val))
+                .filter(record_time.eq(record_time_val))
+                .filter(discarded_time.is_null()),
+        )
+        .set(discarded_time.eq(discarded_time_val))
+        .execute(conn)
+        .map_err(RepositoryError::from)
+    }
+
+    /// Records the line of a pending posting with the record time, status and
+    /// hash of its outcome, if it still has the `pending_status`.
+    pub fn update_pending(
+        conn: &mut PgConnection,
+        record: &NewPostingLine,
+        pending_status: PostingStatus,
+    ) -> RepositoryResult<usize> {
+        diesel::update(posting_line.filter(id.eq(&record.id)).filter(pst_status.eq(pending_status)))
+            .set((record_time.eq(record.record_time), pst_status.eq(record.pst_status), hash.eq(&record.hash)))
+            .execute(conn)
+            .map_err(RepositoryError::from)
+    }
+
+    /// findByOprIdAndRecordTime(...): the lines recorded with a posting.
+    pub fn find_by_opr_id_and_record_time(
+        conn: &mut PgConnection,
+        opr_id_val: &str,
+        record_time_val: NaiveDateTime,
+    ) -> RepositoryResult<Vec<PostingLine>> {
+        posting_line
+            .filter(opr_id.eq(opr_id_val))
+            .filter(record_time.eq(record_time_val))
+            .order_by(id.asc())
+            .load::<PostingLine>(conn)
+            .map_err(RepositoryError::from)
+    }
+
+    /// findPostingsByAccountAndDates(...): all lines at once, latest first. Use
+    /// `find_by_account_and_dates_after`, see `pagination`, for large accounts.
     pub fn find_postings_by_account_and_dates(
         conn: &mut PgConnection,
         account_id_val: &str,
         from_dt: NaiveDateTime,
         to_dt: NaiveDateTime,
-    ) -> QueryResult<Vec<PostingLine>> {
+    ) -> RepositoryResult<Vec<PostingLine>> {
         posting_line
             .filter(account_id.eq(account_id_val))
             .filter(pst_time.gt(from_dt))
             .filter(pst_time.le(to_dt))
             .filter(discarded_time.is_null())
+            .filter(pst_status.ne_all(PostingStatus::INEFFECTIVE))
             .order_by(pst_time.desc())
             .load::<PostingLine>(conn)
+            .map_err(RepositoryError::from)
+    }
+
+    /// The lines of `find_postings_by_account_and_dates` in keyset order: at
+    /// most `limit` lines following `after` in `(pst_time, record_time, id)`
+    /// order, served by the `posting_line_account_keyset_idx` index.
+    pub fn find_by_account_and_dates_after(
+        conn: &mut PgConnection,
+        account_id_val: &str,
+        from_dt: NaiveDateTime,
+        to_dt: NaiveDateTime,
+        after: Option<&LineCursor>,
+        limit: i64,
+    ) -> RepositoryResult<Vec<PostingLine>> {
+        let mut query = posting_line
+            .filter(account_id.eq(account_id_val))
+            .filter(pst_time.gt(from_dt))
+            .filter(pst_time.le(to_dt))
+            .filter(discarded_time.is_null())
+            .filter(pst_status.ne_all(PostingStatus::INEFFECTIVE))
+            .into_boxed();
+        if let Some(cursor) = after {
+            query = query.filter(
+                pst_time.gt(cursor.pst_time).or(pst_time.eq(cursor.pst_time).and(
+                    record_time
+                        .gt(cursor.record_time)
+                        .or(record_time.eq(cursor.record_time).and(id.gt(&cursor.id))),
+                )),
+            );
+        }
+        query
+            .order_by((pst_time.asc(), record_time.asc(), id.asc()))
+            .limit(limit)
+            .load::<PostingLine>(conn)
+            .map_err(RepositoryError::from)
     }
 
     /// findFirstByIdAndAccount(...)
@@ -223,12 +650,96 @@ pub mod posting_line_repository {
         conn: &mut PgConnection,
         transaction_id: &str,
         account_id_val: &str,
-    ) -> QueryResult<Option<PostingLine>> {
+    ) -> RepositoryResult<Option<PostingLine>> {
         posting_line
             .filter(id.eq(transaction_id))
             .filter(account_id.eq(account_id_val))
             .first::<PostingLine>(conn)
             .optional()
+            .map_err(RepositoryError::from)
+    }
+
+    /// Whether any line, whatever its status, books the account.
+    pub fn exists_by_account(conn: &mut PgConnection, account_id_val: &str) -> RepositoryResult<bool> {
+        diesel::select(diesel::dsl::exists(posting_line.filter(account_id.eq(account_id_val))))
+            .get_result::<bool>(conn)
+            .map_err(RepositoryError::from)
+    }
+
+    /// The lines of `find_postings_by_account_and_dates` as recorded at
+    /// `as_of`: lines recorded after it are left out, lines discarded after it
+    /// still count.
+    pub fn find_by_account_and_dates_as_of(
+        conn: &mut PgConnection,
+        account_id_val: &str,
+        from_dt: NaiveDateTime,
+        to_dt: NaiveDateTime,
+        as_of: NaiveDateTime,
+    ) -> RepositoryResult<Vec<PostingLine>> {
+        posting_line
+            .filter(account_id.eq(account_id_val))
+            .filter(pst_time.gt(from_dt))
+            .filter(pst_time.le(to_dt))
+            .filter(record_time.le(as_of))
+            .filter(discarded_time.is_null().or(discarded_time.gt(as_of)))
+            .filter(pst_status.ne_all(PostingStatus::INEFFECTIVE))
+            .order_by(pst_time.desc())
+            .load::<PostingLine>(conn)
+            .map_err(RepositoryError::from)
+    }
+
+    /// The lines of `find_by_account_and_dates_as_of` following `after` in
+    /// `(pst_time, record_time, id)` order, at most `limit` of them.
+    pub fn find_by_account_and_dates_as_of_after(
+        conn: &mut PgConnection,
+        account_id_val: &str,
+        from_dt: NaiveDateTime,
+        to_dt: NaiveDateTime,
+        as_of: NaiveDateTime,
+        after: Option<&LineCursor>,
+        limit: i64,
+    ) -> RepositoryResult<Vec<PostingLine>> {
+        let mut query = posting_line
+            .filter(account_id.eq(account_id_val))
+            .filter(pst_time.gt(from_dt))
+            .filter(pst_time.le(to_dt))
+            .filter(record_time.le(as_of))
+            .filter(discarded_time.is_null().or(discarded_time.gt(as_of)))
+            .filter(pst_status.ne_all(PostingStatus::INEFFECTIVE))
+            .into_boxed();
+        if let Some(cursor) = after {
+            query = query.filter(
+                pst_time.gt(cursor.pst_time).or(pst_time.eq(cursor.pst_time).and(
+                    record_time
+                        .gt(cursor.record_time)
+                        .or(record_time.eq(cursor.record_time).and(id.gt(&cursor.id))),
+                )),
+            );
+        }
+        query
+            .order_by((pst_time.asc(), record_time.asc(), id.asc()))
+            .limit(limit)
+            .load::<PostingLine>(conn)
+            .map_err(RepositoryError::from)
+    }
+
+    /// The lines posted on the account up to `ref_time` as recorded at
+    /// `as_of`, see `find_by_account_and_dates_as_of`. Latest record first.
+    pub fn find_by_account_and_pst_time_lte_as_of(
+        conn: &mut PgConnection,
+        account_id_val: &str,
+        ref_time_val: NaiveDateTime,
+        as_of: NaiveDateTime,
+    ) -> RepositoryResult<Vec<PostingLine>> {
+        posting_line
+            .filter(account_id.eq(account_id_val))
+            .filter(pst_time.le(ref_time_val))
+            .filter(record_time.le(as_of))
+            .filter(discarded_time.is_null().or(discarded_time.gt(as_of)))
+            .filter(pst_status.ne_all(PostingStatus::INEFFECTIVE))
+            .order_by(record_time.desc())
+            .load::<PostingLine>(conn)
+            .map_err(RepositoryError::from)
     }
 
     // ------------------------------------------------------------
@@ -239,13 +750,15 @@ pub mod posting_line_repository {
         conn: &mut PgConnection,
         account_id_val: &str,
         ref_time_val: NaiveDateTime,
-    ) -> QueryResult<Vec<PostingLine>> {
+    ) -> RepositoryResult<Vec<PostingLine>> {
         posting_line
             .filter(account_id.eq(account_id_val))
             .filter(pst_time.le(ref_time_val))
             .filter(discarded_time.is_null())
+            .filter(pst_status.ne_all(PostingStatus::INEFFECTIVE))
             .order_by(record_time.desc())
             .load::<PostingLine>(conn)
+            .map_err(RepositoryError::from)
     }
 
     // ------------------------------------------------------------
@@ -256,13 +769,47 @@ pub mod posting_line_repository {
         conn: &mut PgConnection,
         base_line_val: &str,
         ref_time_val: NaiveDateTime,
-    ) -> QueryResult<Vec<PostingLine>> {
+    ) -> RepositoryResult<Vec<PostingLine>> {
         posting_line
             .filter(base_line.eq(base_line_val))
             .filter(pst_time.le(ref_time_val))
             .filter(discarded_time.is_null())
+            .filter(pst_status.ne_all(PostingStatus::INEFFECTIVE))
             .order_by(record_time.desc())
             .load::<PostingLine>(conn)
+            .map_err(RepositoryError::from)
+    }
+
+    /// The simulated lines of an account up to the reference time, which the
+    /// finders above leave out. Added to those, they give a what-if view.
+    pub fn find_simulated_by_account_and_pst_time_lte(
+        conn: &mut PgConnection,
+        account_id_val: &str,
+        ref_time_val: NaiveDateTime,
+    ) -> RepositoryResult<Vec<PostingLine>> {
+        posting_line
+            .filter(account_id.eq(account_id_val))
+            .filter(pst_time.le(ref_time_val))
+            .filter(discarded_time.is_null())
+            .filter(pst_status.eq(PostingStatus::SIMULATED))
+            .order_by(record_time.desc())
+            .load::<PostingLine>(conn)
+            .map_err(RepositoryError::from)
+    }
+
+    /// Deletes the simulated lines booked on the accounts of a ledger.
+    pub fn delete_simulated_by_ledger(conn: &mut PgConnection, ledger_id_val: &str) -> RepositoryResult<usize> {
+        use crate::schema::ledger_account;
+        let accounts = ledger_account::table
+            .filter(ledger_account::ledger_id.eq(ledger_id_val))
+            .select(ledger_account::id);
+        diesel::delete(
+            posting_line
+                .filter(account_id.eq_any(accounts))
+                .filter(pst_status.eq(PostingStatus::SIMULATED)),
+        )
+        .execute(conn)
+        .map_err(RepositoryError::from)
     }
 }
 
@@ -271,6 +818,91 @@ pub mod posting_line_repository {
 //
 pub mod ledger_stmt_repository {
     use super::*;
+    use crate::models::NewLedgerStmt;
+
+    /// Saves a new LedgerStmt into the database and returns the inserted record.
+    pub fn save(conn: &mut PgConnection, new_stmt: NewLedgerStmt) -> RepositoryResult<LedgerStmt> {
+        use crate::schema::ledger_stmt::dsl::*;
+        diesel::insert_into(ledger_stmt)
+            .values(&new_stmt)
+            .get_result(conn)
+            .map_err(RepositoryError::from)
+    }
+
+    /// Saves a batch of new LedgerStmts in a single statement.
+    pub fn save_all(conn: &mut PgConnection, new_stmts: Vec<NewLedgerStmt>) -> RepositoryResult<Vec<LedgerStmt>> {
+        use crate::schema::ledger_stmt::dsl::*;
+        diesel::insert_into(ledger_stmt)
+            .values(&new_stmts)
+            .get_results(conn)
+            .map_err(RepositoryError::from)
+    }
+
+    /// findFirstByLedgerAndStmtStatusAndPstTimeGreaterThanEqual(...)
+    pub fn find_first_by_ledger_and_stmt_status_and_pst_time_gte(
+        conn: &mut PgConnection,
+        ledger_id_val: &str,
+        stmt_status_val: StmtStatus,
+        ref_time_val: NaiveDateTime,
+    ) -> RepositoryResult<Option<LedgerStmt>> {
+        use crate::schema::ledger_stmt::dsl::*;
+        ledger_stmt
+            .filter(ledger_id.eq(ledger_id_val))
+            .filter(stmt_status.eq(stmt_status_val))
+            .filter(pst_time.ge(ref_time_val))
+            .order_by((pst_time.asc(), stmt_seq_nbr.asc()))
+            .first::<LedgerStmt>(conn)
+            .optional()
+            .map_err(RepositoryError::from)
+    }
+
+    /// findByLedgerAndStmtStatusAndPstTimeGreaterThanEqualOrderByPstTimeAscStmtSeqNbrAsc(...)
+    pub fn find_by_ledger_and_stmt_status_and_pst_time_gte(
+        conn: &mut PgConnection,
+        ledger_id_val: &str,
+        stmt_status_val: StmtStatus,
+        ref_time_val: NaiveDateTime,
+    ) -> RepositoryResult<Vec<LedgerStmt>> {
+        use crate::schema::ledger_stmt::dsl::*;
+        ledger_stmt
+            .filter(ledger_id.eq(ledger_id_val))
+            .filter(stmt_status.eq(stmt_status_val))
+            .filter(pst_time.ge(ref_time_val))
+            .order_by((pst_time.asc(), stmt_seq_nbr.asc()))
+            .load::<LedgerStmt>(conn)
+            .map_err(RepositoryError::from)
+    }
+
+    /// findById(...)
+    pub fn find_by_id(conn: &mut PgConnection, stmt_id: &str) -> RepositoryResult<Option<LedgerStmt>> {
+        use crate::schema::ledger_stmt::dsl::*;
+        ledger_stmt
+            .find(stmt_id)
+            .first::<LedgerStmt>(conn)
+            .optional()
+            .map_err(RepositoryError::from)
+    }
+
+    /// Deletes a simulated LedgerStmt. Closed statements document the ledger
+    /// and are never deleted, so they are left untouched (returns 0).
+    pub fn delete_simulated_by_id(conn: &mut PgConnection, stmt_id: &str) -> RepositoryResult<usize> {
+        use crate::schema::ledger_stmt::dsl::*;
+        diesel::delete(
+            ledger_stmt
+                .filter(id.eq(stmt_id))
+                .filter(stmt_status.eq(StmtStatus::SIMULATED)),
+        )
+        .execute(conn)
+        .map_err(RepositoryError::from)
+    }
+
+    /// Deletes all simulated statements of a ledger.
+    pub fn delete_simulated_by_ledger(conn: &mut PgConnection, ledger_id_val: &str) -> RepositoryResult<usize> {
+        use crate::schema::ledger_stmt::dsl::*;
+        diesel::delete(ledger_stmt.filter(ledger_id.eq(ledger_id_val)).filter(stmt_status.eq(StmtStatus::SIMULATED)))
+            .execute(conn)
+            .map_err(RepositoryError::from)
+    }
 
     /// Example from original code, if needed
     pub fn find_first_by_ledger_and_stmt_status_and_pst_time_lte_order_by_pst_time_desc_stmt_seq_nbr_desc(
@@ -278,7 +910,7 @@ pub mod ledger_stmt_repository {
         ledger_id_val: &str,
         stmt_status_val: StmtStatus,
         ref_time_val: NaiveDateTime,
-    ) -> QueryResult<Option<LedgerStmt>> {
+    ) -> RepositoryResult<Option<LedgerStmt>> {
         use crate::schema::ledger_stmt::dsl::*;
         ledger_stmt
             .filter(ledger_id.eq(ledger_id_val))
@@ -287,6 +919,7 @@ pub mod ledger_stmt_repository {
             .order_by((pst_time.desc(), stmt_seq_nbr.desc()))
             .first::<LedgerStmt>(conn)
             .optional()
+            .map_err(RepositoryError::from)
     }
 }
 
@@ -295,14 +928,85 @@ pub mod ledger_stmt_repository {
 //
 pub mod op_note_repository {
     use super::*;
+    use crate::models::NewOpNote;
+
+    /// Saves a new OpNote into the database and returns the inserted record.
+    pub fn save(conn: &mut PgConnection, new_note: NewOpNote) -> RepositoryResult<OpNote> {
+        use crate::schema::op_note::dsl::*;
+        diesel::insert_into(op_note)
+            .values(&new_note)
+            .get_result(conn)
+            .map_err(RepositoryError::from)
+    }
+
+    /// Saves a batch of new OpNotes in a single statement.
+    pub fn save_all(conn: &mut PgConnection, new_notes: Vec<NewOpNote>) -> RepositoryResult<Vec<OpNote>> {
+        use crate::schema::op_note::dsl::*;
+        diesel::insert_into(op_note)
+            .values(&new_notes)
+            .get_results(conn)
+            .map_err(RepositoryError::from)
+    }
+
+    /// Updates an existing OpNote (content, execution planning and status).
+    pub fn update(conn: &mut PgConnection, note: &OpNote) -> RepositoryResult<OpNote> {
+        diesel::update(note)
+            .set(note)
+            .get_result(conn)
+            .map_err(RepositoryError::from)
+    }
+
+    /// Deletes an OpNote.
+    pub fn delete_by_id(conn: &mut PgConnection, note_id: &str) -> RepositoryResult<usize> {
+        use crate::schema::op_note::dsl::*;
+        diesel::delete(op_note.find(note_id)).execute(conn).map_err(RepositoryError::from)
+    }
 
     /// findById(...) for OpNote
-    pub fn find_by_id(conn: &mut PgConnection, note_id: &str) -> QueryResult<Option<OpNote>> {
+    pub fn find_by_id(conn: &mut PgConnection, note_id: &str) -> RepositoryResult<Option<OpNote>> {
+        use crate::schema::op_note::dsl::*;
+        op_note
+            .find(note_id)
+            .first::<OpNote>(conn)
+            .optional()
+            .map_err(RepositoryError::from)
+    }
+
+    /// findByIdForUpdateSkipLocked(...): locks the note for its execution. Notes
+    /// locked by another transaction are skipped, so that workers polling for
+    /// notes never wait for each other.
+    pub fn find_by_id_for_update_skip_locked(conn: &mut PgConnection, note_id: &str) -> RepositoryResult<Option<OpNote>> {
         use crate::schema::op_note::dsl::*;
         op_note
             .find(note_id)
+            .for_update()
+            .skip_locked()
             .first::<OpNote>(conn)
             .optional()
+            .map_err(RepositoryError::from)
+    }
+
+    /// findByRecId(...): the notes attached to a record, such as a posting.
+    pub fn find_by_rec_id(conn: &mut PgConnection, rec_id_val: &str) -> RepositoryResult<Vec<OpNote>> {
+        use crate::schema::op_note::dsl::*;
+        op_note
+            .filter(rec_id.eq(rec_id_val))
+            .order_by((rec_time.asc(), id.asc()))
+            .load::<OpNote>(conn)
+            .map_err(RepositoryError::from)
+    }
+
+    /// The notes to execute at `ref_time`, by execution time: those planned at
+    /// or before it that have not been executed yet, or whose execution failed
+    /// and may be repeated.
+    pub fn find_pending_by_exec_time_lte(conn: &mut PgConnection, ref_time: NaiveDateTime) -> RepositoryResult<Vec<OpNote>> {
+        use crate::schema::op_note::dsl::*;
+        op_note
+            .filter(exec_time.le(ref_time))
+            .filter(exec_status.is_null().or(exec_status.eq(OpNote::EXEC_FAILED).and(repeated_exec.eq(true))))
+            .order_by((exec_time.asc(), rec_time.asc(), id.asc()))
+            .load::<OpNote>(conn)
+            .map_err(RepositoryError::from)
     }
 }
 
@@ -311,14 +1015,100 @@ pub mod op_note_repository {
 //
 pub mod posting_trace_repository {
     use super::*;
+    use crate::models::NewPostingTrace;
+
+    /// Saves a new PostingTrace into the database and returns the inserted record.
+    ///
+    /// Traces document closed statements and are never updated nor deleted,
+    /// unless they document a simulated statement.
+    pub fn save(conn: &mut PgConnection, new_trace: NewPostingTrace) -> RepositoryResult<PostingTrace> {
+        use crate::schema::posting_trace::dsl::*;
+        diesel::insert_into(posting_trace)
+            .values(new_trace)
+            .get_result(conn)
+            .map_err(RepositoryError::from)
+    }
+
+    /// Saves a batch of new PostingTraces in a single statement.
+    pub fn save_all(conn: &mut PgConnection, new_traces: Vec<NewPostingTrace>) -> RepositoryResult<Vec<PostingTrace>> {
+        use crate::schema::posting_trace::dsl::*;
+        diesel::insert_into(posting_trace)
+            .values(new_traces)
+            .get_results(conn)
+            .map_err(RepositoryError::from)
+    }
+
+    /// findByTgtPstId(...): all traces of a statement posting.
+    pub fn find_by_tgt_pst_id(conn: &mut PgConnection, tgt_pst_id_val: &str) -> RepositoryResult<Vec<PostingTrace>> {
+        use crate::schema::posting_trace::dsl::*;
+        posting_trace
+            .filter(tgt_pst_id.eq(tgt_pst_id_val))
+            .order_by(id.asc())
+            .load::<PostingTrace>(conn)
+            .map_err(RepositoryError::from)
+    }
 
     /// findById(...)
-    pub fn find_by_id(conn: &mut PgConnection, trace_id: &str) -> QueryResult<Option<PostingTrace>> {
+    pub fn find_by_id(conn: &mut PgConnection, trace_id: &str) -> RepositoryResult<Option<PostingTrace>> {
         use crate::schema::posting_trace::dsl::*;
         posting_trace
             .find(trace_id)
             .first::<PostingTrace>(conn)
             .optional()
+            .map_err(RepositoryError::from)
+    }
+
+    /// Deletes the traces of the simulated statement postings of a ledger.
+    pub fn delete_simulated_by_ledger(conn: &mut PgConnection, ledger_id_val: &str) -> RepositoryResult<usize> {
+        use crate::schema::posting;
+        use crate::schema::posting_trace::dsl::*;
+        let simulated = posting::table
+            .filter(posting::ledger_id.eq(ledger_id_val))
+            .filter(posting::pst_status.eq(PostingStatus::SIMULATED))
+            .select(posting::id);
+        diesel::delete(posting_trace.filter(tgt_pst_id.eq_any(simulated)))
+            .execute(conn)
+            .map_err(RepositoryError::from)
+    }
+}
+
+//
+// OperationDetailsRepository-like
+//
+pub mod operation_details_repository {
+    use super::*;
+    use crate::models::{NewOperationDetails, OperationDetails};
+    use crate::schema::operation_details::dsl::*;
+
+    /// Saves new OperationDetails into the database and returns the inserted record.
+    ///
+    /// Operation details are referenced by postings and posting lines and are
+    /// therefore never updated nor deleted.
+    pub fn save(conn: &mut PgConnection, new_details: NewOperationDetails) -> RepositoryResult<OperationDetails> {
+        diesel::insert_into(operation_details)
+            .values(&new_details)
+            .get_result(conn)
+            .map_err(RepositoryError::from)
+    }
+
+    /// Saves a batch of new OperationDetails in a single statement.
+    pub fn save_all(
+        conn: &mut PgConnection,
+        new_details: Vec<NewOperationDetails>,
+    ) -> RepositoryResult<Vec<OperationDetails>> {
+        diesel::insert_into(operation_details)
+            .values(&new_details)
+            .get_results(conn)
+            .map_err(RepositoryError::from)
+    }
+
+    /// findById(...)
+    pub fn find_by_id(conn: &mut PgConnection, details_id: &str) -> RepositoryResult<Option<OperationDetails>> {
+        operation_details
+            .find(details_id)
+            .first::<OperationDetails>(conn)
+            .optional()
+            .map_err(RepositoryError::from)
     }
 }
 
@@ -337,11 +1127,12 @@ pub mod chart_of_account_repository {
     ///
     /// # Returns
     ///
-    /// A QueryResult wrapping the inserted ChartOfAccount.
-    pub fn save(conn: &mut PgConnection, new_coa: NewChartOfAccount) -> QueryResult<ChartOfAccount> {
+    /// A RepositoryResult wrapping the inserted ChartOfAccount.
+    pub fn save(conn: &mut PgConnection, new_coa: NewChartOfAccount) -> RepositoryResult<ChartOfAccount> {
         diesel::insert_into(chart_of_account)
             .values(&new_coa)
             .get_result(conn)
+            .map_err(RepositoryError::from)
     }
 
     /// Finds a ChartOfAccount by its ID.
@@ -353,11 +1144,34 @@ pub mod chart_of_account_repository {
     ///
     /// # Returns
     ///
-    /// A QueryResult wrapping an Option containing the ChartOfAccount if found.
-    pub fn find_by_id(conn: &mut PgConnection, id_value: &str) -> QueryResult<Option<ChartOfAccount>> {
+    /// A RepositoryResult wrapping an Option containing the ChartOfAccount if found.
+    pub fn find_by_id(conn: &mut PgConnection, id_value: &str) -> RepositoryResult<Option<ChartOfAccount>> {
         chart_of_account
             .find(id_value)
             .first::<ChartOfAccount>(conn)
             .optional()
-    }    
+            .map_err(RepositoryError::from)
+    }
+
+    /// Finds all ChartOfAccounts ordered by name.
+    pub fn find_all(conn: &mut PgConnection) -> RepositoryResult<Vec<ChartOfAccount>> {
+        chart_of_account
+            .order_by(name.asc())
+            .load::<ChartOfAccount>(conn)
+            .map_err(RepositoryError::from)
+    }
+
+    /// Updates an existing ChartOfAccount with the values of the given record.
+    pub fn update(conn: &mut PgConnection, coa: &ChartOfAccount) -> RepositoryResult<ChartOfAccount> {
+        diesel::update(coa)
+            .set(coa)
+            .get_result(conn)
+            .map_err(RepositoryError::from)
+    }
+
+    /// Deletes a ChartOfAccount. Fails with a foreign key violation as long as
+    /// ledgers or accounts still reference the chart.
+    pub fn delete_by_id(conn: &mut PgConnection, id_value: &str) -> RepositoryResult<usize> {
+        diesel::delete(chart_of_account.find(id_value)).execute(conn).map_err(RepositoryError::from)
+    }
 }
\ No newline at end of file
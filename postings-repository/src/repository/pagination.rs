@@ -0,0 +1,193 @@
+// src/repository/pagination.rs
+/*
+ * Copyright (c) 2018-2024 adorsys GmbH and Co. KG
+ * All rights are reserved.
+ */
+
+//! Keyset pagination over the posting lines of an account.
+//!
+//! Lines are ordered by `(pst_time, record_time, id)`, which is unique. A page
+//! ends with a cursor holding the key of its last line; the next page starts
+//! after that key, so pages stay consistent while new lines are booked and
+//! every page costs one index range scan, however deep it is.
+
+use std::collections::VecDeque;
+use std::fmt;
+
+use chrono::NaiveDateTime;
+use serde::Serialize;
+
+use super::{PostingLineRepository, RepositoryResult};
+use crate::models::PostingLine;
+
+const TIME_FORMAT: &str = "%Y-%m-%dT%H:%M:%S%.f";
+
+/// The position of a posting line in `(pst_time, record_time, id)` order.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LineCursor {
+    pub pst_time: NaiveDateTime,
+    pub record_time: NaiveDateTime,
+    pub id: String,
+}
+
+/// A cursor that was not issued by [`LineCursor::encode`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidCursor;
+
+impl fmt::Display for InvalidCursor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid cursor")
+    }
+}
+
+impl std::error::Error for InvalidCursor {}
+
+impl LineCursor {
+    /// The cursor pointing at the given line.
+    pub fn of(line: &PostingLine) -> Self {
+        Self {
+            pst_time: line.pst_time,
+            record_time: line.record_time,
+            id: line.id.clone(),
+        }
+    }
+
+    /// Encodes the cursor into an opaque string clients hand back unchanged.
+    pub fn encode(&self) -> String {
+        hex::encode(format!(
+            "{}|{}|{}",
+            self.pst_time.format(TIME_FORMAT),
+            self.record_time.format(TIME_FORMAT),
+            self.id
+        ))
+    }
+
+    /// Decodes a cursor produced by [`LineCursor::encode`].
+    pub fn decode(cursor: &str) -> Result<Self, InvalidCursor> {
+        let bytes = hex::decode(cursor).map_err(|_| InvalidCursor)?;
+        let text = String::from_utf8(bytes).map_err(|_| InvalidCursor)?;
+        let mut parts = text.splitn(3, '|');
+        let mut time = || {
+            parts
+                .next()
+                .and_then(|time| NaiveDateTime::parse_from_str(time, TIME_FORMAT).ok())
+                .ok_or(InvalidCursor)
+        };
+        let pst_time = time()?;
+        let record_time = time()?;
+        let id = parts.next().ok_or(InvalidCursor)?.to_string();
+        Ok(Self { pst_time, record_time, id })
+    }
+}
+
+/// One page of results and the cursor of the next one, `None` on the last page.
+#[derive(Debug, Clone, Serialize)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    pub next_cursor: Option<String>,
+}
+
+/// Reads the page of at most `page_size` lines of an account posted in
+/// `(from, to]` that follows `after`, or the first page without a cursor.
+pub fn find_page_by_account_and_dates<C: PostingLineRepository + ?Sized>(
+    conn: &mut C,
+    account_id: &str,
+    from: NaiveDateTime,
+    to: NaiveDateTime,
+    after: Option<&LineCursor>,
+    page_size: usize,
+) -> RepositoryResult<Page<PostingLine>> {
+    // One line more than asked tells whether there is a next page.
+    let items = conn.find_by_account_and_dates_after(account_id, from, to, after, page_size as i64 + 1)?;
+    Ok(page_of(items, page_size))
+}
+
+/// Reads a page of the lines of `find_page_by_account_and_dates` as recorded
+/// at `as_of`: lines recorded after it are left out, lines discarded after it
+/// are kept.
+pub fn find_page_by_account_and_dates_as_of<C: PostingLineRepository + ?Sized>(
+    conn: &mut C,
+    account_id: &str,
+    from: NaiveDateTime,
+    to: NaiveDateTime,
+    as_of: NaiveDateTime,
+    after: Option<&LineCursor>,
+    page_size: usize,
+) -> RepositoryResult<Page<PostingLine>> {
+    let items = conn.find_by_account_and_dates_as_of_after(account_id, from, to, as_of, after, page_size as i64 + 1)?;
+    Ok(page_of(items, page_size))
+}
+
+/// Cuts the lines read for a page of `page_size` lines, holding one more if
+/// there is a next page.
+fn page_of(mut items: Vec<PostingLine>, page_size: usize) -> Page<PostingLine> {
+    let next_cursor = if items.len() > page_size {
+        items.truncate(page_size);
+        items.last().map(|line| LineCursor::of(line).encode())
+    } else {
+        None
+    };
+    Page { items, next_cursor }
+}
+
+/// Iterates over the lines of an account posted in `(from, to]` in
+/// `(pst_time, record_time, id)` order, loading `batch_size` lines at a time.
+pub fn stream_by_account_and_dates<'a, C: PostingLineRepository + ?Sized>(
+    conn: &'a mut C,
+    account_id: &str,
+    from: NaiveDateTime,
+    to: NaiveDateTime,
+    batch_size: usize,
+) -> PostingLineStream<'a, C> {
+    PostingLineStream {
+        conn,
+        account_id: account_id.to_string(),
+        from,
+        to,
+        batch_size: batch_size.max(1),
+        after: None,
+        buffer: VecDeque::new(),
+        done: false,
+    }
+}
+
+/// The iterator of [`stream_by_account_and_dates`]. It stops after the first
+/// error.
+pub struct PostingLineStream<'a, C: ?Sized> {
+    conn: &'a mut C,
+    account_id: String,
+    from: NaiveDateTime,
+    to: NaiveDateTime,
+    batch_size: usize,
+    after: Option<LineCursor>,
+    buffer: VecDeque<PostingLine>,
+    done: bool,
+}
+
+impl<C: PostingLineRepository + ?Sized> Iterator for PostingLineStream<'_, C> {
+    type Item = RepositoryResult<PostingLine>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.buffer.is_empty() && !self.done {
+            let batch = self.conn.find_by_account_and_dates_after(
+                &self.account_id,
+                self.from,
+                self.to,
+                self.after.as_ref(),
+                self.batch_size as i64,
+            );
+            match batch {
+                Ok(lines) => {
+                    self.done = lines.len() < self.batch_size;
+                    self.after = lines.last().map(LineCursor::of);
+                    self.buffer.extend(lines);
+                }
+                Err(err) => {
+                    self.done = true;
+                    return Some(Err(err));
+                }
+            }
+        }
+        self.buffer.pop_front().map(Ok)
+    }
+}
@@ -0,0 +1,473 @@
+// src/repository/pg.rs
+/* 
+ * Copyright (c) 2018-2024 adorsys GmbH and Co. KG
+ * All rights are reserved.
+ */
+
+//! The Postgres implementation of the repository traits, delegating to the
+//! Diesel functions of the repository modules.
+
+use chrono::NaiveDateTime;
+use diesel::{Connection, PgConnection};
+
+use super::*;
+use crate::repository::pagination::LineCursor;
+use crate::models::enums::StmtStatus;
+use crate::models::{
+    AccountStmt, ChartOfAccount, Ledger, LedgerAccount, LedgerStmt, NewAccountStmt, NewChartOfAccount, NewLedger,
+    NewLedgerAccount, NewLedgerStmt, NewOpNote, NewOperationDetails, NewPosting, NewPostingLine, NewPostingTrace,
+    OpNote, OperationDetails, Posting, PostingLine, PostingTrace,
+};
+
+impl Transactional for PgConnection {
+    fn transaction<T, E, F>(&mut self, f: F) -> Result<T, E>
+    where
+        F: FnOnce(&mut Self) -> Result<T, E>,
+        E: From<diesel::result::Error>,
+    {
+        Connection::transaction(self, f)
+    }
+}
+
+impl ChartOfAccountRepository for PgConnection {
+    fn save(&mut self, new_coa: NewChartOfAccount) -> RepositoryResult<ChartOfAccount> {
+        chart_of_account_repository::save(self, new_coa)
+    }
+
+    fn find_by_id(&mut self, id: &str) -> RepositoryResult<Option<ChartOfAccount>> {
+        chart_of_account_repository::find_by_id(self, id)
+    }
+
+    fn find_all(&mut self) -> RepositoryResult<Vec<ChartOfAccount>> {
+        chart_of_account_repository::find_all(self)
+    }
+
+    fn update(&mut self, coa: &ChartOfAccount) -> RepositoryResult<ChartOfAccount> {
+        chart_of_account_repository::update(self, coa)
+    }
+
+    fn delete_by_id(&mut self, id: &str) -> RepositoryResult<usize> {
+        chart_of_account_repository::delete_by_id(self, id)
+    }
+}
+
+impl LedgerRepository for PgConnection {
+    fn find_by_id(&mut self, ledger_id: &str) -> RepositoryResult<Option<Ledger>> {
+        ledger_repository::find_by_id(self, ledger_id)
+    }
+
+    fn find_by_id_for_update(&mut self, ledger_id: &str) -> RepositoryResult<Option<Ledger>> {
+        ledger_repository::find_by_id_for_update(self, ledger_id)
+    }
+
+    fn find_all(&mut self) -> RepositoryResult<Vec<Ledger>> {
+        ledger_repository::find_all(self)
+    }
+
+    fn find_optional_by_name(&mut self, name: &str) -> RepositoryResult<Option<Ledger>> {
+        ledger_repository::find_optional_by_name(self, name)
+    }
+
+    fn save(&mut self, new_ledger: NewLedger) -> RepositoryResult<Ledger> {
+        ledger_repository::save(self, new_ledger)
+    }
+
+    fn save_all(&mut self, new_ledgers: Vec<NewLedger>) -> RepositoryResult<Vec<Ledger>> {
+        ledger_repository::save_all(self, new_ledgers)
+    }
+
+    fn update(&mut self, ledger: &Ledger) -> RepositoryResult<Ledger> {
+        ledger_repository::update(self, ledger)
+    }
+
+    fn delete_by_id(&mut self, ledger_id: &str) -> RepositoryResult<usize> {
+        ledger_repository::delete_by_id(self, ledger_id)
+    }
+}
+
+impl LedgerAccountRepository for PgConnection {
+    fn find_by_id(&mut self, account_id: &str) -> RepositoryResult<Option<LedgerAccount>> {
+        ledger_account_repository::find_by_id(self, account_id)
+    }
+
+    fn find_optional_by_ledger_and_name(
+        &mut self,
+        ledger_id: &str,
+        name: &str,
+    ) -> RepositoryResult<Option<LedgerAccount>> {
+        ledger_account_repository::find_optional_by_ledger_and_name(self, ledger_id, name)
+    }
+
+    fn find_by_ledger(&mut self, ledger_id: &str) -> RepositoryResult<Vec<LedgerAccount>> {
+        ledger_account_repository::find_by_ledger(self, ledger_id)
+    }
+
+    fn find_by_coa(&mut self, coa_id: &str) -> RepositoryResult<Vec<LedgerAccount>> {
+        ledger_account_repository::find_by_coa(self, coa_id)
+    }
+
+    fn save(&mut self, new_account: NewLedgerAccount) -> RepositoryResult<LedgerAccount> {
+        ledger_account_repository::save(self, new_account)
+    }
+
+    fn update(&mut self, account: &LedgerAccount) -> RepositoryResult<LedgerAccount> {
+        ledger_account_repository::update(self, account)
+    }
+
+    fn find_children(&mut self, account_id: &str) -> RepositoryResult<Vec<LedgerAccount>> {
+        ledger_account_repository::find_children(self, account_id)
+    }
+
+    fn find_ancestors(&mut self, account_id: &str) -> RepositoryResult<Vec<LedgerAccount>> {
+        ledger_account_repository::find_ancestors(self, account_id)
+    }
+
+    fn find_subtree(&mut self, account_id: &str) -> RepositoryResult<Vec<LedgerAccount>> {
+        ledger_account_repository::find_subtree(self, account_id)
+    }
+
+    fn delete_by_id(&mut self, account_id: &str) -> RepositoryResult<usize> {
+        ledger_account_repository::delete_by_id(self, account_id)
+    }
+}
+
+impl PostingRepository for PgConnection {
+    fn save(&mut self, new_posting: NewPosting) -> RepositoryResult<Posting> {
+        posting_repository::save(self, new_posting)
+    }
+
+    fn save_all(&mut self, new_postings: Vec<NewPosting>) -> RepositoryResult<Vec<Posting>> {
+        posting_repository::save_all(self, new_postings)
+    }
+
+    fn find_by_id(&mut self, pst_id: &str) -> RepositoryResult<Option<Posting>> {
+        posting_repository::find_by_id(self, pst_id)
+    }
+
+    fn find_by_opr_id(&mut self, opr_id: &str) -> RepositoryResult<Vec<Posting>> {
+        posting_repository::find_by_opr_id(self, opr_id)
+    }
+
+    fn find_by_opr_id_and_discarding_id_is_null(&mut self, opr_id: &str) -> RepositoryResult<Option<Posting>> {
+        posting_repository::find_by_opr_id_and_discarding_id_is_null(self, opr_id)
+    }
+
+    fn update_discarding_id(&mut self, pst_id: &str, discarding_id: &str) -> RepositoryResult<usize> {
+        posting_repository::update_discarding_id(self, pst_id, discarding_id)
+    }
+
+    fn update_pending(&mut self, record: &NewPosting, pending_status: PostingStatus) -> RepositoryResult<usize> {
+        posting_repository::update_pending(self, record, pending_status)
+    }
+
+    fn find_by_pst_status_and_pst_time_lte(
+        &mut self,
+        pst_status: PostingStatus,
+        ref_time: NaiveDateTime,
+    ) -> RepositoryResult<Vec<Posting>> {
+        posting_repository::find_by_pst_status_and_pst_time_lte(self, pst_status, ref_time)
+    }
+
+    fn find_by_ledger_order_by_record_time_asc(&mut self, ledger_id: &str) -> RepositoryResult<Vec<Posting>> {
+        posting_repository::find_by_ledger_order_by_record_time_asc(self, ledger_id)
+    }
+
+    fn find_first_by_ledger_order_by_record_time_desc(
+        &mut self,
+        ledger_id: &str,
+    ) -> RepositoryResult<Option<Posting>> {
+        posting_repository::find_first_by_ledger_order_by_record_time_desc(self, ledger_id)
+    }
+
+    fn delete_simulated_by_ledger(&mut self, ledger_id: &str) -> RepositoryResult<usize> {
+        posting_repository::delete_simulated_by_ledger(self, ledger_id)
+    }
+}
+
+impl PostingLineRepository for PgConnection {
+    fn save(&mut self, new_line: NewPostingLine) -> RepositoryResult<PostingLine> {
+        posting_line_repository::save(self, new_line)
+    }
+
+    fn save_all(&mut self, new_lines: Vec<NewPostingLine>) -> RepositoryResult<Vec<PostingLine>> {
+        posting_line_repository::save_all(self, new_lines)
+    }
+
+    fn update_discarded_time_by_opr_id_and_record_time(
+        &mut self,
+        opr_id: &str,
+        record_time: NaiveDateTime,
+        discarded_time: NaiveDateTime,
+    ) -> RepositoryResult<usize> {
+        posting_line_repository::update_discarded_time_by_opr_id_and_record_time(self, opr_id, record_time, discarded_time)
+    }
+
+    fn update_pending(&mut self, record: &NewPostingLine, pending_status: PostingStatus) -> RepositoryResult<usize> {
+        posting_line_repository::update_pending(self, record, pending_status)
+    }
+
+    fn find_by_opr_id_and_record_time(
+        &mut self,
+        opr_id: &str,
+        record_time: NaiveDateTime,
+    ) -> RepositoryResult<Vec<PostingLine>> {
+        posting_line_repository::find_by_opr_id_and_record_time(self, opr_id, record_time)
+    }
+
+    fn find_postings_by_account_and_dates(
+        &mut self,
+        account_id: &str,
+        from: NaiveDateTime,
+        to: NaiveDateTime,
+    ) -> RepositoryResult<Vec<PostingLine>> {
+        posting_line_repository::find_postings_by_account_and_dates(self, account_id, from, to)
+    }
+
+    fn find_by_account_and_dates_after(
+        &mut self,
+        account_id: &str,
+        from: NaiveDateTime,
+        to: NaiveDateTime,
+        after: Option<&LineCursor>,
+        limit: i64,
+    ) -> RepositoryResult<Vec<PostingLine>> {
+        posting_line_repository::find_by_account_and_dates_after(self, account_id, from, to, after, limit)
+    }
+
+    fn find_first_by_id_and_account(
+        &mut self,
+        line_id: &str,
+        account_id: &str,
+    ) -> RepositoryResult<Option<PostingLine>> {
+        posting_line_repository::find_first_by_id_and_account(self, line_id, account_id)
+    }
+
+    fn exists_by_account(&mut self, account_id: &str) -> RepositoryResult<bool> {
+        posting_line_repository::exists_by_account(self, account_id)
+    }
+
+    fn find_by_account_and_pst_time_lte_and_discarded_is_null_order_by_record_time_desc(
+        &mut self,
+        account_id: &str,
+        ref_time: NaiveDateTime,
+    ) -> RepositoryResult<Vec<PostingLine>> {
+        posting_line_repository::find_by_account_and_pst_time_lte_and_discarded_is_null_order_by_record_time_desc(self, account_id, ref_time)
+    }
+
+    fn find_by_account_and_dates_as_of_after(
+        &mut self,
+        account_id: &str,
+        from: NaiveDateTime,
+        to: NaiveDateTime,
+        as_of: NaiveDateTime,
+        after: Option<&LineCursor>,
+        limit: i64,
+    ) -> RepositoryResult<Vec<PostingLine>> {
+        posting_line_repository::find_by_account_and_dates_as_of_after(self, account_id, from, to, as_of, after, limit)
+    }
+
+    fn find_by_account_and_dates_as_of(
+        &mut self,
+        account_id: &str,
+        from: NaiveDateTime,
+        to: NaiveDateTime,
+        as_of: NaiveDateTime,
+    ) -> RepositoryResult<Vec<PostingLine>> {
+        posting_line_repository::find_by_account_and_dates_as_of(self, account_id, from, to, as_of)
+    }
+
+    fn find_by_account_and_pst_time_lte_as_of(
+        &mut self,
+        account_id: &str,
+        ref_time: NaiveDateTime,
+        as_of: NaiveDateTime,
+    ) -> RepositoryResult<Vec<PostingLine>> {
+        posting_line_repository::find_by_account_and_pst_time_lte_as_of(self, account_id, ref_time, as_of)
+    }
+
+    fn find_by_base_line_and_pst_time_lte_and_discarded_is_null_order_by_record_time_desc(
+        &mut self,
+        base_line: &str,
+        ref_time: NaiveDateTime,
+    ) -> RepositoryResult<Vec<PostingLine>> {
+        posting_line_repository::find_by_base_line_and_pst_time_lte_and_discarded_is_null_order_by_record_time_desc(self, base_line, ref_time)
+    }
+
+    fn find_simulated_by_account_and_pst_time_lte(
+        &mut self,
+        account_id: &str,
+        ref_time: NaiveDateTime,
+    ) -> RepositoryResult<Vec<PostingLine>> {
+        posting_line_repository::find_simulated_by_account_and_pst_time_lte(self, account_id, ref_time)
+    }
+
+    fn delete_simulated_by_ledger(&mut self, ledger_id: &str) -> RepositoryResult<usize> {
+        posting_line_repository::delete_simulated_by_ledger(self, ledger_id)
+    }
+}
+
+impl AccountStmtRepository for PgConnection {
+    fn save(&mut self, new_stmt: NewAccountStmt) -> RepositoryResult<AccountStmt> {
+        account_stmt_repository::save(self, new_stmt)
+    }
+
+    fn find_first_by_account_and_stmt_status_and_pst_time_less_than_order_by_pst_time_desc_stmt_seq_nbr_desc(
+        &mut self,
+        account_id: &str,
+        stmt_status: StmtStatus,
+        ref_time: NaiveDateTime,
+    ) -> RepositoryResult<Option<AccountStmt>> {
+        account_stmt_repository::find_first_by_account_and_stmt_status_and_pst_time_less_than_order_by_pst_time_desc_stmt_seq_nbr_desc(self, account_id, stmt_status, ref_time)
+    }
+
+    fn find_first_by_account_and_stmt_status_and_pst_time_less_than_as_of(
+        &mut self,
+        account_id: &str,
+        stmt_status: StmtStatus,
+        ref_time: NaiveDateTime,
+        as_of: NaiveDateTime,
+    ) -> RepositoryResult<Option<AccountStmt>> {
+        account_stmt_repository::find_first_by_account_and_stmt_status_and_pst_time_less_than_as_of(self, account_id, stmt_status, ref_time, as_of)
+    }
+
+    fn find_first_by_account_and_stmt_status_and_pst_time_gte(
+        &mut self,
+        account_id: &str,
+        stmt_status: StmtStatus,
+        ref_time: NaiveDateTime,
+    ) -> RepositoryResult<Option<AccountStmt>> {
+        account_stmt_repository::find_first_by_account_and_stmt_status_and_pst_time_gte(self, account_id, stmt_status, ref_time)
+    }
+
+    fn find_by_account_and_stmt_status_and_pst_time_gte(
+        &mut self,
+        account_id: &str,
+        stmt_status: StmtStatus,
+        ref_time: NaiveDateTime,
+    ) -> RepositoryResult<Vec<AccountStmt>> {
+        account_stmt_repository::find_by_account_and_stmt_status_and_pst_time_gte(self, account_id, stmt_status, ref_time)
+    }
+
+    fn delete_simulated_by_ledger(&mut self, ledger_id: &str) -> RepositoryResult<usize> {
+        account_stmt_repository::delete_simulated_by_ledger(self, ledger_id)
+    }
+}
+
+impl LedgerStmtRepository for PgConnection {
+    fn save(&mut self, new_stmt: NewLedgerStmt) -> RepositoryResult<LedgerStmt> {
+        ledger_stmt_repository::save(self, new_stmt)
+    }
+
+    fn save_all(&mut self, new_stmts: Vec<NewLedgerStmt>) -> RepositoryResult<Vec<LedgerStmt>> {
+        ledger_stmt_repository::save_all(self, new_stmts)
+    }
+
+    fn find_by_id(&mut self, stmt_id: &str) -> RepositoryResult<Option<LedgerStmt>> {
+        ledger_stmt_repository::find_by_id(self, stmt_id)
+    }
+
+    fn delete_simulated_by_id(&mut self, stmt_id: &str) -> RepositoryResult<usize> {
+        ledger_stmt_repository::delete_simulated_by_id(self, stmt_id)
+    }
+
+    fn delete_simulated_by_ledger(&mut self, ledger_id: &str) -> RepositoryResult<usize> {
+        ledger_stmt_repository::delete_simulated_by_ledger(self, ledger_id)
+    }
+
+    fn find_first_by_ledger_and_stmt_status_and_pst_time_gte(
+        &mut self,
+        ledger_id: &str,
+        stmt_status: StmtStatus,
+        ref_time: NaiveDateTime,
+    ) -> RepositoryResult<Option<LedgerStmt>> {
+        ledger_stmt_repository::find_first_by_ledger_and_stmt_status_and_pst_time_gte(self, ledger_id, stmt_status, ref_time)
+    }
+
+    fn find_by_ledger_and_stmt_status_and_pst_time_gte(
+        &mut self,
+        ledger_id: &str,
+        stmt_status: StmtStatus,
+        ref_time: NaiveDateTime,
+    ) -> RepositoryResult<Vec<LedgerStmt>> {
+        ledger_stmt_repository::find_by_ledger_and_stmt_status_and_pst_time_gte(self, ledger_id, stmt_status, ref_time)
+    }
+
+    fn find_first_by_ledger_and_stmt_status_and_pst_time_lte_order_by_pst_time_desc_stmt_seq_nbr_desc(
+        &mut self,
+        ledger_id: &str,
+        stmt_status: StmtStatus,
+        ref_time: NaiveDateTime,
+    ) -> RepositoryResult<Option<LedgerStmt>> {
+        ledger_stmt_repository::find_first_by_ledger_and_stmt_status_and_pst_time_lte_order_by_pst_time_desc_stmt_seq_nbr_desc(self, ledger_id, stmt_status, ref_time)
+    }
+}
+
+impl PostingTraceRepository for PgConnection {
+    fn save(&mut self, new_trace: NewPostingTrace) -> RepositoryResult<PostingTrace> {
+        posting_trace_repository::save(self, new_trace)
+    }
+
+    fn save_all(&mut self, new_traces: Vec<NewPostingTrace>) -> RepositoryResult<Vec<PostingTrace>> {
+        posting_trace_repository::save_all(self, new_traces)
+    }
+
+    fn find_by_tgt_pst_id(&mut self, tgt_pst_id: &str) -> RepositoryResult<Vec<PostingTrace>> {
+        posting_trace_repository::find_by_tgt_pst_id(self, tgt_pst_id)
+    }
+
+    fn find_by_id(&mut self, trace_id: &str) -> RepositoryResult<Option<PostingTrace>> {
+        posting_trace_repository::find_by_id(self, trace_id)
+    }
+
+    fn delete_simulated_by_ledger(&mut self, ledger_id: &str) -> RepositoryResult<usize> {
+        posting_trace_repository::delete_simulated_by_ledger(self, ledger_id)
+    }
+}
+
+impl OpNoteRepository for PgConnection {
+    fn save(&mut self, new_note: NewOpNote) -> RepositoryResult<OpNote> {
+        op_note_repository::save(self, new_note)
+    }
+
+    fn save_all(&mut self, new_notes: Vec<NewOpNote>) -> RepositoryResult<Vec<OpNote>> {
+        op_note_repository::save_all(self, new_notes)
+    }
+
+    fn update(&mut self, note: &OpNote) -> RepositoryResult<OpNote> {
+        op_note_repository::update(self, note)
+    }
+
+    fn delete_by_id(&mut self, note_id: &str) -> RepositoryResult<usize> {
+        op_note_repository::delete_by_id(self, note_id)
+    }
+
+    fn find_by_id(&mut self, note_id: &str) -> RepositoryResult<Option<OpNote>> {
+        op_note_repository::find_by_id(self, note_id)
+    }
+
+    fn find_by_id_for_update_skip_locked(&mut self, note_id: &str) -> RepositoryResult<Option<OpNote>> {
+        op_note_repository::find_by_id_for_update_skip_locked(self, note_id)
+    }
+
+    fn find_by_rec_id(&mut self, rec_id: &str) -> RepositoryResult<Vec<OpNote>> {
+        op_note_repository::find_by_rec_id(self, rec_id)
+    }
+
+    fn find_pending_by_exec_time_lte(&mut self, ref_time: NaiveDateTime) -> RepositoryResult<Vec<OpNote>> {
+        op_note_repository::find_pending_by_exec_time_lte(self, ref_time)
+    }
+}
+
+impl OperationDetailsRepository for PgConnection {
+    fn save(&mut self, new_details: NewOperationDetails) -> RepositoryResult<OperationDetails> {
+        operation_details_repository::save(self, new_details)
+    }
+
+    fn save_all(&mut self, new_details: Vec<NewOperationDetails>) -> RepositoryResult<Vec<OperationDetails>> {
+        operation_details_repository::save_all(self, new_details)
+    }
+
+    fn find_by_id(&mut self, details_id: &str) -> RepositoryResult<Option<OperationDetails>> {
+        operation_details_repository::find_by_id(self, details_id)
+    }
+}
@@ -0,0 +1,821 @@
+// src/repository/sqlite.rs
+/*
+ * Copyright (c) 2018-2024 adorsys GmbH and Co. KG
+ * All rights are reserved.
+ */
+
+//! The SQLite implementation of the repository traits, available with the
+//! `sqlite` feature. The schema is created by the migrations of
+//! `migrations-sqlite`.
+//!
+//! The queries are those of the Postgres repository modules, with two
+//! differences:
+//! - SQLite cannot return the rows of a multi-row insert, so batches are
+//!   saved row by row within a transaction.
+//! - SQLite has no row locks. Instead, outermost transactions are started
+//!   with `BEGIN IMMEDIATE`, taking the database write lock up front, so
+//!   `find_by_id_for_update` serializes concurrent writers like `FOR UPDATE`.
+
+use chrono::NaiveDateTime;
+use diesel::connection::{AnsiTransactionManager, SimpleConnection, TransactionManager};
+use diesel::prelude::*;
+use diesel::sqlite::SqliteConnection;
+use diesel::{ConnectionError, ConnectionResult};
+
+use super::*;
+use crate::repository::pagination::LineCursor;
+use crate::models::enums::{PostingStatus, StmtStatus};
+use crate::models::{
+    AccountStmt, ChartOfAccount, Ledger, LedgerAccount, LedgerStmt, NewAccountStmt, NewChartOfAccount, NewLedger,
+    NewLedgerAccount, NewLedgerStmt, NewOpNote, NewOperationDetails, NewPosting, NewPostingLine, NewPostingTrace,
+    OpNote, OperationDetails, Posting, PostingLine, PostingTrace,
+};
+
+/// Opens a SQLite database with foreign key enforcement, which SQLite leaves
+/// off by default.
+pub fn establish(database_url: &str) -> ConnectionResult<SqliteConnection> {
+    let mut conn = SqliteConnection::establish(database_url)?;
+    conn.batch_execute("PRAGMA foreign_keys = ON")
+        .map_err(ConnectionError::CouldntSetupConfiguration)?;
+    Ok(conn)
+}
+
+impl Transactional for SqliteConnection {
+    fn transaction<T, E, F>(&mut self, f: F) -> Result<T, E>
+    where
+        F: FnOnce(&mut Self) -> Result<T, E>,
+        E: From<diesel::result::Error>,
+    {
+        let depth = AnsiTransactionManager::transaction_manager_status_mut(self).transaction_depth()?;
+        match depth {
+            None => self.immediate_transaction(f),
+            Some(_) => Connection::transaction(self, f),
+        }
+    }
+}
+
+/// Saves the rows one after the other, keeping none of them if one fails.
+fn save_all<N, R>(
+    conn: &mut SqliteConnection,
+    rows: Vec<N>,
+    save: impl Fn(&mut SqliteConnection, N) -> RepositoryResult<R>,
+) -> RepositoryResult<Vec<R>> {
+    Transactional::transaction(conn, |conn| rows.into_iter().map(|row| save(conn, row)).collect())
+}
+
+impl ChartOfAccountRepository for SqliteConnection {
+    fn save(&mut self, new_coa: NewChartOfAccount) -> RepositoryResult<ChartOfAccount> {
+        use crate::schema::chart_of_account::dsl::*;
+        diesel::insert_into(chart_of_account).values(&new_coa).get_result(self).map_err(RepositoryError::from)
+    }
+
+    fn find_by_id(&mut self, id_value: &str) -> RepositoryResult<Option<ChartOfAccount>> {
+        use crate::schema::chart_of_account::dsl::*;
+        chart_of_account.find(id_value).first::<ChartOfAccount>(self).optional().map_err(RepositoryError::from)
+    }
+
+    fn find_all(&mut self) -> RepositoryResult<Vec<ChartOfAccount>> {
+        use crate::schema::chart_of_account::dsl::*;
+        chart_of_account.order_by(name.asc()).load::<ChartOfAccount>(self).map_err(RepositoryError::from)
+    }
+
+    fn update(&mut self, coa: &ChartOfAccount) -> RepositoryResult<ChartOfAccount> {
+        diesel::update(coa).set(coa).get_result(self).map_err(RepositoryError::from)
+    }
+
+    fn delete_by_id(&mut self, id_value: &str) -> RepositoryResult<usize> {
+        use crate::schema::chart_of_account::dsl::*;
+        diesel::delete(chart_of_account.find(id_value)).execute(self).map_err(RepositoryError::from)
+    }
+}
+
+impl LedgerRepository for SqliteConnection {
+    fn find_by_id(&mut self, ledger_id_val: &str) -> RepositoryResult<Option<Ledger>> {
+        use crate::schema::ledger::dsl::*;
+        ledger.find(ledger_id_val).first::<Ledger>(self).optional().map_err(RepositoryError::from)
+    }
+
+    fn find_by_id_for_update(&mut self, ledger_id_val: &str) -> RepositoryResult<Option<Ledger>> {
+        LedgerRepository::find_by_id(self, ledger_id_val)
+    }
+
+    fn find_all(&mut self) -> RepositoryResult<Vec<Ledger>> {
+        use crate::schema::ledger::dsl::*;
+        ledger.order_by(name.asc()).load::<Ledger>(self).map_err(RepositoryError::from)
+    }
+
+    fn find_optional_by_name(&mut self, ledger_name_val: &str) -> RepositoryResult<Option<Ledger>> {
+        use crate::schema::ledger::dsl::*;
+        ledger.filter(name.eq(ledger_name_val)).first::<Ledger>(self).optional().map_err(RepositoryError::from)
+    }
+
+    fn save(&mut self, new_ledger: NewLedger) -> RepositoryResult<Ledger> {
+        use crate::schema::ledger::dsl::*;
+        diesel::insert_into(ledger).values(&new_ledger).get_result(self).map_err(RepositoryError::from)
+    }
+
+    fn save_all(&mut self, new_ledgers: Vec<NewLedger>) -> RepositoryResult<Vec<Ledger>> {
+        save_all(self, new_ledgers, LedgerRepository::save)
+    }
+
+    fn update(&mut self, ledger_val: &Ledger) -> RepositoryResult<Ledger> {
+        diesel::update(ledger_val).set(ledger_val).get_result(self).map_err(RepositoryError::from)
+    }
+
+    fn delete_by_id(&mut self, ledger_id_val: &str) -> RepositoryResult<usize> {
+        use crate::schema::ledger::dsl::*;
+        diesel::delete(ledger.find(ledger_id_val)).execute(self).map_err(RepositoryError::from)
+    }
+}
+
+impl LedgerAccountRepository for SqliteConnection {
+    fn find_by_id(&mut self, account_id_val: &str) -> RepositoryResult<Option<LedgerAccount>> {
+        use crate::schema::ledger_account::dsl::*;
+        ledger_account.find(account_id_val).first::<LedgerAccount>(self).optional().map_err(RepositoryError::from)
+    }
+
+    fn find_optional_by_ledger_and_name(
+        &mut self,
+        ledger_id_val: &str,
+        account_name: &str,
+    ) -> RepositoryResult<Option<LedgerAccount>> {
+        use crate::schema::ledger_account::dsl::*;
+        ledger_account
+            .filter(ledger_id.eq(ledger_id_val))
+            .filter(name.eq(account_name))
+            .first::<LedgerAccount>(self)
+            .optional()
+            .map_err(RepositoryError::from)
+    }
+
+    fn find_by_ledger(&mut self, ledger_id_val: &str) -> RepositoryResult<Vec<LedgerAccount>> {
+        use crate::schema::ledger_account::dsl::*;
+        ledger_account.filter(ledger_id.eq(ledger_id_val)).order_by(name.asc()).load::<LedgerAccount>(self)
+            .map_err(RepositoryError::from)
+    }
+
+    fn find_by_coa(&mut self, coa_id_val: &str) -> RepositoryResult<Vec<LedgerAccount>> {
+        use crate::schema::ledger_account::dsl::*;
+        ledger_account
+            .filter(coa_id.eq(coa_id_val))
+            .order_by((name.asc(), ledger_id.asc()))
+            .load::<LedgerAccount>(self)
+            .map_err(RepositoryError::from)
+    }
+
+    fn find_children(&mut self, account_id_val: &str) -> RepositoryResult<Vec<LedgerAccount>> {
+        use crate::schema::ledger_account::dsl::*;
+        ledger_account
+            .filter(parent_id.eq(account_id_val))
+            .filter(id.ne(account_id_val))
+            .order_by(name.asc())
+            .load::<LedgerAccount>(self)
+            .map_err(RepositoryError::from)
+    }
+
+    fn find_ancestors(&mut self, account_id_val: &str) -> RepositoryResult<Vec<LedgerAccount>> {
+        diesel::sql_query(ANCESTORS_QUERY)
+            .bind::<diesel::sql_types::Text, _>(account_id_val)
+            .load::<LedgerAccount>(self)
+            .map_err(RepositoryError::from)
+    }
+
+    fn find_subtree(&mut self, account_id_val: &str) -> RepositoryResult<Vec<LedgerAccount>> {
+        diesel::sql_query(SUBTREE_QUERY)
+            .bind::<diesel::sql_types::Text, _>(account_id_val)
+            .load::<LedgerAccount>(self)
+            .map_err(RepositoryError::from)
+    }
+
+    fn save(&mut self, new_account: NewLedgerAccount) -> RepositoryResult<LedgerAccount> {
+        use crate::schema::ledger_account::dsl::*;
+        diesel::insert_into(ledger_account).values(&new_account).get_result(self).map_err(RepositoryError::from)
+    }
+
+    fn update(&mut self, account: &LedgerAccount) -> RepositoryResult<LedgerAccount> {
+        diesel::update(account).set(account).get_result(self).map_err(RepositoryError::from)
+    }
+
+    fn delete_by_id(&mut self, account_id_val: &str) -> RepositoryResult<usize> {
+        use crate::schema::ledger_account::dsl::*;
+        diesel::delete(ledger_account.find(account_id_val)).execute(self).map_err(RepositoryError::from)
+    }
+}
+
+impl PostingRepository for SqliteConnection {
+    fn save(&mut self, new_posting: NewPosting) -> RepositoryResult<Posting> {
+        use crate::schema::posting::dsl::*;
+        diesel::insert_into(posting).values(&new_posting).get_result(self).map_err(RepositoryError::from)
+    }
+
+    fn save_all(&mut self, new_postings: Vec<NewPosting>) -> RepositoryResult<Vec<Posting>> {
+        save_all(self, new_postings, PostingRepository::save)
+    }
+
+    fn find_by_id(&mut self, pst_id: &str) -> RepositoryResult<Option<Posting>> {
+        use crate::schema::posting::dsl::*;
+        posting.find(pst_id).first::<Posting>(self).optional().map_err(RepositoryError::from)
+    }
+
+    fn find_by_opr_id(&mut self, opr_id_val: &str) -> RepositoryResult<Vec<Posting>> {
+        use crate::schema::posting::dsl::*;
+        posting.filter(opr_id.eq(opr_id_val)).load::<Posting>(self).map_err(RepositoryError::from)
+    }
+
+    fn find_by_opr_id_and_discarding_id_is_null(&mut self, opr_id_val: &str) -> RepositoryResult<Option<Posting>> {
+        use crate::schema::posting::dsl::*;
+        posting
+            .filter(opr_id.eq(opr_id_val))
+            .filter(discarding_id.is_null())
+            .filter(pst_status.ne_all(PostingStatus::INEFFECTIVE))
+            .first::<Posting>(self)
+            .optional()
+            .map_err(RepositoryError::from)
+    }
+
+    fn update_discarding_id(&mut self, pst_id: &str, discarding_id_val: &str) -> RepositoryResult<usize> {
+        use crate::schema::posting::dsl::*;
+        diesel::update(posting.filter(id.eq(pst_id)).filter(discarding_id.is_null()))
+            .set(discarding_id.eq(discarding_id_val))
+            .execute(self)
+            .map_err(RepositoryError::from)
+    }
+
+    fn update_pending(&mut self, record: &NewPosting, pending_status: PostingStatus) -> RepositoryResult<usize> {
+        use crate::schema::posting::dsl::*;
+        diesel::update(posting.filter(id.eq(&record.id)).filter(pst_status.eq(pending_status)))
+            .set((
+                antecedent_id.eq(&record.antecedent_id),
+                antecedent_hash.eq(&record.antecedent_hash),
+                hash.eq(&record.hash),
+                hash_alg.eq(&record.hash_alg),
+                record_time.eq(record.record_time),
+                pst_status.eq(record.pst_status),
+                discarded_id.eq(&record.discarded_id),
+                discarded_time.eq(record.discarded_time),
+            ))
+            .execute(self)
+            .map_err(RepositoryError::from)
+    }
+
+    fn find_by_pst_status_and_pst_time_lte(
+        &mut self,
+        pst_status_val: PostingStatus,
+        ref_time_val: NaiveDateTime,
+    ) -> RepositoryResult<Vec<Posting>> {
+        use crate::schema::posting::dsl::*;
+        posting
+            .filter(pst_status.eq(pst_status_val))
+            .filter(pst_time.le(ref_time_val))
+            .order_by((pst_time.asc(), record_time.asc(), id.asc()))
+            .load::<Posting>(self)
+            .map_err(RepositoryError::from)
+    }
+
+    fn find_by_ledger_order_by_record_time_asc(&mut self, ledger_id_val: &str) -> RepositoryResult<Vec<Posting>> {
+        use crate::schema::posting::dsl::*;
+        posting
+            .filter(ledger_id.eq(ledger_id_val))
+            .filter(pst_status.ne_all(PostingStatus::INEFFECTIVE))
+            .order_by((record_time.asc(), id.asc()))
+            .load::<Posting>(self)
+            .map_err(RepositoryError::from)
+    }
+
+    fn find_first_by_ledger_order_by_record_time_desc(&mut self, ledger_id_val: &str) -> RepositoryResult<Option<Posting>> {
+        use crate::schema::posting::dsl::*;
+        posting
+            .filter(ledger_id.eq(ledger_id_val))
+            .filter(pst_status.ne_all(PostingStatus::INEFFECTIVE))
+            .order_by(record_time.desc())
+            .first::<Posting>(self)
+            .optional()
+            .map_err(RepositoryError::from)
+    }
+
+    fn delete_simulated_by_ledger(&mut self, ledger_id_val: &str) -> RepositoryResult<usize> {
+        use crate::schema::posting::dsl::*;
+        diesel::delete(posting.filter(ledger_id.eq(ledger_id_val)).filter(pst_status.eq(PostingStatus::SIMULATED)))
+            .execute(self)
+            .map_err(RepositoryError::from)
+    }
+}
+
+impl PostingLineRepository for SqliteConnection {
+    fn save(&mut self, new_line: NewPostingLine) -> RepositoryResult<PostingLine> {
+        use crate::schema::posting_line::dsl::*;
+        diesel::insert_into(posting_line).values(new_line).get_result(self).map_err(RepositoryError::from)
+    }
+
+    fn save_all(&mut self, new_lines: Vec<NewPostingLine>) -> RepositoryResult<Vec<PostingLine>> {
+        save_all(self, new_lines, PostingLineRepository::save)
+    }
+
+    fn update_discarded_time_by_opr_id_and_record_time(
+        &mut self,
+        opr_id_val: &str,
+        record_time_val: NaiveDateTime,
+        discarded_time_val: NaiveDateTime,
+    ) -> RepositoryResult<usize> {
+        use crate::schema::posting_line::dsl::*;
+        diesel::update(
+            posting_line
+                .filter(opr_id.eq(opr_id_val))
+                .filter(record_time.eq(record_time_val))
+                .filter(discarded_time.is_null()),
+        )
+        .set(discarded_time.eq(discarded_time_val))
+        .execute(self)
+        .map_err(RepositoryError::from)
+    }
+
+    fn update_pending(&mut self, record: &NewPostingLine, pending_status: PostingStatus) -> RepositoryResult<usize> {
+        use crate::schema::posting_line::dsl::*;
+        diesel::update(posting_line.filter(id.eq(&record.id)).filter(pst_status.eq(pending_status)))
+            .set((record_time.eq(record.record_time), pst_status.eq(record.pst_status), hash.eq(&record.hash)))
+            .execute(self)
+            .map_err(RepositoryError::from)
+    }
+
+    fn find_by_opr_id_and_record_time(
+        &mut self,
+        opr_id_val: &str,
+        record_time_val: NaiveDateTime,
+    ) -> RepositoryResult<Vec<PostingLine>> {
+        use crate::schema::posting_line::dsl::*;
+        posting_line
+            .filter(opr_id.eq(opr_id_val))
+            .filter(record_time.eq(record_time_val))
+            .order_by(id.asc())
+            .load::<PostingLine>(self)
+            .map_err(RepositoryError::from)
+    }
+
+    fn find_postings_by_account_and_dates(
+        &mut self,
+        account_id_val: &str,
+        from_dt: NaiveDateTime,
+        to_dt: NaiveDateTime,
+    ) -> RepositoryResult<Vec<PostingLine>> {
+        use crate::schema::posting_line::dsl::*;
+        posting_line
+            .filter(account_id.eq(account_id_val))
+            .filter(pst_time.gt(from_dt))
+            .filter(pst_time.le(to_dt))
+            .filter(discarded_time.is_null())
+            .filter(pst_status.ne_all(PostingStatus::INEFFECTIVE))
+            .order_by(pst_time.desc())
+            .load::<PostingLine>(self)
+            .map_err(RepositoryError::from)
+    }
+
+    fn find_by_account_and_dates_after(
+        &mut self,
+        account_id_val: &str,
+        from_dt: NaiveDateTime,
+        to_dt: NaiveDateTime,
+        after: Option<&LineCursor>,
+        limit: i64,
+    ) -> RepositoryResult<Vec<PostingLine>> {
+        use crate::schema::posting_line::dsl::*;
+        let mut query = posting_line
+            .filter(account_id.eq(account_id_val))
+            .filter(pst_time.gt(from_dt))
+            .filter(pst_time.le(to_dt))
+            .filter(discarded_time.is_null())
+            .filter(pst_status.ne_all(PostingStatus::INEFFECTIVE))
+            .into_boxed();
+        if let Some(cursor) = after {
+            query = query.filter(
+                pst_time.gt(cursor.pst_time).or(pst_time.eq(cursor.pst_time).and(
+                    record_time
+                        .gt(cursor.record_time)
+                        .or(record_time.eq(cursor.record_time).and(id.gt(&cursor.id))),
+                )),
+            );
+        }
+        query
+            .order_by((pst_time.asc(), record_time.asc(), id.asc()))
+            .limit(limit)
+            .load::<PostingLine>(self)
+            .map_err(RepositoryError::from)
+    }
+
+    fn find_first_by_id_and_account(
+        &mut self,
+        transaction_id: &str,
+        account_id_val: &str,
+    ) -> RepositoryResult<Option<PostingLine>> {
+        use crate::schema::posting_line::dsl::*;
+        posting_line
+            .filter(id.eq(transaction_id))
+            .filter(account_id.eq(account_id_val))
+            .first::<PostingLine>(self)
+            .optional()
+            .map_err(RepositoryError::from)
+    }
+
+    fn exists_by_account(&mut self, account_id_val: &str) -> RepositoryResult<bool> {
+        use crate::schema::posting_line::dsl::*;
+        diesel::select(diesel::dsl::exists(posting_line.filter(account_id.eq(account_id_val))))
+            .get_result::<bool>(self)
+            .map_err(RepositoryError::from)
+    }
+
+    fn find_by_account_and_pst_time_lte_and_discarded_is_null_order_by_record_time_desc(
+        &mut self,
+        account_id_val: &str,
+        ref_time_val: NaiveDateTime,
+    ) -> RepositoryResult<Vec<PostingLine>> {
+        use crate::schema::posting_line::dsl::*;
+        posting_line
+            .filter(account_id.eq(account_id_val))
+            .filter(pst_time.le(ref_time_val))
+            .filter(discarded_time.is_null())
+            .filter(pst_status.ne_all(PostingStatus::INEFFECTIVE))
+            .order_by(record_time.desc())
+            .load::<PostingLine>(self)
+            .map_err(RepositoryError::from)
+    }
+
+    fn find_by_account_and_dates_as_of(
+        &mut self,
+        account_id_val: &str,
+        from_dt: NaiveDateTime,
+        to_dt: NaiveDateTime,
+        as_of: NaiveDateTime,
+    ) -> RepositoryResult<Vec<PostingLine>> {
+        use crate::schema::posting_line::dsl::*;
+        posting_line
+            .filter(account_id.eq(account_id_val))
+            .filter(pst_time.gt(from_dt))
+            .filter(pst_time.le(to_dt))
+            .filter(record_time.le(as_of))
+            .filter(discarded_time.is_null().or(discarded_time.gt(as_of)))
+            .filter(pst_status.ne_all(PostingStatus::INEFFECTIVE))
+            .order_by(pst_time.desc())
+            .load::<PostingLine>(self)
+            .map_err(RepositoryError::from)
+    }
+
+    fn find_by_account_and_dates_as_of_after(
+        &mut self,
+        account_id_val: &str,
+        from_dt: NaiveDateTime,
+        to_dt: NaiveDateTime,
+        as_of: NaiveDateTime,
+        after: Option<&LineCursor>,
+        limit: i64,
+    ) -> RepositoryResult<Vec<PostingLine>> {
+        use crate::schema::posting_line::dsl::*;
+        let mut query = posting_line
+            .filter(account_id.eq(account_id_val))
+            .filter(pst_time.gt(from_dt))
+            .filter(pst_time.le(to_dt))
+            .filter(record_time.le(as_of))
+            .filter(discarded_time.is_null().or(discarded_time.gt(as_of)))
+            .filter(pst_status.ne_all(PostingStatus::INEFFECTIVE))
+            .into_boxed();
+        if let Some(cursor) = after {
+            query = query.filter(
+                pst_time.gt(cursor.pst_time).or(pst_time.eq(cursor.pst_time).and(
+                    record_time
+                        .gt(cursor.record_time)
+                        .or(record_time.eq(cursor.record_time).and(id.gt(&cursor.id))),
+                )),
+            );
+        }
+        query
+            .order_by((pst_time.asc(), record_time.asc(), id.asc()))
+            .limit(limit)
+            .load::<PostingLine>(self)
+            .map_err(RepositoryError::from)
+    }
+
+    fn find_by_account_and_pst_time_lte_as_of(
+        &mut self,
+        account_id_val: &str,
+        ref_time_val: NaiveDateTime,
+        as_of: NaiveDateTime,
+    ) -> RepositoryResult<Vec<PostingLine>> {
+        use crate::schema::posting_line::dsl::*;
+        posting_line
+            .filter(account_id.eq(account_id_val))
+            .filter(pst_time.le(ref_time_val))
+            .filter(record_time.le(as_of))
+            .filter(discarded_time.is_null().or(discarded_time.gt(as_of)))
+            .filter(pst_status.ne_all(PostingStatus::INEFFECTIVE))
+            .order_by(record_time.desc())
+            .load::<PostingLine>(self)
+            .map_err(RepositoryError::from)
+    }
+
+    fn find_by_base_line_and_pst_time_lte_and_discarded_is_null_order_by_record_time_desc(
+        &mut self,
+        base_line_val: &str,
+        ref_time_val: NaiveDateTime,
+    ) -> RepositoryResult<Vec<PostingLine>> {
+        use crate::schema::posting_line::dsl::*;
+        posting_line
+            .filter(base_line.eq(base_line_val))
+            .filter(pst_time.le(ref_time_val))
+            .filter(discarded_time.is_null())
+            .filter(pst_status.ne_all(PostingStatus::INEFFECTIVE))
+            .order_by(record_time.desc())
+            .load::<PostingLine>(self)
+            .map_err(RepositoryError::from)
+    }
+
+    fn find_simulated_by_account_and_pst_time_lte(
+        &mut self,
+        account_id_val: &str,
+        ref_time_val: NaiveDateTime,
+    ) -> RepositoryResult<Vec<PostingLine>> {
+        use crate::schema::posting_line::dsl::*;
+        posting_line
+            .filter(account_id.eq(account_id_val))
+            .filter(pst_time.le(ref_time_val))
+            .filter(discarded_time.is_null())
+            .filter(pst_status.eq(PostingStatus::SIMULATED))
+            .order_by(record_time.desc())
+            .load::<PostingLine>(self)
+            .map_err(RepositoryError::from)
+    }
+
+    fn delete_simulated_by_ledger(&mut self, ledger_id_val: &str) -> RepositoryResult<usize> {
+        use crate::schema::ledger_account;
+        use crate::schema::posting_line::dsl::*;
+        let accounts = ledger_account::table
+            .filter(ledger_account::ledger_id.eq(ledger_id_val))
+            .select(ledger_account::id);
+        diesel::delete(posting_line.filter(account_id.eq_any(accounts)).filter(pst_status.eq(PostingStatus::SIMULATED)))
+            .execute(self)
+            .map_err(RepositoryError::from)
+    }
+}
+
+impl AccountStmtRepository for SqliteConnection {
+    fn save(&mut self, new_stmt: NewAccountStmt) -> RepositoryResult<AccountStmt> {
+        use crate::schema::account_stmt::dsl::*;
+        diesel::insert_into(account_stmt).values(new_stmt).get_result(self).map_err(RepositoryError::from)
+    }
+
+    fn find_first_by_account_and_stmt_status_and_pst_time_less_than_order_by_pst_time_desc_stmt_seq_nbr_desc(
+        &mut self,
+        account_id_val: &str,
+        stmt_status_val: StmtStatus,
+        ref_time_val: NaiveDateTime,
+    ) -> RepositoryResult<Option<AccountStmt>> {
+        use crate::schema::account_stmt::dsl::*;
+        account_stmt
+            .filter(account_id.eq(account_id_val))
+            .filter(stmt_status.eq(stmt_status_val))
+            .filter(pst_time.lt(ref_time_val))
+            .order_by((pst_time.desc(), stmt_seq_nbr.desc()))
+            .first::<AccountStmt>(self)
+            .optional()
+            .map_err(RepositoryError::from)
+    }
+
+    fn find_first_by_account_and_stmt_status_and_pst_time_less_than_as_of(
+        &mut self,
+        account_id_val: &str,
+        stmt_status_val: StmtStatus,
+        ref_time_val: NaiveDateTime,
+        as_of: NaiveDateTime,
+    ) -> RepositoryResult<Option<AccountStmt>> {
+        use crate::schema::account_stmt::dsl::*;
+        account_stmt
+            .filter(account_id.eq(account_id_val))
+            .filter(stmt_status.eq(stmt_status_val))
+            .filter(pst_time.lt(ref_time_val))
+            .filter(created.le(as_of))
+            .order_by((pst_time.desc(), stmt_seq_nbr.desc()))
+            .first::<AccountStmt>(self)
+            .optional()
+            .map_err(RepositoryError::from)
+    }
+
+    fn find_first_by_account_and_stmt_status_and_pst_time_gte(
+        &mut self,
+        account_id_val: &str,
+        stmt_status_val: StmtStatus,
+        ref_time_val: NaiveDateTime,
+    ) -> RepositoryResult<Option<AccountStmt>> {
+        use crate::schema::account_stmt::dsl::*;
+        account_stmt
+            .filter(account_id.eq(account_id_val))
+            .filter(stmt_status.eq(stmt_status_val))
+            .filter(pst_time.ge(ref_time_val))
+            .order_by((pst_time.asc(), stmt_seq_nbr.asc()))
+            .first::<AccountStmt>(self)
+            .optional()
+            .map_err(RepositoryError::from)
+    }
+
+    fn find_by_account_and_stmt_status_and_pst_time_gte(
+        &mut self,
+        account_id_val: &str,
+        stmt_status_val: StmtStatus,
+        ref_time_val: NaiveDateTime,
+    ) -> RepositoryResult<Vec<AccountStmt>> {
+        use crate::schema::account_stmt::dsl::*;
+        account_stmt
+            .filter(account_id.eq(account_id_val))
+            .filter(stmt_status.eq(stmt_status_val))
+            .filter(pst_time.ge(ref_time_val))
+            .order_by((pst_time.asc(), stmt_seq_nbr.asc()))
+            .load::<AccountStmt>(self)
+            .map_err(RepositoryError::from)
+    }
+
+    fn delete_simulated_by_ledger(&mut self, ledger_id_val: &str) -> RepositoryResult<usize> {
+        use crate::schema::account_stmt::dsl::*;
+        use crate::schema::ledger_account;
+        let accounts = ledger_account::table
+            .filter(ledger_account::ledger_id.eq(ledger_id_val))
+            .select(ledger_account::id);
+        diesel::delete(account_stmt.filter(account_id.eq_any(accounts)).filter(stmt_status.eq(StmtStatus::SIMULATED)))
+            .execute(self)
+            .map_err(RepositoryError::from)
+    }
+}
+
+impl LedgerStmtRepository for SqliteConnection {
+    fn save(&mut self, new_stmt: NewLedgerStmt) -> RepositoryResult<LedgerStmt> {
+        use crate::schema::ledger_stmt::dsl::*;
+        diesel::insert_into(ledger_stmt).values(&new_stmt).get_result(self).map_err(RepositoryError::from)
+    }
+
+    fn save_all(&mut self, new_stmts: Vec<NewLedgerStmt>) -> RepositoryResult<Vec<LedgerStmt>> {
+        save_all(self, new_stmts, LedgerStmtRepository::save)
+    }
+
+    fn find_by_id(&mut self, stmt_id: &str) -> RepositoryResult<Option<LedgerStmt>> {
+        use crate::schema::ledger_stmt::dsl::*;
+        ledger_stmt.find(stmt_id).first::<LedgerStmt>(self).optional().map_err(RepositoryError::from)
+    }
+
+    fn delete_simulated_by_id(&mut self, stmt_id: &str) -> RepositoryResult<usize> {
+        use crate::schema::ledger_stmt::dsl::*;
+        diesel::delete(ledger_stmt.filter(id.eq(stmt_id)).filter(stmt_status.eq(StmtStatus::SIMULATED)))
+            .execute(self)
+            .map_err(RepositoryError::from)
+    }
+
+    fn delete_simulated_by_ledger(&mut self, ledger_id_val: &str) -> RepositoryResult<usize> {
+        use crate::schema::ledger_stmt::dsl::*;
+        diesel::delete(ledger_stmt.filter(ledger_id.eq(ledger_id_val)).filter(stmt_status.eq(StmtStatus::SIMULATED)))
+            .execute(self)
+            .map_err(RepositoryError::from)
+    }
+
+    fn find_first_by_ledger_and_stmt_status_and_pst_time_gte(
+        &mut self,
+        ledger_id_val: &str,
+        stmt_status_val: StmtStatus,
+        ref_time_val: NaiveDateTime,
+    ) -> RepositoryResult<Option<LedgerStmt>> {
+        use crate::schema::ledger_stmt::dsl::*;
+        ledger_stmt
+            .filter(ledger_id.eq(ledger_id_val))
+            .filter(stmt_status.eq(stmt_status_val))
+            .filter(pst_time.ge(ref_time_val))
+            .order_by((pst_time.asc(), stmt_seq_nbr.asc()))
+            .first::<LedgerStmt>(self)
+            .optional()
+            .map_err(RepositoryError::from)
+    }
+
+    fn find_by_ledger_and_stmt_status_and_pst_time_gte(
+        &mut self,
+        ledger_id_val: &str,
+        stmt_status_val: StmtStatus,
+        ref_time_val: NaiveDateTime,
+    ) -> RepositoryResult<Vec<LedgerStmt>> {
+        use crate::schema::ledger_stmt::dsl::*;
+        ledger_stmt
+            .filter(ledger_id.eq(ledger_id_val))
+            .filter(stmt_status.eq(stmt_status_val))
+            .filter(pst_time.ge(ref_time_val))
+            .order_by((pst_time.asc(), stmt_seq_nbr.asc()))
+            .load::<LedgerStmt>(self)
+            .map_err(RepositoryError::from)
+    }
+
+    fn find_first_by_ledger_and_stmt_status_and_pst_time_lte_order_by_pst_time_desc_stmt_seq_nbr_desc(
+        &mut self,
+        ledger_id_val: &str,
+        stmt_status_val: StmtStatus,
+        ref_time_val: NaiveDateTime,
+    ) -> RepositoryResult<Option<LedgerStmt>> {
+        use crate::schema::ledger_stmt::dsl::*;
+        ledger_stmt
+            .filter(ledger_id.eq(ledger_id_val))
+            .filter(stmt_status.eq(stmt_status_val))
+            .filter(pst_time.le(ref_time_val))
+            .order_by((pst_time.desc(), stmt_seq_nbr.desc()))
+            .first::<LedgerStmt>(self)
+            .optional()
+            .map_err(RepositoryError::from)
+    }
+}
+
+impl PostingTraceRepository for SqliteConnection {
+    fn save(&mut self, new_trace: NewPostingTrace) -> RepositoryResult<PostingTrace> {
+        use crate::schema::posting_trace::dsl::*;
+        diesel::insert_into(posting_trace).values(new_trace).get_result(self).map_err(RepositoryError::from)
+    }
+
+    fn save_all(&mut self, new_traces: Vec<NewPostingTrace>) -> RepositoryResult<Vec<PostingTrace>> {
+        save_all(self, new_traces, PostingTraceRepository::save)
+    }
+
+    fn find_by_tgt_pst_id(&mut self, tgt_pst_id_val: &str) -> RepositoryResult<Vec<PostingTrace>> {
+        use crate::schema::posting_trace::dsl::*;
+        posting_trace.filter(tgt_pst_id.eq(tgt_pst_id_val)).order_by(id.asc()).load::<PostingTrace>(self)
+            .map_err(RepositoryError::from)
+    }
+
+    fn find_by_id(&mut self, trace_id: &str) -> RepositoryResult<Option<PostingTrace>> {
+        use crate::schema::posting_trace::dsl::*;
+        posting_trace.find(trace_id).first::<PostingTrace>(self).optional().map_err(RepositoryError::from)
+    }
+
+    fn delete_simulated_by_ledger(&mut self, ledger_id_val: &str) -> RepositoryResult<usize> {
+        use crate::schema::posting;
+        use crate::schema::posting_trace::dsl::*;
+        let simulated = posting::table
+            .filter(posting::ledger_id.eq(ledger_id_val))
+            .filter(posting::pst_status.eq(PostingStatus::SIMULATED))
+            .select(posting::id);
+        diesel::delete(posting_trace.filter(tgt_pst_id.eq_any(simulated)))
+            .execute(self)
+            .map_err(RepositoryError::from)
+    }
+}
+
+impl OpNoteRepository for SqliteConnection {
+    fn save(&mut self, new_note: NewOpNote) -> RepositoryResult<OpNote> {
+        use crate::schema::op_note::dsl::*;
+        diesel::insert_into(op_note).values(&new_note).get_result(self).map_err(RepositoryError::from)
+    }
+
+    fn save_all(&mut self, new_notes: Vec<NewOpNote>) -> RepositoryResult<Vec<OpNote>> {
+        save_all(self, new_notes, OpNoteRepository::save)
+    }
+
+    fn update(&mut self, note: &OpNote) -> RepositoryResult<OpNote> {
+        diesel::update(note).set(note).get_result(self).map_err(RepositoryError::from)
+    }
+
+    fn delete_by_id(&mut self, note_id: &str) -> RepositoryResult<usize> {
+        use crate::schema::op_note::dsl::*;
+        diesel::delete(op_note.find(note_id)).execute(self).map_err(RepositoryError::from)
+    }
+
+    fn find_by_id(&mut self, note_id: &str) -> RepositoryResult<Option<OpNote>> {
+        use crate::schema::op_note::dsl::*;
+        op_note.find(note_id).first::<OpNote>(self).optional().map_err(RepositoryError::from)
+    }
+
+    /// SQLite locks the whole database for writing: notes are never skipped.
+    fn find_by_id_for_update_skip_locked(&mut self, note_id: &str) -> RepositoryResult<Option<OpNote>> {
+        OpNoteRepository::find_by_id(self, note_id)
+    }
+
+    fn find_by_rec_id(&mut self, rec_id_val: &str) -> RepositoryResult<Vec<OpNote>> {
+        use crate::schema::op_note::dsl::*;
+        op_note
+            .filter(rec_id.eq(rec_id_val))
+            .order_by((rec_time.asc(), id.asc()))
+            .load::<OpNote>(self)
+            .map_err(RepositoryError::from)
+    }
+
+    fn find_pending_by_exec_time_lte(&mut self, ref_time: NaiveDateTime) -> RepositoryResult<Vec<OpNote>> {
+        use crate::schema::op_note::dsl::*;
+        op_note
+            .filter(exec_time.le(ref_time))
+            .filter(exec_status.is_null().or(exec_status.eq(OpNote::EXEC_FAILED).and(repeated_exec.eq(true))))
+            .order_by((exec_time.asc(), rec_time.asc(), id.asc()))
+            .load::<OpNote>(self)
+            .map_err(RepositoryError::from)
+    }
+}
+
+impl OperationDetailsRepository for SqliteConnection {
+    fn save(&mut self, new_details: NewOperationDetails) -> RepositoryResult<OperationDetails> {
+        use crate::schema::operation_details::dsl::*;
+        diesel::insert_into(operation_details).values(&new_details).get_result(self).map_err(RepositoryError::from)
+    }
+
+    fn save_all(&mut self, new_details: Vec<NewOperationDetails>) -> RepositoryResult<Vec<OperationDetails>> {
+        save_all(self, new_details, OperationDetailsRepository::save)
+    }
+
+    fn find_by_id(&mut self, details_id: &str) -> RepositoryResult<Option<OperationDetails>> {
+        use crate::schema::operation_details::dsl::*;
+        operation_details.find(details_id).first::<OperationDetails>(self).optional().map_err(RepositoryError::from)
+    }
+}
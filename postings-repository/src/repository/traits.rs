@@ -0,0 +1,301 @@
+// src/repository/traits.rs
+/* 
+ * Copyright (c) 2018-2024 adorsys GmbH and Co. KG
+ * All rights are reserved.
+ */
+
+//! Repository traits implemented by every storage backend.
+//!
+//! Service code is written against these traits instead of `PgConnection`,
+//! so that it runs on any backend. Methods carry the names of the Diesel
+//! functions of the `*_repository` modules, which implement them for Postgres
+//! (see `pg`). As several traits share method names, call them with the
+//! trait path, e.g. `PostingRepository::find_by_id(conn, pst_id)`. All
+//! backends report failures as [`RepositoryError`](super::RepositoryError)s.
+
+use chrono::NaiveDateTime;
+use super::error::RepositoryResult;
+use super::pagination::LineCursor;
+use crate::models::enums::{PostingStatus, StmtStatus};
+use crate::models::{
+    AccountStmt, ChartOfAccount, Ledger, LedgerAccount, LedgerStmt, NewAccountStmt, NewChartOfAccount, NewLedger,
+    NewLedgerAccount, NewLedgerStmt, NewOpNote, NewOperationDetails, NewPosting, NewPostingLine, NewPostingTrace,
+    OpNote, OperationDetails, Posting, PostingLine, PostingTrace,
+};
+
+/// Runs a unit of work atomically. Nested transactions behave like savepoints:
+/// an error rolls back the work of the nested transaction only.
+pub trait Transactional {
+    fn transaction<T, E, F>(&mut self, f: F) -> Result<T, E>
+    where
+        F: FnOnce(&mut Self) -> Result<T, E>,
+        E: From<diesel::result::Error>;
+}
+
+/// Charts of account.
+pub trait ChartOfAccountRepository {
+    fn save(&mut self, new_coa: NewChartOfAccount) -> RepositoryResult<ChartOfAccount>;
+    fn find_by_id(&mut self, id: &str) -> RepositoryResult<Option<ChartOfAccount>>;
+    fn find_all(&mut self) -> RepositoryResult<Vec<ChartOfAccount>>;
+    fn update(&mut self, coa: &ChartOfAccount) -> RepositoryResult<ChartOfAccount>;
+    fn delete_by_id(&mut self, id: &str) -> RepositoryResult<usize>;
+}
+
+/// Ledgers.
+pub trait LedgerRepository {
+    fn find_by_id(&mut self, ledger_id: &str) -> RepositoryResult<Option<Ledger>>;
+    fn find_by_id_for_update(&mut self, ledger_id: &str) -> RepositoryResult<Option<Ledger>>;
+    fn find_all(&mut self) -> RepositoryResult<Vec<Ledger>>;
+    fn find_optional_by_name(&mut self, name: &str) -> RepositoryResult<Option<Ledger>>;
+    fn save(&mut self, new_ledger: NewLedger) -> RepositoryResult<Ledger>;
+    fn save_all(&mut self, new_ledgers: Vec<NewLedger>) -> RepositoryResult<Vec<Ledger>>;
+    fn update(&mut self, ledger: &Ledger) -> RepositoryResult<Ledger>;
+    fn delete_by_id(&mut self, ledger_id: &str) -> RepositoryResult<usize>;
+}
+
+/// Ledger accounts.
+pub trait LedgerAccountRepository {
+    fn find_by_id(&mut self, account_id: &str) -> RepositoryResult<Option<LedgerAccount>>;
+    fn find_optional_by_ledger_and_name(
+        &mut self,
+        ledger_id: &str,
+        name: &str,
+    ) -> RepositoryResult<Option<LedgerAccount>>;
+    fn find_by_ledger(&mut self, ledger_id: &str) -> RepositoryResult<Vec<LedgerAccount>>;
+    fn find_by_coa(&mut self, coa_id: &str) -> RepositoryResult<Vec<LedgerAccount>>;
+    fn find_children(&mut self, account_id: &str) -> RepositoryResult<Vec<LedgerAccount>>;
+    fn find_ancestors(&mut self, account_id: &str) -> RepositoryResult<Vec<LedgerAccount>>;
+    fn find_subtree(&mut self, account_id: &str) -> RepositoryResult<Vec<LedgerAccount>>;
+    fn save(&mut self, new_account: NewLedgerAccount) -> RepositoryResult<LedgerAccount>;
+    fn update(&mut self, account: &LedgerAccount) -> RepositoryResult<LedgerAccount>;
+    fn delete_by_id(&mut self, account_id: &str) -> RepositoryResult<usize>;
+}
+
+/// Postings.
+pub trait PostingRepository {
+    fn save(&mut self, new_posting: NewPosting) -> RepositoryResult<Posting>;
+    fn save_all(&mut self, new_postings: Vec<NewPosting>) -> RepositoryResult<Vec<Posting>>;
+    fn find_by_id(&mut self, pst_id: &str) -> RepositoryResult<Option<Posting>>;
+    fn find_by_opr_id(&mut self, opr_id: &str) -> RepositoryResult<Vec<Posting>>;
+    fn find_by_opr_id_and_discarding_id_is_null(&mut self, opr_id: &str) -> RepositoryResult<Option<Posting>>;
+    fn update_discarding_id(&mut self, pst_id: &str, discarding_id: &str) -> RepositoryResult<usize>;
+    fn update_pending(&mut self, record: &NewPosting, pending_status: PostingStatus) -> RepositoryResult<usize>;
+    fn find_by_pst_status_and_pst_time_lte(
+        &mut self,
+        pst_status: PostingStatus,
+        ref_time: NaiveDateTime,
+    ) -> RepositoryResult<Vec<Posting>>;
+    fn find_by_ledger_order_by_record_time_asc(&mut self, ledger_id: &str) -> RepositoryResult<Vec<Posting>>;
+    fn find_first_by_ledger_order_by_record_time_desc(
+        &mut self,
+        ledger_id: &str,
+    ) -> RepositoryResult<Option<Posting>>;
+    fn delete_simulated_by_ledger(&mut self, ledger_id: &str) -> RepositoryResult<usize>;
+}
+
+/// Posting lines.
+pub trait PostingLineRepository {
+    fn save(&mut self, new_line: NewPostingLine) -> RepositoryResult<PostingLine>;
+    fn save_all(&mut self, new_lines: Vec<NewPostingLine>) -> RepositoryResult<Vec<PostingLine>>;
+    fn update_discarded_time_by_opr_id_and_record_time(
+        &mut self,
+        opr_id: &str,
+        record_time: NaiveDateTime,
+        discarded_time: NaiveDateTime,
+    ) -> RepositoryResult<usize>;
+    fn update_pending(&mut self, record: &NewPostingLine, pending_status: PostingStatus) -> RepositoryResult<usize>;
+    fn find_by_opr_id_and_record_time(
+        &mut self,
+        opr_id: &str,
+        record_time: NaiveDateTime,
+    ) -> RepositoryResult<Vec<PostingLine>>;
+    fn find_postings_by_account_and_dates(
+        &mut self,
+        account_id: &str,
+        from: NaiveDateTime,
+        to: NaiveDateTime,
+    ) -> RepositoryResult<Vec<PostingLine>>;
+    /// The lines of `find_postings_by_account_and_dates` in
+    /// `(pst_time, record_time, id)` order, starting after `after`.
+    fn find_by_account_and_dates_after(
+        &mut self,
+        account_id: &str,
+        from: NaiveDateTime,
+        to: NaiveDateTime,
+        after: Option<&LineCursor>,
+        limit: i64,
+    ) -> RepositoryResult<Vec<PostingLine>>;
+    fn find_first_by_id_and_account(
+        &mut self,
+        line_id: &str,
+        account_id: &str,
+    ) -> RepositoryResult<Option<PostingLine>>;
+    /// Whether any line, whatever its status, books the account.
+    fn exists_by_account(&mut self, account_id: &str) -> RepositoryResult<bool>;
+    fn find_by_account_and_pst_time_lte_and_discarded_is_null_order_by_record_time_desc(
+        &mut self,
+        account_id: &str,
+        ref_time: NaiveDateTime,
+    ) -> RepositoryResult<Vec<PostingLine>>;
+    /// The lines of `find_postings_by_account_and_dates` as recorded at
+    /// `as_of`: lines recorded after it are left out, lines discarded after it
+    /// still count.
+    fn find_by_account_and_dates_as_of(
+        &mut self,
+        account_id: &str,
+        from: NaiveDateTime,
+        to: NaiveDateTime,
+        as_of: NaiveDateTime,
+    ) -> RepositoryResult<Vec<PostingLine>>;
+    /// The lines of `find_by_account_and_dates_as_of` following `after` in
+    /// `(pst_time, record_time, id)` order, at most `limit` of them.
+    fn find_by_account_and_dates_as_of_after(
+        &mut self,
+        account_id: &str,
+        from: NaiveDateTime,
+        to: NaiveDateTime,
+        as_of: NaiveDateTime,
+        after: Option<&LineCursor>,
+        limit: i64,
+    ) -> RepositoryResult<Vec<PostingLine>>;
+    /// The lines posted on the account up to `ref_time` as recorded at `as_of`,
+    /// latest record first.
+    fn find_by_account_and_pst_time_lte_as_of(
+        &mut self,
+        account_id: &str,
+        ref_time: NaiveDateTime,
+        as_of: NaiveDateTime,
+    ) -> RepositoryResult<Vec<PostingLine>>;
+    fn find_by_base_line_and_pst_time_lte_and_discarded_is_null_order_by_record_time_desc(
+        &mut self,
+        base_line: &str,
+        ref_time: NaiveDateTime,
+    ) -> RepositoryResult<Vec<PostingLine>>;
+    fn find_simulated_by_account_and_pst_time_lte(
+        &mut self,
+        account_id: &str,
+        ref_time: NaiveDateTime,
+    ) -> RepositoryResult<Vec<PostingLine>>;
+    fn delete_simulated_by_ledger(&mut self, ledger_id: &str) -> RepositoryResult<usize>;
+}
+
+/// Account statements.
+pub trait AccountStmtRepository {
+    fn save(&mut self, new_stmt: NewAccountStmt) -> RepositoryResult<AccountStmt>;
+    fn find_first_by_account_and_stmt_status_and_pst_time_less_than_order_by_pst_time_desc_stmt_seq_nbr_desc(
+        &mut self,
+        account_id: &str,
+        stmt_status: StmtStatus,
+        ref_time: NaiveDateTime,
+    ) -> RepositoryResult<Option<AccountStmt>>;
+    /// The statement of the previous finder as recorded at `as_of`: statements
+    /// created after it are left out.
+    fn find_first_by_account_and_stmt_status_and_pst_time_less_than_as_of(
+        &mut self,
+        account_id: &str,
+        stmt_status: StmtStatus,
+        ref_time: NaiveDateTime,
+        as_of: NaiveDateTime,
+    ) -> RepositoryResult<Option<AccountStmt>>;
+    fn find_first_by_account_and_stmt_status_and_pst_time_gte(
+        &mut self,
+        account_id: &str,
+        stmt_status: StmtStatus,
+        ref_time: NaiveDateTime,
+    ) -> RepositoryResult<Option<AccountStmt>>;
+    fn find_by_account_and_stmt_status_and_pst_time_gte(
+        &mut self,
+        account_id: &str,
+        stmt_status: StmtStatus,
+        ref_time: NaiveDateTime,
+    ) -> RepositoryResult<Vec<AccountStmt>>;
+    fn delete_simulated_by_ledger(&mut self, ledger_id: &str) -> RepositoryResult<usize>;
+}
+
+/// Ledger statements.
+pub trait LedgerStmtRepository {
+    fn save(&mut self, new_stmt: NewLedgerStmt) -> RepositoryResult<LedgerStmt>;
+    fn save_all(&mut self, new_stmts: Vec<NewLedgerStmt>) -> RepositoryResult<Vec<LedgerStmt>>;
+    fn find_by_id(&mut self, stmt_id: &str) -> RepositoryResult<Option<LedgerStmt>>;
+    fn delete_simulated_by_id(&mut self, stmt_id: &str) -> RepositoryResult<usize>;
+    fn delete_simulated_by_ledger(&mut self, ledger_id: &str) -> RepositoryResult<usize>;
+    fn find_first_by_ledger_and_stmt_status_and_pst_time_gte(
+        &mut self,
+        ledger_id: &str,
+        stmt_status: StmtStatus,
+        ref_time: NaiveDateTime,
+    ) -> RepositoryResult<Option<LedgerStmt>>;
+    fn find_by_ledger_and_stmt_status_and_pst_time_gte(
+        &mut self,
+        ledger_id: &str,
+        stmt_status: StmtStatus,
+        ref_time: NaiveDateTime,
+    ) -> RepositoryResult<Vec<LedgerStmt>>;
+    fn find_first_by_ledger_and_stmt_status_and_pst_time_lte_order_by_pst_time_desc_stmt_seq_nbr_desc(
+        &mut self,
+        ledger_id: &str,
+        stmt_status: StmtStatus,
+        ref_time: NaiveDateTime,
+    ) -> RepositoryResult<Option<LedgerStmt>>;
+}
+
+/// Posting traces.
+pub trait PostingTraceRepository {
+    fn save(&mut self, new_trace: NewPostingTrace) -> RepositoryResult<PostingTrace>;
+    fn save_all(&mut self, new_traces: Vec<NewPostingTrace>) -> RepositoryResult<Vec<PostingTrace>>;
+    fn find_by_tgt_pst_id(&mut self, tgt_pst_id: &str) -> RepositoryResult<Vec<PostingTrace>>;
+    fn find_by_id(&mut self, trace_id: &str) -> RepositoryResult<Option<PostingTrace>>;
+    fn delete_simulated_by_ledger(&mut self, ledger_id: &str) -> RepositoryResult<usize>;
+}
+
+/// Operation notes.
+pub trait OpNoteRepository {
+    fn save(&mut self, new_note: NewOpNote) -> RepositoryResult<OpNote>;
+    fn save_all(&mut self, new_notes: Vec<NewOpNote>) -> RepositoryResult<Vec<OpNote>>;
+    fn update(&mut self, note: &OpNote) -> RepositoryResult<OpNote>;
+    fn delete_by_id(&mut self, note_id: &str) -> RepositoryResult<usize>;
+    fn find_by_id(&mut self, note_id: &str) -> RepositoryResult<Option<OpNote>>;
+    /// Locks the note like `SELECT ... FOR UPDATE SKIP LOCKED`: returns `None`
+    /// if the note is missing or locked by another transaction.
+    fn find_by_id_for_update_skip_locked(&mut self, note_id: &str) -> RepositoryResult<Option<OpNote>>;
+    fn find_by_rec_id(&mut self, rec_id: &str) -> RepositoryResult<Vec<OpNote>>;
+    fn find_pending_by_exec_time_lte(&mut self, ref_time: NaiveDateTime) -> RepositoryResult<Vec<OpNote>>;
+}
+
+/// Operation details.
+pub trait OperationDetailsRepository {
+    fn save(&mut self, new_details: NewOperationDetails) -> RepositoryResult<OperationDetails>;
+    fn save_all(&mut self, new_details: Vec<NewOperationDetails>) -> RepositoryResult<Vec<OperationDetails>>;
+    fn find_by_id(&mut self, details_id: &str) -> RepositoryResult<Option<OperationDetails>>;
+}
+
+/// A store offering all repositories and transactions.
+pub trait Repositories:
+    Transactional
+    + ChartOfAccountRepository
+    + LedgerRepository
+    + LedgerAccountRepository
+    + PostingRepository
+    + PostingLineRepository
+    + AccountStmtRepository
+    + LedgerStmtRepository
+    + PostingTraceRepository
+    + OpNoteRepository
+    + OperationDetailsRepository
+{
+}
+
+impl<T> Repositories for T where
+    T: Transactional
+        + ChartOfAccountRepository
+        + LedgerRepository
+        + LedgerAccountRepository
+        + PostingRepository
+        + PostingLineRepository
+        + AccountStmtRepository
+        + LedgerStmtRepository
+        + PostingTraceRepository
+        + OpNoteRepository
+        + OperationDetailsRepository
+{
+}
@@ -3,28 +3,42 @@
 pub mod sql_types {
     #[derive(diesel::sql_types::SqlType)]
     #[diesel(postgres_type(name = "account_category"))]
+    #[diesel(sqlite_type(name = "Text"))]
     pub struct AccountCategory;
 
     #[derive(diesel::sql_types::SqlType)]
     #[diesel(postgres_type(name = "balance_side"))]
+    #[diesel(sqlite_type(name = "Text"))]
     pub struct BalanceSide;
 
-    #[derive(diesel::sql_types::SqlType)]
+    #[derive(diesel::sql_types::SqlType, diesel::QueryId)]
     #[diesel(postgres_type(name = "posting_status"))]
+    #[diesel(sqlite_type(name = "Text"))]
     pub struct PostingStatus;
 
     #[derive(diesel::sql_types::SqlType)]
     #[diesel(postgres_type(name = "posting_type"))]
+    #[diesel(sqlite_type(name = "Text"))]
     pub struct PostingType;
 
     #[derive(diesel::sql_types::SqlType, diesel::QueryId)]
     #[diesel(postgres_type(name = "stmt_status"))]
+    #[diesel(sqlite_type(name = "Text"))]
     pub struct StmtStatus;
+
+    /// Monetary amounts: `NUMERIC` on Postgres, `TEXT` on SQLite where a
+    /// `REAL` would lose precision. Not generated by Diesel CLI: keep it when
+    /// regenerating this file.
+    #[derive(diesel::sql_types::SqlType, diesel::QueryId)]
+    #[diesel(postgres_type(oid = 1700, array_oid = 1231))]
+    #[diesel(sqlite_type(name = "Text"))]
+    pub struct Amount;
 }
 
 diesel::table! {
     use diesel::sql_types::*;
     use super::sql_types::StmtStatus;
+    use super::sql_types::Amount;
 
     account_stmt (id) {
         id -> Varchar,
@@ -39,8 +53,8 @@ diesel::table! {
         long_desc -> Nullable<Varchar>,
         account_id -> Varchar,
         youngest_pst_id -> Nullable<Varchar>,
-        total_debit -> Numeric,
-        total_credit -> Numeric,
+        total_debit -> Amount,
+        total_credit -> Amount,
     }
 }
 
@@ -152,6 +166,7 @@ diesel::table! {
         val_time -> Nullable<Timestamp>,
         discarded_id -> Nullable<Varchar>,
         discarded_time -> Nullable<Timestamp>,
+        discarding_id -> Nullable<Varchar>,
         opr_details_id -> Nullable<Varchar>,
     }
 }
@@ -160,12 +175,13 @@ diesel::table! {
     use diesel::sql_types::*;
     use super::sql_types::PostingType;
     use super::sql_types::PostingStatus;
+    use super::sql_types::Amount;
 
     posting_line (id) {
         id -> Varchar,
         account_id -> Varchar,
-        debit_amount -> Numeric,
-        credit_amount -> Numeric,
+        debit_amount -> Amount,
+        credit_amount -> Amount,
         details_id -> Nullable<Varchar>,
         src_account -> Nullable<Varchar>,
         base_line -> Nullable<Varchar>,
@@ -182,6 +198,9 @@ diesel::table! {
 }
 
 diesel::table! {
+    use diesel::sql_types::*;
+    use super::sql_types::Amount;
+
     posting_trace (id) {
         id -> Varchar,
         tgt_pst_id -> Varchar,
@@ -189,8 +208,8 @@ diesel::table! {
         src_pst_time -> Nullable<Timestamp>,
         src_opr_id -> Varchar,
         account_id -> Varchar,
-        debit_amount -> Numeric,
-        credit_amount -> Numeric,
+        debit_amount -> Amount,
+        credit_amount -> Amount,
         src_pst_hash -> Nullable<Varchar>,
     }
 }
@@ -6,27 +6,16 @@
 mod common;
 
 use chrono::{NaiveDateTime, Utc};
-use diesel::prelude::*;
 use rust_decimal::Decimal;
-use postings_repository::models::{AccountStmt, NewAccountStmt};
+use postings_repository::models::NewAccountStmt;
 use postings_repository::models::enums::StmtStatus;
-use postings_repository::repository::{account_stmt_repository, ledger_account_repository};
-use common::{establish_connection, seed_database, TestDatabaseGuard};
+use postings_repository::repository::{AccountStmtRepository, LedgerAccountRepository};
+use common::backend_test;
 
-#[test]
-fn test_create_financial_statement_ok() {
-    // Establish a connection using our common test setup.
-    let mut conn = establish_connection();
-
-    // Seed fixture data
-    seed_database(&mut conn, "tests/fixtures/account_stmt_dataset.sql");    
-
-    // Create a guard that will clean up after the test.
-    let _guard = TestDatabaseGuard::new();
-    
+backend_test!(test_create_financial_statement_ok, "tests/fixtures/account_stmt_dataset.sql", |conn| {
     // Retrieve an existing LedgerAccount by ID from the test database.
     let ledger_account_id = "xVgaTPMcRty9ik3BTQDh1Q_BS_1_0_0";
-    let account = ledger_account_repository::find_by_id(&mut conn, ledger_account_id)
+    let account = LedgerAccountRepository::find_by_id(conn, ledger_account_id)
         .expect("Error fetching LedgerAccount")
         .expect("Missing LedgerAccount with id xVgaTPMcRty9ik3BTQDh1Q_BS_1_0_0");
 
@@ -54,10 +43,105 @@ fn test_create_financial_statement_ok() {
     };
 
     // Insert the new AccountStmt via the repository function.
-    let saved_stmt = account_stmt_repository::save(&mut conn, new_stmt)
+    let saved_stmt = AccountStmtRepository::save(conn, new_stmt)
         .expect("Failed to save AccountStmt");
 
     // Assert that the saved statement has a non-empty ID.
     assert!(!saved_stmt.id.is_empty(), "Saved AccountStmt should have a non-empty id");
-}
+});
+
+
+backend_test!(test_find_by_account_and_stmt_status_and_pst_time_gte, "tests/fixtures/account_stmt_dataset.sql", |conn| {
+    let account_id = "xVgaTPMcRty9ik3BTQDh1Q_BS_1_0_0";
+    let time = |value: &str| NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S").unwrap();
+    for (id, pst_time, stmt_status, stmt_seq_nbr) in [
+        ("stmt_001", "2017-12-31 23:59:00", StmtStatus::CLOSED, 0),
+        ("stmt_002", "2018-01-31 23:59:00", StmtStatus::CLOSED, 1),
+        ("stmt_003", "2018-01-31 23:59:00", StmtStatus::SIMULATED, 2),
+        ("stmt_004", "2018-01-31 23:59:00", StmtStatus::CLOSED, 3),
+    ] {
+        AccountStmtRepository::save(
+            conn,
+            NewAccountStmt {
+                id: id.to_string(),
+                account_id: account_id.to_string(),
+                stmt_status,
+                stmt_seq_nbr,
+                pst_time: time(pst_time),
+                total_credit: Decimal::ZERO,
+                total_debit: Decimal::ZERO,
+                created: None,
+                user_details: None,
+                short_desc: None,
+                long_desc: None,
+                latest_pst_id: None,
+                posting_id: None,
+                youngest_pst_id: None,
+            },
+        )
+        .unwrap();
+    }
 
+    let stmts = AccountStmtRepository::find_by_account_and_stmt_status_and_pst_time_gte(
+        conn,
+        account_id,
+        StmtStatus::CLOSED,
+        time("2018-01-01 00:00:00"),
+    )
+    .unwrap();
+    let ids: Vec<&str> = stmts.iter().map(|stmt| stmt.id.as_str()).collect();
+    assert_eq!(ids, ["stmt_002", "stmt_004"]);
+
+    let stmts = AccountStmtRepository::find_by_account_and_stmt_status_and_pst_time_gte(
+        conn,
+        account_id,
+        StmtStatus::CLOSED,
+        time("2018-02-01 00:00:00"),
+    )
+    .unwrap();
+    assert!(stmts.is_empty());
+});
+
+backend_test!(test_find_first_closed_stmt_as_of, "tests/fixtures/account_stmt_dataset.sql", |conn| {
+    let account_id = "xVgaTPMcRty9ik3BTQDh1Q_BS_1_0_0";
+    let time = |value: &str| NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S").unwrap();
+    // stmt_002 closes January again after a backdated posting.
+    for (id, stmt_seq_nbr, created) in [("stmt_001", 0, "2018-02-01 10:00:00"), ("stmt_002", 1, "2018-02-10 10:00:00")] {
+        AccountStmtRepository::save(
+            conn,
+            NewAccountStmt {
+                id: id.to_string(),
+                account_id: account_id.to_string(),
+                stmt_status: StmtStatus::CLOSED,
+                stmt_seq_nbr,
+                pst_time: time("2018-01-31 23:59:00"),
+                total_credit: Decimal::ZERO,
+                total_debit: Decimal::ZERO,
+                created: Some(time(created)),
+                user_details: None,
+                short_desc: None,
+                long_desc: None,
+                latest_pst_id: None,
+                posting_id: None,
+                youngest_pst_id: None,
+            },
+        )
+        .unwrap();
+    }
+
+    let as_of = |conn: &mut _, value: &str| {
+        AccountStmtRepository::find_first_by_account_and_stmt_status_and_pst_time_less_than_as_of(
+            conn,
+            account_id,
+            StmtStatus::CLOSED,
+            time("2018-02-28 00:00:00"),
+            time(value),
+        )
+        .unwrap()
+        .map(|stmt| stmt.id)
+    };
+    assert_eq!(as_of(conn, "2018-01-31 23:59:00"), None);
+    assert_eq!(as_of(conn, "2018-02-01 10:00:00").as_deref(), Some("stmt_001"));
+    assert_eq!(as_of(conn, "2018-02-09 00:00:00").as_deref(), Some("stmt_001"));
+    assert_eq!(as_of(conn, "2018-02-10 10:00:00").as_deref(), Some("stmt_002"));
+});
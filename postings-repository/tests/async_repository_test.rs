@@ -0,0 +1,160 @@
+// tests/async_repository_test.rs
+//
+// Copyright (c) 2018-2024 adorsys GmbH and Co. KG
+// All rights are reserved.
+#![cfg(feature = "async")]
+
+mod common;
+
+use std::env;
+
+use chrono::NaiveDateTime;
+use common::{establish_connection, seed_database, TestDatabaseGuard};
+use postings_repository::models::enums::{PostingStatus, PostingType, StmtStatus};
+use postings_repository::models::{NewAccountStmt, NewPostingLine};
+use postings_repository::pool::{build_async_pool, AsyncDbPool, PoolConfig};
+use postings_repository::repository::async_repository::{account_stmt_repository, posting_line_repository};
+use rust_decimal::Decimal;
+use serial_test::serial;
+
+const ACCOUNT_ID: &str = "xVgaTPMcRty9ik3BTQDh1Q_BS_1_0_0";
+
+fn time(value: &str) -> NaiveDateTime {
+    NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S").unwrap()
+}
+
+async fn pool() -> AsyncDbPool {
+    let database_url = env::var("DATABASE_URL").expect("DATABASE_URL must be set in .env for tests");
+    let config = PoolConfig { max_size: 2, ..PoolConfig::default() };
+    build_async_pool(&database_url, &config).await.expect("Failed to build the pool")
+}
+
+fn new_line(id: &str, opr_id: &str, pst_time: &str, debit: i64) -> NewPostingLine {
+    NewPostingLine {
+        id: id.to_string(),
+        account_id: ACCOUNT_ID.to_string(),
+        debit_amount: Decimal::new(debit, 0),
+        credit_amount: Decimal::ZERO,
+        details_id: None,
+        src_account: None,
+        base_line: None,
+        sub_opr_src_id: None,
+        record_time: time(pst_time),
+        opr_id: opr_id.to_string(),
+        opr_src: None,
+        pst_time: time(pst_time),
+        pst_type: PostingType::BusiTx,
+        pst_status: PostingStatus::POSTED,
+        hash: "hash".to_string(),
+        discarded_time: None,
+    }
+}
+
+#[tokio::test]
+#[serial]
+async fn test_posting_line_finders() {
+    let mut sync_conn = establish_connection();
+    seed_database(&mut sync_conn, "tests/fixtures/ledger_account_dataset.sql");
+    let _guard = TestDatabaseGuard::new();
+
+    let pool = pool().await;
+    let mut conn = pool.get().await.expect("Failed to get a connection");
+
+    posting_line_repository::save_all(
+        &mut conn,
+        vec![
+            new_line("line_001", "opr_001", "2018-01-01 10:00:00", 10),
+            new_line("line_002", "opr_002", "2018-01-03 10:00:00", 20),
+        ],
+    )
+    .await
+    .expect("Failed to save PostingLines");
+    posting_line_repository::save(&mut conn, new_line("line_003", "opr_003", "2018-01-02 10:00:00", 30))
+        .await
+        .expect("Failed to save PostingLine");
+
+    let updated = posting_line_repository::update_discarded_time_by_opr_id_and_record_time(
+        &mut conn,
+        "opr_003",
+        time("2018-01-02 10:00:00"),
+        time("2018-01-05 10:00:00"),
+    )
+    .await
+    .unwrap();
+    assert_eq!(updated, 1);
+
+    let lines = posting_line_repository::find_postings_by_account_and_dates(
+        &mut conn,
+        ACCOUNT_ID,
+        time("2017-12-31 00:00:00"),
+        time("2018-01-31 00:00:00"),
+    )
+    .await
+    .unwrap();
+    let ids: Vec<&str> = lines.iter().map(|line| line.id.as_str()).collect();
+    assert_eq!(ids, vec!["line_002", "line_001"]);
+
+    let line = posting_line_repository::find_first_by_id_and_account(&mut conn, "line_003", ACCOUNT_ID)
+        .await
+        .unwrap()
+        .expect("line_003 should exist");
+    assert_eq!(line.debit_amount, Decimal::new(30, 0));
+    assert!(line.discarded_time.is_some());
+}
+
+#[tokio::test]
+#[serial]
+async fn test_account_stmt_finders() {
+    let mut sync_conn = establish_connection();
+    seed_database(&mut sync_conn, "tests/fixtures/ledger_account_dataset.sql");
+    let _guard = TestDatabaseGuard::new();
+
+    let pool = pool().await;
+    let mut conn = pool.get().await.expect("Failed to get a connection");
+
+    for (id, pst_time) in [("stmt_001", "2018-01-31 23:59:59"), ("stmt_002", "2018-02-28 23:59:59")] {
+        account_stmt_repository::save(
+            &mut conn,
+            NewAccountStmt {
+                id: id.to_string(),
+                posting_id: None,
+                pst_time: time(pst_time),
+                stmt_status: StmtStatus::CLOSED,
+                latest_pst_id: None,
+                stmt_seq_nbr: 0,
+                created: None,
+                user_details: None,
+                short_desc: None,
+                long_desc: None,
+                account_id: ACCOUNT_ID.to_string(),
+                youngest_pst_id: None,
+                total_debit: Decimal::ZERO,
+                total_credit: Decimal::ZERO,
+            },
+        )
+        .await
+        .expect("Failed to save AccountStmt");
+    }
+
+    let last = account_stmt_repository::find_first_by_account_and_stmt_status_and_pst_time_less_than_order_by_pst_time_desc_stmt_seq_nbr_desc(
+        &mut conn,
+        ACCOUNT_ID,
+        StmtStatus::CLOSED,
+        time("2018-02-15 00:00:00"),
+    )
+    .await
+    .unwrap()
+    .expect("A statement before February 15 should exist");
+    assert_eq!(last.id, "stmt_001");
+
+    let next = account_stmt_repository::find_first_by_account_and_stmt_status_and_pst_time_gte(
+        &mut conn,
+        ACCOUNT_ID,
+        StmtStatus::CLOSED,
+        time("2018-02-01 00:00:00"),
+    )
+    .await
+    .unwrap()
+    .expect("A statement after February 1 should exist");
+    assert_eq!(next.id, "stmt_002");
+}
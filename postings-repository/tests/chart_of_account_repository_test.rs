@@ -5,29 +5,17 @@
 
 mod common;
 
-use common::{establish_connection, seed_database, TestDatabaseGuard};
-use chrono::NaiveDateTime;
-use diesel::prelude::*;
-use diesel::result::Error as DieselError;
-use postings_repository::models::{ChartOfAccount, NewChartOfAccount};
-use postings_repository::repository::chart_of_account_repository;
-use serial_test::serial; // import the serial attribute
-
-#[test]
-#[serial]
-fn test_create_coa_ok() {
-    // Establish a connection and seed fixture data.
-    let mut conn = establish_connection();
-    seed_database(&mut conn, "tests/fixtures/chart_of_account_dataset.sql");
-
-    // Create a guard that will perform cleanup after the test.
-    let _guard = TestDatabaseGuard::new();
+use common::backend_test;
+use chrono::DateTime;
+use postings_repository::models::NewChartOfAccount;
+use postings_repository::repository::ChartOfAccountRepository;
 
+backend_test!(test_create_coa_ok, "tests/fixtures/chart_of_account_dataset.sql", |conn| {
     // Create a new ChartOfAccount with a unique name.
     let new_coa = NewChartOfAccount {
         id: "generated-coa-id-001".to_string(),
         // Using a fixed timestamp for testing purposes
-        created: NaiveDateTime::from_timestamp(1627833600, 0),
+        created: DateTime::from_timestamp(1627833600, 0).unwrap().naive_utc(),
         user_details: "francis".to_string(),
         short_desc: Some("Sample chart of account".to_string()),
         long_desc: None,
@@ -35,45 +23,33 @@ fn test_create_coa_ok() {
     };
 
     // Save the new ChartOfAccount via the repository function.
-    let saved_coa = chart_of_account_repository::save(&mut conn, new_coa)
+    let saved_coa = ChartOfAccountRepository::save(conn, new_coa)
         .expect("Failed to create ChartOfAccount");
 
     // Assert that the saved ChartOfAccount has a non-empty id.
     assert!(!saved_coa.id.is_empty(), "Saved ChartOfAccount should have a non-empty id");
-}
-
-#[test]
-#[serial]
-fn test_load_coa() {
-    let mut conn = establish_connection();
-    seed_database(&mut conn, "tests/fixtures/chart_of_account_dataset.sql");
-    let _guard = TestDatabaseGuard::new();
+});
 
+backend_test!(test_load_coa, "tests/fixtures/chart_of_account_dataset.sql", |conn| {
     // Attempt to load the ChartOfAccount with id "ci8k8bcdTrCsi-F3sT3i-g".
-    let coa = chart_of_account_repository::find_by_id(&mut conn, "ci8k8bcdTrCsi-F3sT3i-g")
+    let coa = ChartOfAccountRepository::find_by_id(conn, "ci8k8bcdTrCsi-F3sT3i-g")
         .expect("Error fetching ChartOfAccount")
         .expect("ChartOfAccount with id ci8k8bcdTrCsi-F3sT3i-g not found");
 
     // Assert that the loaded ChartOfAccount has a non-empty id.
     assert!(!coa.id.is_empty(), "Loaded ChartOfAccount should have a non-empty id");
-}
-
-#[test]
-#[serial]
-fn test_create_coa_unique_constrain_violation_name() {
-    let mut conn = establish_connection();
-    seed_database(&mut conn, "tests/fixtures/chart_of_account_dataset.sql");
-    let _guard = TestDatabaseGuard::new();
+});
 
+backend_test!(test_create_coa_unique_constrain_violation_name, "tests/fixtures/chart_of_account_dataset.sql", |conn| {
     // Load an existing ChartOfAccount with id "ci8k8bcdTrCsi-F3sT3i-g".
-    let existing_coa = chart_of_account_repository::find_by_id(&mut conn, "ci8k8bcdTrCsi-F3sT3i-g")
+    let existing_coa = ChartOfAccountRepository::find_by_id(conn, "ci8k8bcdTrCsi-F3sT3i-g")
         .expect("Error fetching ChartOfAccount")
         .expect("ChartOfAccount with id ci8k8bcdTrCsi-F3sT3i-g should exist");
 
     // Create a new ChartOfAccount with a different id but the same name as the existing one.
     let new_coa = NewChartOfAccount {
         id: "generated-coa-id-002".to_string(),
-        created: NaiveDateTime::from_timestamp(1627833600, 0),
+        created: DateTime::from_timestamp(1627833600, 0).unwrap().naive_utc(),
         user_details: "francis".to_string(),
         short_desc: None,
         long_desc: None,
@@ -81,9 +57,25 @@ fn test_create_coa_unique_constrain_violation_name() {
     };
 
     // Expect a unique constraint violation error when saving.
-    let result = chart_of_account_repository::save(&mut conn, new_coa);
+    let result = ChartOfAccountRepository::save(conn, new_coa);
     assert!(
         result.is_err(),
         "Expected a unique constraint violation error when saving duplicate ChartOfAccount name"
     );
-}
+});
+
+backend_test!(test_update_find_all_and_delete_coa, "tests/fixtures/chart_of_account_dataset.sql", |conn| {
+    let mut coa = ChartOfAccountRepository::find_by_id(conn, "ci8k8bcdTrCsi-F3sT3i-g")
+        .expect("Error fetching ChartOfAccount")
+        .expect("ChartOfAccount with id ci8k8bcdTrCsi-F3sT3i-g should exist");
+    coa.long_desc = Some("Chart of account of the test company".to_string());
+    let updated = ChartOfAccountRepository::update(conn, &coa).expect("Failed to update ChartOfAccount");
+    assert_eq!(updated.long_desc, coa.long_desc);
+
+    let all = ChartOfAccountRepository::find_all(conn).expect("Error fetching ChartOfAccounts");
+    assert!(all.iter().any(|c| c.id == coa.id));
+
+    let deleted = ChartOfAccountRepository::delete_by_id(conn, &coa.id).expect("Failed to delete ChartOfAccount");
+    assert_eq!(deleted, 1);
+    assert!(ChartOfAccountRepository::find_by_id(conn, &coa.id).unwrap().is_none());
+});
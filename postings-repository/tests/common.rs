@@ -1,11 +1,12 @@
 // tests/common.rs
+// Not every test binary uses every helper.
+#![allow(dead_code, unused_macros, unused_imports)]
 
 use diesel::prelude::*;
 use diesel::pg::PgConnection;
 use std::env;
 use dotenv::dotenv;
 use diesel_migrations::{embed_migrations, EmbeddedMigrations, MigrationHarness};
-use diesel::RunQueryDsl;
 use std::fs;
 use std::path::Path;
 use diesel::connection::SimpleConnection;
@@ -22,7 +23,7 @@ pub fn establish_connection() -> PgConnection {
     let database_url = env::var("DATABASE_URL")
         .expect("DATABASE_URL must be set in .env for tests");
     let mut conn = PgConnection::establish(&database_url)
-        .expect(&format!("Error connecting to {}", database_url));
+        .unwrap_or_else(|_| panic!("Error connecting to {}", database_url));
     // Optionally run migrations here
     conn.run_pending_migrations(MIGRATIONS)
     .expect("Failed to run migrations");    
@@ -33,12 +34,12 @@ pub fn establish_connection() -> PgConnection {
 ///
 /// # Arguments
 ///
-/// * `conn` - A mutable reference to a PostgreSQL connection.
+/// * `conn` - A mutable reference to a PostgreSQL or SQLite connection.
 /// * `fixture_file` - The path to the SQL file to execute.
-pub fn seed_database(conn: &mut PgConnection, fixture_file: &str) {
+pub fn seed_database<C: SimpleConnection>(conn: &mut C, fixture_file: &str) {
     let fixture_path = Path::new(fixture_file);
     let sql = fs::read_to_string(fixture_path)
-        .expect(&format!("Failed to read fixture file: {}", fixture_file));
+        .unwrap_or_else(|_| panic!("Failed to read fixture file: {}", fixture_file));
     conn.batch_execute(&sql)
         .expect("Failed to seed the database");
 }
@@ -52,6 +53,58 @@ pub fn cleanup_database(conn: &mut PgConnection) {
         .expect("Failed to clean up the database");
 }
 
+#[cfg(feature = "sqlite")]
+pub const SQLITE_MIGRATIONS: EmbeddedMigrations = embed_migrations!("migrations-sqlite");
+
+/// Open a private in-memory SQLite database and run the SQLite migrations.
+#[cfg(feature = "sqlite")]
+pub fn establish_sqlite_connection() -> diesel::sqlite::SqliteConnection {
+    let mut conn = postings_repository::repository::sqlite::establish(":memory:")
+        .expect("Failed to open the SQLite database");
+    conn.run_pending_migrations(SQLITE_MIGRATIONS)
+        .expect("Failed to run SQLite migrations");
+    conn
+}
+
+/// Define a test running on Postgres and, with the `sqlite` feature, on
+/// SQLite, seeding both with the given fixtures.
+///
+/// The body is expanded once per backend with `$conn` bound to the
+/// connection, so it must go through the repository traits:
+///
+/// ```ignore
+/// backend_test!(test_find_ledger, "tests/fixtures/ledger_account_dataset.sql", |conn| {
+///     assert!(LedgerRepository::find_by_id(conn, "Zd0ND5YwSzGwIfZilhumPg").unwrap().is_some());
+/// });
+/// ```
+macro_rules! backend_test {
+    ($name:ident, $($fixture:literal,)* |$conn:ident| $body:block) => {
+        mod $name {
+            use super::*;
+
+            #[test]
+            #[serial_test::serial]
+            fn postgres() {
+                let mut conn = $crate::common::establish_connection();
+                $($crate::common::seed_database(&mut conn, $fixture);)*
+                let _guard = $crate::common::TestDatabaseGuard::new();
+                let $conn = &mut conn;
+                $body
+            }
+
+            #[cfg(feature = "sqlite")]
+            #[test]
+            fn sqlite() {
+                let mut conn = $crate::common::establish_sqlite_connection();
+                $($crate::common::seed_database(&mut conn, $fixture);)*
+                let $conn = &mut conn;
+                $body
+            }
+        }
+    };
+}
+pub(crate) use backend_test;
+
 pub struct TestDatabaseGuard;
 
 impl TestDatabaseGuard {
@@ -60,6 +113,12 @@ impl TestDatabaseGuard {
     }
 }
 
+impl Default for TestDatabaseGuard {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Drop for TestDatabaseGuard {
     fn drop(&mut self) {
         // Open a new connection for cleanup.
@@ -86,7 +145,7 @@ pub fn ensure_docker_database_running() {
             // Could not connect. Attempt to start Docker Compose.
             println!("Database not reachable; attempting to start Docker Compose...");
             let output = Command::new("docker")
-                .args(&["compose", "-f", "compose-postgres.yml", "up", "-d"])
+                .args(["compose", "-f", "compose-postgres.yml", "up", "-d"])
                 .output()
                 .expect("Failed to execute docker compose command");
 
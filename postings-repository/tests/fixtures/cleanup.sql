@@ -7,6 +7,7 @@ TRUNCATE TABLE
     posting,
     posting_trace,
     op_note,
+    operation_details,
     ledger_account,
     ledger,
     chart_of_account
@@ -0,0 +1,296 @@
+// tests/in_memory_repository_test.rs
+//
+// Copyright (c) 2018-2024 adorsys GmbH and Co. KG
+// All rights are reserved.
+//
+// These tests run against the in-memory backend and need no database.
+
+use chrono::NaiveDateTime;
+use diesel::result::Error;
+use postings_repository::models::enums::{
+    AccountCategory, BalanceSide, PostingStatus, PostingType, StmtStatus,
+};
+use postings_repository::models::{
+    LedgerAccount, NewAccountStmt, NewChartOfAccount, NewLedger, NewLedgerAccount, NewPosting, NewPostingLine,
+};
+use postings_repository::repository::error::constraints;
+use postings_repository::repository::{
+    AccountStmtRepository, ChartOfAccountRepository, InMemoryRepositories, LedgerAccountRepository,
+    LedgerRepository, PostingLineRepository, PostingRepository, RepositoryError, Transactional,
+};
+use rust_decimal::Decimal;
+
+fn time(value: &str) -> NaiveDateTime {
+    NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S%.f").expect("Failed to parse time")
+}
+
+/// A chart of account, a ledger and a cash account.
+fn seeded_store() -> InMemoryRepositories {
+    let mut store = InMemoryRepositories::new();
+    ChartOfAccountRepository::save(
+        &mut store,
+        NewChartOfAccount {
+            id: "coa".to_string(),
+            created: time("2018-08-07 20:58:24"),
+            user_details: "Francis".to_string(),
+            short_desc: None,
+            long_desc: None,
+            name: "IFRS".to_string(),
+        },
+    )
+    .expect("Failed to create ChartOfAccount");
+    LedgerRepository::save(
+        &mut store,
+        NewLedger {
+            id: "ledger".to_string(),
+            created: time("2018-08-07 20:58:24"),
+            user_details: "Francis".to_string(),
+            short_desc: None,
+            long_desc: None,
+            name: "Sample Ledger".to_string(),
+            coa_id: "coa".to_string(),
+        },
+    )
+    .expect("Failed to create Ledger");
+    LedgerAccountRepository::save(&mut store, account("cash", "Cash")).expect("Failed to create LedgerAccount");
+    store
+}
+
+fn account(id: &str, name: &str) -> NewLedgerAccount {
+    NewLedgerAccount {
+        id: id.to_string(),
+        created: time("2018-08-07 20:58:24"),
+        user_details: "Francis".to_string(),
+        short_desc: None,
+        long_desc: None,
+        name: name.to_string(),
+        ledger_id: "ledger".to_string(),
+        parent_id: None,
+        coa_id: "coa".to_string(),
+        balance_side: BalanceSide::Dr,
+        category: AccountCategory::AS,
+    }
+}
+
+fn posting(id: &str, opr_id: &str, record_time: &str) -> NewPosting {
+    NewPosting {
+        id: id.to_string(),
+        antecedent_id: None,
+        antecedent_hash: None,
+        hash: None,
+        hash_alg: None,
+        record_user: "Test User".to_string(),
+        record_time: time(record_time),
+        opr_id: opr_id.to_string(),
+        opr_time: None,
+        opr_type: None,
+        opr_src: None,
+        pst_time: time(record_time),
+        pst_type: PostingType::BusiTx,
+        pst_status: PostingStatus::POSTED,
+        ledger_id: "ledger".to_string(),
+        val_time: None,
+        discarded_id: None,
+        discarded_time: None,
+        discarding_id: None,
+        opr_details_id: None,
+    }
+}
+
+fn line(id: &str, opr_id: &str, pst_time: &str, debit: i64) -> NewPostingLine {
+    NewPostingLine {
+        id: id.to_string(),
+        account_id: "cash".to_string(),
+        debit_amount: Decimal::new(debit, 0),
+        credit_amount: Decimal::ZERO,
+        details_id: None,
+        src_account: None,
+        base_line: None,
+        sub_opr_src_id: None,
+        record_time: time(pst_time),
+        opr_id: opr_id.to_string(),
+        opr_src: None,
+        pst_time: time(pst_time),
+        pst_type: PostingType::BusiTx,
+        pst_status: PostingStatus::POSTED,
+        hash: "hash".to_string(),
+        discarded_time: None,
+    }
+}
+
+fn stmt(id: &str, pst_time: &str, stmt_seq_nbr: i32) -> NewAccountStmt {
+    NewAccountStmt {
+        id: id.to_string(),
+        posting_id: None,
+        pst_time: time(pst_time),
+        stmt_status: StmtStatus::CLOSED,
+        latest_pst_id: None,
+        stmt_seq_nbr,
+        created: None,
+        user_details: None,
+        short_desc: None,
+        long_desc: None,
+        account_id: "cash".to_string(),
+        youngest_pst_id: None,
+        total_debit: Decimal::ZERO,
+        total_credit: Decimal::ZERO,
+    }
+}
+
+#[test]
+fn test_unique_constraints_are_enforced() {
+    let mut store = seeded_store();
+
+    let error = LedgerAccountRepository::save(&mut store, account("cash_2", "Cash")).unwrap_err();
+    assert!(
+        matches!(&error, RepositoryError::UniqueViolation { constraint } if constraint == constraints::LEDGER_ACCOUNT_LEDGER_ID_NAME_UNIQUE),
+        "{:?}",
+        error
+    );
+
+    let error = LedgerAccountRepository::save(&mut store, account("cash", "Petty cash")).unwrap_err();
+    assert!(matches!(&error, RepositoryError::UniqueViolation { constraint } if constraint == "ledger_account_pkey"), "{:?}", error);
+}
+
+#[test]
+fn test_account_hierarchy() {
+    let mut store = seeded_store();
+    for (id, name, parent_id) in [("bank", "1.2", "cash"), ("till", "1.1", "cash"), ("drawer", "1.1.1", "till")] {
+        let mut child = account(id, name);
+        child.parent_id = Some(parent_id.to_string());
+        LedgerAccountRepository::save(&mut store, child).unwrap();
+    }
+    let ids = |accounts: Vec<LedgerAccount>| accounts.into_iter().map(|account| account.id).collect::<Vec<_>>();
+
+    assert_eq!(ids(LedgerAccountRepository::find_children(&mut store, "cash").unwrap()), ["till", "bank"]);
+    assert_eq!(ids(LedgerAccountRepository::find_ancestors(&mut store, "drawer").unwrap()), ["till", "cash"]);
+    assert_eq!(
+        ids(LedgerAccountRepository::find_subtree(&mut store, "cash").unwrap()),
+        ["cash", "till", "bank", "drawer"]
+    );
+    assert!(LedgerAccountRepository::find_subtree(&mut store, "missing").unwrap().is_empty());
+}
+
+#[test]
+fn test_foreign_keys_are_enforced() {
+    let mut store = seeded_store();
+
+    let mut orphan = account("bank", "Bank");
+    orphan.parent_id = Some("missing".to_string());
+    let error = LedgerAccountRepository::save(&mut store, orphan).unwrap_err();
+    assert!(matches!(error, RepositoryError::ForeignKeyViolation { .. }), "{:?}", error);
+    assert!(error.violates(constraints::FK_LEDGER_ACCOUNT_PARENT));
+
+    let error = LedgerRepository::delete_by_id(&mut store, "ledger").unwrap_err();
+    assert!(matches!(error, RepositoryError::ForeignKeyViolation { .. }), "{:?}", error);
+    assert!(error.violates(constraints::FK_LEDGER_ACCOUNT_LEDGER));
+    assert_eq!(LedgerAccountRepository::delete_by_id(&mut store, "cash").unwrap(), 1);
+    assert_eq!(LedgerRepository::delete_by_id(&mut store, "ledger").unwrap(), 1);
+}
+
+#[test]
+fn test_discarded_lines_are_filtered_and_ordered() {
+    let mut store = seeded_store();
+    PostingLineRepository::save_all(
+        &mut store,
+        vec![
+            line("l1", "opr_1", "2018-01-01 10:00:00", 10),
+            line("l2", "opr_2", "2018-01-03 10:00:00", 20),
+            line("l3", "opr_3", "2018-01-02 10:00:00", 30),
+        ],
+    )
+    .expect("Failed to save PostingLines");
+
+    let updated = PostingLineRepository::update_discarded_time_by_opr_id_and_record_time(
+        &mut store,
+        "opr_3",
+        time("2018-01-02 10:00:00"),
+        time("2018-01-05 10:00:00"),
+    )
+    .unwrap();
+    assert_eq!(updated, 1);
+
+    let lines = PostingLineRepository::find_postings_by_account_and_dates(
+        &mut store,
+        "cash",
+        time("2018-01-01 10:00:00"),
+        time("2018-01-31 00:00:00"),
+    )
+    .unwrap();
+    let ids: Vec<&str> = lines.iter().map(|line| line.id.as_str()).collect();
+    assert_eq!(ids, vec!["l2"]);
+
+    let lines = PostingLineRepository::find_by_account_and_pst_time_lte_and_discarded_is_null_order_by_record_time_desc(
+        &mut store,
+        "cash",
+        time("2018-01-31 00:00:00"),
+    )
+    .unwrap();
+    let ids: Vec<&str> = lines.iter().map(|line| line.id.as_str()).collect();
+    assert_eq!(ids, vec!["l2", "l1"]);
+}
+
+#[test]
+fn test_statements_ordered_by_pst_time_and_seq_nbr() {
+    let mut store = seeded_store();
+    AccountStmtRepository::save(&mut store, stmt("s1", "2018-01-31 23:59:59", 0)).unwrap();
+    AccountStmtRepository::save(&mut store, stmt("s3", "2018-02-28 23:59:59", 1)).unwrap();
+    AccountStmtRepository::save(&mut store, stmt("s2", "2018-02-28 23:59:59", 0)).unwrap();
+
+    let last = AccountStmtRepository::find_first_by_account_and_stmt_status_and_pst_time_less_than_order_by_pst_time_desc_stmt_seq_nbr_desc(
+        &mut store,
+        "cash",
+        StmtStatus::CLOSED,
+        time("2018-03-01 00:00:00"),
+    )
+    .unwrap()
+    .unwrap();
+    assert_eq!(last.id, "s3");
+
+    let next = AccountStmtRepository::find_first_by_account_and_stmt_status_and_pst_time_gte(
+        &mut store,
+        "cash",
+        StmtStatus::CLOSED,
+        time("2018-02-01 00:00:00"),
+    )
+    .unwrap()
+    .unwrap();
+    assert_eq!(next.id, "s2");
+}
+
+#[test]
+fn test_postings_discarding_and_record_time_precision() {
+    let mut store = seeded_store();
+    let first = PostingRepository::save(&mut store, posting("p1", "opr", "2018-01-01 10:00:00.123456789")).unwrap();
+    assert_eq!(first.record_time, time("2018-01-01 10:00:00.123456"));
+
+    PostingRepository::save(&mut store, posting("p2", "opr", "2018-01-02 10:00:00")).unwrap();
+    assert_eq!(PostingRepository::update_discarding_id(&mut store, "p1", "p2").unwrap(), 1);
+    assert_eq!(PostingRepository::update_discarding_id(&mut store, "p1", "p3").unwrap(), 0);
+
+    let effective = PostingRepository::find_by_opr_id_and_discarding_id_is_null(&mut store, "opr").unwrap().unwrap();
+    assert_eq!(effective.id, "p2");
+    let latest = PostingRepository::find_first_by_ledger_order_by_record_time_desc(&mut store, "ledger")
+        .unwrap()
+        .unwrap();
+    assert_eq!(latest.id, "p2");
+}
+
+#[test]
+fn test_failed_transaction_and_batch_roll_back() {
+    let mut store = seeded_store();
+
+    let result: Result<(), RepositoryError> = store.transaction(|store| {
+        LedgerAccountRepository::save(store, account("bank", "Bank"))?;
+        Err(Error::RollbackTransaction.into())
+    });
+    assert!(result.is_err());
+    assert!(LedgerAccountRepository::find_by_id(&mut store, "bank").unwrap().is_none());
+
+    let result = PostingRepository::save_all(
+        &mut store,
+        vec![posting("p1", "opr_1", "2018-01-01 10:00:00"), posting("p1", "opr_2", "2018-01-02 10:00:00")],
+    );
+    assert!(result.is_err());
+    assert!(PostingRepository::find_by_id(&mut store, "p1").unwrap().is_none());
+}
@@ -0,0 +1,84 @@
+// tests/ledger_account_hierarchy_test.rs
+//
+// Copyright (c) 2018-2024 adorsys GmbH and Co. KG
+// All rights are reserved.
+
+mod common;
+
+use chrono::Local;
+use common::backend_test;
+use postings_repository::models::enums::{AccountCategory, BalanceSide};
+use postings_repository::models::{LedgerAccount, NewLedgerAccount};
+use postings_repository::repository::LedgerAccountRepository;
+
+const LEDGER_ID: &str = "Zd0ND5YwSzGwIfZilhumPg";
+const COA_ID: &str = "ci8k8PDcTrCsi-F3sT3i-g";
+const ASSETS_ID: &str = "xVgaTPMcRty9ik3BTQDh1Q_BS_1_0_0";
+
+fn new_account(id: &str, name: &str, parent_id: &str) -> NewLedgerAccount {
+    NewLedgerAccount {
+        id: id.to_string(),
+        created: Local::now().naive_local(),
+        user_details: "Sample User".to_string(),
+        short_desc: None,
+        long_desc: None,
+        name: name.to_string(),
+        ledger_id: LEDGER_ID.to_string(),
+        parent_id: Some(parent_id.to_string()),
+        coa_id: COA_ID.to_string(),
+        balance_side: BalanceSide::Dr,
+        category: AccountCategory::AS,
+    }
+}
+
+fn ids(accounts: &[LedgerAccount]) -> Vec<&str> {
+    accounts.iter().map(|account| account.id.as_str()).collect()
+}
+
+/// Below the asset account: 1.1.0 (with 1.1.1) and 1.2.0.
+fn save_asset_tree<C: LedgerAccountRepository>(conn: &mut C) {
+    for account in [
+        new_account("bank", "1.2.0", ASSETS_ID),
+        new_account("cash", "1.1.0", ASSETS_ID),
+        new_account("petty_cash", "1.1.1", "cash"),
+    ] {
+        LedgerAccountRepository::save(conn, account).expect("Failed to save LedgerAccount");
+    }
+}
+
+backend_test!(test_find_children, "tests/fixtures/ledger_account_dataset.sql", |conn| {
+    save_asset_tree(conn);
+
+    let children = LedgerAccountRepository::find_children(conn, ASSETS_ID).expect("Error fetching children");
+    assert_eq!(ids(&children), vec!["cash", "bank"]);
+    assert!(LedgerAccountRepository::find_children(conn, "petty_cash").unwrap().is_empty());
+});
+
+backend_test!(test_find_ancestors, "tests/fixtures/ledger_account_dataset.sql", |conn| {
+    save_asset_tree(conn);
+
+    let ancestors = LedgerAccountRepository::find_ancestors(conn, "petty_cash").expect("Error fetching ancestors");
+    assert_eq!(ids(&ancestors), vec!["cash", ASSETS_ID]);
+    assert!(LedgerAccountRepository::find_ancestors(conn, ASSETS_ID).unwrap().is_empty());
+    assert!(LedgerAccountRepository::find_ancestors(conn, "missing").unwrap().is_empty());
+});
+
+backend_test!(test_find_subtree, "tests/fixtures/ledger_account_dataset.sql", |conn| {
+    save_asset_tree(conn);
+
+    let subtree = LedgerAccountRepository::find_subtree(conn, ASSETS_ID).expect("Error fetching subtree");
+    assert_eq!(ids(&subtree), vec![ASSETS_ID, "cash", "bank", "petty_cash"]);
+    let subtree = LedgerAccountRepository::find_subtree(conn, "cash").expect("Error fetching subtree");
+    assert_eq!(ids(&subtree), vec!["cash", "petty_cash"]);
+    assert!(LedgerAccountRepository::find_subtree(conn, "missing").unwrap().is_empty());
+});
+
+backend_test!(test_self_referencing_root, "tests/fixtures/ledger_account_dataset.sql", |conn| {
+    // A root account may reference itself as parent.
+    LedgerAccountRepository::save(conn, new_account("root", "9.0.0", "root")).expect("Failed to save root");
+    LedgerAccountRepository::save(conn, new_account("leaf", "9.1.0", "root")).expect("Failed to save leaf");
+
+    assert_eq!(ids(&LedgerAccountRepository::find_children(conn, "root").unwrap()), vec!["leaf"]);
+    assert_eq!(ids(&LedgerAccountRepository::find_ancestors(conn, "leaf").unwrap()), vec!["root"]);
+    assert_eq!(ids(&LedgerAccountRepository::find_subtree(conn, "root").unwrap()), vec!["root", "leaf"]);
+});
@@ -6,32 +6,21 @@
 mod common;
 
 use chrono::Local;
-use diesel::prelude::*;
-use postings_repository::models::{LedgerAccount, NewLedgerAccount, ChartOfAccount, Ledger};
+use postings_repository::models::{LedgerAccount, NewLedgerAccount, Ledger};
 use postings_repository::models::enums::{AccountCategory, BalanceSide};
-use postings_repository::repository::{ledger_account_repository, ledger_repository};
-use common::{establish_connection, seed_database, TestDatabaseGuard};
-use std::env;
-use serial_test::serial;
-
-#[test]
-#[serial]
-fn test_create_ledger_account_ok() {
-    // Establish a connection and seed fixture data.
-    let mut conn = establish_connection();
-    seed_database(&mut conn, "tests/fixtures/ledger_account_dataset.sql");
-
-    // Create a guard that will clean up after the test.
-    let _guard = TestDatabaseGuard::new();
+use postings_repository::repository::error::constraints;
+use postings_repository::repository::{LedgerAccountRepository, LedgerRepository};
+use common::backend_test;
 
+backend_test!(test_create_ledger_account_ok, "tests/fixtures/ledger_account_dataset.sql", |conn| {
     // Retrieve a ledger by its ID.
     let ledger_id = "Zd0ND5YwSzGwIfZilhumPg";
-    let ledger: Ledger = ledger_repository::find_by_id(&mut conn, ledger_id)
+    let ledger: Ledger = LedgerRepository::find_by_id(conn, ledger_id)
         .expect("Error fetching Ledger")
         .expect("Ledger with id Zd0ND5YwSzGwIfZilhumPg not found");
 
     // Retrieve a parent LedgerAccount by ledger and name ("3.0.0").
-    let parent_account: LedgerAccount = ledger_account_repository::find_optional_by_ledger_and_name(&mut conn, ledger_id, "3.0.0")
+    let parent_account: LedgerAccount = LedgerAccountRepository::find_optional_by_ledger_and_name(conn, ledger_id, "3.0.0")
         .expect("Error fetching parent LedgerAccount")
         .expect("Parent LedgerAccount with name '3.0.0' not found");
 
@@ -51,19 +40,13 @@ fn test_create_ledger_account_ok() {
         category: AccountCategory::LI,
     };
 
-    let created_account = ledger_account_repository::save(&mut conn, new_account)
+    let created_account = LedgerAccountRepository::save(conn, new_account)
         .expect("Failed to save LedgerAccount");
 
     assert!(!created_account.id.is_empty(), "Created LedgerAccount should have a non-empty id");
-}
-
-#[test]
-#[serial]
-fn test_create_ledger_account_no_ledger() {
-    let mut conn = establish_connection();
-    seed_database(&mut conn, "tests/fixtures/ledger_account_dataset.sql");
-    let _guard = TestDatabaseGuard::new();
+});
 
+backend_test!(test_create_ledger_account_no_ledger, "tests/fixtures/ledger_account_dataset.sql", |conn| {
     // Create a LedgerAccount with missing required foreign keys (no ledger, no chart)
     let new_account = NewLedgerAccount {
         id: "generated-ledgeraccount-id-002".to_string(),
@@ -79,19 +62,13 @@ fn test_create_ledger_account_no_ledger() {
         category: AccountCategory::AS,
     };
 
-    let result = ledger_account_repository::save(&mut conn, new_account);
+    let result = LedgerAccountRepository::save(conn, new_account);
     assert!(result.is_err(), "Expected error when saving LedgerAccount with missing ledger");
-}
-
-#[test]
-#[serial]
-fn test_create_ledger_account_unique_constrain_violation_ledger_name_valid_from() {
-    let mut conn = establish_connection();
-    seed_database(&mut conn, "tests/fixtures/ledger_account_dataset.sql");
-    let _guard = TestDatabaseGuard::new();
+});
 
+backend_test!(test_create_ledger_account_unique_constrain_violation_ledger_name_valid_from, "tests/fixtures/ledger_account_dataset.sql", |conn| {
     // Retrieve an existing LedgerAccount (e.g., the one with id "xVgaTPMcRty9ik3BTQDh1Q_BS_3_0_0").
-    let existing_account = ledger_account_repository::find_by_id(&mut conn, "xVgaTPMcRty9ik3BTQDh1Q_BS_3_0_0")
+    let existing_account = LedgerAccountRepository::find_by_id(conn, "xVgaTPMcRty9ik3BTQDh1Q_BS_3_0_0")
         .expect("Error fetching LedgerAccount")
         .expect("LedgerAccount with id xVgaTPMcRty9ik3BTQDh1Q_BS_3_0_0 should exist");
 
@@ -110,23 +87,38 @@ fn test_create_ledger_account_unique_constrain_violation_ledger_name_valid_from(
         category: existing_account.category,
     };
 
-    let result = ledger_account_repository::save(&mut conn, new_account);
-    assert!(result.is_err(), "Expected a unique constraint violation when saving a duplicate LedgerAccount name");
-}
-
-#[test]
-#[serial]
-fn test_find_by_ledger_and_name_ok() {
-    let mut conn = establish_connection();
-    seed_database(&mut conn, "tests/fixtures/ledger_account_dataset.sql");
-    let _guard = TestDatabaseGuard::new();
+    let error = LedgerAccountRepository::save(conn, new_account)
+        .expect_err("Expected a unique constraint violation when saving a duplicate LedgerAccount name");
+    assert!(error.violates(constraints::LEDGER_ACCOUNT_LEDGER_ID_NAME_UNIQUE), "Unexpected error {:?}", error);
+});
 
+backend_test!(test_find_by_ledger_and_name_ok, "tests/fixtures/ledger_account_dataset.sql", |conn| {
     let ledger_id = "Zd0ND5YwSzGwIfZilhumPg";
-    let ledger = ledger_repository::find_by_id(&mut conn, ledger_id)
+    LedgerRepository::find_by_id(conn, ledger_id)
         .expect("Error fetching Ledger")
         .expect("Ledger not found");
 
-    let found = ledger_account_repository::find_optional_by_ledger_and_name(&mut conn, ledger_id, "1.0.0")
+    let found = LedgerAccountRepository::find_optional_by_ledger_and_name(conn, ledger_id, "1.0.0")
         .expect("Error fetching LedgerAccount by ledger and name");
     assert!(found.is_some(), "Expected to find a LedgerAccount with name '1.0.0'");
-}
+});
+
+backend_test!(test_update_and_delete_ledger_account, "tests/fixtures/ledger_account_dataset.sql", |conn| {
+    let mut account = LedgerAccountRepository::find_by_id(conn, "xVgaTPMcRty9ik3BTQDh1Q_BS_1_0_0")
+        .expect("Error fetching LedgerAccount")
+        .expect("LedgerAccount xVgaTPMcRty9ik3BTQDh1Q_BS_1_0_0 not found");
+    account.short_desc = Some("Assets".to_string());
+    let updated = LedgerAccountRepository::update(conn, &account).expect("Failed to update LedgerAccount");
+    assert_eq!(updated.short_desc.as_deref(), Some("Assets"));
+
+    let deleted = LedgerAccountRepository::delete_by_id(conn, &account.id).expect("Failed to delete LedgerAccount");
+    assert_eq!(deleted, 1);
+    assert!(LedgerAccountRepository::find_by_id(conn, &account.id).unwrap().is_none());
+});
+
+backend_test!(test_find_by_coa, "tests/fixtures/ledger_account_dataset.sql", |conn| {
+    let accounts = LedgerAccountRepository::find_by_coa(conn, "ci8k8PDcTrCsi-F3sT3i-g").expect("Error fetching LedgerAccounts");
+    assert_eq!(accounts.len(), LedgerAccountRepository::find_by_ledger(conn, "Zd0ND5YwSzGwIfZilhumPg").unwrap().len());
+    assert!(accounts.windows(2).all(|pair| pair[0].name <= pair[1].name));
+    assert!(LedgerAccountRepository::find_by_coa(conn, "missing").unwrap().is_empty());
+});
@@ -0,0 +1,123 @@
+// tests/ledger_repository_test.rs
+//
+// Copyright (c) 2018-2024 adorsys GmbH and Co. KG
+// All rights are reserved.
+
+mod common;
+
+use chrono::Local;
+use common::backend_test;
+use postings_repository::models::NewLedger;
+use postings_repository::repository::error::constraints;
+use postings_repository::repository::{LedgerRepository, RepositoryError};
+
+const COA_ID: &str = "ci8k8PDcTrCsi-F3sT3i-g";
+
+fn new_ledger(id: &str, name: &str) -> NewLedger {
+    NewLedger {
+        id: id.to_string(),
+        created: Local::now().naive_local(),
+        user_details: "Sample User".to_string(),
+        short_desc: None,
+        long_desc: None,
+        name: name.to_string(),
+        coa_id: COA_ID.to_string(),
+    }
+}
+
+backend_test!(test_create_ledger_ok, "tests/fixtures/ledger_account_dataset.sql", |conn| {
+    let saved = LedgerRepository::save(conn, new_ledger("generated-ledger-id-001", "Branch GL"))
+        .expect("Failed to save Ledger");
+
+    let found = LedgerRepository::find_optional_by_name(conn, "Branch GL")
+        .expect("Error fetching Ledger by name")
+        .expect("Ledger 'Branch GL' should exist");
+    assert_eq!(found.id, saved.id);
+});
+
+backend_test!(test_create_ledger_unique_constrain_violation_name, "tests/fixtures/ledger_account_dataset.sql", |conn| {
+    // The fixture already contains a ledger named "GL".
+    let error = LedgerRepository::save(conn, new_ledger("generated-ledger-id-002", "GL"))
+        .expect_err("Expected a unique constraint violation when saving a duplicate Ledger name");
+    assert!(
+        matches!(&error, RepositoryError::UniqueViolation { constraint } if constraint == constraints::LEDGER_NAME_UNIQUE),
+        "Unexpected error {:?}",
+        error
+    );
+
+    // Reusing the id of the fixture ledger violates the primary key.
+    let error = LedgerRepository::save(conn, new_ledger("Zd0ND5YwSzGwIfZilhumPg", "Other GL"))
+        .expect_err("Expected a primary key violation when saving a duplicate Ledger id");
+    assert!(error.violates("ledger_pkey"), "Unexpected error {:?}", error);
+});
+
+backend_test!(test_create_ledger_with_missing_coa, "tests/fixtures/ledger_account_dataset.sql", |conn| {
+    let mut ledger = new_ledger("generated-ledger-id-007", "Orphan GL");
+    ledger.coa_id = "missing-coa".to_string();
+    let error = LedgerRepository::save(conn, ledger).expect_err("Expected a foreign key violation");
+    match error {
+        // SQLite does not name the violated foreign key.
+        RepositoryError::ForeignKeyViolation { constraint } => {
+            assert!(constraint.is_none() || constraint.as_deref() == Some(constraints::FK_LEDGER_COA))
+        }
+        other => panic!("Unexpected error {:?}", other),
+    }
+});
+
+backend_test!(test_update_missing_ledger_not_found, "tests/fixtures/ledger_account_dataset.sql", |conn| {
+    let mut ledger = LedgerRepository::find_by_id(conn, "Zd0ND5YwSzGwIfZilhumPg")
+        .expect("Error fetching Ledger")
+        .expect("Ledger with id Zd0ND5YwSzGwIfZilhumPg not found");
+    ledger.id = "missing-ledger".to_string();
+    let result = LedgerRepository::update(conn, &ledger);
+    assert!(matches!(result, Err(RepositoryError::NotFound)), "Unexpected result {:?}", result);
+});
+
+backend_test!(test_save_all_ledgers_ok, "tests/fixtures/ledger_account_dataset.sql", |conn| {
+    let saved = LedgerRepository::save_all(
+        conn,
+        vec![
+            new_ledger("generated-ledger-id-003", "Branch A"),
+            new_ledger("generated-ledger-id-004", "Branch B"),
+        ],
+    )
+    .expect("Failed to save Ledgers");
+
+    assert_eq!(saved.len(), 2);
+});
+
+backend_test!(test_update_ledger_ok, "tests/fixtures/ledger_account_dataset.sql", |conn| {
+    let mut ledger = LedgerRepository::find_by_id(conn, "Zd0ND5YwSzGwIfZilhumPg")
+        .expect("Error fetching Ledger")
+        .expect("Ledger with id Zd0ND5YwSzGwIfZilhumPg not found");
+    ledger.short_desc = Some("General ledger".to_string());
+
+    let updated = LedgerRepository::update(conn, &ledger).expect("Failed to update Ledger");
+    assert_eq!(updated.short_desc.as_deref(), Some("General ledger"));
+});
+
+backend_test!(test_delete_ledger, "tests/fixtures/ledger_account_dataset.sql", |conn| {
+    // A ledger holding accounts can not be deleted.
+    let result = LedgerRepository::delete_by_id(conn, "Zd0ND5YwSzGwIfZilhumPg");
+    assert!(
+        matches!(result, Err(RepositoryError::ForeignKeyViolation { .. })),
+        "Expected a foreign key violation when deleting a Ledger with accounts, got {:?}",
+        result
+    );
+
+    // An unused ledger can.
+    LedgerRepository::save(conn, new_ledger("generated-ledger-id-005", "Unused GL"))
+        .expect("Failed to save Ledger");
+    let deleted = LedgerRepository::delete_by_id(conn, "generated-ledger-id-005")
+        .expect("Failed to delete Ledger");
+    assert_eq!(deleted, 1);
+});
+
+backend_test!(test_find_all_ledgers, "tests/fixtures/ledger_account_dataset.sql", |conn| {
+    LedgerRepository::save(conn, new_ledger("generated-ledger-id-006", "AAA GL"))
+        .expect("Failed to save Ledger");
+
+    let ledgers = LedgerRepository::find_all(conn).expect("Error fetching Ledgers");
+    assert!(ledgers.len() >= 2);
+    assert_eq!(ledgers[0].name, "AAA GL");
+});
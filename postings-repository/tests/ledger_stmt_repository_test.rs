@@ -0,0 +1,99 @@
+// tests/ledger_stmt_repository_test.rs
+//
+// Copyright (c) 2018-2024 adorsys GmbH and Co. KG
+// All rights are reserved.
+
+mod common;
+
+use chrono::{NaiveDateTime, Utc};
+use common::backend_test;
+use postings_repository::models::NewLedgerStmt;
+use postings_repository::models::enums::StmtStatus;
+use postings_repository::repository::LedgerStmtRepository;
+
+const LEDGER_ID: &str = "Zd0ND5YwSzGwIfZilhumPg";
+
+fn new_stmt(id: &str, stmt_status: StmtStatus, stmt_seq_nbr: i32) -> NewLedgerStmt {
+    NewLedgerStmt {
+        id: id.to_string(),
+        posting_id: None,
+        pst_time: NaiveDateTime::parse_from_str("2017-12-31 23:59:00", "%Y-%m-%d %H:%M:%S").unwrap(),
+        stmt_status,
+        latest_pst_id: None,
+        stmt_seq_nbr,
+        created: Some(Utc::now().naive_utc()),
+        user_details: Some("Test User".to_string()),
+        short_desc: None,
+        long_desc: None,
+        ledger_id: LEDGER_ID.to_string(),
+    }
+}
+
+backend_test!(test_create_ledger_stmt_ok, "tests/fixtures/ledger_account_dataset.sql", |conn| {
+    LedgerStmtRepository::save(conn, new_stmt("generated-stmt-id-001", StmtStatus::SIMULATED, 0))
+        .expect("Failed to save LedgerStmt");
+
+    let found = LedgerStmtRepository::find_by_id(conn, "generated-stmt-id-001")
+        .expect("Error fetching LedgerStmt")
+        .expect("LedgerStmt generated-stmt-id-001 should exist");
+    assert_eq!(found.ledger_id, LEDGER_ID);
+});
+
+backend_test!(test_save_all_ledger_stmts_ok, "tests/fixtures/ledger_account_dataset.sql", |conn| {
+    LedgerStmtRepository::save_all(
+        conn,
+        vec![
+            new_stmt("generated-stmt-id-002", StmtStatus::CLOSED, 0),
+            new_stmt("generated-stmt-id-003", StmtStatus::CLOSED, 1),
+        ],
+    )
+    .expect("Failed to save LedgerStmts");
+
+    // The statement with the highest sequence number at the same posting time comes first.
+    let ref_time = NaiveDateTime::parse_from_str("2018-01-01 00:00:00", "%Y-%m-%d %H:%M:%S").unwrap();
+    let latest = LedgerStmtRepository::find_first_by_ledger_and_stmt_status_and_pst_time_lte_order_by_pst_time_desc_stmt_seq_nbr_desc(
+        conn, LEDGER_ID, StmtStatus::CLOSED, ref_time,
+    )
+    .expect("Error fetching LedgerStmt")
+    .expect("A closed LedgerStmt should exist");
+    assert_eq!(latest.id, "generated-stmt-id-003");
+});
+
+backend_test!(test_delete_simulated_ledger_stmt, "tests/fixtures/ledger_account_dataset.sql", |conn| {
+    LedgerStmtRepository::save_all(
+        conn,
+        vec![
+            new_stmt("generated-stmt-id-004", StmtStatus::SIMULATED, 0),
+            new_stmt("generated-stmt-id-005", StmtStatus::CLOSED, 0),
+        ],
+    )
+    .expect("Failed to save LedgerStmts");
+
+    assert_eq!(LedgerStmtRepository::delete_simulated_by_id(conn, "generated-stmt-id-004").unwrap(), 1);
+    // Closed statements are kept.
+    assert_eq!(LedgerStmtRepository::delete_simulated_by_id(conn, "generated-stmt-id-005").unwrap(), 0);
+    assert!(LedgerStmtRepository::find_by_id(conn, "generated-stmt-id-005").unwrap().is_some());
+});
+
+backend_test!(test_find_closed_ledger_stmts_from_pst_time, "tests/fixtures/ledger_account_dataset.sql", |conn| {
+    LedgerStmtRepository::save_all(
+        conn,
+        vec![
+            new_stmt("generated-stmt-id-007", StmtStatus::CLOSED, 1),
+            new_stmt("generated-stmt-id-006", StmtStatus::CLOSED, 0),
+            new_stmt("generated-stmt-id-008", StmtStatus::SIMULATED, 2),
+        ],
+    )
+    .expect("Failed to save LedgerStmts");
+
+    let pst_time = NaiveDateTime::parse_from_str("2017-12-31 23:59:00", "%Y-%m-%d %H:%M:%S").unwrap();
+    let stmts = LedgerStmtRepository::find_by_ledger_and_stmt_status_and_pst_time_gte(conn, LEDGER_ID, StmtStatus::CLOSED, pst_time)
+        .expect("Error fetching LedgerStmts");
+    let ids: Vec<&str> = stmts.iter().map(|stmt| stmt.id.as_str()).collect();
+    assert_eq!(ids, ["generated-stmt-id-006", "generated-stmt-id-007"]);
+
+    let later = NaiveDateTime::parse_from_str("2018-01-01 00:00:00", "%Y-%m-%d %H:%M:%S").unwrap();
+    assert!(LedgerStmtRepository::find_by_ledger_and_stmt_status_and_pst_time_gte(conn, LEDGER_ID, StmtStatus::CLOSED, later)
+        .unwrap()
+        .is_empty());
+});
@@ -0,0 +1,103 @@
+// tests/op_note_repository_test.rs
+//
+// Copyright (c) 2018-2024 adorsys GmbH and Co. KG
+// All rights are reserved.
+
+mod common;
+
+use chrono::NaiveDateTime;
+use common::backend_test;
+use postings_repository::models::{NewOpNote, OpNote};
+use postings_repository::repository::OpNoteRepository;
+
+fn new_note(id: &str) -> NewOpNote {
+    NewOpNote {
+        id: id.to_string(),
+        rec_id: "pst_001".to_string(),
+        note_type: Some("REMINDER".to_string()),
+        content: Some("Check the counterparty".to_string()),
+        rec_time: NaiveDateTime::parse_from_str("2024-01-01 10:00:00", "%Y-%m-%d %H:%M:%S").unwrap(),
+        exec_time: None,
+        premature_exc: Some(false),
+        repeated_exec: Some(false),
+        exec_status: None,
+    }
+}
+
+backend_test!(test_create_op_note_ok, |conn| {
+    OpNoteRepository::save(conn, new_note("generated-note-id-001")).expect("Failed to save OpNote");
+
+    let found = OpNoteRepository::find_by_id(conn, "generated-note-id-001")
+        .expect("Error fetching OpNote")
+        .expect("OpNote generated-note-id-001 should exist");
+    assert_eq!(found.note_type.as_deref(), Some("REMINDER"));
+});
+
+backend_test!(test_save_all_op_notes_ok, |conn| {
+    let saved = OpNoteRepository::save_all(
+        conn,
+        vec![new_note("generated-note-id-002"), new_note("generated-note-id-003")],
+    )
+    .expect("Failed to save OpNotes");
+
+    assert_eq!(saved.len(), 2);
+});
+
+backend_test!(test_update_op_note_ok, |conn| {
+    let mut note = OpNoteRepository::save(conn, new_note("generated-note-id-004"))
+        .expect("Failed to save OpNote");
+    note.exec_status = Some("DONE".to_string());
+
+    let updated = OpNoteRepository::update(conn, &note).expect("Failed to update OpNote");
+    assert_eq!(updated.exec_status.as_deref(), Some("DONE"));
+});
+
+backend_test!(test_delete_op_note_ok, |conn| {
+    OpNoteRepository::save(conn, new_note("generated-note-id-005")).expect("Failed to save OpNote");
+
+    let deleted = OpNoteRepository::delete_by_id(conn, "generated-note-id-005")
+        .expect("Failed to delete OpNote");
+    assert_eq!(deleted, 1);
+    assert!(OpNoteRepository::find_by_id(conn, "generated-note-id-005").unwrap().is_none());
+});
+
+backend_test!(test_find_op_notes_by_rec_id, |conn| {
+    let mut other = new_note("generated-note-id-007");
+    other.rec_id = "pst_002".to_string();
+    OpNoteRepository::save_all(conn, vec![new_note("generated-note-id-006"), other]).unwrap();
+
+    let notes = OpNoteRepository::find_by_rec_id(conn, "pst_001").unwrap();
+    assert_eq!(notes.len(), 1);
+    assert_eq!(notes[0].id, "generated-note-id-006");
+    assert!(OpNoteRepository::find_by_rec_id(conn, "missing").unwrap().is_empty());
+});
+
+backend_test!(test_find_pending_op_notes_by_exec_time, |conn| {
+    let time = |value: &str| NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S").unwrap();
+    let note = |id: &str, exec_time: &str, exec_status: Option<&str>, repeated_exec: bool| NewOpNote {
+        exec_time: Some(time(exec_time)),
+        exec_status: exec_status.map(str::to_string),
+        repeated_exec: Some(repeated_exec),
+        ..new_note(id)
+    };
+    OpNoteRepository::save_all(
+        conn,
+        vec![
+            note("later", "2024-01-03 10:00:00", None, false),
+            note("pending", "2024-01-02 10:00:00", None, false),
+            note("done", "2024-01-01 10:00:00", Some(OpNote::EXEC_DONE), true),
+            note("failed", "2024-01-01 11:00:00", Some(OpNote::EXEC_FAILED), false),
+            note("retried", "2024-01-01 12:00:00", Some(OpNote::EXEC_FAILED), true),
+            new_note("unplanned"),
+        ],
+    )
+    .unwrap();
+
+    let notes = OpNoteRepository::find_pending_by_exec_time_lte(conn, time("2024-01-02 10:00:00")).unwrap();
+    let ids: Vec<&str> = notes.iter().map(|note| note.id.as_str()).collect();
+    assert_eq!(ids, ["retried", "pending"]);
+
+    let locked = OpNoteRepository::find_by_id_for_update_skip_locked(conn, "pending").unwrap();
+    assert_eq!(locked.unwrap().id, "pending");
+    assert!(OpNoteRepository::find_by_id_for_update_skip_locked(conn, "missing").unwrap().is_none());
+});
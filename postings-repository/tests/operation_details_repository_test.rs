@@ -0,0 +1,37 @@
+// tests/operation_details_repository_test.rs
+//
+// Copyright (c) 2018-2024 adorsys GmbH and Co. KG
+// All rights are reserved.
+
+mod common;
+
+use common::backend_test;
+use postings_repository::models::NewOperationDetails;
+use postings_repository::repository::OperationDetailsRepository;
+
+backend_test!(test_create_operation_details_ok, |conn| {
+    let new_details = NewOperationDetails {
+        id: "generated-details-id-001".to_string(),
+        op_details: Some(r#"{"amount":"100.00","currency":"EUR"}"#.to_string()),
+    };
+    OperationDetailsRepository::save(conn, new_details)
+        .expect("Failed to save OperationDetails");
+
+    let found = OperationDetailsRepository::find_by_id(conn, "generated-details-id-001")
+        .expect("Error fetching OperationDetails")
+        .expect("OperationDetails generated-details-id-001 should exist");
+    assert_eq!(found.op_details.as_deref(), Some(r#"{"amount":"100.00","currency":"EUR"}"#));
+});
+
+backend_test!(test_save_all_operation_details_ok, |conn| {
+    let saved = OperationDetailsRepository::save_all(
+        conn,
+        vec![
+            NewOperationDetails { id: "generated-details-id-002".to_string(), op_details: None },
+            NewOperationDetails { id: "generated-details-id-003".to_string(), op_details: None },
+        ],
+    )
+    .expect("Failed to save OperationDetails");
+
+    assert_eq!(saved.len(), 2);
+});
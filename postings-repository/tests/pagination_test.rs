@@ -0,0 +1,184 @@
+// tests/pagination_test.rs
+//
+// Copyright (c) 2018-2024 adorsys GmbH and Co. KG
+// All rights are reserved.
+
+mod common;
+
+use chrono::NaiveDateTime;
+use common::backend_test;
+use postings_repository::models::enums::{AccountCategory, BalanceSide, PostingStatus, PostingType};
+use postings_repository::models::{NewChartOfAccount, NewLedger, NewLedgerAccount, NewPostingLine};
+use postings_repository::repository::pagination::{
+    find_page_by_account_and_dates, find_page_by_account_and_dates_as_of, stream_by_account_and_dates, InvalidCursor,
+    LineCursor,
+};
+use postings_repository::repository::{
+    ChartOfAccountRepository, InMemoryRepositories, LedgerAccountRepository, LedgerRepository, PostingLineRepository,
+};
+use rust_decimal::Decimal;
+
+const ACCOUNT_ID: &str = "xVgaTPMcRty9ik3BTQDh1Q_BS_1_0_0";
+
+fn time(value: &str) -> NaiveDateTime {
+    NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S%.f").unwrap()
+}
+
+fn new_line(id: &str, pst_time: &str, record_time: &str) -> NewPostingLine {
+    NewPostingLine {
+        id: id.to_string(),
+        account_id: ACCOUNT_ID.to_string(),
+        debit_amount: Decimal::new(10, 0),
+        credit_amount: Decimal::ZERO,
+        details_id: None,
+        src_account: None,
+        base_line: None,
+        sub_opr_src_id: None,
+        record_time: time(record_time),
+        opr_id: format!("opr_{}", id),
+        opr_src: None,
+        pst_time: time(pst_time),
+        pst_type: PostingType::BusiTx,
+        pst_status: PostingStatus::POSTED,
+        hash: "hash".to_string(),
+        discarded_time: None,
+    }
+}
+
+/// Five lines, two of them sharing their posting time and two sharing both
+/// their posting and record time, given out of order.
+fn lines() -> Vec<NewPostingLine> {
+    vec![
+        new_line("line_e", "2018-01-05 10:00:00", "2018-01-05 10:00:00"),
+        new_line("line_b", "2018-01-02 10:00:00", "2018-01-03 10:00:00"),
+        new_line("line_d", "2018-01-02 10:00:00", "2018-01-04 10:00:00.250"),
+        new_line("line_c", "2018-01-02 10:00:00", "2018-01-04 10:00:00.250"),
+        new_line("line_a", "2018-01-01 10:00:00", "2018-01-01 10:00:00"),
+    ]
+}
+
+const EXPECTED: [&str; 5] = ["line_a", "line_b", "line_c", "line_d", "line_e"];
+
+fn page_through<C: PostingLineRepository>(conn: &mut C, page_size: usize) -> Vec<Vec<String>> {
+    let (from, to) = (time("2017-12-31 00:00:00"), time("2018-01-31 00:00:00"));
+    let mut pages = Vec::new();
+    let mut after = None;
+    loop {
+        let page = find_page_by_account_and_dates(conn, ACCOUNT_ID, from, to, after.as_ref(), page_size).unwrap();
+        pages.push(page.items.into_iter().map(|line| line.id).collect());
+        match page.next_cursor {
+            Some(cursor) => after = Some(LineCursor::decode(&cursor).unwrap()),
+            None => return pages,
+        }
+    }
+}
+
+backend_test!(test_pages_follow_the_keyset_order, "tests/fixtures/ledger_account_dataset.sql", |conn| {
+    PostingLineRepository::save_all(conn, lines()).unwrap();
+
+    let pages = page_through(conn, 2);
+    assert_eq!(pages, vec![vec!["line_a", "line_b"], vec!["line_c", "line_d"], vec!["line_e"]]);
+
+    // A page holding all remaining lines has no next cursor.
+    let pages = page_through(conn, 5);
+    assert_eq!(pages, vec![EXPECTED.to_vec()]);
+});
+
+backend_test!(test_pages_as_of_record_time, "tests/fixtures/ledger_account_dataset.sql", |conn| {
+    // line_a is discarded before `as_of`, line_b after it and line_e is recorded after it.
+    let mut lines = lines();
+    lines[4].discarded_time = Some(time("2018-01-02 10:00:00"));
+    lines[1].discarded_time = Some(time("2018-01-05 10:00:00"));
+    PostingLineRepository::save_all(conn, lines).unwrap();
+
+    let (from, to, as_of) = (time("2017-12-31 00:00:00"), time("2018-01-31 00:00:00"), time("2018-01-04 12:00:00"));
+    let first = find_page_by_account_and_dates_as_of(conn, ACCOUNT_ID, from, to, as_of, None, 2).unwrap();
+    assert_eq!(first.items.iter().map(|line| line.id.as_str()).collect::<Vec<_>>(), ["line_b", "line_c"]);
+    let after = LineCursor::decode(&first.next_cursor.unwrap()).unwrap();
+    let second = find_page_by_account_and_dates_as_of(conn, ACCOUNT_ID, from, to, as_of, Some(&after), 2).unwrap();
+    assert_eq!(second.items.iter().map(|line| line.id.as_str()).collect::<Vec<_>>(), ["line_d"]);
+    assert!(second.next_cursor.is_none());
+});
+
+backend_test!(test_stream_reads_all_lines_in_batches, "tests/fixtures/ledger_account_dataset.sql", |conn| {
+    PostingLineRepository::save_all(conn, lines()).unwrap();
+
+    let ids: Vec<String> = stream_by_account_and_dates(
+        conn,
+        ACCOUNT_ID,
+        time("2018-01-01 10:00:00"),
+        time("2018-01-31 00:00:00"),
+        2,
+    )
+    .map(|line| line.unwrap().id)
+    .collect();
+    assert_eq!(ids, EXPECTED[1..].to_vec());
+});
+
+#[test]
+fn test_pages_in_memory() {
+    let mut store = InMemoryRepositories::new();
+    let created = time("2018-01-01 00:00:00");
+    ChartOfAccountRepository::save(
+        &mut store,
+        NewChartOfAccount {
+            id: "coa".to_string(),
+            created,
+            user_details: "Francis".to_string(),
+            short_desc: None,
+            long_desc: None,
+            name: "IFRS".to_string(),
+        },
+    )
+    .unwrap();
+    LedgerRepository::save(
+        &mut store,
+        NewLedger {
+            id: "ledger".to_string(),
+            created,
+            user_details: "Francis".to_string(),
+            short_desc: None,
+            long_desc: None,
+            name: "GL".to_string(),
+            coa_id: "coa".to_string(),
+        },
+    )
+    .unwrap();
+    LedgerAccountRepository::save(
+        &mut store,
+        NewLedgerAccount {
+            id: ACCOUNT_ID.to_string(),
+            created,
+            user_details: "Francis".to_string(),
+            short_desc: None,
+            long_desc: None,
+            name: "1.0.0".to_string(),
+            ledger_id: "ledger".to_string(),
+            parent_id: None,
+            coa_id: "coa".to_string(),
+            balance_side: BalanceSide::Dr,
+            category: AccountCategory::AS,
+        },
+    )
+    .unwrap();
+    PostingLineRepository::save_all(&mut store, lines()).unwrap();
+
+    assert_eq!(page_through(&mut store, 3), vec![vec!["line_a", "line_b", "line_c"], vec!["line_d", "line_e"]]);
+}
+
+#[test]
+fn test_cursor_round_trip() {
+    let cursor = LineCursor {
+        pst_time: time("2018-01-02 10:00:00"),
+        record_time: time("2018-01-04 10:00:00.123456789"),
+        id: "line|with|bars".to_string(),
+    };
+    assert_eq!(LineCursor::decode(&cursor.encode()), Ok(cursor));
+
+    assert_eq!(LineCursor::decode("not a cursor"), Err(InvalidCursor));
+    assert_eq!(LineCursor::decode(&hex_of("2018-01-02T10:00:00|garbage")), Err(InvalidCursor));
+}
+
+fn hex_of(text: &str) -> String {
+    text.bytes().map(|byte| format!("{:02x}", byte)).collect()
+}
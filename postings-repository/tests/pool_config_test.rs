@@ -0,0 +1,43 @@
+// tests/pool_config_test.rs
+//
+// Copyright (c) 2018-2024 adorsys GmbH and Co. KG
+// All rights are reserved.
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use postings_repository::pool::{PoolConfig, PoolConfigError};
+
+fn config_of(vars: &[(&str, &str)]) -> Result<PoolConfig, PoolConfigError> {
+    let vars: HashMap<String, String> = vars.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect();
+    PoolConfig::from_lookup(|variable| vars.get(variable).cloned())
+}
+
+#[test]
+fn test_defaults_when_nothing_is_set() {
+    assert_eq!(config_of(&[]).unwrap(), PoolConfig::default());
+}
+
+#[test]
+fn test_settings_are_read() {
+    let config = config_of(&[
+        ("DB_POOL_MAX_SIZE", "32"),
+        ("DB_POOL_MIN_IDLE", "4"),
+        ("DB_POOL_CONNECTION_TIMEOUT_SECS", " 5 "),
+        ("DB_POOL_IDLE_TIMEOUT_SECS", "60"),
+    ])
+    .unwrap();
+
+    assert_eq!(config.max_size, 32);
+    assert_eq!(config.min_idle, Some(4));
+    assert_eq!(config.connection_timeout, Duration::from_secs(5));
+    assert_eq!(config.idle_timeout, Duration::from_secs(60));
+    assert_eq!(config.max_lifetime, PoolConfig::default().max_lifetime);
+}
+
+#[test]
+fn test_invalid_setting_is_reported() {
+    let error = config_of(&[("DB_POOL_MAX_SIZE", "many")]).unwrap_err();
+    assert_eq!(error.variable, "DB_POOL_MAX_SIZE");
+    assert_eq!(error.to_string(), "invalid value \"many\" for DB_POOL_MAX_SIZE");
+}
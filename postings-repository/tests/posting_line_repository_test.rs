@@ -0,0 +1,190 @@
+// tests/posting_line_repository_test.rs
+//
+// Copyright (c) 2018-2024 adorsys GmbH and Co. KG
+// All rights are reserved.
+
+mod common;
+
+use chrono::NaiveDateTime;
+use common::backend_test;
+use postings_repository::models::NewPostingLine;
+use postings_repository::models::enums::{PostingStatus, PostingType};
+use postings_repository::repository::PostingLineRepository;
+use rust_decimal::Decimal;
+
+fn record_time() -> NaiveDateTime {
+    NaiveDateTime::parse_from_str("2018-01-01 10:00:00", "%Y-%m-%d %H:%M:%S").unwrap()
+}
+
+fn new_line(id: &str, account_id: &str, debit: Decimal, credit: Decimal) -> NewPostingLine {
+    NewPostingLine {
+        id: id.to_string(),
+        account_id: account_id.to_string(),
+        debit_amount: debit,
+        credit_amount: credit,
+        details_id: None,
+        src_account: None,
+        base_line: None,
+        sub_opr_src_id: None,
+        record_time: record_time(),
+        opr_id: "opr_001".to_string(),
+        opr_src: None,
+        pst_time: record_time(),
+        pst_type: PostingType::BusiTx,
+        pst_status: PostingStatus::POSTED,
+        hash: "hash".to_string(),
+        discarded_time: None,
+    }
+}
+
+backend_test!(test_create_posting_line_ok, "tests/fixtures/ledger_account_dataset.sql", |conn| {
+    let saved = PostingLineRepository::save(
+        conn,
+        new_line("line_001", "xVgaTPMcRty9ik3BTQDh1Q_BS_1_0_0", Decimal::new(500, 0), Decimal::ZERO),
+    )
+    .expect("Failed to save PostingLine");
+
+    assert_eq!(saved.debit_amount, Decimal::new(500, 0));
+});
+
+backend_test!(test_create_posting_line_no_account, "tests/fixtures/ledger_account_dataset.sql", |conn| {
+    let result = PostingLineRepository::save(
+        conn,
+        new_line("line_001", "unknown-account", Decimal::new(500, 0), Decimal::ZERO),
+    );
+    assert!(result.is_err(), "Expected error when saving a PostingLine with an unknown account");
+});
+
+backend_test!(test_exists_by_account, "tests/fixtures/ledger_account_dataset.sql", |conn| {
+    assert!(!PostingLineRepository::exists_by_account(conn, "xVgaTPMcRty9ik3BTQDh1Q_BS_1_0_0").unwrap());
+    PostingLineRepository::save(
+        conn,
+        new_line("line_001", "xVgaTPMcRty9ik3BTQDh1Q_BS_1_0_0", Decimal::new(500, 0), Decimal::ZERO),
+    )
+    .expect("Failed to save PostingLine");
+
+    assert!(PostingLineRepository::exists_by_account(conn, "xVgaTPMcRty9ik3BTQDh1Q_BS_1_0_0").unwrap());
+    assert!(!PostingLineRepository::exists_by_account(conn, "xVgaTPMcRty9ik3BTQDh1Q_BS_2_0_0").unwrap());
+});
+
+backend_test!(test_save_all_and_find_by_opr_id_and_record_time, "tests/fixtures/ledger_account_dataset.sql", |conn| {
+    PostingLineRepository::save_all(
+        conn,
+        vec![
+            new_line("line_001", "xVgaTPMcRty9ik3BTQDh1Q_BS_1_0_0", Decimal::new(500, 0), Decimal::ZERO),
+            new_line("line_002", "xVgaTPMcRty9ik3BTQDh1Q_BS_2_0_0", Decimal::ZERO, Decimal::new(500, 0)),
+        ],
+    )
+    .expect("Failed to save PostingLines");
+
+    let lines = PostingLineRepository::find_by_opr_id_and_record_time(conn, "opr_001", record_time())
+        .expect("Error fetching PostingLines");
+    assert_eq!(lines.len(), 2);
+    assert_eq!(lines[0].id, "line_001");
+    assert_eq!(lines[1].id, "line_002");
+});
+
+backend_test!(test_update_discarded_time_by_opr_id_and_record_time, "tests/fixtures/ledger_account_dataset.sql", |conn| {
+    PostingLineRepository::save_all(
+        conn,
+        vec![
+            new_line("line_001", "xVgaTPMcRty9ik3BTQDh1Q_BS_1_0_0", Decimal::new(500, 0), Decimal::ZERO),
+            new_line("line_002", "xVgaTPMcRty9ik3BTQDh1Q_BS_2_0_0", Decimal::ZERO, Decimal::new(500, 0)),
+        ],
+    )
+    .expect("Failed to save PostingLines");
+
+    let discarded_time = NaiveDateTime::parse_from_str("2018-01-02 10:00:00", "%Y-%m-%d %H:%M:%S").unwrap();
+    let updated = PostingLineRepository::update_discarded_time_by_opr_id_and_record_time(
+        conn, "opr_001", record_time(), discarded_time,
+    )
+    .expect("Failed to discard PostingLines");
+    assert_eq!(updated, 2);
+
+    // Discarded lines no longer show up in the account statement.
+    let lines = PostingLineRepository::find_by_account_and_pst_time_lte_and_discarded_is_null_order_by_record_time_desc(
+        conn, "xVgaTPMcRty9ik3BTQDh1Q_BS_1_0_0", discarded_time,
+    )
+    .expect("Error fetching PostingLines");
+    assert!(lines.is_empty());
+});
+
+backend_test!(test_proposed_lines_do_not_count, "tests/fixtures/ledger_account_dataset.sql", |conn| {
+    let mut proposed = new_line("line_001", "xVgaTPMcRty9ik3BTQDh1Q_BS_1_0_0", Decimal::new(500, 0), Decimal::ZERO);
+    proposed.pst_status = PostingStatus::PROPOSED;
+    PostingLineRepository::save(conn, proposed.clone()).expect("Failed to save PostingLine");
+
+    let lines = PostingLineRepository::find_by_account_and_pst_time_lte_and_discarded_is_null_order_by_record_time_desc(
+        conn, "xVgaTPMcRty9ik3BTQDh1Q_BS_1_0_0", record_time(),
+    )
+    .expect("Error fetching PostingLines");
+    assert!(lines.is_empty());
+
+    proposed.pst_status = PostingStatus::POSTED;
+    assert_eq!(PostingLineRepository::update_pending(conn, &proposed, PostingStatus::PROPOSED).unwrap(), 1);
+    assert_eq!(PostingLineRepository::update_pending(conn, &proposed, PostingStatus::PROPOSED).unwrap(), 0);
+    let lines = PostingLineRepository::find_by_account_and_pst_time_lte_and_discarded_is_null_order_by_record_time_desc(
+        conn, "xVgaTPMcRty9ik3BTQDh1Q_BS_1_0_0", record_time(),
+    )
+    .expect("Error fetching PostingLines");
+    assert_eq!(lines.len(), 1);
+});
+
+backend_test!(test_simulated_lines_are_kept_apart, "tests/fixtures/ledger_account_dataset.sql", |conn| {
+    let account = "xVgaTPMcRty9ik3BTQDh1Q_BS_1_0_0";
+    PostingLineRepository::save(conn, new_line("line_001", account, Decimal::new(500, 0), Decimal::ZERO)).unwrap();
+    let mut simulated = new_line("line_002", account, Decimal::new(20, 0), Decimal::ZERO);
+    simulated.pst_status = PostingStatus::SIMULATED;
+    PostingLineRepository::save(conn, simulated).unwrap();
+
+    let lines = PostingLineRepository::find_by_account_and_pst_time_lte_and_discarded_is_null_order_by_record_time_desc(
+        conn, account, record_time(),
+    )
+    .unwrap();
+    assert_eq!(lines.iter().map(|line| line.id.as_str()).collect::<Vec<_>>(), ["line_001"]);
+    let lines = PostingLineRepository::find_simulated_by_account_and_pst_time_lte(conn, account, record_time()).unwrap();
+    assert_eq!(lines.iter().map(|line| line.id.as_str()).collect::<Vec<_>>(), ["line_002"]);
+
+    assert_eq!(PostingLineRepository::delete_simulated_by_ledger(conn, "Zd0ND5YwSzGwIfZilhumPg").unwrap(), 1);
+    assert!(PostingLineRepository::find_simulated_by_account_and_pst_time_lte(conn, account, record_time())
+        .unwrap()
+        .is_empty());
+    assert!(PostingLineRepository::find_first_by_id_and_account(conn, "line_001", account).unwrap().is_some());
+});
+
+backend_test!(test_find_lines_as_of_record_time, "tests/fixtures/ledger_account_dataset.sql", |conn| {
+    let account = "xVgaTPMcRty9ik3BTQDh1Q_BS_1_0_0";
+    let time = |value: &str| NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S").unwrap();
+    // line_001 is corrected by line_002 on 2018-01-03, line_003 is backdated on 2018-01-05.
+    PostingLineRepository::save(conn, new_line("line_001", account, Decimal::new(500, 0), Decimal::ZERO)).unwrap();
+    PostingLineRepository::update_discarded_time_by_opr_id_and_record_time(conn, "opr_001", record_time(), time("2018-01-03 10:00:00"))
+        .unwrap();
+    let mut correction = new_line("line_002", account, Decimal::new(400, 0), Decimal::ZERO);
+    correction.record_time = time("2018-01-03 10:00:00");
+    PostingLineRepository::save(conn, correction).unwrap();
+    let mut backdated = new_line("line_003", account, Decimal::new(20, 0), Decimal::ZERO);
+    backdated.opr_id = "opr_002".to_string();
+    backdated.record_time = time("2018-01-05 10:00:00");
+    backdated.pst_time = time("2017-12-31 10:00:00");
+    PostingLineRepository::save(conn, backdated).unwrap();
+
+    let ids = |lines: Vec<postings_repository::models::PostingLine>| lines.into_iter().map(|line| line.id).collect::<Vec<_>>();
+    let ref_time = time("2018-01-31 00:00:00");
+    let lines = PostingLineRepository::find_by_account_and_pst_time_lte_as_of(conn, account, ref_time, time("2017-12-31 10:00:00"));
+    assert!(lines.unwrap().is_empty());
+    let lines = PostingLineRepository::find_by_account_and_pst_time_lte_as_of(conn, account, ref_time, time("2018-01-02 10:00:00"));
+    assert_eq!(ids(lines.unwrap()), ["line_001"]);
+    let lines = PostingLineRepository::find_by_account_and_pst_time_lte_as_of(conn, account, ref_time, time("2018-01-03 10:00:00"));
+    assert_eq!(ids(lines.unwrap()), ["line_002"]);
+    let lines = PostingLineRepository::find_by_account_and_pst_time_lte_as_of(conn, account, ref_time, time("2018-01-06 10:00:00"));
+    assert_eq!(ids(lines.unwrap()), ["line_003", "line_002"]);
+
+    let lines = PostingLineRepository::find_by_account_and_dates_as_of(
+        conn,
+        account,
+        time("2018-01-01 00:00:00"),
+        ref_time,
+        time("2018-01-06 10:00:00"),
+    );
+    assert_eq!(ids(lines.unwrap()), ["line_002"]);
+});
@@ -1,63 +1,166 @@
 mod common;
 
-use common::{establish_connection, seed_database, TestDatabaseGuard};
-use postings_repository::repository::posting_repository;
-use serial_test::serial;
-
-#[test]
-#[serial]
-fn test_find_by_id() {
-    // establish a connection
-    let mut conn = establish_connection();
-    //seed fixture data
-    seed_database(&mut conn, "tests/fixtures/posting_dataset.sql");
-    // create a guard that will perform cleanup after the test
-    let _guard = TestDatabaseGuard::new();  
+use chrono::NaiveDateTime;
+use common::backend_test;
+use postings_repository::models::NewPosting;
+use postings_repository::models::enums::{PostingStatus, PostingType};
+use postings_repository::repository::PostingRepository;
 
+backend_test!(test_find_by_id, "tests/fixtures/posting_dataset.sql", |conn| {
     // Test retrieving a posting by ID
-    let posting = posting_repository::find_by_id(&mut conn, "pst_001").unwrap();
+    let posting = PostingRepository::find_by_id(conn, "pst_001").unwrap();
     assert_eq!(posting.unwrap().id, "pst_001");
-}
+});
 
-#[test]
-#[serial]
-fn test_find_by_opr_id() {  
-    // establish a connection
-    let mut conn = establish_connection();
-    //seed fixture data
-    seed_database(&mut conn, "tests/fixtures/posting_dataset.sql");
-    // create a guard that will perform cleanup after the test
-    let _guard = TestDatabaseGuard::new();  
+backend_test!(test_find_by_opr_id, "tests/fixtures/posting_dataset.sql", |conn| {
     // Test retrieving postings by operation ID
-    let postings = posting_repository::find_by_opr_id(&mut conn, "opr_001").unwrap();
+    let postings = PostingRepository::find_by_opr_id(conn, "opr_001").unwrap();
     assert_eq!(postings.len(), 1);
     assert_eq!(postings[0].id, "pst_001");
-}
+});
 
-#[test]
-#[serial]
-fn test_find_by_opr_id_and_discarding_id_is_null() {    
-    // establish a connection
-    let mut conn = establish_connection();
-    //seed fixture data
-    seed_database(&mut conn, "tests/fixtures/posting_dataset.sql");
-    // create a guard that will perform cleanup after the test
-    let _guard = TestDatabaseGuard::new();  
+backend_test!(test_find_by_opr_id_and_discarding_id_is_null, "tests/fixtures/posting_dataset.sql", |conn| {
     // Test finding non-discarded postings
-    let posting = posting_repository::find_by_opr_id_and_discarding_id_is_null(&mut conn, "opr_001").unwrap();
+    let posting = PostingRepository::find_by_opr_id_and_discarding_id_is_null(conn, "opr_001").unwrap();
     assert_eq!(posting.unwrap().id, "pst_001");
-}
+});
 
-#[test]
-#[serial]
-fn test_find_first_by_ledger_order_by_record_time_desc() {
-    // establish a connection
-    let mut conn = establish_connection();
-    //seed fixture data
-    seed_database(&mut conn, "tests/fixtures/posting_dataset.sql");
-    // create a guard that will perform cleanup after the test
-    let _guard = TestDatabaseGuard::new();  
+backend_test!(test_find_first_by_ledger_order_by_record_time_desc, "tests/fixtures/posting_dataset.sql", |conn| {
     // Test finding the most recent posting for a ledger
-    let posting = posting_repository::find_first_by_ledger_order_by_record_time_desc(&mut conn, "ledger_001").unwrap();
+    let posting = PostingRepository::find_first_by_ledger_order_by_record_time_desc(conn, "ledger_001").unwrap();
+    assert_eq!(posting.unwrap().id, "pst_001");
+});
+fn new_posting(pst_id: &str, opr_id_val: &str) -> NewPosting {
+    let time = NaiveDateTime::parse_from_str("2024-01-02 10:00:00", "%Y-%m-%d %H:%M:%S").unwrap();
+    NewPosting {
+        id: pst_id.to_string(),
+        antecedent_id: None,
+        antecedent_hash: None,
+        hash: None,
+        hash_alg: None,
+        record_user: "test_user".to_string(),
+        record_time: time,
+        opr_id: opr_id_val.to_string(),
+        opr_time: None,
+        opr_type: None,
+        opr_src: None,
+        pst_time: time,
+        pst_type: PostingType::BusiTx,
+        pst_status: PostingStatus::POSTED,
+        ledger_id: "ledger_001".to_string(),
+        val_time: None,
+        discarded_id: None,
+        discarded_time: None,
+        discarding_id: None,
+        opr_details_id: None,
+    }
+}
+
+backend_test!(test_save, "tests/fixtures/posting_dataset.sql", |conn| {
+    PostingRepository::save(conn, new_posting("pst_004", "opr_004")).unwrap();
+
+    // The new posting is the youngest one of the ledger.
+    let posting = PostingRepository::find_first_by_ledger_order_by_record_time_desc(conn, "ledger_001").unwrap();
+    assert_eq!(posting.unwrap().id, "pst_004");
+});
+
+backend_test!(test_save_no_ledger, "tests/fixtures/posting_dataset.sql", |conn| {
+    let mut posting = new_posting("pst_004", "opr_004");
+    posting.ledger_id = "unknown_ledger".to_string();
+    assert!(PostingRepository::save(conn, posting).is_err());
+});
+
+backend_test!(test_save_all, "tests/fixtures/posting_dataset.sql", |conn| {
+    let postings = PostingRepository::save_all(
+        conn,
+        vec![new_posting("pst_004", "opr_004"), new_posting("pst_005", "opr_005")],
+    )
+    .unwrap();
+    assert_eq!(postings.len(), 2);
+});
+
+backend_test!(test_update_discarding_id, "tests/fixtures/posting_dataset.sql", |conn| {
+    // Re-post operation opr_001: pst_004 discards pst_001.
+    PostingRepository::save(conn, new_posting("pst_004", "opr_001")).unwrap();
+    let updated = PostingRepository::update_discarding_id(conn, "pst_001", "pst_004").unwrap();
+    assert_eq!(updated, 1);
+
+    // A posting can only be discarded once.
+    let updated = PostingRepository::update_discarding_id(conn, "pst_001", "pst_005").unwrap();
+    assert_eq!(updated, 0);
+
+    // Only the youngest posting of the operation is effective.
+    let postings = PostingRepository::find_by_opr_id(conn, "opr_001").unwrap();
+    assert_eq!(postings.len(), 2);
+    let posting = PostingRepository::find_by_opr_id_and_discarding_id_is_null(conn, "opr_001").unwrap();
+    assert_eq!(posting.unwrap().id, "pst_004");
+});
+
+backend_test!(test_proposed_postings_are_not_effective, "tests/fixtures/posting_dataset.sql", |conn| {
+    let mut proposal = new_posting("pst_004", "opr_001");
+    proposal.pst_status = PostingStatus::PROPOSED;
+    PostingRepository::save(conn, proposal.clone()).unwrap();
+
+    // The proposal is neither the effective posting of its operation nor part of the journal.
+    let posting = PostingRepository::find_by_opr_id_and_discarding_id_is_null(conn, "opr_001").unwrap();
+    assert_eq!(posting.unwrap().id, "pst_001");
+    let posting = PostingRepository::find_first_by_ledger_order_by_record_time_desc(conn, "ledger_001").unwrap();
+    assert_eq!(posting.unwrap().id, "pst_001");
+    let journal = PostingRepository::find_by_ledger_order_by_record_time_asc(conn, "ledger_001").unwrap();
+    assert!(journal.iter().all(|posting| posting.id != "pst_004"));
+
+    proposal.pst_status = PostingStatus::POSTED;
+    proposal.hash = Some("hash".to_string());
+    assert_eq!(PostingRepository::update_pending(conn, &proposal, PostingStatus::PROPOSED).unwrap(), 1);
+    let posting = PostingRepository::find_by_id(conn, "pst_004").unwrap().unwrap();
+    assert_eq!(posting.pst_status, PostingStatus::POSTED);
+    assert_eq!(posting.hash.as_deref(), Some("hash"));
+    let posting = PostingRepository::find_first_by_ledger_order_by_record_time_desc(conn, "ledger_001").unwrap();
+    assert_eq!(posting.unwrap().id, "pst_004");
+
+    // Only proposed postings are updated.
+    proposal.pst_status = PostingStatus::CANCELLED;
+    assert_eq!(PostingRepository::update_pending(conn, &proposal, PostingStatus::PROPOSED).unwrap(), 0);
+});
+
+backend_test!(test_delete_simulated_postings, "tests/fixtures/posting_dataset.sql", |conn| {
+    let mut simulated = new_posting("pst_004", "opr_004");
+    simulated.pst_status = PostingStatus::SIMULATED;
+    PostingRepository::save(conn, simulated).unwrap();
+
+    let posting = PostingRepository::find_first_by_ledger_order_by_record_time_desc(conn, "ledger_001").unwrap();
     assert_eq!(posting.unwrap().id, "pst_001");
-}
\ No newline at end of file
+    assert!(PostingRepository::find_by_opr_id_and_discarding_id_is_null(conn, "opr_004").unwrap().is_none());
+
+    assert_eq!(PostingRepository::delete_simulated_by_ledger(conn, "missing").unwrap(), 0);
+    assert_eq!(PostingRepository::delete_simulated_by_ledger(conn, "ledger_001").unwrap(), 1);
+    assert!(PostingRepository::find_by_id(conn, "pst_004").unwrap().is_none());
+    assert!(PostingRepository::find_by_id(conn, "pst_001").unwrap().is_some());
+});
+
+backend_test!(test_find_due_deferred_postings, "tests/fixtures/posting_dataset.sql", |conn| {
+    let time = |value: &str| NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S").unwrap();
+    for (pst_id, pst_time) in [("pst_005", "2024-03-01 00:00:00"), ("pst_004", "2024-02-01 00:00:00")] {
+        let mut deferred = new_posting(pst_id, pst_id);
+        deferred.pst_status = PostingStatus::DEFERRED;
+        deferred.pst_time = time(pst_time);
+        PostingRepository::save(conn, deferred).unwrap();
+    }
+
+    // Deferred postings are not effective until promoted.
+    let posting = PostingRepository::find_first_by_ledger_order_by_record_time_desc(conn, "ledger_001").unwrap();
+    assert_eq!(posting.unwrap().id, "pst_001");
+
+    let due = PostingRepository::find_by_pst_status_and_pst_time_lte(conn, PostingStatus::DEFERRED, time("2024-01-31 00:00:00")).unwrap();
+    assert!(due.is_empty());
+    let due = PostingRepository::find_by_pst_status_and_pst_time_lte(conn, PostingStatus::DEFERRED, time("2024-03-01 00:00:00")).unwrap();
+    let ids: Vec<&str> = due.iter().map(|posting| posting.id.as_str()).collect();
+    assert_eq!(ids, ["pst_004", "pst_005"]);
+
+    let mut promoted = new_posting("pst_004", "pst_004");
+    promoted.pst_time = time("2024-02-01 00:00:00");
+    assert_eq!(PostingRepository::update_pending(conn, &promoted, PostingStatus::DEFERRED).unwrap(), 1);
+    assert_eq!(PostingRepository::update_pending(conn, &promoted, PostingStatus::DEFERRED).unwrap(), 0);
+    let due = PostingRepository::find_by_pst_status_and_pst_time_lte(conn, PostingStatus::DEFERRED, time("2024-03-01 00:00:00")).unwrap();
+    assert_eq!(due.len(), 1);
+});
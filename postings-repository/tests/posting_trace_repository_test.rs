@@ -0,0 +1,50 @@
+// tests/posting_trace_repository_test.rs
+//
+// Copyright (c) 2018-2024 adorsys GmbH and Co. KG
+// All rights are reserved.
+
+mod common;
+
+use common::backend_test;
+use postings_repository::models::NewPostingTrace;
+use postings_repository::repository::PostingTraceRepository;
+use rust_decimal::Decimal;
+
+fn new_trace(id: &str, src_pst_id: &str) -> NewPostingTrace {
+    NewPostingTrace {
+        id: id.to_string(),
+        tgt_pst_id: "stmt_pst_001".to_string(),
+        src_pst_id: src_pst_id.to_string(),
+        src_pst_time: None,
+        src_opr_id: format!("opr_of_{}", src_pst_id),
+        account_id: "xVgaTPMcRty9ik3BTQDh1Q_BS_1_0_0".to_string(),
+        debit_amount: Decimal::new(100, 0),
+        credit_amount: Decimal::ZERO,
+        src_pst_hash: None,
+    }
+}
+
+backend_test!(test_create_posting_trace_ok, "tests/fixtures/ledger_account_dataset.sql", |conn| {
+    PostingTraceRepository::save(conn, new_trace("generated-trace-id-001", "pst_001"))
+        .expect("Failed to save PostingTrace");
+
+    let found = PostingTraceRepository::find_by_id(conn, "generated-trace-id-001")
+        .expect("Error fetching PostingTrace")
+        .expect("PostingTrace generated-trace-id-001 should exist");
+    assert_eq!(found.src_pst_id, "pst_001");
+});
+
+backend_test!(test_save_all_and_find_by_tgt_pst_id, "tests/fixtures/ledger_account_dataset.sql", |conn| {
+    PostingTraceRepository::save_all(
+        conn,
+        vec![
+            new_trace("generated-trace-id-002", "pst_001"),
+            new_trace("generated-trace-id-003", "pst_002"),
+        ],
+    )
+    .expect("Failed to save PostingTraces");
+
+    let traces = PostingTraceRepository::find_by_tgt_pst_id(conn, "stmt_pst_001")
+        .expect("Error fetching PostingTraces");
+    assert_eq!(traces.len(), 2);
+});
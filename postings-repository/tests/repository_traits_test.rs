@@ -0,0 +1,42 @@
+// tests/repository_traits_test.rs
+//
+// Copyright (c) 2018-2024 adorsys GmbH and Co. KG
+// All rights are reserved.
+
+mod common;
+
+use chrono::DateTime;
+use common::backend_test;
+use postings_repository::models::{ChartOfAccount, NewChartOfAccount};
+use postings_repository::repository::{ChartOfAccountRepository, Repositories, Transactional};
+
+/// Saves a chart of account through any repository implementation.
+fn save_coa<C: Repositories>(conn: &mut C, id: &str, name: &str) -> ChartOfAccount {
+    let new_coa = NewChartOfAccount {
+        id: id.to_string(),
+        created: DateTime::from_timestamp(1627833600, 0).unwrap().naive_utc(),
+        user_details: "francis".to_string(),
+        short_desc: None,
+        long_desc: None,
+        name: name.to_string(),
+    };
+    ChartOfAccountRepository::save(conn, new_coa).expect("Failed to create ChartOfAccount")
+}
+
+backend_test!(test_pg_connection_implements_repositories, "tests/fixtures/chart_of_account_dataset.sql", |conn| {
+    let saved = save_coa(conn, "traits-coa-id-001", "TraitsCoA");
+    let loaded = ChartOfAccountRepository::find_by_id(conn, &saved.id)
+        .expect("Error fetching ChartOfAccount")
+        .expect("ChartOfAccount should exist");
+    assert_eq!(loaded.id, saved.id);
+    assert_eq!(loaded.name, "TraitsCoA");
+});
+
+backend_test!(test_transaction_rolls_back_on_error, "tests/fixtures/chart_of_account_dataset.sql", |conn| {
+    let result: Result<(), diesel::result::Error> = conn.transaction(|conn| {
+        save_coa(conn, "traits-coa-id-002", "RolledBackCoA");
+        Err(diesel::result::Error::RollbackTransaction)
+    });
+    assert!(result.is_err());
+    assert!(ChartOfAccountRepository::find_by_id(conn, "traits-coa-id-002").unwrap().is_none());
+});
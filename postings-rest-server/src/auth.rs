@@ -0,0 +1,39 @@
+// src/auth.rs
+/* 
+ * Copyright (c) 2018-2024 adorsys GmbH and Co. KG
+ * All rights are reserved.
+ */
+
+use axum::extract::FromRequestParts;
+use axum::http::request::Parts;
+use axum::http::StatusCode;
+
+use crate::error::ApiError;
+use crate::state::AppState;
+
+/// The header carrying the authenticated user, unless configured otherwise.
+pub const DEFAULT_USER_HEADER: &str = "x-authenticated-user";
+
+/// The user acting on a request, as authenticated by the deployment.
+///
+/// The server does not authenticate users itself: the gateway in front of it
+/// authenticates the caller and passes the user in the header configured by
+/// `AppState::with_user_header`. The gateway must drop that header from
+/// incoming requests, otherwise clients could act as any user.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuthenticatedUser(pub String);
+
+impl FromRequestParts<AppState> for AuthenticatedUser {
+    type Rejection = ApiError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &AppState) -> Result<Self, Self::Rejection> {
+        let user = parts
+            .headers
+            .get(&state.user_header)
+            .and_then(|value| value.to_str().ok())
+            .map(str::trim)
+            .filter(|user| !user.is_empty())
+            .ok_or_else(|| ApiError::new(StatusCode::UNAUTHORIZED, "no authenticated user"))?;
+        Ok(AuthenticatedUser(user.to_string()))
+    }
+}
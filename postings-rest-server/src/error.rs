@@ -0,0 +1,97 @@
+// src/error.rs
+/* 
+ * Copyright (c) 2018-2024 adorsys GmbH and Co. KG
+ * All rights are reserved.
+ */
+
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use postings_repository::repository::RepositoryError;
+use postings_service::ServiceError;
+use serde_json::json;
+
+/// An error answered with the given status and a JSON body `{"error": message}`.
+#[derive(Debug)]
+pub struct ApiError {
+    pub status: StatusCode,
+    pub message: String,
+}
+
+pub type ApiResult<T> = Result<T, ApiError>;
+
+impl ApiError {
+    pub fn new(status: StatusCode, message: impl Into<String>) -> Self {
+        Self { status, message: message.into() }
+    }
+
+    pub fn not_found(message: impl Into<String>) -> Self {
+        Self::new(StatusCode::NOT_FOUND, message)
+    }
+
+    pub fn bad_request(message: impl Into<String>) -> Self {
+        Self::new(StatusCode::BAD_REQUEST, message)
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        (self.status, Json(json!({ "error": self.message }))).into_response()
+    }
+}
+
+/// Maps repository errors: missing rows are not found, constraint violations
+/// are conflicts with the stored data or invalid input.
+impl From<RepositoryError> for ApiError {
+    fn from(err: RepositoryError) -> Self {
+        let status = match &err {
+            RepositoryError::NotFound => StatusCode::NOT_FOUND,
+            RepositoryError::UniqueViolation { .. }
+            | RepositoryError::ForeignKeyViolation { .. }
+            | RepositoryError::SerializationFailure => StatusCode::CONFLICT,
+            RepositoryError::CheckViolation { .. } => StatusCode::UNPROCESSABLE_ENTITY,
+            RepositoryError::Connection(_) => StatusCode::SERVICE_UNAVAILABLE,
+            RepositoryError::Other(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+        Self::new(status, err.to_string())
+    }
+}
+
+impl From<ServiceError> for ApiError {
+    fn from(err: ServiceError) -> Self {
+        if let ServiceError::Repository(err) = err {
+            return err.into();
+        }
+        let status = match &err {
+            ServiceError::CoaNotFound(_)
+            | ServiceError::LedgerNotFound(_)
+            | ServiceError::AccountNotFound(_)
+            | ServiceError::OperationNotFound(_)
+            | ServiceError::PostingNotFound(_)
+            | ServiceError::OpNoteNotFound(_) => StatusCode::NOT_FOUND,
+            ServiceError::AccountStmtClosed { .. }
+            | ServiceError::LedgerClosed { .. }
+            | ServiceError::RecordTimeNotAfterAntecedent { .. }
+            | ServiceError::PostingNotProposed { .. }
+            | ServiceError::OpNoteNotDue { .. }
+            | ServiceError::OpNoteExecuted { .. }
+            | ServiceError::OpNoteLocked(_)
+            | ServiceError::AccountBooked { .. } => StatusCode::CONFLICT,
+            ServiceError::CheckerIsMaker { .. } => StatusCode::FORBIDDEN,
+            _ => StatusCode::UNPROCESSABLE_ENTITY,
+        };
+        Self::new(status, err.to_string())
+    }
+}
+
+impl From<diesel::r2d2::PoolError> for ApiError {
+    fn from(err: diesel::r2d2::PoolError) -> Self {
+        Self::new(StatusCode::SERVICE_UNAVAILABLE, err.to_string())
+    }
+}
+
+impl From<tokio::task::JoinError> for ApiError {
+    fn from(err: tokio::task::JoinError) -> Self {
+        Self::new(StatusCode::INTERNAL_SERVER_ERROR, err.to_string())
+    }
+}
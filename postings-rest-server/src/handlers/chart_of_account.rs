@@ -0,0 +1,102 @@
+// src/handlers/chart_of_account.rs
+/* 
+ * Copyright (c) 2018-2024 adorsys GmbH and Co. KG
+ * All rights are reserved.
+ */
+
+use axum::extract::{Path, Query, State};
+use axum::http::StatusCode;
+use axum::Json;
+use postings_repository::models::{ChartOfAccount, NewChartOfAccount};
+use postings_repository::repository::chart_of_account_repository;
+use postings_service::{BuiltinTemplate, CoaDiff, CoaTemplate, ImportedChart};
+use serde::Deserialize;
+
+use super::{check_same_id, deleted, DiffQuery};
+use crate::error::{ApiError, ApiResult};
+use crate::state::AppState;
+
+/// A template to import, either built in, e.g. `"SKR03"`, or given in full.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+pub enum TemplateSource {
+    Builtin(BuiltinTemplate),
+    Custom(CoaTemplate),
+}
+
+/// Creates a chart of account, a ledger and its accounts from a template.
+#[derive(Debug, Deserialize)]
+pub struct ImportRequest {
+    pub template: TemplateSource,
+    pub ledger_name: String,
+    pub user_details: String,
+}
+
+pub async fn list(State(state): State<AppState>) -> ApiResult<Json<Vec<ChartOfAccount>>> {
+    state.run(|_, conn| Ok(Json(chart_of_account_repository::find_all(conn)?))).await
+}
+
+pub async fn create(
+    State(state): State<AppState>,
+    Json(new_coa): Json<NewChartOfAccount>,
+) -> ApiResult<(StatusCode, Json<ChartOfAccount>)> {
+    state
+        .run(|_, conn| Ok((StatusCode::CREATED, Json(chart_of_account_repository::save(conn, new_coa)?))))
+        .await
+}
+
+pub async fn get(State(state): State<AppState>, Path(id): Path<String>) -> ApiResult<Json<ChartOfAccount>> {
+    state
+        .run(move |_, conn| {
+            chart_of_account_repository::find_by_id(conn, &id)?
+                .map(Json)
+                .ok_or_else(|| ApiError::not_found(format!("chart of account {} not found", id)))
+        })
+        .await
+}
+
+pub async fn update(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Json(coa): Json<ChartOfAccount>,
+) -> ApiResult<Json<ChartOfAccount>> {
+    check_same_id(&id, &coa.id)?;
+    state.run(move |_, conn| Ok(Json(chart_of_account_repository::update(conn, &coa)?))).await
+}
+
+pub async fn delete(State(state): State<AppState>, Path(id): Path<String>) -> ApiResult<StatusCode> {
+    state
+        .run(move |_, conn| deleted(chart_of_account_repository::delete_by_id(conn, &id)?, "chart of account", &id))
+        .await
+}
+
+pub async fn import(
+    State(state): State<AppState>,
+    Json(request): Json<ImportRequest>,
+) -> ApiResult<(StatusCode, Json<ImportedChart>)> {
+    let template = match request.template {
+        TemplateSource::Builtin(builtin) => builtin.template(),
+        TemplateSource::Custom(template) => template,
+    };
+    state
+        .run(move |state, conn| {
+            let imported =
+                state.coa_template_service.import(conn, &template, &request.ledger_name, &request.user_details)?;
+            Ok((StatusCode::CREATED, Json(imported)))
+        })
+        .await
+}
+
+/// Exports a chart of account as a template.
+pub async fn export(State(state): State<AppState>, Path(id): Path<String>) -> ApiResult<Json<CoaTemplate>> {
+    state.run(move |state, conn| Ok(Json(state.coa_template_service.export(conn, &id)?))).await
+}
+
+/// Compares the accounts of two charts of account.
+pub async fn diff(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Query(query): Query<DiffQuery>,
+) -> ApiResult<Json<CoaDiff>> {
+    state.run(move |state, conn| Ok(Json(state.coa_template_service.diff_charts(conn, &id, &query.with)?))).await
+}
@@ -0,0 +1,88 @@
+// src/handlers/ledger.rs
+/* 
+ * Copyright (c) 2018-2024 adorsys GmbH and Co. KG
+ * All rights are reserved.
+ */
+
+use axum::extract::{Path, Query, State};
+use axum::http::StatusCode;
+use axum::Json;
+use postings_repository::models::{Ledger, LedgerAccount, NewLedger};
+use postings_repository::repository::{ledger_account_repository, ledger_repository};
+use postings_service::{CoaDiff, CoaTemplate, SimulationPurge};
+
+use super::{check_same_id, deleted, DiffQuery};
+use crate::error::{ApiError, ApiResult};
+use crate::state::AppState;
+
+pub async fn list(State(state): State<AppState>) -> ApiResult<Json<Vec<Ledger>>> {
+    state.run(|_, conn| Ok(Json(ledger_repository::find_all(conn)?))).await
+}
+
+pub async fn create(
+    State(state): State<AppState>,
+    Json(new_ledger): Json<NewLedger>,
+) -> ApiResult<(StatusCode, Json<Ledger>)> {
+    state
+        .run(|_, conn| Ok((StatusCode::CREATED, Json(ledger_repository::save(conn, new_ledger)?))))
+        .await
+}
+
+pub async fn get(State(state): State<AppState>, Path(id): Path<String>) -> ApiResult<Json<Ledger>> {
+    state
+        .run(move |_, conn| {
+            ledger_repository::find_by_id(conn, &id)?
+                .map(Json)
+                .ok_or_else(|| ApiError::not_found(format!("ledger {} not found", id)))
+        })
+        .await
+}
+
+pub async fn update(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Json(ledger): Json<Ledger>,
+) -> ApiResult<Json<Ledger>> {
+    check_same_id(&id, &ledger.id)?;
+    state.run(move |_, conn| Ok(Json(ledger_repository::update(conn, &ledger)?))).await
+}
+
+pub async fn delete(State(state): State<AppState>, Path(id): Path<String>) -> ApiResult<StatusCode> {
+    state
+        .run(move |_, conn| deleted(ledger_repository::delete_by_id(conn, &id)?, "ledger", &id))
+        .await
+}
+
+/// Lists the accounts of a ledger.
+pub async fn accounts(State(state): State<AppState>, Path(id): Path<String>) -> ApiResult<Json<Vec<LedgerAccount>>> {
+    state
+        .run(move |_, conn| {
+            if ledger_repository::find_by_id(conn, &id)?.is_none() {
+                return Err(ApiError::not_found(format!("ledger {} not found", id)));
+            }
+            Ok(Json(ledger_account_repository::find_by_ledger(conn, &id)?))
+        })
+        .await
+}
+
+/// Exports the accounts of a ledger as a template.
+pub async fn export(State(state): State<AppState>, Path(id): Path<String>) -> ApiResult<Json<CoaTemplate>> {
+    state.run(move |state, conn| Ok(Json(state.coa_template_service.export_ledger(conn, &id)?))).await
+}
+
+/// Compares the accounts of two ledgers.
+pub async fn diff(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Query(query): Query<DiffQuery>,
+) -> ApiResult<Json<CoaDiff>> {
+    state.run(move |state, conn| Ok(Json(state.coa_template_service.diff_ledgers(conn, &id, &query.with)?))).await
+}
+
+/// Purges the simulated postings and statements of a ledger.
+pub async fn purge_simulation(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> ApiResult<Json<SimulationPurge>> {
+    state.run(move |state, conn| Ok(Json(state.simulation_service.purge(conn, &id)?))).await
+}
@@ -0,0 +1,217 @@
+// src/handlers/ledger_account.rs
+/* 
+ * Copyright (c) 2018-2024 adorsys GmbH and Co. KG
+ * All rights are reserved.
+ */
+
+use axum::extract::{Path, Query, State};
+use axum::http::StatusCode;
+use axum::Json;
+use chrono::{NaiveDateTime, Utc};
+use postings_repository::models::{LedgerAccount, NewLedgerAccount, PostingLine};
+use postings_repository::repository::ledger_account_repository;
+use postings_repository::repository::pagination::{LineCursor, Page};
+use postings_service::{AccountStmtReport, ReportNode};
+use serde::{Deserialize, Serialize};
+
+use super::{check_same_id, deleted, page_size};
+use crate::error::{ApiError, ApiResult};
+use crate::state::AppState;
+
+/// Query of a balance; the balance is read as of now if no time is given.
+#[derive(Debug, Deserialize)]
+pub struct BalanceQuery {
+    pub ref_time: Option<NaiveDateTime>,
+}
+
+/// Query of the balance of an account. The what-if balance includes the
+/// simulated postings. With `as_of`, the balance is read as recorded at that
+/// time.
+#[derive(Debug, Deserialize)]
+pub struct AccountBalanceQuery {
+    pub ref_time: Option<NaiveDateTime>,
+    #[serde(default)]
+    pub what_if: bool,
+    pub as_of: Option<NaiveDateTime>,
+}
+
+/// Query of an account statement over the posting times in `(from, to]`,
+/// as recorded at `as_of` if given. The lines are paged like `LinesQuery`.
+#[derive(Debug, Deserialize)]
+pub struct StatementQuery {
+    pub from: NaiveDateTime,
+    pub to: NaiveDateTime,
+    pub as_of: Option<NaiveDateTime>,
+    pub cursor: Option<String>,
+    pub limit: Option<usize>,
+}
+
+/// Query of a page of the lines posted in `(from, to]`. The first page is
+/// read without a cursor, the following ones with the `next_cursor` of the
+/// previous page.
+#[derive(Debug, Deserialize)]
+pub struct LinesQuery {
+    pub from: NaiveDateTime,
+    pub to: NaiveDateTime,
+    pub cursor: Option<String>,
+    pub limit: Option<usize>,
+}
+
+/// The balances of an account at both ends of a period and a page of the
+/// lines booked in between. The next page is read with `next_cursor`.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AccountStatement {
+    pub opening: AccountStmtReport,
+    pub closing: AccountStmtReport,
+    pub lines: Vec<PostingLine>,
+    pub next_cursor: Option<String>,
+}
+
+pub async fn create(
+    State(state): State<AppState>,
+    Json(new_account): Json<NewLedgerAccount>,
+) -> ApiResult<(StatusCode, Json<LedgerAccount>)> {
+    state
+        .run(|state, conn| {
+            let account = state.account_tree_service.create_account(conn, new_account)?;
+            Ok((StatusCode::CREATED, Json(account)))
+        })
+        .await
+}
+
+pub async fn get(State(state): State<AppState>, Path(id): Path<String>) -> ApiResult<Json<LedgerAccount>> {
+    state
+        .run(move |_, conn| {
+            ledger_account_repository::find_by_id(conn, &id)?
+                .map(Json)
+                .ok_or_else(|| ApiError::not_found(format!("ledger account {} not found", id)))
+        })
+        .await
+}
+
+pub async fn update(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Json(account): Json<LedgerAccount>,
+) -> ApiResult<Json<LedgerAccount>> {
+    check_same_id(&id, &account.id)?;
+    state
+        .run(move |state, conn| Ok(Json(state.account_tree_service.update_account(conn, &account)?)))
+        .await
+}
+
+pub async fn delete(State(state): State<AppState>, Path(id): Path<String>) -> ApiResult<StatusCode> {
+    state
+        .run(move |_, conn| deleted(ledger_account_repository::delete_by_id(conn, &id)?, "ledger account", &id))
+        .await
+}
+
+/// Lists the accounts whose parent is the account, by name.
+pub async fn children(State(state): State<AppState>, Path(id): Path<String>) -> ApiResult<Json<Vec<LedgerAccount>>> {
+    state
+        .run(move |_, conn| {
+            if ledger_account_repository::find_by_id(conn, &id)?.is_none() {
+                return Err(ApiError::not_found(format!("ledger account {} not found", id)));
+            }
+            Ok(Json(ledger_account_repository::find_children(conn, &id)?))
+        })
+        .await
+}
+
+pub async fn balance(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Query(query): Query<AccountBalanceQuery>,
+) -> ApiResult<Json<AccountStmtReport>> {
+    let ref_time = query.ref_time.unwrap_or_else(|| Utc::now().naive_utc());
+    if query.what_if && query.as_of.is_some() {
+        return Err(ApiError::bad_request("what_if cannot be combined with as_of"));
+    }
+    state
+        .run(move |state, conn| {
+            let stmt = match (query.what_if, query.as_of) {
+                (true, _) => state.simulation_service.read_stmt(conn, &id, ref_time)?,
+                (false, Some(as_of)) => state.account_stmt_service.read_stmt_as_of(conn, &id, ref_time, as_of)?,
+                (false, None) => state.account_stmt_service.read_stmt(conn, &id, ref_time)?,
+            };
+            Ok(Json(stmt))
+        })
+        .await
+}
+
+pub async fn statement(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Query(query): Query<StatementQuery>,
+) -> ApiResult<Json<AccountStatement>> {
+    if query.from > query.to {
+        return Err(ApiError::bad_request("from must not be after to"));
+    }
+    let page_size = page_size(query.limit)?;
+    let after = query
+        .cursor
+        .as_deref()
+        .map(LineCursor::decode)
+        .transpose()
+        .map_err(|err| ApiError::bad_request(err.to_string()))?;
+    state
+        .run(move |state, conn| {
+            let service = &state.account_stmt_service;
+            let (opening, closing, page) = match query.as_of {
+                Some(as_of) => (
+                    service.read_stmt_as_of(conn, &id, query.from, as_of)?,
+                    service.read_stmt_as_of(conn, &id, query.to, as_of)?,
+                    service.find_lines_page_as_of(conn, &id, query.from, query.to, as_of, after.as_ref(), page_size)?,
+                ),
+                None => (
+                    service.read_stmt(conn, &id, query.from)?,
+                    service.read_stmt(conn, &id, query.to)?,
+                    service.find_lines_page(conn, &id, query.from, query.to, after.as_ref(), page_size)?,
+                ),
+            };
+            Ok(Json(AccountStatement { opening, closing, lines: page.items, next_cursor: page.next_cursor }))
+        })
+        .await
+}
+
+pub async fn lines(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Query(query): Query<LinesQuery>,
+) -> ApiResult<Json<Page<PostingLine>>> {
+    if query.from > query.to {
+        return Err(ApiError::bad_request("from must not be after to"));
+    }
+    let page_size = page_size(query.limit)?;
+    let after = query
+        .cursor
+        .as_deref()
+        .map(LineCursor::decode)
+        .transpose()
+        .map_err(|err| ApiError::bad_request(err.to_string()))?;
+    state
+        .run(move |state, conn| {
+            let page = state.account_stmt_service.find_lines_page(
+                conn,
+                &id,
+                query.from,
+                query.to,
+                after.as_ref(),
+                page_size,
+            )?;
+            Ok(Json(page))
+        })
+        .await
+}
+
+/// Reads the balance of an account including all its descendants, as a tree.
+pub async fn rolled_up_balance(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Query(query): Query<BalanceQuery>,
+) -> ApiResult<Json<ReportNode>> {
+    let ref_time = query.ref_time.unwrap_or_else(|| Utc::now().naive_utc());
+    state
+        .run(move |state, conn| Ok(Json(state.account_tree_service.rolled_up_balance(conn, &id, ref_time)?)))
+        .await
+}
@@ -0,0 +1,50 @@
+// src/handlers/mod.rs
+/* 
+ * Copyright (c) 2018-2024 adorsys GmbH and Co. KG
+ * All rights are reserved.
+ */
+
+pub mod chart_of_account;
+pub mod ledger;
+pub mod ledger_account;
+pub mod posting;
+
+use axum::http::StatusCode;
+use serde::Deserialize;
+
+use crate::error::{ApiError, ApiResult};
+
+/// The page size of listings that do not ask for one.
+pub const DEFAULT_PAGE_SIZE: usize = 100;
+/// The largest page size a listing may ask for.
+pub const MAX_PAGE_SIZE: usize = 1000;
+
+/// Query of a diff: the record of the path is compared with the record `with`.
+#[derive(Debug, Deserialize)]
+pub struct DiffQuery {
+    pub with: String,
+}
+
+/// Refuses updates whose body identifies another record than the path.
+fn check_same_id(path_id: &str, body_id: &str) -> ApiResult<()> {
+    if path_id != body_id {
+        return Err(ApiError::bad_request(format!("id {} of the body differs from id {} of the path", body_id, path_id)));
+    }
+    Ok(())
+}
+
+/// Answers a delete with 204, or 404 if nothing was deleted.
+fn deleted(count: usize, what: &str, id: &str) -> ApiResult<StatusCode> {
+    match count {
+        0 => Err(ApiError::not_found(format!("{} {} not found", what, id))),
+        _ => Ok(StatusCode::NO_CONTENT),
+    }
+}
+
+/// Checks the page size asked for, defaulting to `DEFAULT_PAGE_SIZE`.
+fn page_size(limit: Option<usize>) -> ApiResult<usize> {
+    match limit.unwrap_or(DEFAULT_PAGE_SIZE) {
+        size @ 1..=MAX_PAGE_SIZE => Ok(size),
+        size => Err(ApiError::bad_request(format!("limit {} is not between 1 and {}", size, MAX_PAGE_SIZE))),
+    }
+}
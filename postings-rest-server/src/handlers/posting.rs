@@ -0,0 +1,178 @@
+// src/handlers/posting.rs
+/* 
+ * Copyright (c) 2018-2024 adorsys GmbH and Co. KG
+ * All rights are reserved.
+ */
+
+use axum::extract::{Path, Query, State};
+use axum::http::StatusCode;
+use axum::Json;
+use chrono::{NaiveDateTime, Utc};
+use postings_repository::models::enums::{PostingStatus, PostingType};
+use postings_repository::models::{NewPosting, NewPostingLine, Posting, PostingLine};
+use postings_repository::repository::{posting_line_repository, posting_repository};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+
+use crate::auth::AuthenticatedUser;
+use crate::error::{ApiError, ApiResult};
+use crate::state::AppState;
+
+/// A posting submitted by a client. The recording user, the record time, the
+/// hash chain and the denormalized line fields are set by the server.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PostingRequest {
+    pub id: String,
+    pub opr_id: String,
+    pub opr_time: Option<NaiveDateTime>,
+    pub opr_type: Option<String>,
+    pub opr_src: Option<String>,
+    pub pst_time: NaiveDateTime,
+    pub pst_type: PostingType,
+    pub pst_status: PostingStatus,
+    pub ledger_id: String,
+    pub val_time: Option<NaiveDateTime>,
+    pub opr_details_id: Option<String>,
+    pub lines: Vec<PostingLineRequest>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PostingLineRequest {
+    pub id: String,
+    pub account_id: String,
+    pub debit_amount: Decimal,
+    pub credit_amount: Decimal,
+    pub details_id: Option<String>,
+    pub src_account: Option<String>,
+    pub base_line: Option<String>,
+    pub sub_opr_src_id: Option<String>,
+}
+
+/// A posting together with its lines.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PostingResponse {
+    pub posting: Posting,
+    pub lines: Vec<PostingLine>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct OprQuery {
+    pub opr_id: String,
+}
+
+impl PostingRequest {
+    fn into_new_posting(self, record_user: String, record_time: NaiveDateTime) -> (NewPosting, Vec<NewPostingLine>) {
+        let lines = self
+            .lines
+            .into_iter()
+            .map(|line| NewPostingLine {
+                id: line.id,
+                account_id: line.account_id,
+                debit_amount: line.debit_amount,
+                credit_amount: line.credit_amount,
+                details_id: line.details_id,
+                src_account: line.src_account,
+                base_line: line.base_line,
+                sub_opr_src_id: line.sub_opr_src_id,
+                // Posting fields are denormalized by the posting service.
+                record_time,
+                opr_id: String::new(),
+                opr_src: None,
+                pst_time: self.pst_time,
+                pst_type: self.pst_type,
+                pst_status: self.pst_status,
+                hash: String::new(),
+                discarded_time: None,
+            })
+            .collect();
+        let posting = NewPosting {
+            id: self.id,
+            antecedent_id: None,
+            antecedent_hash: None,
+            hash: None,
+            hash_alg: None,
+            record_user,
+            record_time,
+            opr_id: self.opr_id,
+            opr_time: self.opr_time,
+            opr_type: self.opr_type,
+            opr_src: self.opr_src,
+            pst_time: self.pst_time,
+            pst_type: self.pst_type,
+            pst_status: self.pst_status,
+            ledger_id: self.ledger_id,
+            val_time: self.val_time,
+            discarded_id: None,
+            discarded_time: None,
+            discarding_id: None,
+            opr_details_id: self.opr_details_id,
+        };
+        (posting, lines)
+    }
+}
+
+/// Records a posting. Submitting a posting for an operation that has already
+/// been posted discards the previous posting of the operation. A PROPOSED
+/// posting only takes effect once approved by another user. The posting is
+/// recorded on behalf of the authenticated user.
+pub async fn create(
+    State(state): State<AppState>,
+    AuthenticatedUser(maker): AuthenticatedUser,
+    Json(request): Json<PostingRequest>,
+) -> ApiResult<(StatusCode, Json<PostingResponse>)> {
+    state
+        .run(|state, conn| {
+            let (posting, lines) = request.into_new_posting(maker, Utc::now().naive_utc());
+            let (posting, lines) = state.posting_service.new_posting(conn, posting, lines)?;
+            Ok((StatusCode::CREATED, Json(PostingResponse { posting, lines })))
+        })
+        .await
+}
+
+pub async fn get(State(state): State<AppState>, Path(id): Path<String>) -> ApiResult<Json<PostingResponse>> {
+    state
+        .run(move |_, conn| {
+            let posting = posting_repository::find_by_id(conn, &id)?
+                .ok_or_else(|| ApiError::not_found(format!("posting {} not found", id)))?;
+            let lines = posting_line_repository::find_by_opr_id_and_record_time(conn, &posting.opr_id, posting.record_time)?;
+            Ok(Json(PostingResponse { posting, lines }))
+        })
+        .await
+}
+
+/// Lists all postings of an operation, the discarded ones included.
+pub async fn find_by_opr_id(
+    State(state): State<AppState>,
+    Query(query): Query<OprQuery>,
+) -> ApiResult<Json<Vec<Posting>>> {
+    state
+        .run(move |_, conn| Ok(Json(posting_repository::find_by_opr_id(conn, &query.opr_id)?)))
+        .await
+}
+
+/// Approves a proposed posting on behalf of the authenticated user, the
+/// posting is recorded in the journal now.
+pub async fn approve(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    AuthenticatedUser(checker): AuthenticatedUser,
+) -> ApiResult<Json<PostingResponse>> {
+    state
+        .run(move |state, conn| {
+            let (posting, lines) = state.posting_service.approve(conn, &id, &checker, Utc::now().naive_utc())?;
+            Ok(Json(PostingResponse { posting, lines }))
+        })
+        .await
+}
+
+/// Rejects a proposed posting on behalf of the authenticated user, the
+/// posting is cancelled.
+pub async fn reject(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    AuthenticatedUser(checker): AuthenticatedUser,
+) -> ApiResult<Json<Posting>> {
+    state
+        .run(move |state, conn| Ok(Json(state.posting_service.reject(conn, &id, &checker, Utc::now().naive_utc())?)))
+        .await
+}
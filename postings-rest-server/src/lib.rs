@@ -0,0 +1,74 @@
+/* 
+ * Copyright (c) 2018-2024 adorsys GmbH and Co. KG
+ * All rights are reserved.
+ */
+
+pub mod auth;
+pub mod error;
+pub mod handlers;
+pub mod state;
+
+use std::time::Duration;
+
+use axum::routing::get;
+use axum::Router;
+use tokio::task::JoinHandle;
+
+pub use auth::AuthenticatedUser;
+pub use error::{ApiError, ApiResult};
+pub use state::{build_pool, AppState, DbPool};
+
+use handlers::{chart_of_account, ledger, ledger_account, posting};
+
+/// Runs the deferred posting scheduler of the state every `period`, logging
+/// the postings it fails to promote.
+pub fn spawn_deferred_posting_scheduler(state: AppState, period: Duration) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(period);
+        loop {
+            interval.tick().await;
+            match state.run(|state, conn| Ok(state.deferred_posting_scheduler.run_due(conn)?)).await {
+                Ok(run) => {
+                    for (posting_id, err) in run.failed {
+                        tracing::warn!(posting_id = %posting_id, error = %err, "failed to promote deferred posting");
+                    }
+                }
+                Err(err) => tracing::error!(error = %err.message, "failed to run the deferred posting scheduler"),
+            }
+        }
+    })
+}
+
+/// Builds the routes of the postings API.
+pub fn router(state: AppState) -> Router {
+    Router::new()
+        .route("/chart-of-accounts", get(chart_of_account::list).post(chart_of_account::create))
+        .route("/chart-of-accounts/import", axum::routing::post(chart_of_account::import))
+        .route(
+            "/chart-of-accounts/{id}",
+            get(chart_of_account::get).put(chart_of_account::update).delete(chart_of_account::delete),
+        )
+        .route("/chart-of-accounts/{id}/export", get(chart_of_account::export))
+        .route("/chart-of-accounts/{id}/diff", get(chart_of_account::diff))
+        .route("/ledgers", get(ledger::list).post(ledger::create))
+        .route("/ledgers/{id}", get(ledger::get).put(ledger::update).delete(ledger::delete))
+        .route("/ledgers/{id}/accounts", get(ledger::accounts))
+        .route("/ledgers/{id}/export", get(ledger::export))
+        .route("/ledgers/{id}/diff", get(ledger::diff))
+        .route("/ledgers/{id}/simulation", axum::routing::delete(ledger::purge_simulation))
+        .route("/ledger-accounts", axum::routing::post(ledger_account::create))
+        .route(
+            "/ledger-accounts/{id}",
+            get(ledger_account::get).put(ledger_account::update).delete(ledger_account::delete),
+        )
+        .route("/ledger-accounts/{id}/balance", get(ledger_account::balance))
+        .route("/ledger-accounts/{id}/statement", get(ledger_account::statement))
+        .route("/ledger-accounts/{id}/lines", get(ledger_account::lines))
+        .route("/ledger-accounts/{id}/children", get(ledger_account::children))
+        .route("/ledger-accounts/{id}/rolled-up-balance", get(ledger_account::rolled_up_balance))
+        .route("/postings", get(posting::find_by_opr_id).post(posting::create))
+        .route("/postings/{id}", get(posting::get))
+        .route("/postings/{id}/approve", axum::routing::post(posting::approve))
+        .route("/postings/{id}/reject", axum::routing::post(posting::reject))
+        .with_state(state)
+}
@@ -1,3 +1,50 @@
-fn main() {
-    println!("Hello, world!");
+/* 
+ * Copyright (c) 2018-2024 adorsys GmbH and Co. KG
+ * All rights are reserved.
+ */
+
+use std::env;
+use std::time::Duration;
+
+use dotenv::dotenv;
+use postings_repository::pool::PoolConfig;
+use postings_rest_server::{build_pool, router, spawn_deferred_posting_scheduler, AppState};
+use postings_service::{BackdatedPostingPolicy, PostingService};
+
+#[tokio::main]
+async fn main() {
+    dotenv().ok();
+    tracing_subscriber::fmt::init();
+    let database_url = env::var("DATABASE_URL").expect("DATABASE_URL must be set");
+    let bind_address = env::var("BIND_ADDRESS").unwrap_or_else(|_| "127.0.0.1:8080".to_string());
+
+    let pool_config = PoolConfig::from_env().unwrap_or_else(|err| panic!("Invalid pool configuration: {}", err));
+    let pool = build_pool(&database_url, &pool_config).expect("Failed to create connection pool");
+    let scheduler_period = match env::var("DEFERRED_POSTING_INTERVAL_SECS").map(|secs| secs.trim().parse::<u64>()) {
+        Err(_) => 60,
+        Ok(Ok(0)) => panic!("DEFERRED_POSTING_INTERVAL_SECS must be at least 1 second"),
+        Ok(Ok(secs)) => secs,
+        Ok(Err(_)) => panic!("DEFERRED_POSTING_INTERVAL_SECS must be a number of seconds"),
+    };
+    let backdated_posting_policy = match env::var("BACKDATED_POSTING_POLICY").as_deref().map(str::trim) {
+        Err(_) | Ok("REJECT") => BackdatedPostingPolicy::Reject,
+        Ok("RECLOSE") => BackdatedPostingPolicy::Reclose,
+        Ok(other) => panic!("BACKDATED_POSTING_POLICY must be REJECT or RECLOSE, not {}", other),
+    };
+    let posting_service = PostingService::new().with_backdated_posting_policy(backdated_posting_policy);
+    let mut state = AppState::with_posting_service(pool, posting_service);
+    if let Ok(user_header) = env::var("USER_HEADER") {
+        let user_header = user_header
+            .trim()
+            .parse()
+            .unwrap_or_else(|err| panic!("USER_HEADER must be a header name: {}", err));
+        state = state.with_user_header(user_header);
+    }
+    spawn_deferred_posting_scheduler(state.clone(), Duration::from_secs(scheduler_period));
+    let listener = tokio::net::TcpListener::bind(&bind_address)
+        .await
+        .unwrap_or_else(|err| panic!("Failed to bind {}: {}", bind_address, err));
+    axum::serve(listener, router(state))
+        .await
+        .expect("Server error");
 }
@@ -0,0 +1,89 @@
+// src/state.rs
+/* 
+ * Copyright (c) 2018-2024 adorsys GmbH and Co. KG
+ * All rights are reserved.
+ */
+
+use std::sync::Arc;
+
+use axum::http::HeaderName;
+use diesel::pg::PgConnection;
+use diesel::r2d2::{ConnectionManager, Pool, PoolError};
+use postings_repository::pool::PoolConfig;
+use postings_service::{
+    AccountStmtService, AccountTreeService, CoaTemplateService, DeferredPostingScheduler, PostingService,
+    SimulationService, SystemClock,
+};
+
+use crate::auth::DEFAULT_USER_HEADER;
+use crate::error::ApiResult;
+
+pub type DbPool = Pool<ConnectionManager<PgConnection>>;
+
+/// Builds the connection pool of the handlers with the given settings.
+pub fn build_pool(database_url: &str, config: &PoolConfig) -> Result<DbPool, PoolError> {
+    Pool::builder()
+        .max_size(config.max_size)
+        .min_idle(config.min_idle)
+        .connection_timeout(config.connection_timeout)
+        .idle_timeout(Some(config.idle_timeout))
+        .max_lifetime(Some(config.max_lifetime))
+        .build(ConnectionManager::new(database_url))
+}
+
+/// The state shared by all handlers.
+#[derive(Debug, Clone)]
+pub struct AppState {
+    pub pool: DbPool,
+    pub posting_service: PostingService,
+    pub account_stmt_service: AccountStmtService,
+    pub account_tree_service: AccountTreeService,
+    pub coa_template_service: CoaTemplateService,
+    pub simulation_service: SimulationService,
+    pub deferred_posting_scheduler: DeferredPostingScheduler,
+    /// The header carrying the authenticated user, see `AuthenticatedUser`.
+    pub user_header: HeaderName,
+}
+
+impl AppState {
+    pub fn new(pool: DbPool) -> Self {
+        Self::with_posting_service(pool, PostingService::new())
+    }
+
+    /// Creates the state recording postings, including statement postings,
+    /// with the given service.
+    pub fn with_posting_service(pool: DbPool, posting_service: PostingService) -> Self {
+        let account_stmt_service = AccountStmtService::with_posting_service(posting_service.clone());
+        Self {
+            pool,
+            simulation_service: SimulationService::with_services(posting_service.clone(), account_stmt_service.clone()),
+            deferred_posting_scheduler: DeferredPostingScheduler::with_services(posting_service.clone(), Arc::new(SystemClock)),
+            posting_service,
+            account_stmt_service,
+            account_tree_service: AccountTreeService::new(),
+            coa_template_service: CoaTemplateService::new(),
+            user_header: HeaderName::from_static(DEFAULT_USER_HEADER),
+        }
+    }
+
+    /// Takes the authenticated user from the given header.
+    pub fn with_user_header(mut self, user_header: HeaderName) -> Self {
+        self.user_header = user_header;
+        self
+    }
+
+    /// Runs blocking repository and service calls on a pooled connection,
+    /// outside of the async runtime.
+    pub async fn run<T, F>(&self, f: F) -> ApiResult<T>
+    where
+        T: Send + 'static,
+        F: FnOnce(&AppState, &mut PgConnection) -> ApiResult<T> + Send + 'static,
+    {
+        let state = self.clone();
+        tokio::task::spawn_blocking(move || {
+            let mut conn = state.pool.get()?;
+            f(&state, &mut conn)
+        })
+        .await?
+    }
+}
@@ -0,0 +1,454 @@
+// tests/api_test.rs
+//
+// Copyright (c) 2018-2024 adorsys GmbH and Co. KG
+// All rights are reserved.
+
+mod common;
+
+use axum::body::Body;
+use axum::http::{Method, Request, StatusCode};
+use axum::Router;
+use common::{establish_connection, seed_database, test_router, TestDatabaseGuard};
+use http_body_util::BodyExt;
+use postings_rest_server::auth::DEFAULT_USER_HEADER;
+use serde_json::{json, Value};
+use serial_test::serial;
+use tower::ServiceExt;
+
+const LEDGER_ID: &str = "Zd0ND5YwSzGwIfZilhumPg";
+const CASH: &str = "xVgaTPMcRty9ik3BTQDh1Q_BS_1_0_0";
+const EQUITY: &str = "xVgaTPMcRty9ik3BTQDh1Q_BS_2_0_0";
+const MAKER: &str = "Test User";
+
+async fn send(app: &Router, method: Method, uri: &str, body: Option<Value>) -> (StatusCode, Value) {
+    send_request(app, Request::builder().method(method).uri(uri), body).await
+}
+
+/// Sends a request on behalf of the user authenticated by the gateway.
+async fn send_as(app: &Router, user: &str, method: Method, uri: &str, body: Option<Value>) -> (StatusCode, Value) {
+    let request = Request::builder().method(method).uri(uri).header(DEFAULT_USER_HEADER, user);
+    send_request(app, request, body).await
+}
+
+async fn send_request(app: &Router, request: axum::http::request::Builder, body: Option<Value>) -> (StatusCode, Value) {
+    let request = match body {
+        Some(body) => request
+            .header("content-type", "application/json")
+            .body(Body::from(body.to_string())),
+        None => request.body(Body::empty()),
+    }
+    .unwrap();
+    let response = app.clone().oneshot(request).await.unwrap();
+    let status = response.status();
+    let bytes = response.into_body().collect().await.unwrap().to_bytes();
+    let value = if bytes.is_empty() { Value::Null } else { serde_json::from_slice(&bytes).unwrap() };
+    (status, value)
+}
+
+fn posting(id: &str, opr_id: &str, pst_time: &str, amount: &str) -> Value {
+    json!({
+        "id": id,
+        "opr_id": opr_id,
+        "opr_time": null,
+        "opr_type": "Deposit",
+        "opr_src": null,
+        "pst_time": pst_time,
+        "pst_type": "BusiTx",
+        "pst_status": "POSTED",
+        "ledger_id": LEDGER_ID,
+        "val_time": null,
+        "opr_details_id": null,
+        "lines": [
+            { "id": format!("{}_dr", id), "account_id": CASH, "debit_amount": amount, "credit_amount": "0",
+              "details_id": null, "src_account": null, "base_line": null, "sub_opr_src_id": null },
+            { "id": format!("{}_cr", id), "account_id": EQUITY, "debit_amount": "0", "credit_amount": amount,
+              "details_id": null, "src_account": null, "base_line": null, "sub_opr_src_id": null }
+        ]
+    })
+}
+
+#[tokio::test]
+#[serial]
+async fn test_chart_of_account_crud() {
+    let _conn = establish_connection();
+    let _guard = TestDatabaseGuard::new();
+    let app = test_router();
+
+    let coa = json!({
+        "id": "coa_001",
+        "created": "2018-08-07T20:58:24.232",
+        "user_details": "Francis",
+        "short_desc": null,
+        "long_desc": null,
+        "name": "IFRS"
+    });
+    let (status, created) = send(&app, Method::POST, "/chart-of-accounts", Some(coa.clone())).await;
+    assert_eq!(status, StatusCode::CREATED);
+    assert_eq!(created["name"], "IFRS");
+
+    // The name of a chart of account is unique.
+    let mut duplicate = coa.clone();
+    duplicate["id"] = json!("coa_002");
+    let (status, body) = send(&app, Method::POST, "/chart-of-accounts", Some(duplicate)).await;
+    assert_eq!(status, StatusCode::CONFLICT);
+    assert_eq!(body["error"], "duplicate key violates unique constraint chart_of_account_name_unique");
+
+    let mut changed = created.clone();
+    changed["short_desc"] = json!("Sample chart of account");
+    let (status, updated) = send(&app, Method::PUT, "/chart-of-accounts/coa_001", Some(changed)).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(updated["short_desc"], "Sample chart of account");
+
+    let (status, _) = send(&app, Method::PUT, "/chart-of-accounts/coa_002", Some(updated)).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let (status, all) = send(&app, Method::GET, "/chart-of-accounts", None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(all.as_array().unwrap().len(), 1);
+
+    let (status, _) = send(&app, Method::DELETE, "/chart-of-accounts/coa_001", None).await;
+    assert_eq!(status, StatusCode::NO_CONTENT);
+    let (status, body) = send(&app, Method::GET, "/chart-of-accounts/coa_001", None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert!(body["error"].is_string());
+}
+
+#[tokio::test]
+#[serial]
+async fn test_chart_of_account_import() {
+    let _conn = establish_connection();
+    let _guard = TestDatabaseGuard::new();
+    let app = test_router();
+
+    let request = json!({ "template": "IFRS_SAMPLE", "ledger_name": "GL", "user_details": "Francis" });
+    let (status, imported) = send(&app, Method::POST, "/chart-of-accounts/import", Some(request)).await;
+    assert_eq!(status, StatusCode::CREATED);
+    assert_eq!(imported["ledger"]["coa_id"], imported["coa"]["id"]);
+    assert!(!imported["accounts"].as_array().unwrap().is_empty());
+
+    let coa_uri = format!("/chart-of-accounts/{}/export", imported["coa"]["id"].as_str().unwrap());
+    let (status, exported) = send(&app, Method::GET, &coa_uri, None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(exported["accounts"].as_array().unwrap().len(), imported["accounts"].as_array().unwrap().len());
+
+    let mut changed = exported.clone();
+    changed["name"] = json!("IFRS (copy)");
+    changed["accounts"].as_array_mut().unwrap().pop();
+    let request = json!({ "template": changed, "ledger_name": "GL copy", "user_details": "Francis" });
+    let (status, copy) = send(&app, Method::POST, "/chart-of-accounts/import", Some(request)).await;
+    assert_eq!(status, StatusCode::CREATED);
+    let diff_uri = format!(
+        "/ledgers/{}/diff?with={}",
+        imported["ledger"]["id"].as_str().unwrap(),
+        copy["ledger"]["id"].as_str().unwrap()
+    );
+    let (status, diff) = send(&app, Method::GET, &diff_uri, None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(diff["removed"].as_array().unwrap().len(), 1);
+    assert!(diff["added"].as_array().unwrap().is_empty());
+
+    let (status, _) = send(&app, Method::GET, "/ledgers/missing/export", None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    let custom = json!({
+        "template": {
+            "name": "Custom",
+            "accounts": [{ "number": "1", "name": "Assets", "category": "AS", "parent": "0" }]
+        },
+        "ledger_name": "Custom ledger",
+        "user_details": "Francis"
+    });
+    let (status, body) = send(&app, Method::POST, "/chart-of-accounts/import", Some(custom)).await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    assert!(body["error"].is_string());
+}
+
+#[tokio::test]
+#[serial]
+async fn test_ledger_and_accounts() {
+    let mut conn = establish_connection();
+    seed_database(&mut conn, "tests/fixtures/api_dataset.sql");
+    let _guard = TestDatabaseGuard::new();
+    let app = test_router();
+
+    let (status, accounts) = send(&app, Method::GET, &format!("/ledgers/{}/accounts", LEDGER_ID), None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(accounts.as_array().unwrap().len(), 5);
+
+    // A ledger holding accounts can not be deleted.
+    let (status, _) = send(&app, Method::DELETE, &format!("/ledgers/{}", LEDGER_ID), None).await;
+    assert_eq!(status, StatusCode::CONFLICT);
+
+    let account = json!({
+        "id": "account_001",
+        "created": "2018-08-07T20:58:24.232",
+        "user_details": "Sample User",
+        "short_desc": null,
+        "long_desc": null,
+        "name": "1.1.0",
+        "ledger_id": LEDGER_ID,
+        "parent_id": CASH,
+        "coa_id": "ci8k8PDcTrCsi-F3sT3i-g",
+        "balance_side": "Dr",
+        "category": "AS"
+    });
+    let (status, _) = send(&app, Method::POST, "/ledger-accounts", Some(account)).await;
+    assert_eq!(status, StatusCode::CREATED);
+    let (status, account) = send(&app, Method::GET, "/ledger-accounts/account_001", None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(account["parent_id"], CASH);
+
+    let (status, children) = send(&app, Method::GET, &format!("/ledger-accounts/{}/children", CASH), None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(children[0]["id"], "account_001");
+
+    let uri = format!("/ledger-accounts/{}/rolled-up-balance?ref_time=2018-01-31T00:00:00", CASH);
+    let (status, tree) = send(&app, Method::GET, &uri, None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(tree["children"][0]["account_id"], "account_001");
+
+    // A child must belong to the chart of account of its parent.
+    let coa = json!({
+        "id": "coa_002",
+        "created": "2018-08-07T20:58:24.232",
+        "user_details": "Francis",
+        "short_desc": null,
+        "long_desc": null,
+        "name": "SKR03"
+    });
+    let (status, _) = send(&app, Method::POST, "/chart-of-accounts", Some(coa)).await;
+    assert_eq!(status, StatusCode::CREATED);
+    let mut other_coa = account.clone();
+    other_coa["id"] = json!("account_002");
+    other_coa["name"] = json!("1.2.0");
+    other_coa["coa_id"] = json!("coa_002");
+    let (status, _) = send(&app, Method::POST, "/ledger-accounts", Some(other_coa)).await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+}
+
+#[tokio::test]
+#[serial]
+async fn test_postings_and_balances() {
+    let mut conn = establish_connection();
+    seed_database(&mut conn, "tests/fixtures/api_dataset.sql");
+    let _guard = TestDatabaseGuard::new();
+    let app = test_router();
+
+    let (status, created) =
+        send_as(&app, MAKER, Method::POST, "/postings", Some(posting("pst_001", "opr_001", "2018-01-01T10:00:00", "100.00"))).await;
+    assert_eq!(status, StatusCode::CREATED);
+    assert_eq!(created["lines"].as_array().unwrap().len(), 2);
+    assert!(created["posting"]["hash"].is_string());
+
+    // The operation is posted again with another amount.
+    let (status, _) =
+        send_as(&app, MAKER, Method::POST, "/postings", Some(posting("pst_002", "opr_001", "2018-01-01T10:00:00", "120.00"))).await;
+    assert_eq!(status, StatusCode::CREATED);
+
+    let (status, found) = send(&app, Method::GET, "/postings/pst_001", None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(found["posting"]["discarding_id"], "pst_002");
+
+    let (status, postings) = send(&app, Method::GET, "/postings?opr_id=opr_001", None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(postings.as_array().unwrap().len(), 2);
+
+    let (status, balance) =
+        send(&app, Method::GET, &format!("/ledger-accounts/{}/balance?ref_time=2018-01-31T00:00:00", CASH), None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(balance["balance"], "120.00");
+
+    let uri = format!("/ledger-accounts/{}/statement?from=2017-12-31T00:00:00&to=2018-01-31T00:00:00", CASH);
+    let (status, statement) = send(&app, Method::GET, &uri, None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(statement["opening"]["balance"], "0");
+    assert_eq!(statement["closing"]["balance"], "120.00");
+    assert_eq!(statement["lines"].as_array().unwrap().len(), 1);
+
+    // As recorded before the second posting.
+    let as_of = created["posting"]["record_time"].as_str().unwrap();
+    let (status, balance) =
+        send(&app, Method::GET, &format!("/ledger-accounts/{}/balance?ref_time=2018-01-31T00:00:00&as_of={}", CASH, as_of), None)
+            .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(balance["balance"], "100.00");
+    let (status, statement) = send(&app, Method::GET, &format!("{}&as_of={}", uri, as_of), None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(statement["closing"]["balance"], "100.00");
+    assert_eq!(statement["lines"][0]["id"], "pst_001_dr");
+
+    let uri = format!("/ledger-accounts/{}/balance?what_if=true&as_of={}", CASH, as_of);
+    let (status, _) = send(&app, Method::GET, &uri, None).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    // The category of a booked account is fixed.
+    let uri = format!("/ledger-accounts/{}", CASH);
+    let (_, mut account) = send(&app, Method::GET, &uri, None).await;
+    account["category"] = json!("LI");
+    let (status, _) = send(&app, Method::PUT, &uri, Some(account)).await;
+    assert_eq!(status, StatusCode::CONFLICT);
+}
+
+#[tokio::test]
+#[serial]
+async fn test_posting_approval() {
+    let mut conn = establish_connection();
+    seed_database(&mut conn, "tests/fixtures/api_dataset.sql");
+    let _guard = TestDatabaseGuard::new();
+    let app = test_router();
+
+    let mut proposal = posting("pst_001", "opr_001", "2018-01-01T10:00:00", "100.00");
+    proposal["pst_status"] = json!("PROPOSED");
+    let (status, created) = send_as(&app, MAKER, Method::POST, "/postings", Some(proposal)).await;
+    assert_eq!(status, StatusCode::CREATED);
+    assert_eq!(created["posting"]["pst_status"], "PROPOSED");
+
+    // The checker is the authenticated user, not a user named in the body.
+    let (status, _) =
+        send(&app, Method::POST, "/postings/pst_001/approve", Some(json!({ "user": "Checker" }))).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    let (status, _) = send_as(&app, MAKER, Method::POST, "/postings/pst_001/approve", None).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+
+    let (status, approved) = send_as(&app, "Checker", Method::POST, "/postings/pst_001/approve", None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(approved["posting"]["pst_status"], "POSTED");
+    assert!(approved["posting"]["hash"].is_string());
+
+    let (status, _) = send_as(&app, "Checker", Method::POST, "/postings/pst_001/reject", None).await;
+    assert_eq!(status, StatusCode::CONFLICT);
+    let (status, _) = send_as(&app, "Checker", Method::POST, "/postings/missing/reject", None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+#[serial]
+async fn test_maker_can_not_approve_own_proposal() {
+    let mut conn = establish_connection();
+    seed_database(&mut conn, "tests/fixtures/api_dataset.sql");
+    let _guard = TestDatabaseGuard::new();
+    let app = test_router();
+
+    // The maker is the authenticated user, a user named in the body is ignored.
+    let mut proposal = posting("pst_001", "opr_001", "2018-01-01T10:00:00", "100.00");
+    proposal["pst_status"] = json!("PROPOSED");
+    proposal["record_user"] = json!("Someone Else");
+    let (status, _) = send(&app, Method::POST, "/postings", Some(proposal.clone())).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    let (status, created) = send_as(&app, "Mallory", Method::POST, "/postings", Some(proposal)).await;
+    assert_eq!(status, StatusCode::CREATED);
+    assert_eq!(created["posting"]["record_user"], "Mallory");
+
+    let (status, _) = send_as(&app, "Mallory", Method::POST, "/postings/pst_001/approve", None).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    let (status, _) = send_as(&app, "Mallory", Method::POST, "/postings/pst_001/reject", None).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+}
+
+#[tokio::test]
+#[serial]
+async fn test_simulation() {
+    let mut conn = establish_connection();
+    seed_database(&mut conn, "tests/fixtures/api_dataset.sql");
+    let _guard = TestDatabaseGuard::new();
+    let app = test_router();
+
+    let (status, _) =
+        send_as(&app, MAKER, Method::POST, "/postings", Some(posting("pst_001", "opr_001", "2018-01-01T10:00:00", "100.00"))).await;
+    assert_eq!(status, StatusCode::CREATED);
+    let mut fee = posting("pst_002", "opr_002", "2018-01-02T10:00:00", "5.00");
+    fee["pst_status"] = json!("SIMULATED");
+    let (status, created) = send_as(&app, MAKER, Method::POST, "/postings", Some(fee)).await;
+    assert_eq!(status, StatusCode::CREATED);
+    assert_eq!(created["posting"]["pst_status"], "SIMULATED");
+
+    let uri = format!("/ledger-accounts/{}/balance?ref_time=2018-01-31T00:00:00", CASH);
+    let (status, balance) = send(&app, Method::GET, &uri, None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(balance["balance"], "100.00");
+    let (status, balance) = send(&app, Method::GET, &format!("{}&what_if=true", uri), None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(balance["balance"], "105.00");
+
+    let (status, purge) = send(&app, Method::DELETE, &format!("/ledgers/{}/simulation", LEDGER_ID), None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(purge["postings"], 1);
+    assert_eq!(purge["posting_lines"], 2);
+    let (status, balance) = send(&app, Method::GET, &format!("{}&what_if=true", uri), None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(balance["balance"], "100.00");
+}
+
+#[tokio::test]
+#[serial]
+async fn test_posting_errors() {
+    let mut conn = establish_connection();
+    seed_database(&mut conn, "tests/fixtures/api_dataset.sql");
+    let _guard = TestDatabaseGuard::new();
+    let app = test_router();
+
+    let mut unbalanced = posting("pst_001", "opr_001", "2018-01-01T10:00:00", "100.00");
+    unbalanced["lines"][1]["credit_amount"] = json!("99.99");
+    let (status, _) = send_as(&app, MAKER, Method::POST, "/postings", Some(unbalanced)).await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+
+    let mut unknown_ledger = posting("pst_001", "opr_001", "2018-01-01T10:00:00", "100.00");
+    unknown_ledger["ledger_id"] = json!("unknown");
+    let (status, _) = send_as(&app, MAKER, Method::POST, "/postings", Some(unknown_ledger)).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    let (status, _) = send(&app, Method::GET, "/postings/unknown", None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    let (status, _) = send(&app, Method::GET, "/ledger-accounts/unknown/balance", None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+#[serial]
+async fn test_account_lines_are_paginated() {
+    let mut conn = establish_connection();
+    seed_database(&mut conn, "tests/fixtures/api_dataset.sql");
+    let _guard = TestDatabaseGuard::new();
+    let app = test_router();
+
+    for (day, id) in ["pst_001", "pst_002", "pst_003"].iter().enumerate() {
+        let pst_time = format!("2018-01-0{}T10:00:00", day + 1);
+        let opr_id = format!("opr_00{}", day + 1);
+        let (status, _) = send_as(&app, MAKER, Method::POST, "/postings", Some(posting(id, &opr_id, &pst_time, "10.00"))).await;
+        assert_eq!(status, StatusCode::CREATED);
+    }
+
+    let lines_uri = format!("/ledger-accounts/{}/lines?from=2017-12-31T00:00:00&to=2018-01-31T00:00:00", CASH);
+    let (status, first) = send(&app, Method::GET, &format!("{}&limit=2", lines_uri), None).await;
+    assert_eq!(status, StatusCode::OK);
+    let ids: Vec<&str> = first["items"].as_array().unwrap().iter().map(|line| line["id"].as_str().unwrap()).collect();
+    assert_eq!(ids, vec!["pst_001_dr", "pst_002_dr"]);
+
+    let cursor = first["next_cursor"].as_str().expect("A second page should follow");
+    let (status, second) = send(&app, Method::GET, &format!("{}&limit=2&cursor={}", lines_uri, cursor), None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(second["items"].as_array().unwrap().len(), 1);
+    assert_eq!(second["items"][0]["id"], "pst_003_dr");
+    assert!(second["next_cursor"].is_null());
+
+    let (status, _) = send(&app, Method::GET, &format!("{}&limit=1001", lines_uri), None).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let (status, _) = send(&app, Method::GET, &format!("{}&limit=0", lines_uri), None).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let (status, _) = send(&app, Method::GET, &format!("{}&cursor=zz", lines_uri), None).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    // The lines of a statement are paged the same way.
+    let stmt_uri = format!("/ledger-accounts/{}/statement?from=2017-12-31T00:00:00&to=2018-01-31T00:00:00", CASH);
+    let (status, first) = send(&app, Method::GET, &format!("{}&limit=2", stmt_uri), None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(first["closing"]["balance"], "30.00");
+    assert_eq!(first["lines"].as_array().unwrap().len(), 2);
+    let cursor = first["next_cursor"].as_str().expect("A second page should follow");
+    let (status, second) = send(&app, Method::GET, &format!("{}&limit=2&cursor={}", stmt_uri, cursor), None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(second["lines"][0]["id"], "pst_003_dr");
+    assert!(second["next_cursor"].is_null());
+    let (status, _) = send(&app, Method::GET, &format!("{}&limit=1001", stmt_uri), None).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
@@ -0,0 +1,547 @@
+// src/account_stmt_service.rs
+/*
+ * Copyright (c) 2018-2024 adorsys GmbH and Co. KG
+ * All rights are reserved.
+ */
+
+use std::collections::BTreeMap;
+
+use chrono::NaiveDateTime;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+
+use postings_repository::models::enums::{BalanceSide, PostingStatus, PostingType, StmtStatus};
+use postings_repository::models::{
+    AccountStmt, LedgerAccount, NewAccountStmt, NewPosting, NewPostingLine, NewPostingTrace, PostingLine,
+};
+use postings_repository::repository::pagination::{self, LineCursor, Page};
+use postings_repository::repository::{
+    AccountStmtRepository, LedgerAccountRepository, PostingLineRepository, PostingRepository, PostingTraceRepository,
+    Repositories,
+};
+
+use crate::error::{ServiceError, ServiceResult};
+use crate::new_id;
+use crate::posting_service::PostingService;
+
+/// The state of an account at a reference time.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AccountStmtReport {
+    pub account_id: String,
+    pub balance_side: BalanceSide,
+    pub ref_time: NaiveDateTime,
+    /// The closed statement the totals were computed from, if any.
+    pub base_stmt_id: Option<String>,
+    pub total_debit: Decimal,
+    pub total_credit: Decimal,
+    /// The balance of the account, positive on its balance side.
+    pub balance: Decimal,
+}
+
+/// Computes the balance of an account, positive when the account is on its
+/// balance side. `DrCr` accounts are presented like debit accounts.
+pub fn signed_balance(balance_side: BalanceSide, total_debit: Decimal, total_credit: Decimal) -> Decimal {
+    match balance_side {
+        BalanceSide::Dr | BalanceSide::DrCr => total_debit - total_credit,
+        BalanceSide::Cr => total_credit - total_debit,
+    }
+}
+
+/// Statement postings only document balances. Their lines are mechanical and
+/// are left out of balance computations.
+pub(crate) fn affects_balance(pst_type: PostingType) -> bool {
+    !matches!(pst_type, PostingType::BalStmt | PostingType::PnLStmt | PostingType::BsStmt)
+}
+
+/// Builds the statement of an account from its base statement and the lines
+/// posted after it.
+fn to_report(
+    account: LedgerAccount,
+    ref_time: NaiveDateTime,
+    base_stmt: Option<AccountStmt>,
+    lines: &[PostingLine],
+) -> AccountStmtReport {
+    let (total_debit, total_credit) = totals(base_stmt.as_ref(), lines);
+    AccountStmtReport {
+        account_id: account.id,
+        balance_side: account.balance_side,
+        ref_time,
+        base_stmt_id: base_stmt.map(|stmt| stmt.id),
+        total_debit,
+        total_credit,
+        balance: signed_balance(account.balance_side, total_debit, total_credit),
+    }
+}
+
+/// Adds the amounts of the lines to the totals of the base statement.
+fn totals(base_stmt: Option<&AccountStmt>, lines: &[PostingLine]) -> (Decimal, Decimal) {
+    let (total_debit, total_credit) = base_stmt
+        .map(|stmt| (stmt.total_debit, stmt.total_credit))
+        .unwrap_or((Decimal::ZERO, Decimal::ZERO));
+    lines.iter().fold((total_debit, total_credit), |(debit, credit), line| {
+        (debit + line.debit_amount, credit + line.credit_amount)
+    })
+}
+
+/// Reads and closes account statements.
+#[derive(Debug, Clone, Default)]
+pub struct AccountStmtService {
+    posting_service: PostingService,
+}
+
+impl AccountStmtService {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a service recording statement postings with the given posting service.
+    pub fn with_posting_service(posting_service: PostingService) -> Self {
+        Self { posting_service }
+    }
+
+    /// Reads the statement of an account at the reference time.
+    ///
+    /// Starts from the last CLOSED statement before the reference time and adds
+    /// the effective posting lines posted after that statement, up to and
+    /// including the reference time.
+    pub fn read_stmt<C: Repositories>(
+        &self,
+        conn: &mut C,
+        account_id: &str,
+        ref_time: NaiveDateTime,
+    ) -> ServiceResult<AccountStmtReport> {
+        self.report(conn, account_id, ref_time, StmtStatus::CLOSED)
+    }
+
+    /// Reads the statement of an account at the reference time as the ledger
+    /// recorded it at `as_of`.
+    ///
+    /// Like `read_stmt`, on both time axes: the base statement is the last
+    /// CLOSED statement created by `as_of`, and the lines are those recorded by
+    /// `as_of`, including lines discarded after it. Later records, such as
+    /// backdated postings and corrections, leave the statement unchanged, so
+    /// that historic reports can be reproduced.
+    pub fn read_stmt_as_of<C: Repositories>(
+        &self,
+        conn: &mut C,
+        account_id: &str,
+        ref_time: NaiveDateTime,
+        as_of: NaiveDateTime,
+    ) -> ServiceResult<AccountStmtReport> {
+        let account = LedgerAccountRepository::find_by_id(conn, account_id)?
+            .ok_or_else(|| ServiceError::AccountNotFound(account_id.to_string()))?;
+        let base_stmt = AccountStmtRepository::find_first_by_account_and_stmt_status_and_pst_time_less_than_as_of(
+            conn,
+            &account.id,
+            StmtStatus::CLOSED,
+            ref_time,
+            as_of,
+        )?;
+        let mut lines = match &base_stmt {
+            Some(stmt) => PostingLineRepository::find_by_account_and_dates_as_of(conn, &account.id, stmt.pst_time, ref_time, as_of)?,
+            None => PostingLineRepository::find_by_account_and_pst_time_lte_as_of(conn, &account.id, ref_time, as_of)?,
+        };
+        lines.retain(|line| affects_balance(line.pst_type));
+        Ok(to_report(account, ref_time, base_stmt, &lines))
+    }
+
+    /// Reads the statement of an account at the reference time like `read_stmt`,
+    /// adding the simulated lines posted after the base statement.
+    pub(crate) fn read_what_if_stmt<C: Repositories>(
+        &self,
+        conn: &mut C,
+        account_id: &str,
+        ref_time: NaiveDateTime,
+    ) -> ServiceResult<AccountStmtReport> {
+        self.report(conn, account_id, ref_time, StmtStatus::SIMULATED)
+    }
+
+    /// Reads a CLOSED statement, or a SIMULATED one including simulated lines.
+    fn report<C: Repositories>(
+        &self,
+        conn: &mut C,
+        account_id: &str,
+        ref_time: NaiveDateTime,
+        stmt_status: StmtStatus,
+    ) -> ServiceResult<AccountStmtReport> {
+        let account = LedgerAccountRepository::find_by_id(conn, account_id)?
+            .ok_or_else(|| ServiceError::AccountNotFound(account_id.to_string()))?;
+        let (base_stmt, lines) = self.stmt_lines(conn, &account, ref_time, stmt_status)?;
+        Ok(to_report(account, ref_time, base_stmt, &lines))
+    }
+
+    /// Lists the effective lines booked on the account after `from` up to and
+    /// including `to`, most recent posting time first. Statement lines are left out.
+    pub fn find_lines<C: Repositories>(
+        &self,
+        conn: &mut C,
+        account_id: &str,
+        from: NaiveDateTime,
+        to: NaiveDateTime,
+    ) -> ServiceResult<Vec<PostingLine>> {
+        if LedgerAccountRepository::find_by_id(conn, account_id)?.is_none() {
+            return Err(ServiceError::AccountNotFound(account_id.to_string()));
+        }
+        let mut lines = PostingLineRepository::find_postings_by_account_and_dates(conn, account_id, from, to)?;
+        lines.retain(|line| affects_balance(line.pst_type));
+        Ok(lines)
+    }
+
+    /// Lists the lines of `find_lines` as the ledger recorded them at `as_of`:
+    /// lines recorded later are left out, lines discarded later are listed.
+    pub fn find_lines_as_of<C: Repositories>(
+        &self,
+        conn: &mut C,
+        account_id: &str,
+        from: NaiveDateTime,
+        to: NaiveDateTime,
+        as_of: NaiveDateTime,
+    ) -> ServiceResult<Vec<PostingLine>> {
+        if LedgerAccountRepository::find_by_id(conn, account_id)?.is_none() {
+            return Err(ServiceError::AccountNotFound(account_id.to_string()));
+        }
+        let mut lines = PostingLineRepository::find_by_account_and_dates_as_of(conn, account_id, from, to, as_of)?;
+        lines.retain(|line| affects_balance(line.pst_type));
+        Ok(lines)
+    }
+
+    /// Pages through the lines of `find_lines` in `(pst_time, record_time, id)`
+    /// order. Statement lines are left out after paging, so a page may hold
+    /// fewer than `page_size` lines; only a missing `next_cursor` marks the end.
+    pub fn find_lines_page<C: Repositories>(
+        &self,
+        conn: &mut C,
+        account_id: &str,
+        from: NaiveDateTime,
+        to: NaiveDateTime,
+        after: Option<&LineCursor>,
+        page_size: usize,
+    ) -> ServiceResult<Page<PostingLine>> {
+        if LedgerAccountRepository::find_by_id(conn, account_id)?.is_none() {
+            return Err(ServiceError::AccountNotFound(account_id.to_string()));
+        }
+        let mut page = pagination::find_page_by_account_and_dates(conn, account_id, from, to, after, page_size)?;
+        page.items.retain(|line| affects_balance(line.pst_type));
+        Ok(page)
+    }
+
+    /// Pages through the lines of `find_lines_as_of` like `find_lines_page`.
+    #[allow(clippy::too_many_arguments)]
+    pub fn find_lines_page_as_of<C: Repositories>(
+        &self,
+        conn: &mut C,
+        account_id: &str,
+        from: NaiveDateTime,
+        to: NaiveDateTime,
+        as_of: NaiveDateTime,
+        after: Option<&LineCursor>,
+        page_size: usize,
+    ) -> ServiceResult<Page<PostingLine>> {
+        if LedgerAccountRepository::find_by_id(conn, account_id)?.is_none() {
+            return Err(ServiceError::AccountNotFound(account_id.to_string()));
+        }
+        let mut page =
+            pagination::find_page_by_account_and_dates_as_of(conn, account_id, from, to, as_of, after, page_size)?;
+        page.items.retain(|line| affects_balance(line.pst_type));
+        Ok(page)
+    }
+
+    /// Loads the last closed statement before the reference time and the
+    /// effective lines posted after it.
+    pub(crate) fn base_stmt_and_lines<C: Repositories>(
+        &self,
+        conn: &mut C,
+        account: &LedgerAccount,
+        ref_time: NaiveDateTime,
+    ) -> ServiceResult<(Option<AccountStmt>, Vec<PostingLine>)> {
+        let base_stmt = AccountStmtRepository::find_first_by_account_and_stmt_status_and_pst_time_less_than_order_by_pst_time_desc_stmt_seq_nbr_desc(
+            conn,
+            &account.id,
+            StmtStatus::CLOSED,
+            ref_time,
+        )?;
+        let mut lines = match &base_stmt {
+            Some(stmt) => PostingLineRepository::find_postings_by_account_and_dates(conn, &account.id, stmt.pst_time, ref_time)?,
+            None => PostingLineRepository::find_by_account_and_pst_time_lte_and_discarded_is_null_order_by_record_time_desc(
+                conn,
+                &account.id,
+                ref_time,
+            )?,
+        };
+        lines.retain(|line| affects_balance(line.pst_type));
+        Ok((base_stmt, lines))
+    }
+
+    /// Loads the lines of a statement of the given status: those of
+    /// `base_stmt_and_lines`, plus the simulated lines posted after the base
+    /// statement for a SIMULATED statement.
+    fn stmt_lines<C: Repositories>(
+        &self,
+        conn: &mut C,
+        account: &LedgerAccount,
+        ref_time: NaiveDateTime,
+        stmt_status: StmtStatus,
+    ) -> ServiceResult<(Option<AccountStmt>, Vec<PostingLine>)> {
+        let (base_stmt, mut lines) = self.base_stmt_and_lines(conn, account, ref_time)?;
+        if stmt_status == StmtStatus::SIMULATED {
+            let base_time = base_stmt.as_ref().map(|stmt| stmt.pst_time);
+            lines.extend(
+                PostingLineRepository::find_simulated_by_account_and_pst_time_lte(conn, &account.id, ref_time)?
+                    .into_iter()
+                    .filter(|line| base_time.is_none_or(|base_time| line.pst_time > base_time))
+                    .filter(|line| affects_balance(line.pst_type)),
+            );
+        }
+        Ok((base_stmt, lines))
+    }
+
+    /// Closes the statement of an account at the given posting time.
+    ///
+    /// The balance of the account is documented by a `BAL_STMT` posting, whose
+    /// id is also the id of the new CLOSED statement. One posting trace is written
+    /// for each posting included since the previous closed statement. The account
+    /// must not already be closed at or after the posting time.
+    pub fn close_account_stmt<C: Repositories>(
+        &self,
+        conn: &mut C,
+        account_id: &str,
+        pst_time: NaiveDateTime,
+        record_user: &str,
+    ) -> ServiceResult<AccountStmt> {
+        self.save_stmt(conn, account_id, pst_time, record_user, StmtStatus::CLOSED)
+    }
+
+    /// Records a CLOSED statement, or a SIMULATED one including simulated lines
+    /// and documented by a simulated posting.
+    pub(crate) fn save_stmt<C: Repositories>(
+        &self,
+        conn: &mut C,
+        account_id: &str,
+        pst_time: NaiveDateTime,
+        record_user: &str,
+        stmt_status: StmtStatus,
+    ) -> ServiceResult<AccountStmt> {
+        conn.transaction(|conn| {
+            let account = LedgerAccountRepository::find_by_id(conn, account_id)?
+                .ok_or_else(|| ServiceError::AccountNotFound(account_id.to_string()))?;
+            if let Some(closed) = AccountStmtRepository::find_first_by_account_and_stmt_status_and_pst_time_gte(
+                conn,
+                &account.id,
+                StmtStatus::CLOSED,
+                pst_time,
+            )? {
+                return Err(ServiceError::AccountStmtClosed { account_id: account.id, pst_time: closed.pst_time });
+            }
+            self.write_stmt(conn, &account, pst_time, record_user, stmt_status, None)
+        })
+    }
+
+    /// Closes again the statements of an account at or after the given posting
+    /// time, once postings have been recorded before them.
+    ///
+    /// Each posting time of a CLOSED statement is closed again in order, with a
+    /// `stmt_seq_nbr` above those of all the statements of the account, so that
+    /// the new statement supersedes the previous ones at that time. It gets its
+    /// own `BAL_STMT` posting and fresh posting traces. Returns the new statements.
+    pub fn reclose_account_stmts<C: Repositories>(
+        &self,
+        conn: &mut C,
+        account_id: &str,
+        pst_time: NaiveDateTime,
+        record_user: &str,
+    ) -> ServiceResult<Vec<AccountStmt>> {
+        conn.transaction(|conn| {
+            let account = LedgerAccountRepository::find_by_id(conn, account_id)?
+                .ok_or_else(|| ServiceError::AccountNotFound(account_id.to_string()))?;
+            let closed =
+                AccountStmtRepository::find_by_account_and_stmt_status_and_pst_time_gte(conn, &account.id, StmtStatus::CLOSED, pst_time)?;
+            // Statements are closed in order, the last one carries the highest number.
+            let Some(mut stmt_seq_nbr) = closed.iter().map(|stmt| stmt.stmt_seq_nbr).max() else {
+                return Ok(Vec::new());
+            };
+            let mut stmt_times: Vec<NaiveDateTime> = closed.iter().map(|stmt| stmt.pst_time).collect();
+            stmt_times.dedup();
+
+            let mut stmts = Vec::with_capacity(stmt_times.len());
+            for stmt_time in stmt_times {
+                stmt_seq_nbr += 1;
+                stmts.push(self.write_stmt(conn, &account, stmt_time, record_user, StmtStatus::CLOSED, Some(stmt_seq_nbr))?);
+            }
+            Ok(stmts)
+        })
+    }
+
+    /// Computes and records a statement with its posting and traces. Its
+    /// `stmt_seq_nbr` follows the one of its base statement unless given.
+    fn write_stmt<C: Repositories>(
+        &self,
+        conn: &mut C,
+        account: &LedgerAccount,
+        pst_time: NaiveDateTime,
+        record_user: &str,
+        stmt_status: StmtStatus,
+        stmt_seq_nbr: Option<i32>,
+    ) -> ServiceResult<AccountStmt> {
+        let (base_stmt, lines) = self.stmt_lines(conn, account, pst_time, stmt_status)?;
+        let (total_debit, total_credit) = totals(base_stmt.as_ref(), &lines);
+
+        let stmt_id = new_id();
+        let record_time = self.posting_service.stmt_record_time(conn, &account.ledger_id)?;
+        let mut new_posting =
+            stmt_posting(&stmt_id, &account.ledger_id, PostingType::BalStmt, pst_time, record_time, record_user);
+        if stmt_status == StmtStatus::SIMULATED {
+            new_posting.pst_status = PostingStatus::SIMULATED;
+        }
+        let (posting, _) = self.posting_service.new_posting(
+            conn,
+            new_posting,
+            stmt_lines(&stmt_id, &account.id, PostingType::BalStmt, total_debit, total_credit),
+        )?;
+
+        let traces = self.traces(conn, &posting.id, account, &lines)?;
+        let youngest_pst_id = traces.iter().max_by_key(|(record_time, _, _)| *record_time).map(|t| t.2.id.clone());
+        let latest_pst_id = traces.iter().max_by_key(|(_, pst_time, _)| *pst_time).map(|t| t.2.id.clone());
+        PostingTraceRepository::save_all(conn, traces.into_iter().map(|t| t.2).collect())?;
+
+        let stmt = AccountStmtRepository::save(
+            conn,
+            NewAccountStmt {
+                id: stmt_id,
+                posting_id: Some(posting.id),
+                pst_time,
+                stmt_status,
+                latest_pst_id,
+                stmt_seq_nbr: stmt_seq_nbr.unwrap_or_else(|| base_stmt.map_or(0, |stmt| stmt.stmt_seq_nbr + 1)),
+                created: Some(posting.record_time),
+                user_details: Some(record_user.to_string()),
+                short_desc: None,
+                long_desc: None,
+                account_id: account.id.clone(),
+                youngest_pst_id,
+                total_debit,
+                total_credit,
+            },
+        )?;
+        Ok(stmt)
+    }
+
+    /// Builds one trace per source posting of the lines, together with the record
+    /// time and the posting time of that posting.
+    fn traces<C: Repositories>(
+        &self,
+        conn: &mut C,
+        tgt_pst_id: &str,
+        account: &LedgerAccount,
+        lines: &[PostingLine],
+    ) -> ServiceResult<Vec<(NaiveDateTime, NaiveDateTime, NewPostingTrace)>> {
+        let mut by_posting: BTreeMap<(&str, NaiveDateTime), (Decimal, Decimal)> = BTreeMap::new();
+        for line in lines {
+            let amounts = by_posting.entry((line.opr_id.as_str(), line.record_time)).or_default();
+            amounts.0 += line.debit_amount;
+            amounts.1 += line.credit_amount;
+        }
+
+        let mut traces = Vec::with_capacity(by_posting.len());
+        for ((opr_id, record_time), (debit_amount, credit_amount)) in by_posting {
+            // Lines are recorded with their posting: a simulated posting may
+            // share its operation with the effective one.
+            let src = PostingRepository::find_by_opr_id(conn, opr_id)?
+                .into_iter()
+                .find(|posting| posting.record_time == record_time)
+                .ok_or_else(|| ServiceError::OperationNotFound(opr_id.to_string()))?;
+            traces.push((
+                src.record_time,
+                src.pst_time,
+                NewPostingTrace {
+                    id: new_id(),
+                    tgt_pst_id: tgt_pst_id.to_string(),
+                    src_pst_id: src.id,
+                    src_pst_time: Some(src.pst_time),
+                    src_opr_id: src.opr_id,
+                    account_id: account.id.clone(),
+                    debit_amount,
+                    credit_amount,
+                    src_pst_hash: src.hash,
+                },
+            ));
+        }
+        Ok(traces)
+    }
+}
+
+/// The posting documenting a statement at the statement time, recorded at
+/// `record_time`.
+pub(crate) fn stmt_posting(
+    stmt_id: &str,
+    ledger_id: &str,
+    pst_type: PostingType,
+    pst_time: NaiveDateTime,
+    record_time: NaiveDateTime,
+    record_user: &str,
+) -> NewPosting {
+    NewPosting {
+        id: stmt_id.to_string(),
+        antecedent_id: None,
+        antecedent_hash: None,
+        hash: None,
+        hash_alg: None,
+        record_user: record_user.to_string(),
+        record_time,
+        opr_id: stmt_id.to_string(),
+        opr_time: Some(pst_time),
+        opr_type: Some(stmt_opr_type(pst_type).to_string()),
+        opr_src: None,
+        pst_time,
+        pst_type,
+        pst_status: PostingStatus::POSTED,
+        ledger_id: ledger_id.to_string(),
+        val_time: None,
+        discarded_id: None,
+        discarded_time: None,
+        discarding_id: None,
+        opr_details_id: None,
+    }
+}
+
+/// The operation type recorded on statement postings, named like the posting type.
+fn stmt_opr_type(pst_type: PostingType) -> &'static str {
+    match pst_type {
+        PostingType::PnLStmt => "PnL_STMT",
+        PostingType::BsStmt => "BS_STMT",
+        PostingType::LdgClsng => "LDG_CLSNG",
+        _ => "BAL_STMT",
+    }
+}
+
+/// The statement posting is mechanical: both lines book the account, the first
+/// one carrying the totals on their own sides and the second one mirroring it.
+pub(crate) fn stmt_lines(
+    line_id_prefix: &str,
+    account_id: &str,
+    pst_type: PostingType,
+    total_debit: Decimal,
+    total_credit: Decimal,
+) -> Vec<NewPostingLine> {
+    [("dr", total_debit, total_credit), ("cr", total_credit, total_debit)]
+        .into_iter()
+        .map(|(suffix, debit_amount, credit_amount)| NewPostingLine {
+            id: format!("{}_{}", line_id_prefix, suffix),
+            account_id: account_id.to_string(),
+            debit_amount,
+            credit_amount,
+            details_id: None,
+            src_account: None,
+            base_line: None,
+            sub_opr_src_id: None,
+            // Posting fields are denormalized by the posting service.
+            record_time: NaiveDateTime::default(),
+            opr_id: String::new(),
+            opr_src: None,
+            pst_time: NaiveDateTime::default(),
+            pst_type,
+            pst_status: PostingStatus::POSTED,
+            hash: String::new(),
+            discarded_time: None,
+        })
+        .collect()
+}
@@ -0,0 +1,149 @@
+// src/account_tree_service.rs
+/*
+ * Copyright (c) 2018-2024 adorsys GmbH and Co. KG
+ * All rights are reserved.
+ */
+
+use std::collections::HashMap;
+
+use chrono::NaiveDateTime;
+use rust_decimal::Decimal;
+
+use postings_repository::models::{LedgerAccount, NewLedgerAccount};
+use postings_repository::repository::{LedgerAccountRepository, PostingLineRepository, Repositories};
+
+use crate::account_stmt_service::{signed_balance, AccountStmtService};
+use crate::error::{ServiceError, ServiceResult};
+use crate::financial_stmt_service::ReportNode;
+
+/// Maintains the `parent_id` hierarchy of ledger accounts and rolls balances
+/// up the tree.
+///
+/// A child account must belong to the chart of account of its parent, and the
+/// hierarchy must stay a tree. A root account has no parent or references
+/// itself.
+#[derive(Debug, Clone, Default)]
+pub struct AccountTreeService {
+    account_stmt_service: AccountStmtService,
+}
+
+impl AccountTreeService {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a service reading account balances with the given statement service.
+    pub fn with_account_stmt_service(account_stmt_service: AccountStmtService) -> Self {
+        Self { account_stmt_service }
+    }
+
+    /// Creates an account below its parent, which must exist and share its
+    /// chart of account.
+    pub fn create_account<C: Repositories>(
+        &self,
+        conn: &mut C,
+        new_account: NewLedgerAccount,
+    ) -> ServiceResult<LedgerAccount> {
+        if let Some(parent_id) = new_account.parent_id.as_deref().filter(|parent_id| *parent_id != new_account.id) {
+            let parent = find_account(conn, parent_id)?;
+            check_same_coa(&new_account.id, &new_account.coa_id, &parent)?;
+        }
+        Ok(LedgerAccountRepository::save(conn, new_account)?)
+    }
+
+    /// Updates an account. Its parent must share its chart of account and must
+    /// not be the account itself or one of its descendants, and so must its
+    /// children. The ledger, category and balance side of an account booked by
+    /// posting lines are fixed, as its lines and statements depend on them.
+    pub fn update_account<C: Repositories>(&self, conn: &mut C, account: &LedgerAccount) -> ServiceResult<LedgerAccount> {
+        conn.transaction(|conn| {
+            if let Some(current) = LedgerAccountRepository::find_by_id(conn, &account.id)? {
+                let booked_fields_changed = current.ledger_id != account.ledger_id
+                    || current.category != account.category
+                    || current.balance_side != account.balance_side;
+                if booked_fields_changed && PostingLineRepository::exists_by_account(conn, &account.id)? {
+                    return Err(ServiceError::AccountBooked { account_id: account.id.clone() });
+                }
+            }
+            if let Some(parent_id) = account.parent_id.as_deref().filter(|parent_id| *parent_id != account.id) {
+                let parent = find_account(conn, parent_id)?;
+                check_same_coa(&account.id, &account.coa_id, &parent)?;
+                let subtree = LedgerAccountRepository::find_subtree(conn, &account.id)?;
+                if subtree.iter().any(|descendant| descendant.id == parent_id) {
+                    return Err(ServiceError::CyclicAccountHierarchy {
+                        account_id: account.id.clone(),
+                        parent_id: parent_id.to_string(),
+                    });
+                }
+            }
+            for child in LedgerAccountRepository::find_children(conn, &account.id)? {
+                check_same_coa(&child.id, &child.coa_id, account)?;
+            }
+            Ok(LedgerAccountRepository::update(conn, account)?)
+        })
+    }
+
+    /// Reads the balances of an account and of all its descendants at the
+    /// reference time. Each node holds the totals of its own lines plus the
+    /// totals of its children, presented on its own balance side.
+    pub fn rolled_up_balance<C: Repositories>(
+        &self,
+        conn: &mut C,
+        account_id: &str,
+        ref_time: NaiveDateTime,
+    ) -> ServiceResult<ReportNode> {
+        let subtree = LedgerAccountRepository::find_subtree(conn, account_id)?;
+        let root = subtree.first().cloned().ok_or_else(|| ServiceError::AccountNotFound(account_id.to_string()))?;
+        // The subtree is ordered level by level, so children keep their name order.
+        let mut children: HashMap<String, Vec<LedgerAccount>> = HashMap::new();
+        for account in subtree.into_iter().skip(1) {
+            let parent_id = account.parent_id.clone().unwrap_or_default();
+            children.entry(parent_id).or_default().push(account);
+        }
+        self.node(conn, root, &mut children, ref_time)
+    }
+
+    fn node<C: Repositories>(
+        &self,
+        conn: &mut C,
+        account: LedgerAccount,
+        children: &mut HashMap<String, Vec<LedgerAccount>>,
+        ref_time: NaiveDateTime,
+    ) -> ServiceResult<ReportNode> {
+        let stmt = self.account_stmt_service.read_stmt(conn, &account.id, ref_time)?;
+        let child_nodes = children
+            .remove(&account.id)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|child| self.node(conn, child, children, ref_time))
+            .collect::<ServiceResult<Vec<ReportNode>>>()?;
+        let total_debit = stmt.total_debit + child_nodes.iter().map(|child| child.total_debit).sum::<Decimal>();
+        let total_credit = stmt.total_credit + child_nodes.iter().map(|child| child.total_credit).sum::<Decimal>();
+        Ok(ReportNode {
+            account_id: account.id,
+            name: account.name,
+            balance_side: account.balance_side,
+            total_debit,
+            total_credit,
+            balance: signed_balance(account.balance_side, total_debit, total_credit),
+            children: child_nodes,
+        })
+    }
+}
+
+fn find_account<C: Repositories>(conn: &mut C, account_id: &str) -> ServiceResult<LedgerAccount> {
+    LedgerAccountRepository::find_by_id(conn, account_id)?
+        .ok_or_else(|| ServiceError::AccountNotFound(account_id.to_string()))
+}
+
+fn check_same_coa(account_id: &str, coa_id: &str, parent: &LedgerAccount) -> ServiceResult<()> {
+    if coa_id != parent.coa_id {
+        return Err(ServiceError::CoaDiffersFromParent {
+            account_id: account_id.to_string(),
+            coa_id: coa_id.to_string(),
+            parent_id: parent.id.clone(),
+            parent_coa_id: parent.coa_id.clone(),
+        });
+    }
+    Ok(())
+}
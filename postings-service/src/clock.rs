@@ -0,0 +1,47 @@
+// src/clock.rs
+/*
+ * Copyright (c) 2018-2024 adorsys GmbH and Co. KG
+ * All rights are reserved.
+ */
+
+use std::fmt;
+use std::sync::Mutex;
+
+use chrono::{NaiveDateTime, Utc};
+
+/// The source of the current time of the services that act on their own, such
+/// as `DeferredPostingScheduler`, or record postings of their own, such as the
+/// statement postings of `PostingService`.
+pub trait Clock: fmt::Debug + Send + Sync {
+    fn now(&self) -> NaiveDateTime;
+}
+
+/// The system time in UTC, the default clock.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> NaiveDateTime {
+        Utc::now().naive_utc()
+    }
+}
+
+/// A clock standing still until it is set, for tests and replays.
+#[derive(Debug)]
+pub struct FixedClock(Mutex<NaiveDateTime>);
+
+impl FixedClock {
+    pub fn new(now: NaiveDateTime) -> Self {
+        Self(Mutex::new(now))
+    }
+
+    pub fn set(&self, now: NaiveDateTime) {
+        *self.0.lock().unwrap_or_else(|err| err.into_inner()) = now;
+    }
+}
+
+impl Clock for FixedClock {
+    fn now(&self) -> NaiveDateTime {
+        *self.0.lock().unwrap_or_else(|err| err.into_inner())
+    }
+}
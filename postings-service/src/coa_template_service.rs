@@ -0,0 +1,392 @@
+// src/coa_template_service.rs
+/*
+ * Copyright (c) 2018-2024 adorsys GmbH and Co. KG
+ * All rights are reserved.
+ */
+
+//! Charts of account described by templates, and their import.
+//!
+//! A template lists the accounts of a chart with their number, name,
+//! category, balance side and the number of their parent. It is written in
+//! JSON:
+//!
+//! ```json
+//! {
+//!   "name": "IFRS",
+//!   "short_desc": "Sample chart of account",
+//!   "accounts": [
+//!     { "number": "1.0.0", "name": "Assets", "category": "AS" },
+//!     { "number": "1.1.0", "name": "Cash", "category": "AS", "parent": "1.0.0" }
+//!   ]
+//! }
+//! ```
+//!
+//! Imported accounts are named after their number and described by their
+//! name, like the accounts of the sample data. Exports read them back the same
+//! way, so charts and ledgers can be compared as templates.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+
+use postings_repository::models::enums::{AccountCategory, BalanceSide};
+use postings_repository::models::{
+    ChartOfAccount, Ledger, LedgerAccount, NewChartOfAccount, NewLedger, NewLedgerAccount,
+};
+use postings_repository::repository::{
+    ChartOfAccountRepository, LedgerAccountRepository, LedgerRepository, Repositories,
+};
+
+use crate::clock::{Clock, SystemClock};
+use crate::error::{ServiceError, ServiceResult};
+use crate::financial_stmt_service::category_balance_side;
+use crate::new_id;
+
+/// A chart of account and its accounts.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CoaTemplate {
+    pub name: String,
+    #[serde(default)]
+    pub short_desc: Option<String>,
+    #[serde(default)]
+    pub long_desc: Option<String>,
+    pub accounts: Vec<AccountTemplate>,
+}
+
+/// An account of a template.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AccountTemplate {
+    /// The account number, unique within the template.
+    pub number: String,
+    pub name: String,
+    pub category: AccountCategory,
+    /// The side on which the account increases, the usual side of its
+    /// category if not given.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub balance_side: Option<BalanceSide>,
+    /// The number of the parent account, `None` for a root account.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent: Option<String>,
+}
+
+impl AccountTemplate {
+    pub fn balance_side(&self) -> BalanceSide {
+        self.balance_side.unwrap_or_else(|| category_balance_side(self.category))
+    }
+}
+
+/// The templates shipped with the service.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BuiltinTemplate {
+    /// The revised OHADA accounting system of the West and Central African states.
+    #[serde(rename = "SYSCOHADA")]
+    Syscohada,
+    /// The German DATEV standard chart of accounts 03.
+    #[serde(rename = "SKR03")]
+    Skr03,
+    /// A small chart following the IFRS statements.
+    #[serde(rename = "IFRS_SAMPLE")]
+    IfrsSample,
+}
+
+impl BuiltinTemplate {
+    pub const ALL: [BuiltinTemplate; 3] = [BuiltinTemplate::Syscohada, BuiltinTemplate::Skr03, BuiltinTemplate::IfrsSample];
+
+    /// Parses the template. The built-in templates are checked by the tests.
+    pub fn template(self) -> CoaTemplate {
+        let json = match self {
+            BuiltinTemplate::Syscohada => include_str!("../templates/syscohada.json"),
+            BuiltinTemplate::Skr03 => include_str!("../templates/skr03.json"),
+            BuiltinTemplate::IfrsSample => include_str!("../templates/ifrs_sample.json"),
+        };
+        serde_json::from_str(json).expect("built-in chart of account templates are valid JSON")
+    }
+}
+
+impl CoaTemplate {
+    /// Reads a template from JSON.
+    pub fn from_json(json: &str) -> ServiceResult<Self> {
+        serde_json::from_str(json).map_err(|err| ServiceError::InvalidTemplate(err.to_string()))
+    }
+
+    /// Writes the template as pretty printed JSON.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("templates serialize to JSON")
+    }
+
+    /// Checks the numbers and parents of the accounts and returns them with
+    /// every parent before its children, keeping the template order otherwise.
+    pub fn ordered_accounts(&self) -> ServiceResult<Vec<&AccountTemplate>> {
+        let mut by_number: HashMap<&str, &AccountTemplate> = HashMap::new();
+        for account in &self.accounts {
+            if account.number.is_empty() {
+                return Err(ServiceError::InvalidTemplate(format!("account {:?} has no number", account.name)));
+            }
+            if by_number.insert(&account.number, account).is_some() {
+                return Err(ServiceError::InvalidTemplate(format!("account number {} is not unique", account.number)));
+            }
+        }
+        let mut ordered = Vec::with_capacity(self.accounts.len());
+        let mut placed: HashSet<&str> = HashSet::new();
+        for account in &self.accounts {
+            // Walks up to the first placed ancestor, then places the chain top down.
+            let mut chain = Vec::new();
+            let mut current = Some(account);
+            while let Some(account) = current.filter(|account| !placed.contains(account.number.as_str())) {
+                if chain.iter().any(|other: &&AccountTemplate| other.number == account.number) {
+                    return Err(ServiceError::InvalidTemplate(format!(
+                        "account {} is its own ancestor",
+                        account.number
+                    )));
+                }
+                chain.push(account);
+                current = match account.parent.as_deref() {
+                    Some(parent) => Some(*by_number.get(parent).ok_or_else(|| {
+                        ServiceError::InvalidTemplate(format!(
+                            "parent {} of account {} is not part of the template",
+                            parent, account.number
+                        ))
+                    })?),
+                    None => None,
+                };
+            }
+            for account in chain.into_iter().rev() {
+                placed.insert(&account.number);
+                ordered.push(account);
+            }
+        }
+        Ok(ordered)
+    }
+
+    /// Compares the accounts of two templates by number. Names, descriptions
+    /// and parents are not compared.
+    pub fn diff(&self, other: &CoaTemplate) -> CoaDiff {
+        let from: BTreeMap<&str, &AccountTemplate> =
+            self.accounts.iter().map(|account| (account.number.as_str(), account)).collect();
+        let to: BTreeMap<&str, &AccountTemplate> =
+            other.accounts.iter().map(|account| (account.number.as_str(), account)).collect();
+        let mut diff = CoaDiff::default();
+        for (number, account) in &from {
+            let Some(other) = to.get(number) else {
+                diff.removed.push((*account).clone());
+                continue;
+            };
+            if account.category != other.category {
+                diff.recategorized.push(AccountChange::new(number, account.category, other.category));
+            }
+            if account.balance_side() != other.balance_side() {
+                diff.balance_side_changed.push(AccountChange::new(number, account.balance_side(), other.balance_side()));
+            }
+        }
+        diff.added = to
+            .iter()
+            .filter(|(number, _)| !from.contains_key(*number))
+            .map(|(_, account)| (*account).clone())
+            .collect();
+        diff
+    }
+}
+
+/// The differences between two templates, each list ordered by account number.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CoaDiff {
+    /// The accounts only found in the second template.
+    pub added: Vec<AccountTemplate>,
+    /// The accounts only found in the first template.
+    pub removed: Vec<AccountTemplate>,
+    pub recategorized: Vec<AccountChange<AccountCategory>>,
+    pub balance_side_changed: Vec<AccountChange<BalanceSide>>,
+}
+
+impl CoaDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty()
+            && self.removed.is_empty()
+            && self.recategorized.is_empty()
+            && self.balance_side_changed.is_empty()
+    }
+}
+
+/// A property of an account that differs between two templates.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AccountChange<T> {
+    pub number: String,
+    pub from: T,
+    pub to: T,
+}
+
+impl<T> AccountChange<T> {
+    fn new(number: &str, from: T, to: T) -> Self {
+        Self { number: number.to_string(), from, to }
+    }
+}
+
+/// The records created by an import.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImportedChart {
+    pub coa: ChartOfAccount,
+    pub ledger: Ledger,
+    /// The accounts, every parent before its children.
+    pub accounts: Vec<LedgerAccount>,
+}
+
+/// Imports charts of account from templates.
+#[derive(Debug, Clone)]
+pub struct CoaTemplateService {
+    clock: Arc<dyn Clock>,
+}
+
+impl Default for CoaTemplateService {
+    fn default() -> Self {
+        Self { clock: Arc::new(SystemClock) }
+    }
+}
+
+impl CoaTemplateService {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Tells the creation time of the imported records with the given clock.
+    pub fn with_clock(self, clock: Arc<dyn Clock>) -> Self {
+        Self { clock }
+    }
+
+    /// Creates the chart of account of the template, a ledger named
+    /// `ledger_name` using it, and all accounts of the template in that ledger,
+    /// in one transaction.
+    pub fn import<C: Repositories>(
+        &self,
+        conn: &mut C,
+        template: &CoaTemplate,
+        ledger_name: &str,
+        user_details: &str,
+    ) -> ServiceResult<ImportedChart> {
+        let accounts = template.ordered_accounts()?;
+        let created = self.clock.now();
+        conn.transaction(|conn| {
+            let coa = ChartOfAccountRepository::save(
+                conn,
+                NewChartOfAccount {
+                    id: new_id(),
+                    created,
+                    user_details: user_details.to_string(),
+                    short_desc: template.short_desc.clone(),
+                    long_desc: template.long_desc.clone(),
+                    name: template.name.clone(),
+                },
+            )?;
+            let ledger = LedgerRepository::save(
+                conn,
+                NewLedger {
+                    id: new_id(),
+                    created,
+                    user_details: user_details.to_string(),
+                    short_desc: None,
+                    long_desc: None,
+                    name: ledger_name.to_string(),
+                    coa_id: coa.id.clone(),
+                },
+            )?;
+            let mut ids: HashMap<&str, String> = HashMap::new();
+            let mut saved = Vec::with_capacity(accounts.len());
+            for account in accounts {
+                let id = new_id();
+                ids.insert(&account.number, id.clone());
+                saved.push(LedgerAccountRepository::save(
+                    conn,
+                    NewLedgerAccount {
+                        id,
+                        created,
+                        user_details: user_details.to_string(),
+                        short_desc: Some(account.name.clone()),
+                        long_desc: None,
+                        name: account.number.clone(),
+                        ledger_id: ledger.id.clone(),
+                        parent_id: account.parent.as_deref().map(|parent| ids[parent].clone()),
+                        coa_id: coa.id.clone(),
+                        balance_side: account.balance_side(),
+                        category: account.category,
+                    },
+                )?);
+            }
+            Ok(ImportedChart { coa, ledger, accounts: saved })
+        })
+    }
+
+    /// Exports the accounts of a chart of account over all its ledgers. An
+    /// account number used in several ledgers is exported once, as found in
+    /// the first ledger by id.
+    pub fn export<C: Repositories>(&self, conn: &mut C, coa_id: &str) -> ServiceResult<CoaTemplate> {
+        let coa = ChartOfAccountRepository::find_by_id(conn, coa_id)?
+            .ok_or_else(|| ServiceError::CoaNotFound(coa_id.to_string()))?;
+        let accounts = LedgerAccountRepository::find_by_coa(conn, coa_id)?;
+        to_template(conn, coa, accounts)
+    }
+
+    /// Exports the accounts of a ledger, described by its chart of account.
+    pub fn export_ledger<C: Repositories>(&self, conn: &mut C, ledger_id: &str) -> ServiceResult<CoaTemplate> {
+        let ledger = LedgerRepository::find_by_id(conn, ledger_id)?
+            .ok_or_else(|| ServiceError::LedgerNotFound(ledger_id.to_string()))?;
+        let coa = ChartOfAccountRepository::find_by_id(conn, &ledger.coa_id)?
+            .ok_or_else(|| ServiceError::CoaNotFound(ledger.coa_id.clone()))?;
+        let accounts = LedgerAccountRepository::find_by_ledger(conn, ledger_id)?;
+        to_template(conn, coa, accounts)
+    }
+
+    /// Compares the accounts of two charts of account.
+    pub fn diff_charts<C: Repositories>(&self, conn: &mut C, from_coa_id: &str, to_coa_id: &str) -> ServiceResult<CoaDiff> {
+        Ok(self.export(conn, from_coa_id)?.diff(&self.export(conn, to_coa_id)?))
+    }
+
+    /// Compares the accounts of two ledgers.
+    pub fn diff_ledgers<C: Repositories>(
+        &self,
+        conn: &mut C,
+        from_ledger_id: &str,
+        to_ledger_id: &str,
+    ) -> ServiceResult<CoaDiff> {
+        Ok(self.export_ledger(conn, from_ledger_id)?.diff(&self.export_ledger(conn, to_ledger_id)?))
+    }
+}
+
+/// Builds a template from accounts ordered by name. Parents outside of the
+/// given accounts are read from the repository.
+fn to_template<C: Repositories>(
+    conn: &mut C,
+    coa: ChartOfAccount,
+    accounts: Vec<LedgerAccount>,
+) -> ServiceResult<CoaTemplate> {
+    let mut numbers: HashMap<String, String> =
+        accounts.iter().map(|account| (account.id.clone(), account.name.clone())).collect();
+    let mut exported: HashSet<String> = HashSet::new();
+    let mut templates = Vec::with_capacity(accounts.len());
+    for account in accounts {
+        if !exported.insert(account.name.clone()) {
+            continue;
+        }
+        let parent = match account.parent_id.as_deref().filter(|parent_id| *parent_id != account.id) {
+            Some(parent_id) => Some(match numbers.get(parent_id) {
+                Some(number) => number.clone(),
+                None => {
+                    let parent = LedgerAccountRepository::find_by_id(conn, parent_id)?
+                        .ok_or_else(|| ServiceError::AccountNotFound(parent_id.to_string()))?;
+                    numbers.insert(parent.id, parent.name.clone());
+                    parent.name
+                }
+            }),
+            None => None,
+        };
+        let balance_side =
+            Some(account.balance_side).filter(|balance_side| *balance_side != category_balance_side(account.category));
+        templates.push(AccountTemplate {
+            number: account.name.clone(),
+            name: account.short_desc.unwrap_or(account.name),
+            category: account.category,
+            balance_side,
+            parent,
+        });
+    }
+    Ok(CoaTemplate { name: coa.name, short_desc: coa.short_desc, long_desc: coa.long_desc, accounts: templates })
+}
@@ -0,0 +1,86 @@
+// src/deferred_posting_scheduler.rs
+/*
+ * Copyright (c) 2018-2024 adorsys GmbH and Co. KG
+ * All rights are reserved.
+ */
+
+use std::sync::Arc;
+
+use postings_repository::models::enums::PostingStatus;
+use postings_repository::models::Posting;
+use postings_repository::repository::{PostingRepository, Repositories};
+
+use crate::clock::{Clock, SystemClock};
+use crate::error::{ServiceError, ServiceResult};
+use crate::posting_service::PostingService;
+
+/// The user recorded on the promotions and statements of the scheduler.
+pub const SCHEDULER_USER: &str = "SCHEDULER";
+
+/// The outcome of a run of the scheduler.
+#[derive(Debug, Default)]
+pub struct SchedulerRun {
+    /// The postings promoted during the run.
+    pub promoted: Vec<Posting>,
+    /// The due postings that could not be promoted. Those falling before a
+    /// closed statement or ledger closing are cancelled, the others stay
+    /// deferred and are tried again on the next run.
+    pub failed: Vec<(String, ServiceError)>,
+}
+
+/// Promotes DEFERRED postings to POSTED once their posting time has come.
+///
+/// Each promotion is recorded in its own transaction together with its op note.
+/// Statements closed after the posting time are handled by the
+/// `BackdatedPostingPolicy` of the posting service: the statements are closed
+/// again, or the promotion fails and the posting is cancelled with an op note
+/// of type `POSTING_PROMOTION_FAILED_NOTE`, since it would fail on every run.
+/// A posting that is no longer deferred is skipped, so a run interrupted by a
+/// restart or overlapping with another one promotes every posting once.
+#[derive(Debug, Clone)]
+pub struct DeferredPostingScheduler {
+    posting_service: PostingService,
+    clock: Arc<dyn Clock>,
+}
+
+impl Default for DeferredPostingScheduler {
+    fn default() -> Self {
+        Self::with_clock(Arc::new(SystemClock))
+    }
+}
+
+impl DeferredPostingScheduler {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a scheduler telling the time with the given clock, which also
+    /// tells the record time of the statements it closes again.
+    pub fn with_clock(clock: Arc<dyn Clock>) -> Self {
+        Self { posting_service: PostingService::default().with_clock(clock.clone()), clock }
+    }
+
+    /// Creates a scheduler promoting postings with the given service.
+    pub fn with_services(posting_service: PostingService, clock: Arc<dyn Clock>) -> Self {
+        Self { posting_service, clock }
+    }
+
+    /// Promotes the deferred postings due at the current time of the clock, in
+    /// posting time order.
+    pub fn run_due<C: Repositories>(&self, conn: &mut C) -> ServiceResult<SchedulerRun> {
+        let now = self.clock.now();
+        let mut run = SchedulerRun::default();
+        for due in PostingRepository::find_by_pst_status_and_pst_time_lte(conn, PostingStatus::DEFERRED, now)? {
+            match self.posting_service.promote(conn, &due.id, SCHEDULER_USER, self.clock.now()) {
+                Ok(Some((posting, _))) => run.promoted.push(posting),
+                Ok(None) => {}
+                Err(err @ (ServiceError::AccountStmtClosed { .. } | ServiceError::LedgerClosed { .. })) => {
+                    self.posting_service.cancel_deferred(conn, &due.id, &err.to_string(), self.clock.now())?;
+                    run.failed.push((due.id, err));
+                }
+                Err(err) => run.failed.push((due.id, err)),
+            }
+        }
+        Ok(run)
+    }
+}
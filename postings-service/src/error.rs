@@ -0,0 +1,181 @@
+// src/error.rs
+/* 
+ * Copyright (c) 2018-2024 adorsys GmbH and Co. KG
+ * All rights are reserved.
+ */
+
+use std::fmt;
+
+use chrono::NaiveDateTime;
+use postings_repository::models::enums::PostingStatus;
+use postings_repository::repository::RepositoryError;
+use rust_decimal::Decimal;
+
+/// Errors raised by the posting services.
+#[derive(Debug)]
+pub enum ServiceError {
+    /// A posting must carry at least one posting line.
+    NoPostingLines,
+    /// The sum of the debit amounts differs from the sum of the credit amounts.
+    UnbalancedPosting { total_debit: Decimal, total_credit: Decimal },
+    /// The amounts of a posting line can not be negative.
+    NegativeAmount { line_id: String },
+    /// A posting line books either a debit or a credit amount, not both.
+    DebitAndCreditLine { line_id: String },
+    /// The referenced chart of account does not exist.
+    CoaNotFound(String),
+    /// The referenced ledger does not exist.
+    LedgerNotFound(String),
+    /// The referenced ledger account does not exist.
+    AccountNotFound(String),
+    /// The ledger account does not belong to the ledger of the posting.
+    AccountNotInLedger { account_id: String, ledger_id: String },
+    /// The operation has no effective posting.
+    OperationNotFound(String),
+    /// The referenced posting does not exist.
+    PostingNotFound(String),
+    /// Only proposed postings can be approved or rejected.
+    PostingNotProposed { posting_id: String, pst_status: PostingStatus },
+    /// A proposed posting must be approved or rejected by another user than
+    /// the one who recorded it.
+    CheckerIsMaker { posting_id: String, user: String },
+    /// The record time of a posting must be after the record time of the
+    /// youngest posting of its ledger, which the posting is chained to.
+    RecordTimeNotAfterAntecedent { record_time: NaiveDateTime, antecedent_id: String },
+    /// The account has already been closed at the given posting time, which is
+    /// at or after the requested closing time.
+    AccountStmtClosed { account_id: String, pst_time: NaiveDateTime },
+    /// The ledger has already been closed at the given posting time.
+    LedgerClosed { ledger_id: String, pst_time: NaiveDateTime },
+    /// The result of a ledger can only be transferred to an equity account.
+    NotAnEquityAccount(String),
+    /// The grand totals of the ledger differ.
+    UnbalancedLedger { ledger_id: String, total_debit: Decimal, total_credit: Decimal },
+    /// A child account must belong to the chart of account of its parent.
+    CoaDiffersFromParent { account_id: String, coa_id: String, parent_id: String, parent_coa_id: String },
+    /// The parent of an account can not be the account itself or one of its descendants.
+    CyclicAccountHierarchy { account_id: String, parent_id: String },
+    /// The ledger, category and balance side of an account can not change once
+    /// posting lines book the account.
+    AccountBooked { account_id: String },
+    /// The chart of account template is malformed.
+    InvalidTemplate(String),
+    /// The referenced op note does not exist.
+    OpNoteNotFound(String),
+    /// The op note is planned later and does not allow a premature execution.
+    OpNoteNotDue { note_id: String, exec_time: Option<NaiveDateTime> },
+    /// The op note has already been executed and does not allow a repeated execution.
+    OpNoteExecuted { note_id: String, exec_status: String },
+    /// The op note is being executed by another worker.
+    OpNoteLocked(String),
+    /// No handler is registered for the type of the op note.
+    NoOpNoteHandler { note_id: String, note_type: Option<String> },
+    /// Raised by op note handlers failing for reasons of their own.
+    OpNoteHandlerFailed(String),
+    /// Error returned by the repository layer.
+    Repository(RepositoryError),
+}
+
+pub type ServiceResult<T> = Result<T, ServiceError>;
+
+impl fmt::Display for ServiceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ServiceError::NoPostingLines => write!(f, "posting has no posting lines"),
+            ServiceError::UnbalancedPosting { total_debit, total_credit } => write!(
+                f,
+                "posting is not balanced: total debit {} differs from total credit {}",
+                total_debit, total_credit
+            ),
+            ServiceError::NegativeAmount { line_id } => write!(f, "posting line {} has a negative amount", line_id),
+            ServiceError::DebitAndCreditLine { line_id } => {
+                write!(f, "posting line {} books both a debit and a credit amount", line_id)
+            }
+            ServiceError::CoaNotFound(id) => write!(f, "chart of account {} not found", id),
+            ServiceError::LedgerNotFound(id) => write!(f, "ledger {} not found", id),
+            ServiceError::AccountNotFound(id) => write!(f, "ledger account {} not found", id),
+            ServiceError::AccountNotInLedger { account_id, ledger_id } => write!(
+                f,
+                "ledger account {} does not belong to ledger {}",
+                account_id, ledger_id
+            ),
+            ServiceError::OperationNotFound(opr_id) => write!(f, "no effective posting found for operation {}", opr_id),
+            ServiceError::PostingNotFound(id) => write!(f, "posting {} not found", id),
+            ServiceError::PostingNotProposed { posting_id, pst_status } => {
+                write!(f, "posting {} is {:?}, not PROPOSED", posting_id, pst_status)
+            }
+            ServiceError::CheckerIsMaker { posting_id, user } => {
+                write!(f, "posting {} was recorded by {} and must be checked by another user", posting_id, user)
+            }
+            ServiceError::RecordTimeNotAfterAntecedent { record_time, antecedent_id } => write!(
+                f,
+                "record time {} is not after the record time of the youngest posting {} of the ledger",
+                record_time, antecedent_id
+            ),
+            ServiceError::AccountStmtClosed { account_id, pst_time } => {
+                write!(f, "ledger account {} is already closed at {}", account_id, pst_time)
+            }
+            ServiceError::LedgerClosed { ledger_id, pst_time } => {
+                write!(f, "ledger {} is already closed at {}", ledger_id, pst_time)
+            }
+            ServiceError::NotAnEquityAccount(id) => write!(f, "ledger account {} is not an equity account", id),
+            ServiceError::UnbalancedLedger { ledger_id, total_debit, total_credit } => write!(
+                f,
+                "ledger {} is not balanced: total debit {} differs from total credit {}",
+                ledger_id, total_debit, total_credit
+            ),
+            ServiceError::CoaDiffersFromParent { account_id, coa_id, parent_id, parent_coa_id } => write!(
+                f,
+                "ledger account {} of chart of account {} can not be a child of ledger account {} of chart of account {}",
+                account_id, coa_id, parent_id, parent_coa_id
+            ),
+            ServiceError::CyclicAccountHierarchy { account_id, parent_id } => write!(
+                f,
+                "ledger account {} can not be the parent of ledger account {}, which is one of its ancestors",
+                parent_id, account_id
+            ),
+            ServiceError::AccountBooked { account_id } => write!(
+                f,
+                "ledger account {} has posting lines, its ledger, category and balance side can not change",
+                account_id
+            ),
+            ServiceError::InvalidTemplate(reason) => write!(f, "invalid chart of account template: {}", reason),
+            ServiceError::OpNoteNotFound(id) => write!(f, "op note {} not found", id),
+            ServiceError::OpNoteNotDue { note_id, exec_time } => match exec_time {
+                Some(exec_time) => write!(f, "op note {} is planned at {} and can not be executed before", note_id, exec_time),
+                None => write!(f, "op note {} is not planned and can not be executed before", note_id),
+            },
+            ServiceError::OpNoteExecuted { note_id, exec_status } => {
+                write!(f, "op note {} is already {} and can not be executed again", note_id, exec_status)
+            }
+            ServiceError::OpNoteLocked(id) => write!(f, "op note {} is being executed", id),
+            ServiceError::NoOpNoteHandler { note_id, note_type } => {
+                write!(f, "no handler for the type {:?} of op note {}", note_type, note_id)
+            }
+            ServiceError::OpNoteHandlerFailed(reason) => write!(f, "op note handler failed: {}", reason),
+            ServiceError::Repository(err) => write!(f, "repository error: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for ServiceError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ServiceError::Repository(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<RepositoryError> for ServiceError {
+    fn from(err: RepositoryError) -> Self {
+        ServiceError::Repository(err)
+    }
+}
+
+/// Needed by transactions, which fail with Diesel errors of their own.
+impl From<diesel::result::Error> for ServiceError {
+    fn from(err: diesel::result::Error) -> Self {
+        ServiceError::Repository(err.into())
+    }
+}
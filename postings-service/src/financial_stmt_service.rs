@@ -0,0 +1,327 @@
+// src/financial_stmt_service.rs
+/*
+ * Copyright (c) 2018-2024 adorsys GmbH and Co. KG
+ * All rights are reserved.
+ */
+
+use std::collections::HashSet;
+
+use chrono::NaiveDateTime;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+
+use postings_repository::models::enums::{AccountCategory, BalanceSide, PostingType};
+use postings_repository::models::Posting;
+use postings_repository::repository::Repositories;
+
+use crate::account_stmt_service::{signed_balance, stmt_lines, stmt_posting};
+use crate::error::{ServiceError, ServiceResult};
+use crate::ledger_stmt_service::{LedgerStmtService, TrialBalance, TrialBalanceLine};
+use crate::new_id;
+use crate::posting_service::PostingService;
+
+/// The categories presented in a profit and loss report, in report order.
+const PNL_CATEGORIES: [AccountCategory; 5] = [
+    AccountCategory::RE,
+    AccountCategory::EX,
+    AccountCategory::NORE,
+    AccountCategory::NOEX,
+    AccountCategory::NOOP,
+];
+
+/// The categories presented in a balance sheet, in report order.
+const BS_CATEGORIES: [AccountCategory; 3] = [AccountCategory::AS, AccountCategory::LI, AccountCategory::EQ];
+
+/// The side on which the accounts of a category usually increase.
+pub fn category_balance_side(category: AccountCategory) -> BalanceSide {
+    match category {
+        AccountCategory::RE | AccountCategory::LI | AccountCategory::EQ | AccountCategory::NORE => BalanceSide::Cr,
+        AccountCategory::EX | AccountCategory::AS | AccountCategory::NOEX => BalanceSide::Dr,
+        AccountCategory::NOOP => BalanceSide::DrCr,
+    }
+}
+
+/// An account of a report, with the totals of the account and of all its
+/// descendants within the same section.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReportNode {
+    pub account_id: String,
+    pub name: String,
+    pub balance_side: BalanceSide,
+    pub total_debit: Decimal,
+    pub total_credit: Decimal,
+    /// The rolled up balance, positive on the balance side of the account.
+    pub balance: Decimal,
+    pub children: Vec<ReportNode>,
+}
+
+/// The accounts of one category, arranged along their `parent_id` hierarchy.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReportSection {
+    pub category: AccountCategory,
+    /// The balance of all accounts of the category, positive on the balance
+    /// side of the category.
+    pub total: Decimal,
+    pub accounts: Vec<ReportNode>,
+}
+
+impl ReportSection {
+    fn of(category: AccountCategory, trial_balance: &TrialBalance) -> ServiceResult<Self> {
+        let lines: Vec<&TrialBalanceLine> =
+            trial_balance.lines.iter().filter(|line| line.category == category).collect();
+        let total_debit: Decimal = lines.iter().map(|line| line.total_debit).sum();
+        let total_credit: Decimal = lines.iter().map(|line| line.total_credit).sum();
+        // Accounts whose parent is not part of the section are presented at the top.
+        let mut visited = HashSet::new();
+        let accounts = lines
+            .iter()
+            .filter(|line| {
+                line.parent_id
+                    .as_ref()
+                    .is_none_or(|parent_id| !lines.iter().any(|other| &other.account_id == parent_id))
+            })
+            .map(|line| node(line, &lines, &mut visited))
+            .collect::<ServiceResult<_>>()?;
+        // Accounts not reached from the top are part of a cycle of parents.
+        if let Some(line) = lines.iter().find(|line| !visited.contains(line.account_id.as_str())) {
+            return Err(cyclic(line));
+        }
+        Ok(ReportSection {
+            category,
+            total: signed_balance(category_balance_side(category), total_debit, total_credit),
+            accounts,
+        })
+    }
+}
+
+fn cyclic(line: &TrialBalanceLine) -> ServiceError {
+    ServiceError::CyclicAccountHierarchy {
+        account_id: line.account_id.clone(),
+        parent_id: line.parent_id.clone().unwrap_or_default(),
+    }
+}
+
+/// Builds the node of an account and its descendants, failing on accounts
+/// reached twice.
+fn node<'a>(
+    line: &'a TrialBalanceLine,
+    lines: &[&'a TrialBalanceLine],
+    visited: &mut HashSet<&'a str>,
+) -> ServiceResult<ReportNode> {
+    if !visited.insert(line.account_id.as_str()) {
+        return Err(cyclic(line));
+    }
+    let children: Vec<ReportNode> = lines
+        .iter()
+        .filter(|child| child.parent_id.as_deref() == Some(line.account_id.as_str()))
+        .map(|child| node(child, lines, visited))
+        .collect::<ServiceResult<_>>()?;
+    let total_debit = line.total_debit + children.iter().map(|child| child.total_debit).sum::<Decimal>();
+    let total_credit = line.total_credit + children.iter().map(|child| child.total_credit).sum::<Decimal>();
+    Ok(ReportNode {
+        account_id: line.account_id.clone(),
+        name: line.name.clone(),
+        balance_side: line.balance_side,
+        total_debit,
+        total_credit,
+        balance: signed_balance(line.balance_side, total_debit, total_credit),
+        children,
+    })
+}
+
+fn section_total(sections: &[ReportSection], category: AccountCategory) -> Decimal {
+    sections
+        .iter()
+        .find(|section| section.category == category)
+        .map(|section| section.total)
+        .unwrap_or(Decimal::ZERO)
+}
+
+/// The result of a ledger since its last closing, up to a reference time.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProfitAndLoss {
+    pub ledger_id: String,
+    pub ref_time: NaiveDateTime,
+    pub sections: Vec<ReportSection>,
+    /// Revenue minus expenses.
+    pub operating_result: Decimal,
+    /// Non-operating revenue minus non-operating expenses and the net debit of
+    /// non-operating income or expense accounts.
+    pub non_operating_result: Decimal,
+    pub net_result: Decimal,
+}
+
+impl ProfitAndLoss {
+    fn of(trial_balance: &TrialBalance) -> ServiceResult<Self> {
+        let sections: Vec<ReportSection> = PNL_CATEGORIES
+            .iter()
+            .map(|category| ReportSection::of(*category, trial_balance))
+            .collect::<ServiceResult<_>>()?;
+        let operating_result =
+            section_total(&sections, AccountCategory::RE) - section_total(&sections, AccountCategory::EX);
+        let non_operating_result = section_total(&sections, AccountCategory::NORE)
+            - section_total(&sections, AccountCategory::NOEX)
+            - section_total(&sections, AccountCategory::NOOP);
+        Ok(ProfitAndLoss {
+            ledger_id: trial_balance.ledger_id.clone(),
+            ref_time: trial_balance.ref_time,
+            sections,
+            operating_result,
+            non_operating_result,
+            net_result: operating_result + non_operating_result,
+        })
+    }
+}
+
+/// The assets, liabilities and equity of a ledger at a reference time.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BalanceSheet {
+    pub ledger_id: String,
+    pub ref_time: NaiveDateTime,
+    pub sections: Vec<ReportSection>,
+    pub total_assets: Decimal,
+    pub total_liabilities: Decimal,
+    pub total_equity: Decimal,
+    /// The result not yet transferred to equity by a ledger closing.
+    pub net_result: Decimal,
+}
+
+impl BalanceSheet {
+    fn of(trial_balance: &TrialBalance) -> ServiceResult<Self> {
+        let sections: Vec<ReportSection> = BS_CATEGORIES
+            .iter()
+            .map(|category| ReportSection::of(*category, trial_balance))
+            .collect::<ServiceResult<_>>()?;
+        Ok(BalanceSheet {
+            ledger_id: trial_balance.ledger_id.clone(),
+            ref_time: trial_balance.ref_time,
+            total_assets: section_total(&sections, AccountCategory::AS),
+            total_liabilities: section_total(&sections, AccountCategory::LI),
+            total_equity: section_total(&sections, AccountCategory::EQ),
+            net_result: ProfitAndLoss::of(trial_balance)?.net_result,
+            sections,
+        })
+    }
+
+    /// Assets equal liabilities, equity and the pending result.
+    pub fn is_balanced(&self) -> bool {
+        self.total_assets == self.total_liabilities + self.total_equity + self.net_result
+    }
+}
+
+/// Builds profit and loss reports and balance sheets out of the trial balance
+/// of a ledger.
+#[derive(Debug, Clone, Default)]
+pub struct FinancialStmtService {
+    ledger_stmt_service: LedgerStmtService,
+    posting_service: PostingService,
+}
+
+impl FinancialStmtService {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a service using the given services to read balances and to record
+    /// snapshot postings.
+    pub fn with_services(ledger_stmt_service: LedgerStmtService, posting_service: PostingService) -> Self {
+        Self { ledger_stmt_service, posting_service }
+    }
+
+    /// Groups the revenue and expense accounts of the ledger into a profit and
+    /// loss report.
+    pub fn profit_and_loss<C: Repositories>(
+        &self,
+        conn: &mut C,
+        ledger_id: &str,
+        ref_time: NaiveDateTime,
+    ) -> ServiceResult<ProfitAndLoss> {
+        let trial_balance = self.ledger_stmt_service.trial_balance(conn, ledger_id, ref_time)?;
+        ProfitAndLoss::of(&trial_balance)
+    }
+
+    /// Groups the asset, liability and equity accounts of the ledger into a
+    /// balance sheet.
+    pub fn balance_sheet<C: Repositories>(
+        &self,
+        conn: &mut C,
+        ledger_id: &str,
+        ref_time: NaiveDateTime,
+    ) -> ServiceResult<BalanceSheet> {
+        let trial_balance = self.ledger_stmt_service.trial_balance(conn, ledger_id, ref_time)?;
+        BalanceSheet::of(&trial_balance)
+    }
+
+    /// Builds the profit and loss report and records it as a `PnL_STMT` posting.
+    /// Nothing is recorded if no account of the report has been booked.
+    pub fn record_profit_and_loss<C: Repositories>(
+        &self,
+        conn: &mut C,
+        ledger_id: &str,
+        ref_time: NaiveDateTime,
+        record_user: &str,
+    ) -> ServiceResult<(Option<Posting>, ProfitAndLoss)> {
+        conn.transaction(|conn| {
+            let trial_balance = self.ledger_stmt_service.trial_balance(conn, ledger_id, ref_time)?;
+            let report = ProfitAndLoss::of(&trial_balance)?;
+            let posting =
+                self.snapshot(conn, &trial_balance, &PNL_CATEGORIES, PostingType::PnLStmt, record_user)?;
+            Ok((posting, report))
+        })
+    }
+
+    /// Builds the balance sheet and records it as a `BS_STMT` posting.
+    /// Nothing is recorded if no account of the report has been booked.
+    pub fn record_balance_sheet<C: Repositories>(
+        &self,
+        conn: &mut C,
+        ledger_id: &str,
+        ref_time: NaiveDateTime,
+        record_user: &str,
+    ) -> ServiceResult<(Option<Posting>, BalanceSheet)> {
+        conn.transaction(|conn| {
+            let trial_balance = self.ledger_stmt_service.trial_balance(conn, ledger_id, ref_time)?;
+            let report = BalanceSheet::of(&trial_balance)?;
+            let posting = self.snapshot(conn, &trial_balance, &BS_CATEGORIES, PostingType::BsStmt, record_user)?;
+            Ok((posting, report))
+        })
+    }
+
+    /// Records the totals of every booked account of the categories with a
+    /// mechanical statement posting. Statement postings do not affect balances.
+    /// Returns `None` if none of the accounts has been booked, a posting needs
+    /// at least one line.
+    fn snapshot<C: Repositories>(
+        &self,
+        conn: &mut C,
+        trial_balance: &TrialBalance,
+        categories: &[AccountCategory],
+        pst_type: PostingType,
+        record_user: &str,
+    ) -> ServiceResult<Option<Posting>> {
+        let pst_id = new_id();
+        let lines: Vec<_> = trial_balance
+            .lines
+            .iter()
+            .filter(|line| categories.contains(&line.category))
+            .filter(|line| !line.total_debit.is_zero() || !line.total_credit.is_zero())
+            .flat_map(|line| {
+                stmt_lines(
+                    &format!("{}_{}", pst_id, line.account_id),
+                    &line.account_id,
+                    pst_type,
+                    line.total_debit,
+                    line.total_credit,
+                )
+            })
+            .collect();
+        if lines.is_empty() {
+            return Ok(None);
+        }
+        let record_time = self.posting_service.stmt_record_time(conn, &trial_balance.ledger_id)?;
+        let posting =
+            stmt_posting(&pst_id, &trial_balance.ledger_id, pst_type, trial_balance.ref_time, record_time, record_user);
+        let (posting, _) = self.posting_service.new_posting(conn, posting, lines)?;
+        Ok(Some(posting))
+    }
+}
@@ -0,0 +1,352 @@
+// src/journal.rs
+/*
+ * Copyright (c) 2018-2024 adorsys GmbH and Co. KG
+ * All rights are reserved.
+ */
+
+//! Hash chaining of the postings of a ledger.
+//!
+//! Each posting carries the hash of its canonical serialization (including its
+//! lines) and the hash of its antecedent, the youngest posting of the ledger at
+//! the time of recording. Editing a recorded posting or line therefore breaks
+//! the chain, which is what `JournalVerifier` looks for.
+
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::sync::Arc;
+
+use chrono::NaiveDateTime;
+use rust_decimal::Decimal;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+
+use postings_repository::models::enums::{PostingStatus, PostingType};
+use postings_repository::models::{NewPosting, NewPostingLine, Posting, PostingLine};
+use postings_repository::repository::{PostingLineRepository, PostingRepository, Repositories};
+
+use crate::error::ServiceResult;
+
+/// A hash algorithm used to seal postings. The name is recorded in `Posting.hash_alg`.
+pub trait HashAlgorithm: fmt::Debug + Send + Sync {
+    fn name(&self) -> &str;
+
+    /// Returns the hex encoded digest of the data.
+    fn hash(&self, data: &[u8]) -> String;
+}
+
+/// SHA-256, the default hash algorithm.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Sha256Hash;
+
+impl HashAlgorithm for Sha256Hash {
+    fn name(&self) -> &str {
+        "SHA-256"
+    }
+
+    fn hash(&self, data: &[u8]) -> String {
+        hex::encode(Sha256::digest(data))
+    }
+}
+
+/// Canonical form of a posting. Fields changed after recording (`discarding_id`,
+/// the `discarded_time` of the lines) and the hash itself are left out; the
+/// verifier checks them against the discarding posting instead.
+#[derive(Serialize)]
+struct CanonicalPosting<'a> {
+    id: &'a str,
+    antecedent_id: Option<&'a str>,
+    antecedent_hash: Option<&'a str>,
+    hash_alg: Option<&'a str>,
+    record_user: &'a str,
+    record_time: String,
+    opr_id: &'a str,
+    opr_time: Option<String>,
+    opr_type: Option<&'a str>,
+    opr_src: Option<&'a str>,
+    pst_time: String,
+    pst_type: PostingType,
+    pst_status: PostingStatus,
+    ledger_id: &'a str,
+    val_time: Option<String>,
+    discarded_id: Option<&'a str>,
+    discarded_time: Option<String>,
+    opr_details_id: Option<&'a str>,
+    lines: Vec<CanonicalLine<'a>>,
+}
+
+#[derive(Serialize)]
+struct CanonicalLine<'a> {
+    id: &'a str,
+    account_id: &'a str,
+    debit_amount: String,
+    credit_amount: String,
+    details_id: Option<&'a str>,
+    src_account: Option<&'a str>,
+    base_line: Option<&'a str>,
+    sub_opr_src_id: Option<&'a str>,
+    record_time: String,
+    opr_id: &'a str,
+    opr_src: Option<&'a str>,
+    pst_time: String,
+    pst_type: PostingType,
+    pst_status: PostingStatus,
+}
+
+/// Timestamps are stored with microsecond precision.
+fn canonical_time(time: NaiveDateTime) -> String {
+    time.format("%Y-%m-%dT%H:%M:%S%.6f").to_string()
+}
+
+fn canonical_amount(amount: Decimal) -> String {
+    amount.normalize().to_string()
+}
+
+impl<'a> CanonicalLine<'a> {
+    fn of_new(line: &'a NewPostingLine) -> Self {
+        CanonicalLine {
+            id: &line.id,
+            account_id: &line.account_id,
+            debit_amount: canonical_amount(line.debit_amount),
+            credit_amount: canonical_amount(line.credit_amount),
+            details_id: line.details_id.as_deref(),
+            src_account: line.src_account.as_deref(),
+            base_line: line.base_line.as_deref(),
+            sub_opr_src_id: line.sub_opr_src_id.as_deref(),
+            record_time: canonical_time(line.record_time),
+            opr_id: &line.opr_id,
+            opr_src: line.opr_src.as_deref(),
+            pst_time: canonical_time(line.pst_time),
+            pst_type: line.pst_type,
+            pst_status: line.pst_status,
+        }
+    }
+
+    fn of(line: &'a PostingLine) -> Self {
+        CanonicalLine {
+            id: &line.id,
+            account_id: &line.account_id,
+            debit_amount: canonical_amount(line.debit_amount),
+            credit_amount: canonical_amount(line.credit_amount),
+            details_id: line.details_id.as_deref(),
+            src_account: line.src_account.as_deref(),
+            base_line: line.base_line.as_deref(),
+            sub_opr_src_id: line.sub_opr_src_id.as_deref(),
+            record_time: canonical_time(line.record_time),
+            opr_id: &line.opr_id,
+            opr_src: line.opr_src.as_deref(),
+            pst_time: canonical_time(line.pst_time),
+            pst_type: line.pst_type,
+            pst_status: line.pst_status,
+        }
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        serde_json::to_vec(self).expect("canonical posting line is serializable")
+    }
+}
+
+impl<'a> CanonicalPosting<'a> {
+    fn of_new(posting: &'a NewPosting, lines: &'a [NewPostingLine]) -> Self {
+        let mut lines: Vec<CanonicalLine> = lines.iter().map(CanonicalLine::of_new).collect();
+        lines.sort_by(|a, b| a.id.cmp(b.id));
+        CanonicalPosting {
+            id: &posting.id,
+            antecedent_id: posting.antecedent_id.as_deref(),
+            antecedent_hash: posting.antecedent_hash.as_deref(),
+            hash_alg: posting.hash_alg.as_deref(),
+            record_user: &posting.record_user,
+            record_time: canonical_time(posting.record_time),
+            opr_id: &posting.opr_id,
+            opr_time: posting.opr_time.map(canonical_time),
+            opr_type: posting.opr_type.as_deref(),
+            opr_src: posting.opr_src.as_deref(),
+            pst_time: canonical_time(posting.pst_time),
+            pst_type: posting.pst_type,
+            pst_status: posting.pst_status,
+            ledger_id: &posting.ledger_id,
+            val_time: posting.val_time.map(canonical_time),
+            discarded_id: posting.discarded_id.as_deref(),
+            discarded_time: posting.discarded_time.map(canonical_time),
+            opr_details_id: posting.opr_details_id.as_deref(),
+            lines,
+        }
+    }
+
+    fn of(posting: &'a Posting, lines: &'a [PostingLine]) -> Self {
+        let mut lines: Vec<CanonicalLine> = lines.iter().map(CanonicalLine::of).collect();
+        lines.sort_by(|a, b| a.id.cmp(b.id));
+        CanonicalPosting {
+            id: &posting.id,
+            antecedent_id: posting.antecedent_id.as_deref(),
+            antecedent_hash: posting.antecedent_hash.as_deref(),
+            hash_alg: posting.hash_alg.as_deref(),
+            record_user: &posting.record_user,
+            record_time: canonical_time(posting.record_time),
+            opr_id: &posting.opr_id,
+            opr_time: posting.opr_time.map(canonical_time),
+            opr_type: posting.opr_type.as_deref(),
+            opr_src: posting.opr_src.as_deref(),
+            pst_time: canonical_time(posting.pst_time),
+            pst_type: posting.pst_type,
+            pst_status: posting.pst_status,
+            ledger_id: &posting.ledger_id,
+            val_time: posting.val_time.map(canonical_time),
+            discarded_id: posting.discarded_id.as_deref(),
+            discarded_time: posting.discarded_time.map(canonical_time),
+            opr_details_id: posting.opr_details_id.as_deref(),
+            lines,
+        }
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        serde_json::to_vec(self).expect("canonical posting is serializable")
+    }
+}
+
+/// The hash of a line seals the line together with the hash of its posting.
+fn line_hash(algorithm: &dyn HashAlgorithm, posting_hash: &str, line: &CanonicalLine) -> String {
+    let mut data = posting_hash.as_bytes().to_vec();
+    data.extend(line.to_bytes());
+    algorithm.hash(&data)
+}
+
+/// Chains a new posting to its antecedent and computes the hashes of the posting
+/// and its lines. The lines are expected to be denormalized already.
+pub fn seal(
+    algorithm: &dyn HashAlgorithm,
+    antecedent: Option<&Posting>,
+    posting: &mut NewPosting,
+    lines: &mut [NewPostingLine],
+) {
+    posting.antecedent_id = antecedent.map(|a| a.id.clone());
+    posting.antecedent_hash = antecedent.and_then(|a| a.hash.clone());
+    posting.hash_alg = Some(algorithm.name().to_string());
+
+    let posting_hash = algorithm.hash(&CanonicalPosting::of_new(posting, lines).to_bytes());
+    for line in lines.iter_mut() {
+        line.hash = line_hash(algorithm, &posting_hash, &CanonicalLine::of_new(line));
+    }
+    posting.hash = Some(posting_hash);
+}
+
+/// The reason a link of the chain is broken.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BrokenLinkReason {
+    /// The posting has no hash or was sealed with an algorithm unknown to the verifier.
+    UnknownHashAlgorithm(Option<String>),
+    /// The posting or its lines have been modified after recording.
+    HashMismatch,
+    /// The line with the given id has been modified after recording.
+    LineHashMismatch(String),
+    /// The `discarded_time` of the line with the given id differs from the
+    /// record time of the posting discarding it.
+    DiscardedTimeMismatch(String),
+    /// The antecedent hash differs from the hash of the antecedent posting.
+    AntecedentHashMismatch,
+    /// More than one posting claims the same antecedent.
+    Fork,
+    /// The posting can not be reached from the first posting of the ledger.
+    Unreachable,
+}
+
+/// The first broken link found while walking the chain of a ledger.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BrokenLink {
+    pub posting_id: String,
+    pub reason: BrokenLinkReason,
+}
+
+/// Walks the hash chain of a ledger and checks every link.
+#[derive(Debug, Clone)]
+pub struct JournalVerifier {
+    algorithms: Vec<Arc<dyn HashAlgorithm>>,
+}
+
+impl Default for JournalVerifier {
+    fn default() -> Self {
+        Self { algorithms: vec![Arc::new(Sha256Hash)] }
+    }
+}
+
+impl JournalVerifier {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers an additional algorithm postings may have been sealed with.
+    pub fn with_algorithm(mut self, algorithm: Arc<dyn HashAlgorithm>) -> Self {
+        self.algorithms.push(algorithm);
+        self
+    }
+
+    fn algorithm(&self, name: Option<&str>) -> Option<&dyn HashAlgorithm> {
+        let name = name?;
+        self.algorithms.iter().find(|a| a.name() == name).map(|a| a.as_ref())
+    }
+
+    /// Verifies the chain of the ledger, starting at its first posting.
+    ///
+    /// Returns the first broken link, or `None` if the journal is intact.
+    pub fn verify_ledger<C: Repositories>(&self, conn: &mut C, ledger_id: &str) -> ServiceResult<Option<BrokenLink>> {
+        let postings = PostingRepository::find_by_ledger_order_by_record_time_asc(conn, ledger_id)?;
+        let mut successors: HashMap<Option<&str>, Vec<&Posting>> = HashMap::new();
+        for posting in &postings {
+            successors.entry(posting.antecedent_id.as_deref()).or_default().push(posting);
+        }
+
+        let record_times: HashMap<&str, NaiveDateTime> =
+            postings.iter().map(|posting| (posting.id.as_str(), posting.record_time)).collect();
+        let broken = |posting: &Posting, reason| Some(BrokenLink { posting_id: posting.id.clone(), reason });
+        let mut visited = HashSet::new();
+        let mut antecedent: Option<&Posting> = None;
+        loop {
+            let next = match successors.get(&antecedent.map(|a| a.id.as_str())) {
+                None => break,
+                Some(next) if next.len() > 1 => return Ok(broken(next[1], BrokenLinkReason::Fork)),
+                Some(next) => next[0],
+            };
+            if next.antecedent_hash != antecedent.and_then(|a| a.hash.clone()) {
+                return Ok(broken(next, BrokenLinkReason::AntecedentHashMismatch));
+            }
+            let discarded_time = next.discarding_id.as_deref().and_then(|id| record_times.get(id).copied());
+            if let Some(reason) = self.verify_posting(conn, next, discarded_time)? {
+                return Ok(broken(next, reason));
+            }
+            visited.insert(next.id.as_str());
+            antecedent = Some(next);
+        }
+
+        Ok(postings
+            .iter()
+            .find(|p| !visited.contains(p.id.as_str()))
+            .and_then(|orphan| broken(orphan, BrokenLinkReason::Unreachable)))
+    }
+
+    /// Recomputes the hashes of a posting and its lines, and checks that the lines
+    /// are stamped with the record time of the discarding posting, if any.
+    fn verify_posting<C: Repositories>(
+        &self,
+        conn: &mut C,
+        posting: &Posting,
+        discarded_time: Option<NaiveDateTime>,
+    ) -> ServiceResult<Option<BrokenLinkReason>> {
+        let Some(algorithm) = self.algorithm(posting.hash_alg.as_deref()) else {
+            return Ok(Some(BrokenLinkReason::UnknownHashAlgorithm(posting.hash_alg.clone())));
+        };
+        let lines = PostingLineRepository::find_by_opr_id_and_record_time(conn, &posting.opr_id, posting.record_time)?;
+        let canonical = CanonicalPosting::of(posting, &lines);
+        let posting_hash = algorithm.hash(&canonical.to_bytes());
+        if posting.hash.as_deref() != Some(posting_hash.as_str()) {
+            return Ok(Some(BrokenLinkReason::HashMismatch));
+        }
+        for line in &lines {
+            if line.hash != line_hash(algorithm, &posting_hash, &CanonicalLine::of(line)) {
+                return Ok(Some(BrokenLinkReason::LineHashMismatch(line.id.clone())));
+            }
+            if line.discarded_time != discarded_time {
+                return Ok(Some(BrokenLinkReason::DiscardedTimeMismatch(line.id.clone())));
+            }
+        }
+        Ok(None)
+    }
+}
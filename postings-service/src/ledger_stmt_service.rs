@@ -0,0 +1,358 @@
+// src/ledger_stmt_service.rs
+/*
+ * Copyright (c) 2018-2024 adorsys GmbH and Co. KG
+ * All rights are reserved.
+ */
+
+use chrono::NaiveDateTime;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+
+use postings_repository::models::enums::{AccountCategory, BalanceSide, PostingStatus, PostingType, StmtStatus};
+use postings_repository::models::{LedgerStmt, NewLedgerStmt, NewPostingLine, Posting};
+use postings_repository::repository::{
+    LedgerAccountRepository, LedgerRepository, LedgerStmtRepository, Repositories,
+};
+
+use crate::account_stmt_service::{stmt_posting, AccountStmtService};
+use crate::error::{ServiceError, ServiceResult};
+use crate::new_id;
+use crate::posting_service::PostingService;
+
+/// The totals of one account in a trial balance.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TrialBalanceLine {
+    pub account_id: String,
+    pub name: String,
+    pub parent_id: Option<String>,
+    pub category: AccountCategory,
+    pub balance_side: BalanceSide,
+    pub total_debit: Decimal,
+    pub total_credit: Decimal,
+    pub balance: Decimal,
+}
+
+/// The debit and credit totals of all accounts of a ledger at a reference time.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TrialBalance {
+    pub ledger_id: String,
+    pub ref_time: NaiveDateTime,
+    pub lines: Vec<TrialBalanceLine>,
+    pub total_debit: Decimal,
+    pub total_credit: Decimal,
+}
+
+impl TrialBalance {
+    /// A ledger only holding balanced postings has equal grand totals.
+    pub fn is_balanced(&self) -> bool {
+        self.total_debit == self.total_credit
+    }
+}
+
+/// The categories whose accounts are zeroed by the closing of a ledger.
+const RESULT_CATEGORIES: [AccountCategory; 5] = [
+    AccountCategory::RE,
+    AccountCategory::EX,
+    AccountCategory::NORE,
+    AccountCategory::NOEX,
+    AccountCategory::NOOP,
+];
+
+/// Builds trial balances and ledger statements.
+#[derive(Debug, Clone, Default)]
+pub struct LedgerStmtService {
+    account_stmt_service: AccountStmtService,
+    posting_service: PostingService,
+}
+
+impl LedgerStmtService {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a service reading account statements and recording closing
+    /// postings with the given services.
+    pub fn with_services(account_stmt_service: AccountStmtService, posting_service: PostingService) -> Self {
+        Self { account_stmt_service, posting_service }
+    }
+
+    /// Lists the debit and credit totals of every account of the ledger at the
+    /// reference time.
+    pub fn trial_balance<C: Repositories>(
+        &self,
+        conn: &mut C,
+        ledger_id: &str,
+        ref_time: NaiveDateTime,
+    ) -> ServiceResult<TrialBalance> {
+        self.build_trial_balance(conn, ledger_id, ref_time, None)
+    }
+
+    /// Lists the totals of `trial_balance` as the ledger recorded them at
+    /// `as_of`, see `AccountStmtService::read_stmt_as_of`. A trial balance
+    /// reported at `as_of` is reproduced exactly.
+    pub fn trial_balance_as_of<C: Repositories>(
+        &self,
+        conn: &mut C,
+        ledger_id: &str,
+        ref_time: NaiveDateTime,
+        as_of: NaiveDateTime,
+    ) -> ServiceResult<TrialBalance> {
+        self.build_trial_balance(conn, ledger_id, ref_time, Some(as_of))
+    }
+
+    /// Builds the trial balance from the current statements of the accounts, or
+    /// from those recorded at `as_of`.
+    fn build_trial_balance<C: Repositories>(
+        &self,
+        conn: &mut C,
+        ledger_id: &str,
+        ref_time: NaiveDateTime,
+        as_of: Option<NaiveDateTime>,
+    ) -> ServiceResult<TrialBalance> {
+        if LedgerRepository::find_by_id(conn, ledger_id)?.is_none() {
+            return Err(ServiceError::LedgerNotFound(ledger_id.to_string()));
+        }
+
+        let mut lines = Vec::new();
+        for account in LedgerAccountRepository::find_by_ledger(conn, ledger_id)? {
+            let stmt = match as_of {
+                Some(as_of) => self.account_stmt_service.read_stmt_as_of(conn, &account.id, ref_time, as_of)?,
+                None => self.account_stmt_service.read_stmt(conn, &account.id, ref_time)?,
+            };
+            lines.push(TrialBalanceLine {
+                account_id: account.id,
+                name: account.name,
+                parent_id: account.parent_id,
+                category: account.category,
+                balance_side: account.balance_side,
+                total_debit: stmt.total_debit,
+                total_credit: stmt.total_credit,
+                balance: stmt.balance,
+            });
+        }
+
+        Ok(TrialBalance {
+            ledger_id: ledger_id.to_string(),
+            ref_time,
+            total_debit: lines.iter().map(|line| line.total_debit).sum(),
+            total_credit: lines.iter().map(|line| line.total_credit).sum(),
+            lines,
+        })
+    }
+
+    /// Builds the trial balance of the ledger and records it as a SIMULATED or
+    /// CLOSED ledger statement.
+    ///
+    /// The statement is refused if the grand totals differ. A CLOSED statement is
+    /// also refused if the ledger has already been closed at or after the
+    /// reference time.
+    pub fn create_ledger_stmt<C: Repositories>(
+        &self,
+        conn: &mut C,
+        ledger_id: &str,
+        ref_time: NaiveDateTime,
+        stmt_status: StmtStatus,
+        user_details: &str,
+    ) -> ServiceResult<(LedgerStmt, TrialBalance)> {
+        conn.transaction(|conn| {
+            if stmt_status == StmtStatus::CLOSED {
+                check_not_closed(conn, ledger_id, ref_time)?;
+            }
+            self.save_ledger_stmt(conn, ledger_id, ref_time, stmt_status, user_details, None, None)
+        })
+    }
+
+    /// Records again the CLOSED statements of the ledger at or after the given
+    /// posting time, once postings have been recorded before them.
+    ///
+    /// Each posting time of a CLOSED statement gets a new statement with the
+    /// trial balance at that time and a `stmt_seq_nbr` above those of all the
+    /// statements of the ledger. Ledger closings, documented by a `LDG_CLSNG`
+    /// posting, are final and refused. Returns the new statements.
+    pub fn reclose_ledger_stmts<C: Repositories>(
+        &self,
+        conn: &mut C,
+        ledger_id: &str,
+        pst_time: NaiveDateTime,
+        user_details: &str,
+    ) -> ServiceResult<Vec<LedgerStmt>> {
+        conn.transaction(|conn| {
+            let closed =
+                LedgerStmtRepository::find_by_ledger_and_stmt_status_and_pst_time_gte(conn, ledger_id, StmtStatus::CLOSED, pst_time)?;
+            if let Some(closing) = closed.iter().find(|stmt| stmt.posting_id.is_some()) {
+                return Err(ServiceError::LedgerClosed { ledger_id: ledger_id.to_string(), pst_time: closing.pst_time });
+            }
+            let Some(mut stmt_seq_nbr) = closed.iter().map(|stmt| stmt.stmt_seq_nbr).max() else {
+                return Ok(Vec::new());
+            };
+            let mut stmt_times: Vec<NaiveDateTime> = closed.iter().map(|stmt| stmt.pst_time).collect();
+            stmt_times.dedup();
+
+            let mut stmts = Vec::with_capacity(stmt_times.len());
+            for stmt_time in stmt_times {
+                stmt_seq_nbr += 1;
+                let (stmt, _) = self.save_ledger_stmt(
+                    conn,
+                    ledger_id,
+                    stmt_time,
+                    StmtStatus::CLOSED,
+                    user_details,
+                    None,
+                    Some(stmt_seq_nbr),
+                )?;
+                stmts.push(stmt);
+            }
+            Ok(stmts)
+        })
+    }
+
+    /// Closes the ledger at the closing time.
+    ///
+    /// The balances of all revenue and expense accounts, operating or not, are
+    /// transferred to the given equity account with a `LDG_CLSNG` posting, and a
+    /// CLOSED ledger statement referencing that posting is written. The posting
+    /// is recorded even if there is nothing to transfer, it tells the closing
+    /// of the ledger apart from other CLOSED ledger statements. From then on
+    /// the posting service refuses postings at or before the closing time,
+    /// whatever its `BackdatedPostingPolicy`.
+    pub fn close_ledger<C: Repositories>(
+        &self,
+        conn: &mut C,
+        ledger_id: &str,
+        closing_time: NaiveDateTime,
+        equity_account_id: &str,
+        record_user: &str,
+    ) -> ServiceResult<(LedgerStmt, TrialBalance)> {
+        conn.transaction(|conn| {
+            if LedgerRepository::find_by_id_for_update(conn, ledger_id)?.is_none() {
+                return Err(ServiceError::LedgerNotFound(ledger_id.to_string()));
+            }
+            check_not_closed(conn, ledger_id, closing_time)?;
+
+            let equity_account = LedgerAccountRepository::find_by_id(conn, equity_account_id)?
+                .ok_or_else(|| ServiceError::AccountNotFound(equity_account_id.to_string()))?;
+            if equity_account.ledger_id != ledger_id {
+                return Err(ServiceError::AccountNotInLedger {
+                    account_id: equity_account.id,
+                    ledger_id: ledger_id.to_string(),
+                });
+            }
+            if equity_account.category != AccountCategory::EQ {
+                return Err(ServiceError::NotAnEquityAccount(equity_account.id));
+            }
+
+            let trial_balance = self.trial_balance(conn, ledger_id, closing_time)?;
+            let pst_id = new_id();
+            let mut lines: Vec<NewPostingLine> = trial_balance
+                .lines
+                .iter()
+                .filter(|line| RESULT_CATEGORIES.contains(&line.category))
+                .filter(|line| line.total_debit != line.total_credit)
+                .enumerate()
+                .map(|(idx, line)| {
+                    closing_line(&format!("{}_{}", pst_id, idx), &line.account_id, line.total_debit - line.total_credit)
+                })
+                .collect();
+            let result: Decimal = lines.iter().map(|line| line.debit_amount - line.credit_amount).sum();
+
+            // The posting marks the closing as final, so it is recorded even with
+            // nothing to transfer: the equity line then books zero.
+            lines.push(closing_line(&format!("{}_{}", pst_id, lines.len()), &equity_account.id, result));
+            let record_time = self.posting_service.stmt_record_time(conn, ledger_id)?;
+            let posting = stmt_posting(&pst_id, ledger_id, PostingType::LdgClsng, closing_time, record_time, record_user);
+            let (posting, _) = self.posting_service.new_posting(conn, posting, lines)?;
+
+            self.save_ledger_stmt(conn, ledger_id, closing_time, StmtStatus::CLOSED, record_user, Some(&posting), None)
+        })
+    }
+
+    /// Records the trial balance of the ledger, refusing it if its grand totals
+    /// differ. Its `stmt_seq_nbr` follows the one of the previous statement
+    /// unless given.
+    #[allow(clippy::too_many_arguments)]
+    fn save_ledger_stmt<C: Repositories>(
+        &self,
+        conn: &mut C,
+        ledger_id: &str,
+        ref_time: NaiveDateTime,
+        stmt_status: StmtStatus,
+        user_details: &str,
+        posting: Option<&Posting>,
+        stmt_seq_nbr: Option<i32>,
+    ) -> ServiceResult<(LedgerStmt, TrialBalance)> {
+        let trial_balance = self.trial_balance(conn, ledger_id, ref_time)?;
+        if !trial_balance.is_balanced() {
+            return Err(ServiceError::UnbalancedLedger {
+                ledger_id: ledger_id.to_string(),
+                total_debit: trial_balance.total_debit,
+                total_credit: trial_balance.total_credit,
+            });
+        }
+
+        let previous = LedgerStmtRepository::find_first_by_ledger_and_stmt_status_and_pst_time_lte_order_by_pst_time_desc_stmt_seq_nbr_desc(
+            conn,
+            ledger_id,
+            stmt_status,
+            ref_time,
+        )?;
+        let stmt = LedgerStmtRepository::save(
+            conn,
+            NewLedgerStmt {
+                id: new_id(),
+                posting_id: posting.map(|posting| posting.id.clone()),
+                pst_time: ref_time,
+                stmt_status,
+                latest_pst_id: None,
+                stmt_seq_nbr: stmt_seq_nbr.unwrap_or_else(|| previous.map_or(0, |stmt| stmt.stmt_seq_nbr + 1)),
+                created: Some(posting.map_or_else(|| self.posting_service.now(), |posting| posting.record_time)),
+                user_details: Some(user_details.to_string()),
+                short_desc: None,
+                long_desc: None,
+                ledger_id: ledger_id.to_string(),
+            },
+        )?;
+        Ok((stmt, trial_balance))
+    }
+}
+
+/// Refuses to close the ledger again at or before an existing closing.
+fn check_not_closed<C: Repositories>(conn: &mut C, ledger_id: &str, pst_time: NaiveDateTime) -> ServiceResult<()> {
+    match LedgerStmtRepository::find_first_by_ledger_and_stmt_status_and_pst_time_gte(
+        conn,
+        ledger_id,
+        StmtStatus::CLOSED,
+        pst_time,
+    )? {
+        Some(closed) => Err(ServiceError::LedgerClosed { ledger_id: ledger_id.to_string(), pst_time: closed.pst_time }),
+        None => Ok(()),
+    }
+}
+
+/// A closing line booking the opposite of the given net debit amount, so that
+/// the account balance becomes zero.
+fn closing_line(id: &str, account_id: &str, net_debit: Decimal) -> NewPostingLine {
+    let (debit_amount, credit_amount) = if net_debit.is_sign_negative() {
+        (-net_debit, Decimal::ZERO)
+    } else {
+        (Decimal::ZERO, net_debit)
+    };
+    NewPostingLine {
+        id: id.to_string(),
+        account_id: account_id.to_string(),
+        debit_amount,
+        credit_amount,
+        details_id: None,
+        src_account: None,
+        base_line: None,
+        sub_opr_src_id: None,
+        // Posting fields are denormalized by the posting service.
+        record_time: NaiveDateTime::default(),
+        opr_id: String::new(),
+        opr_src: None,
+        pst_time: NaiveDateTime::default(),
+        pst_type: PostingType::LdgClsng,
+        pst_status: PostingStatus::POSTED,
+        hash: String::new(),
+        discarded_time: None,
+    }
+}
@@ -3,12 +3,35 @@
  * All rights are reserved.
  */
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+pub mod account_stmt_service;
+pub mod account_tree_service;
+pub mod clock;
+pub mod coa_template_service;
+pub mod deferred_posting_scheduler;
+pub mod error;
+pub mod financial_stmt_service;
+pub mod journal;
+pub mod ledger_stmt_service;
+pub mod op_note_engine;
+pub mod posting_service;
+pub mod simulation_service;
 
-    #[test]
-    fn it_works() {
+pub use account_stmt_service::{AccountStmtReport, AccountStmtService};
+pub use account_tree_service::AccountTreeService;
+pub use clock::{Clock, FixedClock, SystemClock};
+pub use coa_template_service::{
+    AccountChange, AccountTemplate, BuiltinTemplate, CoaDiff, CoaTemplate, CoaTemplateService, ImportedChart,
+};
+pub use deferred_posting_scheduler::{DeferredPostingScheduler, SchedulerRun};
+pub use error::{ServiceError, ServiceResult};
+pub use financial_stmt_service::{BalanceSheet, FinancialStmtService, ProfitAndLoss, ReportNode, ReportSection};
+pub use journal::{HashAlgorithm, JournalVerifier, Sha256Hash};
+pub use ledger_stmt_service::{LedgerStmtService, TrialBalance, TrialBalanceLine};
+pub use op_note_engine::{OpNoteEngine, OpNoteExecution, OpNoteHandler};
+pub use posting_service::{BackdatedPostingPolicy, PostingService};
+pub use simulation_service::{SimulationPurge, SimulationService};
 
-    }
+/// Generates the id of a new record.
+pub(crate) fn new_id() -> String {
+    uuid::Uuid::new_v4().to_string()
 }
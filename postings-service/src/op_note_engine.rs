@@ -0,0 +1,166 @@
+// src/op_note_engine.rs
+/*
+ * Copyright (c) 2018-2024 adorsys GmbH and Co. KG
+ * All rights are reserved.
+ */
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::sync::Arc;
+
+use chrono::NaiveDateTime;
+
+use postings_repository::models::OpNote;
+use postings_repository::repository::{OpNoteRepository, Repositories};
+
+use crate::clock::{Clock, SystemClock};
+use crate::error::{ServiceError, ServiceResult};
+
+/// Executes the op notes of one type. The record of the note, such as a
+/// posting, is referenced by its `rec_id`.
+pub type OpNoteHandler<C> = Arc<dyn Fn(&mut C, &OpNote) -> ServiceResult<()> + Send + Sync>;
+
+/// The execution of an op note.
+#[derive(Debug)]
+pub struct OpNoteExecution {
+    /// The note with its new execution status.
+    pub note: OpNote,
+    /// The error of the handler, if the execution failed.
+    pub error: Option<ServiceError>,
+}
+
+/// Executes op notes planned as tasks, such as reminders and follow-ups of
+/// postings, with the handler registered for their `note_type`.
+///
+/// A note is executed in a transaction locking it: the changes of its handler
+/// are committed together with its `exec_status`, `OpNote::EXEC_DONE`, or
+/// rolled back if the handler fails and the note is marked `OpNote::EXEC_FAILED`.
+/// Notes locked by another worker are skipped, so a note is never executed
+/// twice at the same time.
+///
+/// Notes are due at their `exec_time` and run once, unless `repeated_exec`
+/// allows running them again. `premature_exc` allows executing them on demand
+/// before they are due.
+pub struct OpNoteEngine<C> {
+    handlers: BTreeMap<String, OpNoteHandler<C>>,
+    clock: Arc<dyn Clock>,
+}
+
+impl<C> Default for OpNoteEngine<C> {
+    fn default() -> Self {
+        Self::with_clock(Arc::new(SystemClock))
+    }
+}
+
+impl<C> Clone for OpNoteEngine<C> {
+    fn clone(&self) -> Self {
+        Self { handlers: self.handlers.clone(), clock: self.clock.clone() }
+    }
+}
+
+impl<C> fmt::Debug for OpNoteEngine<C> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("OpNoteEngine")
+            .field("note_types", &self.handlers.keys().collect::<Vec<_>>())
+            .field("clock", &self.clock)
+            .finish()
+    }
+}
+
+impl<C> OpNoteEngine<C> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates an engine telling the time with the given clock.
+    pub fn with_clock(clock: Arc<dyn Clock>) -> Self {
+        Self { handlers: BTreeMap::new(), clock }
+    }
+
+    /// Registers the handler of the notes of the given type, replacing the
+    /// previous one.
+    pub fn register<F>(&mut self, note_type: &str, handler: F) -> &mut Self
+    where
+        F: Fn(&mut C, &OpNote) -> ServiceResult<()> + Send + Sync + 'static,
+    {
+        self.handlers.insert(note_type.to_string(), Arc::new(handler));
+        self
+    }
+
+    fn handler(&self, note: &OpNote) -> Option<&OpNoteHandler<C>> {
+        note.note_type.as_deref().and_then(|note_type| self.handlers.get(note_type))
+    }
+}
+
+impl<C: Repositories> OpNoteEngine<C> {
+    /// Executes the notes due at the current time of the clock, by execution
+    /// time: those not executed yet, and the failed ones allowing a repeated
+    /// execution. Notes without a registered handler and notes locked by
+    /// another worker are left for later.
+    pub fn poll(&self, conn: &mut C) -> ServiceResult<Vec<OpNoteExecution>> {
+        let now = self.clock.now();
+        let mut executions = Vec::new();
+        for due in OpNoteRepository::find_pending_by_exec_time_lte(conn, now)? {
+            if self.handler(&due).is_none() {
+                continue;
+            }
+            let execution = conn.transaction(|conn| {
+                // Another worker may have executed the note since it was read.
+                match OpNoteRepository::find_by_id_for_update_skip_locked(conn, &due.id)? {
+                    Some(note) if is_pending(&note, now) => self.run(conn, note).map(Some),
+                    _ => Ok(None),
+                }
+            })?;
+            executions.extend(execution);
+        }
+        Ok(executions)
+    }
+
+    /// Executes a note on demand. A note planned later is only executed if it
+    /// allows a premature execution, and a note already executed only if it
+    /// allows a repeated execution.
+    pub fn execute(&self, conn: &mut C, note_id: &str) -> ServiceResult<OpNoteExecution> {
+        let now = self.clock.now();
+        conn.transaction(|conn| {
+            let Some(note) = OpNoteRepository::find_by_id_for_update_skip_locked(conn, note_id)? else {
+                return Err(match OpNoteRepository::find_by_id(conn, note_id)? {
+                    Some(_) => ServiceError::OpNoteLocked(note_id.to_string()),
+                    None => ServiceError::OpNoteNotFound(note_id.to_string()),
+                });
+            };
+            if !is_due(&note, now) && note.premature_exc != Some(true) {
+                return Err(ServiceError::OpNoteNotDue { note_id: note.id, exec_time: note.exec_time });
+            }
+            if let Some(exec_status) = &note.exec_status {
+                if note.repeated_exec != Some(true) {
+                    return Err(ServiceError::OpNoteExecuted { note_id: note.id.clone(), exec_status: exec_status.clone() });
+                }
+            }
+            self.run(conn, note)
+        })
+    }
+
+    /// Runs the handler of a locked note and records the execution status.
+    fn run(&self, conn: &mut C, note: OpNote) -> ServiceResult<OpNoteExecution> {
+        let handler = self
+            .handler(&note)
+            .ok_or_else(|| ServiceError::NoOpNoteHandler { note_id: note.id.clone(), note_type: note.note_type.clone() })?;
+        let error = conn.transaction(|conn| handler(conn, &note)).err();
+        let exec_status = if error.is_none() { OpNote::EXEC_DONE } else { OpNote::EXEC_FAILED };
+        let note = OpNoteRepository::update(conn, &OpNote { exec_status: Some(exec_status.to_string()), ..note })?;
+        Ok(OpNoteExecution { note, error })
+    }
+}
+
+fn is_due(note: &OpNote, now: NaiveDateTime) -> bool {
+    note.exec_time.is_some_and(|exec_time| exec_time <= now)
+}
+
+/// Whether polling executes the note, see `find_pending_by_exec_time_lte`.
+fn is_pending(note: &OpNote, now: NaiveDateTime) -> bool {
+    is_due(note, now)
+        && match note.exec_status.as_deref() {
+            None => true,
+            Some(exec_status) => exec_status == OpNote::EXEC_FAILED && note.repeated_exec == Some(true),
+        }
+}
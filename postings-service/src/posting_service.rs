@@ -0,0 +1,722 @@
+// src/posting_service.rs
+/* 
+ * Copyright (c) 2018-2024 adorsys GmbH and Co. KG
+ * All rights are reserved.
+ */
+
+use std::collections::BTreeSet;
+use std::sync::Arc;
+
+use chrono::{Duration, NaiveDateTime};
+use rust_decimal::Decimal;
+
+use postings_repository::models::enums::{PostingStatus, PostingType, StmtStatus};
+use postings_repository::models::{NewOpNote, NewPosting, NewPostingLine, Posting, PostingLine};
+use postings_repository::repository::{
+    AccountStmtRepository, LedgerAccountRepository, LedgerRepository, LedgerStmtRepository, OpNoteRepository,
+    PostingLineRepository, PostingRepository, Repositories,
+};
+
+use crate::account_stmt_service::{affects_balance, AccountStmtService};
+use crate::clock::{Clock, SystemClock};
+use crate::error::{ServiceError, ServiceResult};
+use crate::journal::{self, HashAlgorithm, Sha256Hash};
+use crate::ledger_stmt_service::LedgerStmtService;
+use crate::new_id;
+
+/// The type of the op note recording the approval of a proposed posting. Its
+/// content is the user who approved it.
+pub const POSTING_APPROVED_NOTE: &str = "POSTING_APPROVED";
+/// The type of the op note recording the rejection of a proposed posting. Its
+/// content is the user who rejected it.
+pub const POSTING_REJECTED_NOTE: &str = "POSTING_REJECTED";
+/// The type of the op note recording the promotion of a deferred posting. Its
+/// content is the user who promoted it.
+pub const POSTING_PROMOTED_NOTE: &str = "POSTING_PROMOTED";
+/// The type of the op note recording why a deferred posting could not be
+/// promoted and was cancelled. Its content is the reason.
+pub const POSTING_PROMOTION_FAILED_NOTE: &str = "POSTING_PROMOTION_FAILED";
+
+/// How postings are handled when they fall before CLOSED statements: a
+/// backdated posting changes balances that have already been reported.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum BackdatedPostingPolicy {
+    /// Refuses the posting.
+    #[default]
+    Reject,
+    /// Records the posting and closes the later statements again, with new
+    /// `stmt_seq_nbr` values and fresh posting traces.
+    Reclose,
+}
+
+/// Records balanced double-entry postings in the journal.
+#[derive(Debug, Clone)]
+pub struct PostingService {
+    hash_algorithm: Arc<dyn HashAlgorithm>,
+    backdated_posting_policy: BackdatedPostingPolicy,
+    clock: Arc<dyn Clock>,
+}
+
+/// A posting checked and sealed by `PostingService::prepare`.
+struct Prepared {
+    posting: NewPosting,
+    lines: Vec<NewPostingLine>,
+    discarded: Option<Posting>,
+    reclosing: Option<Reclosing>,
+}
+
+/// The CLOSED statements a backdated posting invalidates: those of the
+/// accounts and of the ledger at or after the posting time.
+struct Reclosing {
+    pst_time: NaiveDateTime,
+    account_ids: BTreeSet<String>,
+}
+
+impl Default for PostingService {
+    fn default() -> Self {
+        Self::with_hash_algorithm(Arc::new(Sha256Hash))
+    }
+}
+
+impl PostingService {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a service sealing postings with the given hash algorithm.
+    pub fn with_hash_algorithm(hash_algorithm: Arc<dyn HashAlgorithm>) -> Self {
+        Self {
+            hash_algorithm,
+            backdated_posting_policy: BackdatedPostingPolicy::default(),
+            clock: Arc::new(SystemClock),
+        }
+    }
+
+    /// Handles postings before CLOSED statements with the given policy.
+    pub fn with_backdated_posting_policy(self, backdated_posting_policy: BackdatedPostingPolicy) -> Self {
+        Self { backdated_posting_policy, ..self }
+    }
+
+    /// Tells the record time of statement postings with the given clock.
+    pub fn with_clock(self, clock: Arc<dyn Clock>) -> Self {
+        Self { clock, ..self }
+    }
+
+    /// The current time of the clock of the service.
+    pub(crate) fn now(&self) -> NaiveDateTime {
+        self.clock.now()
+    }
+
+    /// The record time of a statement posting of the ledger: the current time
+    /// of the clock, or right after the youngest posting of the ledger if that
+    /// one was recorded later.
+    pub(crate) fn stmt_record_time<C: Repositories>(&self, conn: &mut C, ledger_id: &str) -> ServiceResult<NaiveDateTime> {
+        record_time_after_youngest(conn, ledger_id, self.now())
+    }
+
+    /// Creates a new posting together with its posting lines.
+    ///
+    /// The posting is rejected unless the total debit amount equals the total
+    /// credit amount and every line references an account of the posting's ledger.
+    /// The operation and posting information of the posting are denormalized into
+    /// each line, and everything is persisted in a single transaction.
+    ///
+    /// If the operation has already been posted, the effective posting of the
+    /// operation is discarded by the new one (see `repost`).
+    ///
+    /// The posting is chained to the youngest posting of the ledger and sealed
+    /// with the hash algorithm of the service (see `journal`). Its record time
+    /// must therefore be after the record time of that posting.
+    ///
+    /// Once a ledger has been closed, postings at or before the closing time are
+    /// refused, as are re-postings of operations posted at or before that time.
+    /// Postings at or before CLOSED account statements of their accounts, or
+    /// CLOSED ledger statements, are handled by the `BackdatedPostingPolicy` of
+    /// the service.
+    ///
+    /// Postings with the status PROPOSED are recorded by `propose`, those with
+    /// the status SIMULATED by `simulate` and those with the status DEFERRED by
+    /// `defer`.
+    pub fn new_posting<C: Repositories>(
+        &self,
+        conn: &mut C,
+        new_posting: NewPosting,
+        new_lines: Vec<NewPostingLine>,
+    ) -> ServiceResult<(Posting, Vec<PostingLine>)> {
+        match new_posting.pst_status {
+            PostingStatus::PROPOSED => return self.propose(conn, new_posting, new_lines),
+            PostingStatus::SIMULATED => return self.simulate(conn, new_posting, new_lines),
+            PostingStatus::DEFERRED => return self.defer(conn, new_posting, new_lines),
+            _ => {}
+        }
+        validate_balance(new_posting.pst_type, &new_lines)?;
+
+        conn.transaction(|conn| {
+            let prepared = self.prepare(conn, new_posting, new_lines)?;
+            let posting = PostingRepository::save(conn, prepared.posting)?;
+            if let Some(discarded) = prepared.discarded {
+                discard(conn, &discarded, &posting)?;
+            }
+            let lines = PostingLineRepository::save_all(conn, prepared.lines)?;
+            self.reclose(conn, &posting, &posting.record_user, prepared.reclosing)?;
+
+            Ok((posting, lines))
+        })
+    }
+
+    /// Records a posting proposed for approval by a second user (see `approve`).
+    ///
+    /// The posting and its lines are checked like those of `new_posting`, then
+    /// recorded with the status PROPOSED, neither chained nor sealed. Until
+    /// approved, they do not count toward balances and do not discard the
+    /// effective posting of the operation.
+    pub fn propose<C: Repositories>(
+        &self,
+        conn: &mut C,
+        new_posting: NewPosting,
+        new_lines: Vec<NewPostingLine>,
+    ) -> ServiceResult<(Posting, Vec<PostingLine>)> {
+        save_ineffective(conn, new_posting, new_lines, PostingStatus::PROPOSED)
+    }
+
+    /// Records a simulated posting, see `SimulationService`.
+    ///
+    /// The posting and its lines are checked like those of `new_posting`, then
+    /// recorded with the status SIMULATED, neither chained nor sealed. They
+    /// only count in what-if views and never discard a posting.
+    pub fn simulate<C: Repositories>(
+        &self,
+        conn: &mut C,
+        new_posting: NewPosting,
+        new_lines: Vec<NewPostingLine>,
+    ) -> ServiceResult<(Posting, Vec<PostingLine>)> {
+        save_ineffective(conn, new_posting, new_lines, PostingStatus::SIMULATED)
+    }
+
+    /// Records a posting taking effect at its posting time, once promoted by
+    /// `promote` (see `DeferredPostingScheduler`).
+    ///
+    /// The posting and its lines are checked like those of `new_posting`, then
+    /// recorded with the status DEFERRED, neither chained nor sealed. Until
+    /// promoted, they do not count toward balances and do not discard the
+    /// effective posting of the operation.
+    pub fn defer<C: Repositories>(
+        &self,
+        conn: &mut C,
+        new_posting: NewPosting,
+        new_lines: Vec<NewPostingLine>,
+    ) -> ServiceResult<(Posting, Vec<PostingLine>)> {
+        save_ineffective(conn, new_posting, new_lines, PostingStatus::DEFERRED)
+    }
+
+    /// Promotes a deferred posting on behalf of `user`.
+    ///
+    /// The posting is recorded at `now`, or right after the youngest posting of
+    /// its ledger if that one was recorded later, like a new posting: it discards
+    /// the effective posting of its operation, is chained, sealed and turned
+    /// POSTED. An op note of type `POSTING_PROMOTED_NOTE` records the user.
+    ///
+    /// Returns `None` if the posting is no longer deferred, so that promoting a
+    /// posting twice has no effect.
+    pub fn promote<C: Repositories>(
+        &self,
+        conn: &mut C,
+        posting_id: &str,
+        user: &str,
+        now: NaiveDateTime,
+    ) -> ServiceResult<Option<(Posting, Vec<PostingLine>)>> {
+        conn.transaction(|conn| {
+            let deferred = find_posting(conn, posting_id)?;
+            if LedgerRepository::find_by_id_for_update(conn, &deferred.ledger_id)?.is_none() {
+                return Err(ServiceError::LedgerNotFound(deferred.ledger_id));
+            }
+            // Read again under the ledger lock, another promotion may have won the race.
+            let deferred = find_posting(conn, posting_id)?;
+            if deferred.pst_status != PostingStatus::DEFERRED {
+                return Ok(None);
+            }
+            let record_time = record_time_after_youngest(conn, &deferred.ledger_id, now)?;
+            let mut deferred_lines =
+                PostingLineRepository::find_by_opr_id_and_record_time(conn, &deferred.opr_id, deferred.record_time)?;
+            deferred_lines.retain(|line| line.pst_status == PostingStatus::DEFERRED);
+
+            let new_posting = NewPosting { record_time, pst_status: PostingStatus::POSTED, ..to_new_posting(deferred) };
+            let new_lines = deferred_lines.into_iter().map(to_new_line).collect();
+            let prepared = self.prepare(conn, new_posting, new_lines)?;
+
+            update_pending(conn, &prepared.posting, &prepared.lines, PostingStatus::DEFERRED)?;
+            let posting = find_posting(conn, posting_id)?;
+            if let Some(discarded) = prepared.discarded {
+                discard(conn, &discarded, &posting)?;
+            }
+            self.reclose(conn, &posting, user, prepared.reclosing)?;
+            record_decision(conn, &posting, POSTING_PROMOTED_NOTE, user, record_time)?;
+            let lines = PostingLineRepository::find_by_opr_id_and_record_time(conn, &posting.opr_id, posting.record_time)?;
+            Ok(Some((posting, lines)))
+        })
+    }
+
+    /// Cancels a deferred posting that can not be promoted, e.g. because its
+    /// posting time falls before a closed statement. The posting and its lines
+    /// are turned CANCELLED and an op note of type
+    /// `POSTING_PROMOTION_FAILED_NOTE` recorded at `now` gives the reason.
+    ///
+    /// Returns `None` if the posting is no longer deferred.
+    pub fn cancel_deferred<C: Repositories>(
+        &self,
+        conn: &mut C,
+        posting_id: &str,
+        reason: &str,
+        now: NaiveDateTime,
+    ) -> ServiceResult<Option<Posting>> {
+        conn.transaction(|conn| {
+            let deferred = find_posting(conn, posting_id)?;
+            if deferred.pst_status != PostingStatus::DEFERRED {
+                return Ok(None);
+            }
+            let mut deferred_lines =
+                PostingLineRepository::find_by_opr_id_and_record_time(conn, &deferred.opr_id, deferred.record_time)?;
+            deferred_lines.retain(|line| line.pst_status == PostingStatus::DEFERRED);
+            let record = NewPosting { pst_status: PostingStatus::CANCELLED, ..to_new_posting(deferred) };
+            let lines: Vec<NewPostingLine> = deferred_lines
+                .into_iter()
+                .map(|line| NewPostingLine { pst_status: PostingStatus::CANCELLED, ..to_new_line(line) })
+                .collect();
+
+            update_pending(conn, &record, &lines, PostingStatus::DEFERRED)?;
+            let posting = find_posting(conn, posting_id)?;
+            record_decision(conn, &posting, POSTING_PROMOTION_FAILED_NOTE, reason, now)?;
+            Ok(Some(posting))
+        })
+    }
+
+    /// Approves a proposed posting on behalf of `checker`, who must not be the
+    /// user who recorded it.
+    ///
+    /// The posting is recorded at `now`, or right after the youngest posting of
+    /// its ledger if that one was recorded later, like a new posting: it
+    /// discards the effective posting of its operation, is chained to the
+    /// youngest posting of the ledger, sealed, and turned POSTED. An op note of
+    /// type `POSTING_APPROVED_NOTE` records the checker.
+    pub fn approve<C: Repositories>(
+        &self,
+        conn: &mut C,
+        posting_id: &str,
+        checker: &str,
+        now: NaiveDateTime,
+    ) -> ServiceResult<(Posting, Vec<PostingLine>)> {
+        conn.transaction(|conn| {
+            let (proposal, proposed_lines) = find_proposal(conn, posting_id, checker)?;
+            let record_time = record_time_after_youngest(conn, &proposal.ledger_id, now)?;
+            let new_posting = NewPosting { record_time, pst_status: PostingStatus::POSTED, ..to_new_posting(proposal) };
+            let new_lines = proposed_lines.into_iter().map(to_new_line).collect();
+            let prepared = self.prepare(conn, new_posting, new_lines)?;
+
+            update_pending(conn, &prepared.posting, &prepared.lines, PostingStatus::PROPOSED)?;
+            let posting = find_posting(conn, posting_id)?;
+            if let Some(discarded) = prepared.discarded {
+                discard(conn, &discarded, &posting)?;
+            }
+            self.reclose(conn, &posting, checker, prepared.reclosing)?;
+            record_decision(conn, &posting, POSTING_APPROVED_NOTE, checker, record_time)?;
+            let lines = PostingLineRepository::find_by_opr_id_and_record_time(conn, &posting.opr_id, posting.record_time)?;
+            Ok((posting, lines))
+        })
+    }
+
+    /// Rejects a proposed posting on behalf of `checker`, who must not be the
+    /// user who recorded it. The posting and its lines are marked CANCELLED
+    /// and an op note of type `POSTING_REJECTED_NOTE` recorded at `now`
+    /// records the checker.
+    pub fn reject<C: Repositories>(
+        &self,
+        conn: &mut C,
+        posting_id: &str,
+        checker: &str,
+        now: NaiveDateTime,
+    ) -> ServiceResult<Posting> {
+        conn.transaction(|conn| {
+            let (proposal, proposed_lines) = find_proposal(conn, posting_id, checker)?;
+            let record = NewPosting { pst_status: PostingStatus::CANCELLED, ..to_new_posting(proposal) };
+            let lines: Vec<NewPostingLine> = proposed_lines
+                .into_iter()
+                .map(|line| NewPostingLine { pst_status: PostingStatus::CANCELLED, ..to_new_line(line) })
+                .collect();
+
+            update_pending(conn, &record, &lines, PostingStatus::PROPOSED)?;
+            let posting = find_posting(conn, posting_id)?;
+            record_decision(conn, &posting, POSTING_REJECTED_NOTE, checker, now)?;
+            Ok(posting)
+        })
+    }
+
+    /// Re-posts an operation that has already been posted.
+    ///
+    /// The new posting discards the currently effective posting of the operation:
+    /// it references it through `discarded_id`/`discarded_time`, the discarded
+    /// posting gets its `discarding_id`, and its lines are stamped with the record
+    /// time of the new posting so that they no longer count in account statements.
+    pub fn repost<C: Repositories>(
+        &self,
+        conn: &mut C,
+        new_posting: NewPosting,
+        new_lines: Vec<NewPostingLine>,
+    ) -> ServiceResult<(Posting, Vec<PostingLine>)> {
+        conn.transaction(|conn| {
+            if PostingRepository::find_by_opr_id_and_discarding_id_is_null(conn, &new_posting.opr_id)?.is_none() {
+                return Err(ServiceError::OperationNotFound(new_posting.opr_id.clone()));
+            }
+            self.new_posting(conn, new_posting, new_lines)
+        })
+    }
+
+    /// Checks a posting to be recorded in the journal and prepares it: it is
+    /// linked to the effective posting of its operation, which it discards,
+    /// chained to the youngest posting of its ledger and sealed with its
+    /// denormalized lines. Returns the posting, its lines, the discarded
+    /// posting and the statements to close again.
+    fn prepare<C: Repositories>(
+        &self,
+        conn: &mut C,
+        mut new_posting: NewPosting,
+        new_lines: Vec<NewPostingLine>,
+    ) -> ServiceResult<Prepared> {
+        check_accounts(conn, &new_posting, &new_lines)?;
+
+        let discarded = PostingRepository::find_by_opr_id_and_discarding_id_is_null(conn, &new_posting.opr_id)?;
+        if let Some(discarded) = &discarded {
+            new_posting.discarded_id = Some(discarded.id.clone());
+            new_posting.discarded_time = Some(discarded.record_time);
+        }
+
+        // Neither the new posting nor the discarded one may fall into a closed period.
+        let pst_time = discarded
+            .as_ref()
+            .map_or(new_posting.pst_time, |discarded| discarded.pst_time.min(new_posting.pst_time));
+        let reclosing = self.check_backdated(conn, &new_posting, &new_lines, discarded.as_ref(), pst_time)?;
+
+        let antecedent = PostingRepository::find_first_by_ledger_order_by_record_time_desc(conn, &new_posting.ledger_id)?;
+        if let Some(antecedent) = &antecedent {
+            if new_posting.record_time <= antecedent.record_time {
+                return Err(ServiceError::RecordTimeNotAfterAntecedent {
+                    record_time: new_posting.record_time,
+                    antecedent_id: antecedent.id.clone(),
+                });
+            }
+        }
+
+        let mut lines: Vec<NewPostingLine> = new_lines
+            .into_iter()
+            .map(|line| denormalize(&new_posting, line))
+            .collect();
+        journal::seal(self.hash_algorithm.as_ref(), antecedent.as_ref(), &mut new_posting, &mut lines);
+        Ok(Prepared { posting: new_posting, lines, discarded, reclosing })
+    }
+
+    /// Applies the backdated posting policy to a posting, or the re-posting of
+    /// a discarded one, at or before CLOSED statements.
+    ///
+    /// Ledger closings, which always reference their `LDG_CLSNG` posting, are
+    /// final, whatever the policy. Statement postings do not change balances:
+    /// they only fall into a closed period of the ledger. Returns the
+    /// statements to close again once the posting is recorded.
+    fn check_backdated<C: Repositories>(
+        &self,
+        conn: &mut C,
+        new_posting: &NewPosting,
+        new_lines: &[NewPostingLine],
+        discarded: Option<&Posting>,
+        pst_time: NaiveDateTime,
+    ) -> ServiceResult<Option<Reclosing>> {
+        let reclose = self.backdated_posting_policy == BackdatedPostingPolicy::Reclose;
+        let closed_ledger_stmts = LedgerStmtRepository::find_by_ledger_and_stmt_status_and_pst_time_gte(
+            conn,
+            &new_posting.ledger_id,
+            StmtStatus::CLOSED,
+            pst_time,
+        )?;
+        if let Some(closed) = closed_ledger_stmts.iter().find(|stmt| !reclose || stmt.posting_id.is_some()) {
+            return Err(ServiceError::LedgerClosed { ledger_id: closed.ledger_id.clone(), pst_time: closed.pst_time });
+        }
+        if !affects_balance(new_posting.pst_type) {
+            return Ok(None);
+        }
+
+        let mut account_ids: BTreeSet<String> = new_lines.iter().map(|line| line.account_id.clone()).collect();
+        if let Some(discarded) = discarded {
+            for line in PostingLineRepository::find_by_opr_id_and_record_time(conn, &discarded.opr_id, discarded.record_time)? {
+                account_ids.insert(line.account_id);
+            }
+        }
+        let mut reclosed_account_ids = BTreeSet::new();
+        for account_id in account_ids {
+            let closed =
+                AccountStmtRepository::find_by_account_and_stmt_status_and_pst_time_gte(conn, &account_id, StmtStatus::CLOSED, pst_time)?;
+            match closed.first() {
+                None => {}
+                Some(closed) if !reclose => {
+                    return Err(ServiceError::AccountStmtClosed { account_id, pst_time: closed.pst_time });
+                }
+                Some(_) => {
+                    reclosed_account_ids.insert(account_id);
+                }
+            }
+        }
+
+        if reclosed_account_ids.is_empty() && closed_ledger_stmts.is_empty() {
+            return Ok(None);
+        }
+        Ok(Some(Reclosing { pst_time, account_ids: reclosed_account_ids }))
+    }
+
+    /// Closes again on behalf of `user` the statements invalidated by a
+    /// recorded posting, see `check_backdated`.
+    fn reclose<C: Repositories>(
+        &self,
+        conn: &mut C,
+        posting: &Posting,
+        user: &str,
+        reclosing: Option<Reclosing>,
+    ) -> ServiceResult<()> {
+        let Some(reclosing) = reclosing else {
+            return Ok(());
+        };
+        let account_stmt_service = AccountStmtService::with_posting_service(self.clone());
+        for account_id in &reclosing.account_ids {
+            account_stmt_service.reclose_account_stmts(conn, account_id, reclosing.pst_time, user)?;
+        }
+        LedgerStmtService::with_services(account_stmt_service, self.clone()).reclose_ledger_stmts(
+            conn,
+            &posting.ledger_id,
+            reclosing.pst_time,
+            user,
+        )?;
+        Ok(())
+    }
+}
+
+/// Records a posting and its lines with an ineffective status, outside of the
+/// journal.
+fn save_ineffective<C: Repositories>(
+    conn: &mut C,
+    new_posting: NewPosting,
+    new_lines: Vec<NewPostingLine>,
+    pst_status: PostingStatus,
+) -> ServiceResult<(Posting, Vec<PostingLine>)> {
+    validate_balance(new_posting.pst_type, &new_lines)?;
+
+    conn.transaction(|conn| {
+        check_accounts(conn, &new_posting, &new_lines)?;
+        let new_posting = NewPosting {
+            antecedent_id: None,
+            antecedent_hash: None,
+            hash: None,
+            hash_alg: None,
+            pst_status,
+            discarded_id: None,
+            discarded_time: None,
+            discarding_id: None,
+            ..new_posting
+        };
+        let lines: Vec<NewPostingLine> = new_lines
+            .into_iter()
+            .map(|line| NewPostingLine { hash: String::new(), discarded_time: None, ..denormalize(&new_posting, line) })
+            .collect();
+
+        let posting = PostingRepository::save(conn, new_posting)?;
+        let lines = PostingLineRepository::save_all(conn, lines)?;
+        Ok((posting, lines))
+    })
+}
+
+/// Locks the ledger of a posting and checks that every line references one of
+/// its accounts.
+fn check_accounts<C: Repositories>(conn: &mut C, posting: &NewPosting, lines: &[NewPostingLine]) -> ServiceResult<()> {
+    if LedgerRepository::find_by_id_for_update(conn, &posting.ledger_id)?.is_none() {
+        return Err(ServiceError::LedgerNotFound(posting.ledger_id.clone()));
+    }
+    for line in lines {
+        let account = LedgerAccountRepository::find_by_id(conn, &line.account_id)?
+            .ok_or_else(|| ServiceError::AccountNotFound(line.account_id.clone()))?;
+        if account.ledger_id != posting.ledger_id {
+            return Err(ServiceError::AccountNotInLedger {
+                account_id: account.id,
+                ledger_id: posting.ledger_id.clone(),
+            });
+        }
+    }
+    Ok(())
+}
+
+fn find_posting<C: Repositories>(conn: &mut C, posting_id: &str) -> ServiceResult<Posting> {
+    PostingRepository::find_by_id(conn, posting_id)?.ok_or_else(|| ServiceError::PostingNotFound(posting_id.to_string()))
+}
+
+/// Reads a proposed posting and its lines for a decision of `checker`.
+fn find_proposal<C: Repositories>(
+    conn: &mut C,
+    posting_id: &str,
+    checker: &str,
+) -> ServiceResult<(Posting, Vec<PostingLine>)> {
+    let posting = find_posting(conn, posting_id)?;
+    if posting.pst_status != PostingStatus::PROPOSED {
+        return Err(ServiceError::PostingNotProposed { posting_id: posting.id, pst_status: posting.pst_status });
+    }
+    if posting.record_user == checker {
+        return Err(ServiceError::CheckerIsMaker { posting_id: posting.id, user: posting.record_user });
+    }
+    let mut lines = PostingLineRepository::find_by_opr_id_and_record_time(conn, &posting.opr_id, posting.record_time)?;
+    lines.retain(|line| line.pst_status == PostingStatus::PROPOSED);
+    Ok((posting, lines))
+}
+
+/// The record time of a pending posting recorded in the journal at `now`: right
+/// after the youngest posting of the ledger if that one was recorded at or
+/// after `now`, so that the posting can be chained to it.
+fn record_time_after_youngest<C: Repositories>(
+    conn: &mut C,
+    ledger_id: &str,
+    now: NaiveDateTime,
+) -> ServiceResult<NaiveDateTime> {
+    Ok(match PostingRepository::find_first_by_ledger_order_by_record_time_desc(conn, ledger_id)? {
+        Some(youngest) if youngest.record_time >= now => youngest.record_time + Duration::microseconds(1),
+        _ => now,
+    })
+}
+
+/// Records the outcome of a pending posting, unless another one has been
+/// recorded concurrently.
+fn update_pending<C: Repositories>(
+    conn: &mut C,
+    posting: &NewPosting,
+    lines: &[NewPostingLine],
+    pending_status: PostingStatus,
+) -> ServiceResult<()> {
+    if PostingRepository::update_pending(conn, posting, pending_status)? == 0 {
+        let current = find_posting(conn, &posting.id)?;
+        return Err(ServiceError::PostingNotProposed { posting_id: current.id, pst_status: current.pst_status });
+    }
+    for line in lines {
+        PostingLineRepository::update_pending(conn, line, pending_status)?;
+    }
+    Ok(())
+}
+
+/// Records the decision on a pending posting in an op note.
+fn record_decision<C: Repositories>(
+    conn: &mut C,
+    posting: &Posting,
+    note_type: &str,
+    checker: &str,
+    rec_time: NaiveDateTime,
+) -> ServiceResult<()> {
+    OpNoteRepository::save(
+        conn,
+        NewOpNote {
+            id: new_id(),
+            rec_id: posting.id.clone(),
+            note_type: Some(note_type.to_string()),
+            content: Some(checker.to_string()),
+            rec_time,
+            exec_time: None,
+            premature_exc: None,
+            repeated_exec: None,
+            exec_status: None,
+        },
+    )?;
+    Ok(())
+}
+
+fn to_new_posting(posting: Posting) -> NewPosting {
+    NewPosting {
+        id: posting.id,
+        antecedent_id: posting.antecedent_id,
+        antecedent_hash: posting.antecedent_hash,
+        hash: posting.hash,
+        hash_alg: posting.hash_alg,
+        record_user: posting.record_user,
+        record_time: posting.record_time,
+        opr_id: posting.opr_id,
+        opr_time: posting.opr_time,
+        opr_type: posting.opr_type,
+        opr_src: posting.opr_src,
+        pst_time: posting.pst_time,
+        pst_type: posting.pst_type,
+        pst_status: posting.pst_status,
+        ledger_id: posting.ledger_id,
+        val_time: posting.val_time,
+        discarded_id: posting.discarded_id,
+        discarded_time: posting.discarded_time,
+        discarding_id: posting.discarding_id,
+        opr_details_id: posting.opr_details_id,
+    }
+}
+
+fn to_new_line(line: PostingLine) -> NewPostingLine {
+    NewPostingLine {
+        id: line.id,
+        account_id: line.account_id,
+        debit_amount: line.debit_amount,
+        credit_amount: line.credit_amount,
+        details_id: line.details_id,
+        src_account: line.src_account,
+        base_line: line.base_line,
+        sub_opr_src_id: line.sub_opr_src_id,
+        record_time: line.record_time,
+        opr_id: line.opr_id,
+        opr_src: line.opr_src,
+        pst_time: line.pst_time,
+        pst_type: line.pst_type,
+        pst_status: line.pst_status,
+        hash: line.hash,
+        discarded_time: line.discarded_time,
+    }
+}
+
+/// Marks a posting and its lines as discarded by the given posting.
+fn discard<C: Repositories>(conn: &mut C, discarded: &Posting, discarding: &Posting) -> ServiceResult<()> {
+    PostingRepository::update_discarding_id(conn, &discarded.id, &discarding.id)?;
+    PostingLineRepository::update_discarded_time_by_opr_id_and_record_time(
+        conn,
+        &discarded.opr_id,
+        discarded.record_time,
+        discarding.record_time,
+    )?;
+    Ok(())
+}
+
+/// Checks that the posting lines are not empty and that debits equal credits.
+/// Amounts can not be negative and a line books either a debit or a credit,
+/// except on statement postings, whose lines mirror the totals of an account.
+fn validate_balance(pst_type: PostingType, lines: &[NewPostingLine]) -> ServiceResult<()> {
+    if lines.is_empty() {
+        return Err(ServiceError::NoPostingLines);
+    }
+    for line in lines {
+        if line.debit_amount < Decimal::ZERO || line.credit_amount < Decimal::ZERO {
+            return Err(ServiceError::NegativeAmount { line_id: line.id.clone() });
+        }
+        if affects_balance(pst_type) && !line.debit_amount.is_zero() && !line.credit_amount.is_zero() {
+            return Err(ServiceError::DebitAndCreditLine { line_id: line.id.clone() });
+        }
+    }
+    let total_debit: Decimal = lines.iter().map(|l| l.debit_amount).sum();
+    let total_credit: Decimal = lines.iter().map(|l| l.credit_amount).sum();
+    if total_debit != total_credit {
+        return Err(ServiceError::UnbalancedPosting { total_debit, total_credit });
+    }
+    Ok(())
+}
+
+/// Copies the posting information carried by each posting line from its posting.
+fn denormalize(posting: &NewPosting, line: NewPostingLine) -> NewPostingLine {
+    NewPostingLine {
+        record_time: posting.record_time,
+        opr_id: posting.opr_id.clone(),
+        opr_src: posting.opr_src.clone(),
+        pst_time: posting.pst_time,
+        pst_type: posting.pst_type,
+        pst_status: posting.pst_status,
+        ..line
+    }
+}
@@ -0,0 +1,114 @@
+// src/simulation_service.rs
+/*
+ * Copyright (c) 2018-2024 adorsys GmbH and Co. KG
+ * All rights are reserved.
+ */
+
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+
+use postings_repository::models::enums::{PostingStatus, StmtStatus};
+use postings_repository::models::{AccountStmt, NewPosting, NewPostingLine, Posting, PostingLine};
+use postings_repository::repository::{
+    AccountStmtRepository, LedgerRepository, LedgerStmtRepository, PostingLineRepository, PostingRepository,
+    PostingTraceRepository, Repositories,
+};
+
+use crate::account_stmt_service::{AccountStmtReport, AccountStmtService};
+use crate::error::{ServiceError, ServiceResult};
+use crate::posting_service::PostingService;
+
+/// The simulated records deleted by a purge.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SimulationPurge {
+    pub ledger_id: String,
+    pub ledger_stmts: usize,
+    pub account_stmts: usize,
+    pub posting_traces: usize,
+    pub posting_lines: usize,
+    pub postings: usize,
+}
+
+/// Previews postings, such as fee or interest runs, before they are posted.
+///
+/// Simulated postings are recorded with the status SIMULATED: they are not
+/// chained in the journal and are left out of balances and statements, except
+/// for the what-if views of this service. Once the preview is over, the
+/// simulated data of the ledger is purged.
+#[derive(Debug, Clone, Default)]
+pub struct SimulationService {
+    posting_service: PostingService,
+    account_stmt_service: AccountStmtService,
+}
+
+impl SimulationService {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a service recording postings and statements with the given services.
+    pub fn with_services(posting_service: PostingService, account_stmt_service: AccountStmtService) -> Self {
+        Self { posting_service, account_stmt_service }
+    }
+
+    /// Records a posting as SIMULATED, whatever its status.
+    pub fn post<C: Repositories>(
+        &self,
+        conn: &mut C,
+        new_posting: NewPosting,
+        new_lines: Vec<NewPostingLine>,
+    ) -> ServiceResult<(Posting, Vec<PostingLine>)> {
+        let new_posting = NewPosting { pst_status: PostingStatus::SIMULATED, ..new_posting };
+        self.posting_service.simulate(conn, new_posting, new_lines)
+    }
+
+    /// Reads the what-if statement of an account at the reference time: the
+    /// statement of `AccountStmtService::read_stmt`, adding the simulated lines
+    /// posted after its base statement.
+    pub fn read_stmt<C: Repositories>(
+        &self,
+        conn: &mut C,
+        account_id: &str,
+        ref_time: NaiveDateTime,
+    ) -> ServiceResult<AccountStmtReport> {
+        self.account_stmt_service.read_what_if_stmt(conn, account_id, ref_time)
+    }
+
+    /// Records the what-if statement of an account at the posting time as a
+    /// SIMULATED statement, documented by a simulated `BAL_STMT` posting and
+    /// traced like a closed statement. Closed statements are not affected.
+    pub fn simulate_account_stmt<C: Repositories>(
+        &self,
+        conn: &mut C,
+        account_id: &str,
+        pst_time: NaiveDateTime,
+        record_user: &str,
+    ) -> ServiceResult<AccountStmt> {
+        self.account_stmt_service.save_stmt(conn, account_id, pst_time, record_user, StmtStatus::SIMULATED)
+    }
+
+    /// Deletes the simulated postings of the ledger together with their lines,
+    /// and all simulated account and ledger statements of the ledger with their
+    /// traces.
+    pub fn purge<C: Repositories>(&self, conn: &mut C, ledger_id: &str) -> ServiceResult<SimulationPurge> {
+        conn.transaction(|conn| {
+            if LedgerRepository::find_by_id_for_update(conn, ledger_id)?.is_none() {
+                return Err(ServiceError::LedgerNotFound(ledger_id.to_string()));
+            }
+            // Statements reference traces and postings, traces reference postings.
+            let ledger_stmts = LedgerStmtRepository::delete_simulated_by_ledger(conn, ledger_id)?;
+            let account_stmts = AccountStmtRepository::delete_simulated_by_ledger(conn, ledger_id)?;
+            let posting_traces = PostingTraceRepository::delete_simulated_by_ledger(conn, ledger_id)?;
+            let posting_lines = PostingLineRepository::delete_simulated_by_ledger(conn, ledger_id)?;
+            let postings = PostingRepository::delete_simulated_by_ledger(conn, ledger_id)?;
+            Ok(SimulationPurge {
+                ledger_id: ledger_id.to_string(),
+                ledger_stmts,
+                account_stmts,
+                posting_traces,
+                posting_lines,
+                postings,
+            })
+        })
+    }
+}
@@ -0,0 +1,166 @@
+// tests/account_stmt_service_test.rs
+//
+// Copyright (c) 2018-2024 adorsys GmbH and Co. KG
+// All rights are reserved.
+
+mod common;
+
+use std::sync::Arc;
+
+use chrono::Duration;
+use common::{establish_connection, seed_database, time, TestDatabaseGuard};
+use postings_repository::models::enums::{PostingType, StmtStatus};
+use postings_repository::repository::{posting_repository, posting_trace_repository};
+use postings_service::{AccountStmtService, FixedClock, PostingService, ServiceError};
+use rust_decimal::Decimal;
+use serial_test::serial;
+
+const ASSET_ACCOUNT: &str = "xVgaTPMcRty9ik3BTQDh1Q_BS_1_0_0";
+const EQUITY_ACCOUNT: &str = "xVgaTPMcRty9ik3BTQDh1Q_BS_2_0_0";
+
+#[test]
+#[serial]
+fn test_read_stmt_from_closed_stmt() {
+    let mut conn = establish_connection();
+    seed_database(&mut conn, "tests/fixtures/account_stmt_service_dataset.sql");
+    let _guard = TestDatabaseGuard::new();
+
+    let stmt = AccountStmtService::new()
+        .read_stmt(&mut conn, ASSET_ACCOUNT, time("2018-02-28 23:59:59"))
+        .expect("Failed to read statement");
+
+    assert_eq!(stmt.base_stmt_id.as_deref(), Some("stmt_001"));
+    assert_eq!(stmt.total_debit, Decimal::new(1300, 0));
+    assert_eq!(stmt.total_credit, Decimal::new(250, 0));
+    assert_eq!(stmt.balance, Decimal::new(1050, 0));
+}
+
+#[test]
+#[serial]
+fn test_read_stmt_without_closed_stmt() {
+    let mut conn = establish_connection();
+    seed_database(&mut conn, "tests/fixtures/account_stmt_service_dataset.sql");
+    let _guard = TestDatabaseGuard::new();
+
+    // The closed statement is after the reference time: all lines are summed up.
+    let stmt = AccountStmtService::new()
+        .read_stmt(&mut conn, ASSET_ACCOUNT, time("2018-01-20 00:00:00"))
+        .expect("Failed to read statement");
+
+    assert_eq!(stmt.base_stmt_id, None);
+    assert_eq!(stmt.total_debit, Decimal::new(1000, 0));
+    assert_eq!(stmt.total_credit, Decimal::ZERO);
+    assert_eq!(stmt.balance, Decimal::new(1000, 0));
+}
+
+#[test]
+#[serial]
+fn test_read_stmt_credit_account() {
+    let mut conn = establish_connection();
+    seed_database(&mut conn, "tests/fixtures/account_stmt_service_dataset.sql");
+    let _guard = TestDatabaseGuard::new();
+
+    let stmt = AccountStmtService::new()
+        .read_stmt(&mut conn, EQUITY_ACCOUNT, time("2018-02-28 23:59:59"))
+        .expect("Failed to read statement");
+
+    assert_eq!(stmt.total_debit, Decimal::new(50, 0));
+    assert_eq!(stmt.total_credit, Decimal::new(300, 0));
+    // Credit accounts are positive on the credit side.
+    assert_eq!(stmt.balance, Decimal::new(250, 0));
+}
+
+#[test]
+#[serial]
+fn test_read_stmt_unknown_account() {
+    let mut conn = establish_connection();
+    seed_database(&mut conn, "tests/fixtures/account_stmt_service_dataset.sql");
+    let _guard = TestDatabaseGuard::new();
+
+    let result = AccountStmtService::new().read_stmt(&mut conn, "unknown", time("2018-02-28 23:59:59"));
+    assert!(matches!(result, Err(ServiceError::AccountNotFound(_))));
+}
+
+#[test]
+#[serial]
+fn test_close_account_stmt_ok() {
+    let mut conn = establish_connection();
+    seed_database(&mut conn, "tests/fixtures/account_stmt_service_dataset.sql");
+    let _guard = TestDatabaseGuard::new();
+    let service = AccountStmtService::new();
+
+    let stmt = service
+        .close_account_stmt(&mut conn, ASSET_ACCOUNT, time("2018-02-28 23:59:59"), "Test User")
+        .expect("Failed to close statement");
+
+    assert_eq!(stmt.stmt_status, StmtStatus::CLOSED);
+    assert_eq!(stmt.stmt_seq_nbr, 1);
+    assert_eq!(stmt.total_debit, Decimal::new(1300, 0));
+    assert_eq!(stmt.total_credit, Decimal::new(250, 0));
+
+    // The statement is documented by a BAL_STMT posting carrying the same id.
+    let posting = posting_repository::find_by_id(&mut conn, &stmt.id)
+        .unwrap()
+        .expect("The statement posting should exist");
+    assert_eq!(stmt.posting_id.as_deref(), Some(posting.id.as_str()));
+    assert_eq!(posting.pst_type, PostingType::BalStmt);
+    assert!(posting.hash.is_some());
+
+    // One trace per included posting: pst_002 and pst_003.
+    let traces = posting_trace_repository::find_by_tgt_pst_id(&mut conn, &posting.id).unwrap();
+    let mut src_pst_ids: Vec<&str> = traces.iter().map(|t| t.src_pst_id.as_str()).collect();
+    src_pst_ids.sort();
+    assert_eq!(src_pst_ids, vec!["pst_002", "pst_003"]);
+    let youngest = traces.iter().find(|t| t.src_pst_id == "pst_003").unwrap();
+    assert_eq!(youngest.credit_amount, Decimal::new(50, 0));
+    assert_eq!(youngest.src_pst_hash.as_deref(), Some("hash_003"));
+    assert_eq!(stmt.youngest_pst_id.as_deref(), Some(youngest.id.as_str()));
+    assert_eq!(stmt.latest_pst_id.as_deref(), Some(youngest.id.as_str()));
+
+    // The mechanical statement lines do not change the balance.
+    let read = service.read_stmt(&mut conn, ASSET_ACCOUNT, time("2018-02-28 23:59:59")).unwrap();
+    assert_eq!(read.total_debit, Decimal::new(1300, 0));
+    assert_eq!(read.total_credit, Decimal::new(250, 0));
+
+    // Later reads start from the new statement.
+    let read = service.read_stmt(&mut conn, ASSET_ACCOUNT, time("2018-03-31 23:59:59")).unwrap();
+    assert_eq!(read.base_stmt_id.as_deref(), Some(stmt.id.as_str()));
+    assert_eq!(read.total_debit, Decimal::new(1377, 0));
+    assert_eq!(read.balance, Decimal::new(1127, 0));
+}
+
+#[test]
+#[serial]
+fn test_stmt_posting_is_recorded_by_clock() {
+    let mut conn = establish_connection();
+    seed_database(&mut conn, "tests/fixtures/account_stmt_service_dataset.sql");
+    let _guard = TestDatabaseGuard::new();
+    let clock = Arc::new(FixedClock::new(time("2030-01-01 00:00:00")));
+    let service = AccountStmtService::with_posting_service(PostingService::new().with_clock(clock.clone()));
+
+    let stmt = service.close_account_stmt(&mut conn, ASSET_ACCOUNT, time("2018-02-28 23:59:59"), "Test User").unwrap();
+    assert_eq!(stmt.created, Some(time("2030-01-01 00:00:00")));
+    let posting = posting_repository::find_by_id(&mut conn, &stmt.id).unwrap().unwrap();
+    assert_eq!(posting.record_time, time("2030-01-01 00:00:00"));
+
+    // A clock standing still records the next statement right after the previous one.
+    let stmt = service.close_account_stmt(&mut conn, ASSET_ACCOUNT, time("2018-03-31 23:59:59"), "Test User").unwrap();
+    assert_eq!(stmt.created, Some(time("2030-01-01 00:00:00") + Duration::microseconds(1)));
+}
+
+#[test]
+#[serial]
+fn test_close_account_stmt_already_closed() {
+    let mut conn = establish_connection();
+    seed_database(&mut conn, "tests/fixtures/account_stmt_service_dataset.sql");
+    let _guard = TestDatabaseGuard::new();
+
+    // stmt_001 closes the account at the end of January.
+    let result = AccountStmtService::new().close_account_stmt(
+        &mut conn,
+        ASSET_ACCOUNT,
+        time("2018-01-20 00:00:00"),
+        "Test User",
+    );
+    assert!(matches!(result, Err(ServiceError::AccountStmtClosed { .. })));
+}
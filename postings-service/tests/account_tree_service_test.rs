@@ -0,0 +1,156 @@
+// tests/account_tree_service_test.rs
+//
+// Copyright (c) 2018-2024 adorsys GmbH and Co. KG
+// All rights are reserved.
+//
+// The account hierarchy on the in-memory backend, without a database.
+
+mod common;
+
+use common::{post, time};
+use postings_repository::models::enums::{AccountCategory, BalanceSide};
+use postings_repository::models::{NewChartOfAccount, NewLedger, NewLedgerAccount};
+use postings_repository::repository::{
+    ChartOfAccountRepository, InMemoryRepositories, LedgerAccountRepository, LedgerRepository,
+};
+use postings_service::{AccountTreeService, ServiceError};
+use rust_decimal::Decimal;
+
+const LEDGER_ID: &str = "ledger";
+
+fn coa(id: &str, name: &str) -> NewChartOfAccount {
+    NewChartOfAccount {
+        id: id.to_string(),
+        created: time("2018-01-01 00:00:00"),
+        user_details: "Francis".to_string(),
+        short_desc: None,
+        long_desc: None,
+        name: name.to_string(),
+    }
+}
+
+fn account(id: &str, parent_id: Option<&str>, category: AccountCategory, balance_side: BalanceSide) -> NewLedgerAccount {
+    NewLedgerAccount {
+        id: id.to_string(),
+        created: time("2018-01-01 00:00:00"),
+        user_details: "Francis".to_string(),
+        short_desc: None,
+        long_desc: None,
+        name: id.to_string(),
+        ledger_id: LEDGER_ID.to_string(),
+        parent_id: parent_id.map(str::to_string),
+        coa_id: "coa".to_string(),
+        balance_side,
+        category,
+    }
+}
+
+/// Assets holding bank and cash, equity, and the postings of January.
+fn january(service: &AccountTreeService) -> InMemoryRepositories {
+    let mut store = InMemoryRepositories::new();
+    ChartOfAccountRepository::save(&mut store, coa("coa", "IFRS")).unwrap();
+    ChartOfAccountRepository::save(&mut store, coa("coa_2", "SKR03")).unwrap();
+    LedgerRepository::save(
+        &mut store,
+        NewLedger {
+            id: LEDGER_ID.to_string(),
+            created: time("2018-01-01 00:00:00"),
+            user_details: "Francis".to_string(),
+            short_desc: None,
+            long_desc: None,
+            name: "Sample Ledger".to_string(),
+            coa_id: "coa".to_string(),
+        },
+    )
+    .unwrap();
+    for new_account in [
+        account("assets", None, AccountCategory::AS, BalanceSide::Dr),
+        account("cash", Some("assets"), AccountCategory::AS, BalanceSide::Dr),
+        account("bank", Some("assets"), AccountCategory::AS, BalanceSide::Dr),
+        account("equity", None, AccountCategory::EQ, BalanceSide::Cr),
+    ] {
+        service.create_account(&mut store, new_account).unwrap();
+    }
+
+    post(&mut store, "pst_001", LEDGER_ID, "2018-01-05 10:00:00", &[("cash", 100, 0), ("equity", 0, 100)]);
+    post(&mut store, "pst_002", LEDGER_ID, "2018-01-10 10:00:00", &[("bank", 40, 0), ("cash", 0, 40)]);
+    store
+}
+
+#[test]
+fn test_balances_roll_up_the_tree() {
+    let service = AccountTreeService::new();
+    let mut store = january(&service);
+
+    let assets = service.rolled_up_balance(&mut store, "assets", time("2018-01-31 23:59:59")).unwrap();
+    assert_eq!(assets.total_debit, Decimal::from(140));
+    assert_eq!(assets.total_credit, Decimal::from(40));
+    assert_eq!(assets.balance, Decimal::from(100));
+    let children: Vec<(&str, Decimal)> =
+        assets.children.iter().map(|child| (child.account_id.as_str(), child.balance)).collect();
+    assert_eq!(children, vec![("bank", Decimal::from(40)), ("cash", Decimal::from(60))]);
+
+    // Before the transfer to the bank.
+    let assets = service.rolled_up_balance(&mut store, "assets", time("2018-01-06 00:00:00")).unwrap();
+    assert_eq!(assets.balance, Decimal::from(100));
+
+    let error = service.rolled_up_balance(&mut store, "missing", time("2018-01-31 23:59:59")).unwrap_err();
+    assert!(matches!(error, ServiceError::AccountNotFound(id) if id == "missing"));
+}
+
+#[test]
+fn test_child_must_share_coa_of_parent() {
+    let service = AccountTreeService::new();
+    let mut store = january(&service);
+
+    let mut petty_cash = account("petty_cash", Some("cash"), AccountCategory::AS, BalanceSide::Dr);
+    petty_cash.coa_id = "coa_2".to_string();
+    let error = service.create_account(&mut store, petty_cash).unwrap_err();
+    assert!(matches!(error, ServiceError::CoaDiffersFromParent { ref parent_id, .. } if parent_id == "cash"));
+
+    // Moving a parent to another chart of account would orphan its children.
+    let mut assets = LedgerAccountRepository::find_by_id(&mut store, "assets").unwrap().unwrap();
+    assets.coa_id = "coa_2".to_string();
+    let error = service.update_account(&mut store, &assets).unwrap_err();
+    assert!(matches!(error, ServiceError::CoaDiffersFromParent { ref account_id, .. } if account_id == "bank"));
+}
+
+#[test]
+fn test_hierarchy_must_stay_a_tree() {
+    let service = AccountTreeService::new();
+    let mut store = january(&service);
+
+    let mut assets = LedgerAccountRepository::find_by_id(&mut store, "assets").unwrap().unwrap();
+    assets.parent_id = Some("cash".to_string());
+    let error = service.update_account(&mut store, &assets).unwrap_err();
+    assert!(matches!(error, ServiceError::CyclicAccountHierarchy { ref parent_id, .. } if parent_id == "cash"));
+
+    // Moving an account below another branch is fine.
+    let mut bank = LedgerAccountRepository::find_by_id(&mut store, "bank").unwrap().unwrap();
+    bank.parent_id = Some("cash".to_string());
+    service.update_account(&mut store, &bank).unwrap();
+    assert_eq!(LedgerAccountRepository::find_ancestors(&mut store, "bank").unwrap().len(), 2);
+}
+
+#[test]
+fn test_booked_account_keeps_ledger_category_and_balance_side() {
+    let service = AccountTreeService::new();
+    let mut store = january(&service);
+
+    let cash = LedgerAccountRepository::find_by_id(&mut store, "cash").unwrap().unwrap();
+    let mut changed = cash.clone();
+    changed.category = AccountCategory::LI;
+    let error = service.update_account(&mut store, &changed).unwrap_err();
+    assert!(matches!(error, ServiceError::AccountBooked { ref account_id } if account_id == "cash"));
+    let mut changed = cash.clone();
+    changed.balance_side = BalanceSide::Cr;
+    assert!(matches!(service.update_account(&mut store, &changed), Err(ServiceError::AccountBooked { .. })));
+
+    // Other fields of a booked account, and accounts without lines, can change.
+    let mut renamed = cash;
+    renamed.name = "Petty Cash".to_string();
+    assert_eq!(service.update_account(&mut store, &renamed).unwrap().name, "Petty Cash");
+    let mut assets = LedgerAccountRepository::find_by_id(&mut store, "assets").unwrap().unwrap();
+    assets.balance_side = BalanceSide::DrCr;
+    service.update_account(&mut store, &assets).unwrap();
+}
@@ -0,0 +1,112 @@
+// tests/as_of_query_test.rs
+//
+// Copyright (c) 2018-2024 adorsys GmbH and Co. KG
+// All rights are reserved.
+
+mod common;
+
+use chrono::{NaiveDateTime, Utc};
+use common::{business_posting, establish_connection, post, posting_lines, seed_database, time, TestDatabaseGuard};
+use diesel::prelude::*;
+use postings_service::{AccountStmtService, BackdatedPostingPolicy, LedgerStmtService, PostingService};
+use rust_decimal::Decimal;
+use serial_test::serial;
+
+const LEDGER_ID: &str = "Zd0ND5YwSzGwIfZilhumPg";
+const CASH: &str = "xVgaTPMcRty9ik3BTQDh1Q_BS_1_0_0";
+const EQUITY: &str = "xVgaTPMcRty9ik3BTQDh1Q_BS_2_0_0";
+
+/// Records a posting at `record_time` for the posting time `pst_time`.
+fn record(conn: &mut PgConnection, service: &PostingService, id: &str, pst_time: &str, record_time: NaiveDateTime, amount: i64) {
+    let mut posting = business_posting(id, LEDGER_ID, pst_time);
+    posting.record_time = record_time;
+    service
+        .new_posting(conn, posting, posting_lines(id, &[(CASH, amount, 0), (EQUITY, 0, amount)]))
+        .expect("Failed to create posting");
+}
+
+/// January as recorded over time: pst_002 is corrected on 2018-01-08 and a
+/// posting of 2018-01-03 is only recorded on 2018-01-10.
+fn record_january(conn: &mut PgConnection) {
+    post(conn, "pst_001", LEDGER_ID, "2018-01-01 10:00:00", &[(CASH, 100, 0), (EQUITY, 0, 100)]);
+    post(conn, "pst_002", LEDGER_ID, "2018-01-05 10:00:00", &[(CASH, 30, 0), (EQUITY, 0, 30)]);
+    let mut correction = business_posting("pst_003", LEDGER_ID, "2018-01-05 10:00:00");
+    correction.opr_id = "opr_of_pst_002".to_string();
+    correction.record_time = time("2018-01-08 10:00:00");
+    PostingService::new()
+        .repost(conn, correction, posting_lines("pst_003", &[(CASH, 50, 0), (EQUITY, 0, 50)]))
+        .expect("Failed to repost");
+    record(conn, &PostingService::new(), "pst_004", "2018-01-03 10:00:00", time("2018-01-10 10:00:00"), 7);
+}
+
+#[test]
+#[serial]
+fn test_read_stmt_as_of_record_time() {
+    let mut conn = establish_connection();
+    seed_database(&mut conn, "tests/fixtures/posting_service_dataset.sql");
+    let _guard = TestDatabaseGuard::new();
+    record_january(&mut conn);
+    let service = AccountStmtService::new();
+    let ref_time = time("2018-01-31 23:59:59");
+
+    let balance_as_of = |conn: &mut PgConnection, as_of: &str| {
+        service.read_stmt_as_of(conn, CASH, ref_time, time(as_of)).unwrap().balance
+    };
+    assert_eq!(balance_as_of(&mut conn, "2017-12-31 00:00:00"), Decimal::ZERO);
+    assert_eq!(balance_as_of(&mut conn, "2018-01-01 10:00:00"), Decimal::new(100, 0));
+    // pst_002 is discarded after 2018-01-06, it still counts as of then.
+    assert_eq!(balance_as_of(&mut conn, "2018-01-06 00:00:00"), Decimal::new(130, 0));
+    assert_eq!(balance_as_of(&mut conn, "2018-01-09 00:00:00"), Decimal::new(150, 0));
+    assert_eq!(balance_as_of(&mut conn, "2018-01-10 10:00:00"), Decimal::new(157, 0));
+
+    // On the posting time axis, as recorded before the backdated posting.
+    let stmt = service.read_stmt_as_of(&mut conn, CASH, time("2018-01-04 00:00:00"), time("2018-01-09 00:00:00")).unwrap();
+    assert_eq!(stmt.balance, Decimal::new(100, 0));
+    let stmt = service.read_stmt_as_of(&mut conn, CASH, time("2018-01-04 00:00:00"), time("2018-01-10 10:00:00")).unwrap();
+    assert_eq!(stmt.balance, Decimal::new(107, 0));
+
+    // As of now, the current statement.
+    let now = Utc::now().naive_utc();
+    assert_eq!(service.read_stmt_as_of(&mut conn, CASH, ref_time, now).unwrap(), service.read_stmt(&mut conn, CASH, ref_time).unwrap());
+
+    let lines = service
+        .find_lines_as_of(&mut conn, CASH, time("2018-01-01 00:00:00"), ref_time, time("2018-01-06 00:00:00"))
+        .unwrap();
+    let mut ids: Vec<&str> = lines.iter().map(|line| line.opr_id.as_str()).collect();
+    ids.sort();
+    assert_eq!(ids, ["opr_of_pst_001", "opr_of_pst_002"]);
+    // The line of pst_002 is listed although it has been discarded since.
+    assert!(lines.iter().any(|line| line.discarded_time == Some(time("2018-01-08 10:00:00"))));
+
+    let trial_balance = LedgerStmtService::new()
+        .trial_balance_as_of(&mut conn, LEDGER_ID, ref_time, time("2018-01-06 00:00:00"))
+        .unwrap();
+    assert!(trial_balance.is_balanced());
+    assert_eq!(trial_balance.total_debit, Decimal::new(130, 0));
+}
+
+#[test]
+#[serial]
+fn test_reclosed_stmts_as_of_record_time() {
+    let mut conn = establish_connection();
+    seed_database(&mut conn, "tests/fixtures/posting_service_dataset.sql");
+    let _guard = TestDatabaseGuard::new();
+    record_january(&mut conn);
+    let posting_service = PostingService::new().with_backdated_posting_policy(BackdatedPostingPolicy::Reclose);
+    let service = AccountStmtService::with_posting_service(posting_service.clone());
+    let closed = service.close_account_stmt(&mut conn, CASH, time("2018-01-31 23:59:59"), "Test User").unwrap();
+    let reported_at = closed.created.unwrap();
+    let reported = service.read_stmt_as_of(&mut conn, CASH, time("2018-02-28 23:59:59"), reported_at).unwrap();
+    assert_eq!(reported.base_stmt_id.as_deref(), Some(closed.id.as_str()));
+
+    // A backdated posting closes January again.
+    record(&mut conn, &posting_service, "pst_005", "2018-01-20 10:00:00", Utc::now().naive_utc(), 3);
+    let current = service.read_stmt(&mut conn, CASH, time("2018-02-28 23:59:59")).unwrap();
+    assert_ne!(current.base_stmt_id, reported.base_stmt_id);
+    assert_eq!(current.balance, Decimal::new(160, 0));
+
+    // The report of the first closing is reproduced exactly.
+    let reproduced = service.read_stmt_as_of(&mut conn, CASH, time("2018-02-28 23:59:59"), reported_at).unwrap();
+    assert_eq!(reproduced, reported);
+    assert_eq!(reproduced.balance, Decimal::new(157, 0));
+}
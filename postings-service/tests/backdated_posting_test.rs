@@ -0,0 +1,167 @@
+// tests/backdated_posting_test.rs
+//
+// Copyright (c) 2018-2024 adorsys GmbH and Co. KG
+// All rights are reserved.
+
+mod common;
+
+use chrono::Utc;
+use common::{business_posting, establish_connection, post, posting_lines, seed_database, time, TestDatabaseGuard};
+use diesel::prelude::*;
+use postings_repository::models::enums::StmtStatus;
+use postings_repository::repository::{posting_trace_repository, AccountStmtRepository, LedgerStmtRepository};
+use postings_service::{
+    AccountStmtService, BackdatedPostingPolicy, JournalVerifier, LedgerStmtService, PostingService, ServiceError,
+};
+use rust_decimal::Decimal;
+use serial_test::serial;
+
+const LEDGER_ID: &str = "Zd0ND5YwSzGwIfZilhumPg";
+const CASH: &str = "xVgaTPMcRty9ik3BTQDh1Q_BS_1_0_0";
+const EQUITY: &str = "xVgaTPMcRty9ik3BTQDh1Q_BS_2_0_0";
+const REVENUE: &str = "xVgaTPMcRty9ik3BTQDh1Q_PL_4_0_0";
+const EXPENSE: &str = "xVgaTPMcRty9ik3BTQDh1Q_PL_5_0_0";
+
+fn reclosing_service() -> PostingService {
+    PostingService::new().with_backdated_posting_policy(BackdatedPostingPolicy::Reclose)
+}
+
+/// Records a posting now at the posting time `pst_time`.
+fn post_backdated(
+    conn: &mut PgConnection,
+    service: &PostingService,
+    id: &str,
+    pst_time: &str,
+    amounts: &[(&str, i64, i64)],
+) -> Result<(), ServiceError> {
+    let mut posting = business_posting(id, LEDGER_ID, pst_time);
+    posting.record_time = Utc::now().naive_utc();
+    service.new_posting(conn, posting, posting_lines(id, amounts)).map(|_| ())
+}
+
+fn closed_stmts(conn: &mut PgConnection, account_id: &str) -> Vec<postings_repository::models::AccountStmt> {
+    AccountStmtRepository::find_by_account_and_stmt_status_and_pst_time_gte(
+        conn,
+        account_id,
+        StmtStatus::CLOSED,
+        time("2018-01-01 00:00:00"),
+    )
+    .unwrap()
+}
+
+#[test]
+#[serial]
+fn test_backdated_posting_is_rejected_by_default() {
+    let mut conn = establish_connection();
+    seed_database(&mut conn, "tests/fixtures/posting_service_dataset.sql");
+    let _guard = TestDatabaseGuard::new();
+    post(&mut conn, "pst_001", LEDGER_ID, "2018-01-01 10:00:00", &[(CASH, 100, 0), (EQUITY, 0, 100)]);
+    AccountStmtService::new()
+        .close_account_stmt(&mut conn, CASH, time("2018-01-15 23:59:59"), "Test User")
+        .expect("Failed to close account statement");
+
+    let result = post_backdated(&mut conn, &PostingService::new(), "pst_002", "2018-01-10 10:00:00", &[(CASH, 20, 0), (REVENUE, 0, 20)]);
+    match result {
+        Err(ServiceError::AccountStmtClosed { account_id, pst_time }) => {
+            assert_eq!(account_id, CASH);
+            assert_eq!(pst_time, time("2018-01-15 23:59:59"));
+        }
+        other => panic!("Expected AccountStmtClosed, got {:?}", other),
+    }
+
+    // Accounts without closed statements, and later posting times, are not affected.
+    post_backdated(&mut conn, &PostingService::new(), "pst_003", "2018-01-10 10:00:00", &[(EXPENSE, 20, 0), (EQUITY, 0, 20)])
+        .expect("Accounts without closed statements accept backdated postings");
+    post_backdated(&mut conn, &PostingService::new(), "pst_004", "2018-01-20 10:00:00", &[(CASH, 20, 0), (REVENUE, 0, 20)])
+        .expect("Postings after the closed statement are accepted");
+    assert_eq!(closed_stmts(&mut conn, CASH).len(), 1);
+}
+
+#[test]
+#[serial]
+fn test_backdated_posting_recloses_later_stmts() {
+    let mut conn = establish_connection();
+    seed_database(&mut conn, "tests/fixtures/posting_service_dataset.sql");
+    let _guard = TestDatabaseGuard::new();
+    let service = reclosing_service();
+    let stmt_service = AccountStmtService::with_posting_service(service.clone());
+    post(&mut conn, "pst_001", LEDGER_ID, "2018-01-01 10:00:00", &[(CASH, 100, 0), (EQUITY, 0, 100)]);
+    stmt_service.close_account_stmt(&mut conn, CASH, time("2018-01-15 23:59:59"), "Test User").unwrap();
+    stmt_service.close_account_stmt(&mut conn, CASH, time("2018-01-31 23:59:59"), "Test User").unwrap();
+    let (ledger_stmt, _) = LedgerStmtService::with_services(stmt_service.clone(), service.clone())
+        .create_ledger_stmt(&mut conn, LEDGER_ID, time("2018-01-31 23:59:59"), StmtStatus::CLOSED, "Test User")
+        .unwrap();
+
+    post_backdated(&mut conn, &service, "pst_002", "2018-01-10 10:00:00", &[(CASH, 20, 0), (REVENUE, 0, 20)])
+        .expect("Backdated posting is recorded");
+
+    // Both statements of the account are closed again, after the existing ones.
+    let stmts = closed_stmts(&mut conn, CASH);
+    let seq_nbrs: Vec<(&str, i32)> = stmts
+        .iter()
+        .map(|stmt| (if stmt.pst_time == time("2018-01-15 23:59:59") { "mid" } else { "end" }, stmt.stmt_seq_nbr))
+        .collect();
+    assert_eq!(seq_nbrs, [("mid", 0), ("mid", 2), ("end", 1), ("end", 3)]);
+    assert_eq!(stmts[1].total_debit, Decimal::new(120, 0));
+    assert_eq!(stmts[3].total_debit, Decimal::new(120, 0));
+    // The backdated posting is traced by the first statement closed again.
+    let traces = posting_trace_repository::find_by_tgt_pst_id(&mut conn, stmts[1].posting_id.as_deref().unwrap()).unwrap();
+    let mut sources: Vec<&str> = traces.iter().map(|trace| trace.src_pst_id.as_str()).collect();
+    sources.sort();
+    assert_eq!(sources, ["pst_001", "pst_002"]);
+    let report = stmt_service.read_stmt(&mut conn, CASH, time("2018-02-28 23:59:59")).unwrap();
+    assert_eq!(report.base_stmt_id.as_deref(), Some(stmts[3].id.as_str()));
+    assert_eq!(report.balance, Decimal::new(120, 0));
+
+    // The revenue account had no closed statement, the ledger statement is recorded again.
+    assert_eq!(closed_stmts(&mut conn, REVENUE).len(), 0);
+    let ledger_stmts = LedgerStmtRepository::find_by_ledger_and_stmt_status_and_pst_time_gte(
+        &mut conn,
+        LEDGER_ID,
+        StmtStatus::CLOSED,
+        ledger_stmt.pst_time,
+    )
+    .unwrap();
+    assert_eq!(ledger_stmts.len(), 2);
+    assert_eq!(ledger_stmts[1].stmt_seq_nbr, ledger_stmt.stmt_seq_nbr + 1);
+    assert_eq!(JournalVerifier::new().verify_ledger(&mut conn, LEDGER_ID).unwrap(), None);
+}
+
+#[test]
+#[serial]
+fn test_ledger_closing_is_final_when_reclosing() {
+    let mut conn = establish_connection();
+    seed_database(&mut conn, "tests/fixtures/posting_service_dataset.sql");
+    let _guard = TestDatabaseGuard::new();
+    let service = reclosing_service();
+    post(&mut conn, "pst_001", LEDGER_ID, "2018-01-01 10:00:00", &[(CASH, 100, 0), (REVENUE, 0, 100)]);
+    LedgerStmtService::with_services(AccountStmtService::with_posting_service(service.clone()), service.clone())
+        .close_ledger(&mut conn, LEDGER_ID, time("2018-01-31 23:59:59"), EQUITY, "Test User")
+        .expect("Failed to close ledger");
+
+    let result = post_backdated(&mut conn, &service, "pst_002", "2018-01-10 10:00:00", &[(CASH, 20, 0), (REVENUE, 0, 20)]);
+    assert!(matches!(result, Err(ServiceError::LedgerClosed { .. })));
+}
+
+#[test]
+#[serial]
+fn test_ledger_closing_without_transfer_is_final_when_reclosing() {
+    let mut conn = establish_connection();
+    seed_database(&mut conn, "tests/fixtures/posting_service_dataset.sql");
+    let _guard = TestDatabaseGuard::new();
+    let service = reclosing_service();
+    // Only balance sheet accounts are booked, there is no result to transfer.
+    post(&mut conn, "pst_001", LEDGER_ID, "2018-01-01 10:00:00", &[(CASH, 100, 0), (EQUITY, 0, 100)]);
+    let (closing, _) =
+        LedgerStmtService::with_services(AccountStmtService::with_posting_service(service.clone()), service.clone())
+            .close_ledger(&mut conn, LEDGER_ID, time("2018-01-31 23:59:59"), EQUITY, "Test User")
+            .expect("Failed to close ledger");
+    assert!(closing.posting_id.is_some());
+
+    let result = post_backdated(&mut conn, &service, "pst_002", "2018-01-10 10:00:00", &[(CASH, 20, 0), (EQUITY, 0, 20)]);
+    assert!(matches!(result, Err(ServiceError::LedgerClosed { .. })), "{:?}", result);
+    let ledger_stmts =
+        LedgerStmtRepository::find_by_ledger_and_stmt_status_and_pst_time_gte(&mut conn, LEDGER_ID, StmtStatus::CLOSED, closing.pst_time)
+            .unwrap();
+    assert_eq!(ledger_stmts.len(), 1);
+}
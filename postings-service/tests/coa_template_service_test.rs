@@ -0,0 +1,202 @@
+// tests/coa_template_service_test.rs
+//
+// Copyright (c) 2018-2024 adorsys GmbH and Co. KG
+// All rights are reserved.
+
+mod common;
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use common::{establish_connection, time, TestDatabaseGuard};
+use postings_repository::models::enums::{AccountCategory, BalanceSide};
+use postings_repository::repository::{
+    ChartOfAccountRepository, InMemoryRepositories, LedgerAccountRepository, LedgerRepository,
+};
+use postings_service::{
+    AccountChange, AccountTemplate, BuiltinTemplate, CoaTemplate, CoaTemplateService, FixedClock, ServiceError,
+};
+use serial_test::serial;
+
+fn account(number: &str, parent: Option<&str>) -> AccountTemplate {
+    AccountTemplate {
+        number: number.to_string(),
+        name: format!("Account {}", number),
+        category: AccountCategory::AS,
+        balance_side: None,
+        parent: parent.map(str::to_string),
+    }
+}
+
+fn template(accounts: Vec<AccountTemplate>) -> CoaTemplate {
+    CoaTemplate {
+        name: "Sample".to_string(),
+        short_desc: None,
+        long_desc: None,
+        accounts,
+    }
+}
+
+#[test]
+fn test_builtin_templates_are_valid() {
+    for builtin in BuiltinTemplate::ALL {
+        let template = builtin.template();
+        let ordered = template.ordered_accounts().expect("Invalid built-in template");
+        assert_eq!(ordered.len(), template.accounts.len(), "{:?}", builtin);
+    }
+}
+
+#[test]
+fn test_template_json_round_trip() {
+    let json = r#"{
+        "name": "IFRS",
+        "accounts": [
+            { "number": "1.0.0", "name": "Assets", "category": "AS" },
+            { "number": "1.4.9", "name": "Accumulated depreciation", "category": "AS", "balance_side": "Cr", "parent": "1.0.0" }
+        ]
+    }"#;
+    let template = CoaTemplate::from_json(json).expect("Failed to read template");
+    assert_eq!(template.accounts[0].balance_side(), BalanceSide::Dr);
+    assert_eq!(template.accounts[1].balance_side(), BalanceSide::Cr);
+    assert_eq!(CoaTemplate::from_json(&template.to_json()).unwrap(), template);
+
+    let error = CoaTemplate::from_json(r#"{ "name": "IFRS" }"#).unwrap_err();
+    assert!(matches!(error, ServiceError::InvalidTemplate(_)));
+}
+
+#[test]
+fn test_parents_are_ordered_first() {
+    let template = template(vec![account("1.1", Some("1")), account("1.1.1", Some("1.1")), account("1", None)]);
+    let numbers: Vec<&str> =
+        template.ordered_accounts().unwrap().iter().map(|account| account.number.as_str()).collect();
+    assert_eq!(numbers, vec!["1", "1.1", "1.1.1"]);
+}
+
+#[test]
+fn test_invalid_templates_are_rejected() {
+    let invalid = [
+        template(vec![account("1", None), account("1", None)]),
+        template(vec![account("1.1", Some("1"))]),
+        template(vec![account("1", Some("2")), account("2", Some("1"))]),
+        template(vec![account("1", Some("1"))]),
+    ];
+    for template in invalid {
+        let error = template.ordered_accounts().unwrap_err();
+        assert!(matches!(error, ServiceError::InvalidTemplate(_)), "{:?}", error);
+    }
+}
+
+#[test]
+fn test_import_in_memory() {
+    let mut store = InMemoryRepositories::new();
+    let created = time("2018-01-01 10:00:00");
+    let service = CoaTemplateService::new().with_clock(Arc::new(FixedClock::new(created)));
+    let template = BuiltinTemplate::Syscohada.template();
+
+    let imported = service.import(&mut store, &template, "Grand livre", "Francis").expect("Failed to import");
+    assert_eq!(imported.coa.name, "SYSCOHADA");
+    assert_eq!(imported.coa.created, created);
+    assert_eq!(imported.ledger.created, created);
+    assert!(imported.accounts.iter().all(|account| account.created == created));
+    assert_eq!(imported.ledger.coa_id, imported.coa.id);
+    assert_eq!(imported.accounts.len(), template.accounts.len());
+
+    let accounts = LedgerAccountRepository::find_by_ledger(&mut store, &imported.ledger.id).unwrap();
+    let by_name: HashMap<&str, _> = accounts.iter().map(|account| (account.name.as_str(), account)).collect();
+    let clients = by_name["411"];
+    assert_eq!(clients.short_desc.as_deref(), Some("Clients"));
+    assert_eq!(clients.balance_side, BalanceSide::Dr);
+    assert_eq!(clients.parent_id.as_deref(), Some(by_name["41"].id.as_str()));
+    assert!(accounts.iter().all(|account| account.coa_id == imported.coa.id));
+
+    // A second import of the same chart fails on its name and leaves nothing behind.
+    let error = service.import(&mut store, &template, "Second ledger", "Francis").unwrap_err();
+    assert!(matches!(error, ServiceError::Repository(_)));
+    assert_eq!(ChartOfAccountRepository::find_all(&mut store).unwrap().len(), 1);
+    assert!(LedgerRepository::find_optional_by_name(&mut store, "Second ledger").unwrap().is_none());
+}
+
+#[test]
+fn test_template_diff() {
+    let mut to_template = template(vec![account("1", None), account("1.2", Some("1")), account("2", None)]);
+    to_template.accounts[0].balance_side = Some(BalanceSide::Cr);
+    to_template.accounts[2].category = AccountCategory::LI;
+    let from_template = template(vec![account("1", None), account("1.1", Some("1")), account("2", None)]);
+
+    let diff = from_template.diff(&to_template);
+    assert_eq!(diff.added.iter().map(|account| account.number.as_str()).collect::<Vec<_>>(), vec!["1.2"]);
+    assert_eq!(diff.removed.iter().map(|account| account.number.as_str()).collect::<Vec<_>>(), vec!["1.1"]);
+    assert_eq!(
+        diff.recategorized,
+        vec![AccountChange { number: "2".to_string(), from: AccountCategory::AS, to: AccountCategory::LI }]
+    );
+    // The balance side of "2" follows its category and changes with it.
+    let changed: Vec<&str> = diff.balance_side_changed.iter().map(|change| change.number.as_str()).collect();
+    assert_eq!(changed, vec!["1", "2"]);
+    assert!(from_template.diff(&from_template).is_empty());
+}
+
+#[test]
+fn test_export_and_diff_ledgers_in_memory() {
+    let mut store = InMemoryRepositories::new();
+    let service = CoaTemplateService::new();
+    let template = BuiltinTemplate::IfrsSample.template();
+    let first = service.import(&mut store, &template, "Entity A", "Francis").unwrap();
+
+    let exported = service.export(&mut store, &first.coa.id).unwrap();
+    assert!(template.diff(&exported).is_empty());
+    let mut expected = template.accounts.clone();
+    let mut actual = exported.accounts.clone();
+    expected.sort_by(|a, b| a.number.cmp(&b.number));
+    actual.sort_by(|a, b| a.number.cmp(&b.number));
+    assert_eq!(actual, expected);
+
+    let mut changed = template.clone();
+    changed.name = "IFRS (entity B)".to_string();
+    changed.accounts.retain(|account| account.number != "1.4.9");
+    let second = service.import(&mut store, &changed, "Entity B", "Francis").unwrap();
+
+    let diff = service.diff_ledgers(&mut store, &first.ledger.id, &second.ledger.id).unwrap();
+    assert_eq!(diff.removed.iter().map(|account| account.number.as_str()).collect::<Vec<_>>(), vec!["1.4.9"]);
+    assert!(diff.added.is_empty() && diff.recategorized.is_empty() && diff.balance_side_changed.is_empty());
+    assert_eq!(service.diff_charts(&mut store, &second.coa.id, &first.coa.id).unwrap().added.len(), 1);
+
+    let error = service.export(&mut store, "missing").unwrap_err();
+    assert!(matches!(error, ServiceError::CoaNotFound(_)), "{:?}", error);
+    let error = service.diff_ledgers(&mut store, &first.ledger.id, "missing").unwrap_err();
+    assert!(matches!(error, ServiceError::LedgerNotFound(_)), "{:?}", error);
+}
+
+#[test]
+#[serial]
+fn test_import_builtin_templates() {
+    let mut conn = establish_connection();
+    let _guard = TestDatabaseGuard::new();
+    let service = CoaTemplateService::new();
+
+    for (builtin, ledger_name) in [(BuiltinTemplate::Skr03, "Hauptbuch"), (BuiltinTemplate::IfrsSample, "GL")] {
+        let template = builtin.template();
+        let imported = service.import(&mut conn, &template, ledger_name, "Francis").expect("Failed to import");
+        let accounts = LedgerAccountRepository::find_by_ledger(&mut conn, &imported.ledger.id).unwrap();
+        assert_eq!(accounts.len(), template.accounts.len());
+    }
+    let ifrs = LedgerRepository::find_optional_by_name(&mut conn, "GL").unwrap().unwrap();
+    let cash = LedgerAccountRepository::find_optional_by_ledger_and_name(&mut conn, &ifrs.id, "1.1.0").unwrap().unwrap();
+    let ancestors = LedgerAccountRepository::find_ancestors(&mut conn, &cash.id).unwrap();
+    assert_eq!(ancestors.iter().map(|account| account.name.as_str()).collect::<Vec<_>>(), vec!["1.0.0"]);
+}
+
+#[test]
+#[serial]
+fn test_export_builtin_template() {
+    let mut conn = establish_connection();
+    let _guard = TestDatabaseGuard::new();
+    let service = CoaTemplateService::new();
+
+    let template = BuiltinTemplate::Skr03.template();
+    let imported = service.import(&mut conn, &template, "Hauptbuch", "Francis").expect("Failed to import");
+    let exported = service.export_ledger(&mut conn, &imported.ledger.id).expect("Failed to export");
+    assert_eq!(exported.name, template.name);
+    assert_eq!(exported.accounts.len(), template.accounts.len());
+    assert!(template.diff(&exported).is_empty());
+}
@@ -0,0 +1,213 @@
+// tests/common.rs
+// Not every test binary uses every helper.
+#![allow(dead_code)]
+
+use diesel::prelude::*;
+use diesel::pg::PgConnection;
+use std::env;
+use dotenv::dotenv;
+use diesel_migrations::{embed_migrations, EmbeddedMigrations, MigrationHarness};
+use std::fs;
+use std::path::Path;
+use diesel::connection::SimpleConnection;
+
+pub const MIGRATIONS: EmbeddedMigrations = embed_migrations!("../postings-repository/migrations");
+
+/// Establish a database connection and run pending migrations.
+/// (Assuming you already have this function for seeding.)
+pub fn establish_connection() -> PgConnection {
+    use std::env;
+    use dotenv::dotenv;
+    ensure_docker_database_running();
+    dotenv().ok();
+    let database_url = env::var("DATABASE_URL")
+        .expect("DATABASE_URL must be set in .env for tests");
+    let mut conn = PgConnection::establish(&database_url)
+        .unwrap_or_else(|_| panic!("Error connecting to {}", database_url));
+    // Optionally run migrations here
+    conn.run_pending_migrations(MIGRATIONS)
+    .expect("Failed to run migrations");    
+    conn
+}
+
+/// Seed the database with fixture data from the specified SQL file.
+///
+/// # Arguments
+///
+/// * `conn` - A mutable reference to a PostgreSQL connection.
+/// * `fixture_file` - The path to the SQL file to execute.
+pub fn seed_database(conn: &mut PgConnection, fixture_file: &str) {
+    let fixture_path = Path::new(fixture_file);
+    let sql = fs::read_to_string(fixture_path)
+        .unwrap_or_else(|_| panic!("Failed to read fixture file: {}", fixture_file));
+    conn.batch_execute(&sql)
+        .expect("Failed to seed the database");
+}
+
+/// Clean up the database by executing the cleanup SQL file.
+pub fn cleanup_database(conn: &mut PgConnection) {
+    let cleanup_path = Path::new("tests/fixtures/cleanup.sql");
+    let sql = fs::read_to_string(cleanup_path)
+        .expect("Failed to read cleanup file");
+    conn.batch_execute(&sql)
+        .expect("Failed to clean up the database");
+}
+
+pub struct TestDatabaseGuard;
+
+impl TestDatabaseGuard {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl Default for TestDatabaseGuard {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for TestDatabaseGuard {
+    fn drop(&mut self) {
+        // Open a new connection for cleanup.
+        let mut conn = establish_connection();
+        cleanup_database(&mut conn);
+    }
+}
+
+use std::process::Command;
+use diesel::result::ConnectionError;
+
+/// Try to establish a connection; if it fails, attempt to start the Docker container.
+pub fn ensure_docker_database_running() {
+    dotenv().ok();
+    let database_url = env::var("DATABASE_URL")
+        .expect("DATABASE_URL must be set in .env for tests");
+
+    // Try to connect once
+    match PgConnection::establish(&database_url) {
+        Ok(_) => {
+            // Connection successful, nothing to do.
+        },
+        Err(ConnectionError::BadConnection(_)) => {
+            // Could not connect. Attempt to start Docker Compose.
+            println!("Database not reachable; attempting to start Docker Compose...");
+            let output = Command::new("docker")
+                .args(["compose", "-f", "../postings-repository/compose-postgres.yml", "up", "-d"])
+                .output()
+                .expect("Failed to execute docker compose command");
+
+            if !output.status.success() {
+                panic!(
+                    "Docker compose failed to start the database: {}",
+                    String::from_utf8_lossy(&output.stderr)
+                );
+            }
+
+            // Optionally, wait a few seconds here for the DB to be ready.
+            std::thread::sleep(std::time::Duration::from_secs(5));
+        },
+        Err(err) => {
+            panic!("Unexpected error connecting to database: {:?}", err);
+        }
+    }
+}
+
+use chrono::NaiveDateTime;
+use postings_repository::models::enums::{PostingStatus, PostingType};
+use postings_repository::models::{NewPosting, NewPostingLine, Posting};
+use postings_repository::repository::Repositories;
+use postings_service::PostingService;
+use rust_decimal::Decimal;
+
+/// Parses a `%Y-%m-%d %H:%M:%S` time.
+pub fn time(value: &str) -> NaiveDateTime {
+    NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S").expect("Failed to parse time")
+}
+
+/// The user recording the postings of `business_posting`.
+pub const TEST_USER: &str = "Test User";
+
+/// Builds a POSTED business transaction recorded by `TEST_USER` and recorded
+/// and posted at the given time.
+pub fn business_posting(id: &str, ledger_id: &str, at: &str) -> NewPosting {
+    new_posting(id, ledger_id, at, PostingType::BusiTx, PostingStatus::POSTED, TEST_USER)
+}
+
+/// Builds a posting of the given type and status, recorded by `record_user`
+/// and recorded and posted at the given time.
+pub fn new_posting(
+    id: &str,
+    ledger_id: &str,
+    at: &str,
+    pst_type: PostingType,
+    pst_status: PostingStatus,
+    record_user: &str,
+) -> NewPosting {
+    NewPosting {
+        id: id.to_string(),
+        antecedent_id: None,
+        antecedent_hash: None,
+        hash: None,
+        hash_alg: None,
+        record_user: record_user.to_string(),
+        record_time: time(at),
+        opr_id: format!("opr_of_{}", id),
+        opr_time: None,
+        opr_type: None,
+        opr_src: None,
+        pst_time: time(at),
+        pst_type,
+        pst_status,
+        ledger_id: ledger_id.to_string(),
+        val_time: None,
+        discarded_id: None,
+        discarded_time: None,
+        discarding_id: None,
+        opr_details_id: None,
+    }
+}
+
+/// Builds the lines of a posting from `(account_id, debit, credit)` triples.
+/// The posting fields are denormalized by the posting service.
+pub fn posting_lines(pst_id: &str, amounts: &[(&str, i64, i64)]) -> Vec<NewPostingLine> {
+    amounts
+        .iter()
+        .enumerate()
+        .map(|(idx, (account_id, debit, credit))| NewPostingLine {
+            id: format!("{}_line_{}", pst_id, idx),
+            account_id: account_id.to_string(),
+            debit_amount: Decimal::new(*debit, 0),
+            credit_amount: Decimal::new(*credit, 0),
+            details_id: None,
+            src_account: None,
+            base_line: None,
+            sub_opr_src_id: None,
+            record_time: NaiveDateTime::default(),
+            opr_id: String::new(),
+            opr_src: None,
+            pst_time: NaiveDateTime::default(),
+            pst_type: PostingType::BusiTx,
+            pst_status: PostingStatus::POSTED,
+            hash: String::new(),
+            discarded_time: None,
+        })
+        .collect()
+}
+
+/// Records a POSTED business transaction with the posting service.
+pub fn post<C: Repositories>(
+    conn: &mut C,
+    id: &str,
+    ledger_id: &str,
+    at: &str,
+    amounts: &[(&str, i64, i64)],
+) -> Posting {
+    record(conn, business_posting(id, ledger_id, at), amounts)
+}
+
+/// Records a posting with the lines of `posting_lines` with the posting service.
+pub fn record<C: Repositories>(conn: &mut C, posting: NewPosting, amounts: &[(&str, i64, i64)]) -> Posting {
+    let lines = posting_lines(&posting.id, amounts);
+    PostingService::new().new_posting(conn, posting, lines).expect("Failed to create posting").0
+}
@@ -0,0 +1,140 @@
+// tests/deferred_posting_scheduler_test.rs
+//
+// Copyright (c) 2018-2024 adorsys GmbH and Co. KG
+// All rights are reserved.
+
+mod common;
+
+use std::sync::Arc;
+
+use common::{
+    establish_connection, new_posting, post, posting_lines, seed_database, time, TestDatabaseGuard, TEST_USER,
+};
+use postings_repository::models::enums::{PostingStatus, PostingType, StmtStatus};
+use postings_repository::repository::{
+    posting_repository, posting_trace_repository, AccountStmtRepository, OpNoteRepository,
+};
+use postings_service::deferred_posting_scheduler::SCHEDULER_USER;
+use postings_service::posting_service::{POSTING_PROMOTED_NOTE, POSTING_PROMOTION_FAILED_NOTE};
+use postings_service::{
+    AccountStmtService, BackdatedPostingPolicy, DeferredPostingScheduler, FixedClock, JournalVerifier, PostingService,
+    ServiceError,
+};
+use rust_decimal::Decimal;
+use serial_test::serial;
+
+const LEDGER_ID: &str = "Zd0ND5YwSzGwIfZilhumPg";
+const CASH_ACCOUNT: &str = "xVgaTPMcRty9ik3BTQDh1Q_BS_1_0_0";
+const EQUITY_ACCOUNT: &str = "xVgaTPMcRty9ik3BTQDh1Q_BS_2_0_0";
+
+#[test]
+#[serial]
+fn test_due_deferred_postings_are_promoted_once() {
+    let mut conn = establish_connection();
+    seed_database(&mut conn, "tests/fixtures/posting_service_dataset.sql");
+    let _guard = TestDatabaseGuard::new();
+    let stmt_service = AccountStmtService::new();
+    let clock = Arc::new(FixedClock::new(time("2018-01-09 00:00:00")));
+    let posting_service = PostingService::new().with_backdated_posting_policy(BackdatedPostingPolicy::Reclose);
+    let scheduler = DeferredPostingScheduler::with_services(posting_service, clock.clone());
+
+    post(&mut conn, "pst_001", LEDGER_ID, "2018-01-01 10:00:00", &[(CASH_ACCOUNT, 100, 0), (EQUITY_ACCOUNT, 0, 100)]);
+    let mut deferred =
+        new_posting("def_001", LEDGER_ID, "2018-01-02 10:00:00", PostingType::BusiTx, PostingStatus::DEFERRED, TEST_USER);
+    deferred.pst_time = time("2018-01-10 10:00:00");
+    let (deferred, _) = PostingService::new()
+        .new_posting(&mut conn, deferred, posting_lines("def_001", &[(CASH_ACCOUNT, 20, 0), (EQUITY_ACCOUNT, 0, 20)]))
+        .unwrap();
+    assert!(deferred.hash.is_none() && deferred.antecedent_id.is_none());
+
+    let closed = stmt_service.close_account_stmt(&mut conn, CASH_ACCOUNT, time("2018-01-15 23:59:59"), "Test User").unwrap();
+    assert_eq!(closed.total_debit, Decimal::new(100, 0));
+
+    // Not due yet.
+    let run = scheduler.run_due(&mut conn).unwrap();
+    assert!(run.promoted.is_empty() && run.failed.is_empty());
+
+    clock.set(time("2018-01-20 00:00:00"));
+    let run = scheduler.run_due(&mut conn).unwrap();
+    assert!(run.failed.is_empty(), "{:?}", run.failed);
+    assert_eq!(run.promoted.len(), 1);
+    let promoted = &run.promoted[0];
+    assert_eq!(promoted.id, "def_001");
+    assert_eq!(promoted.pst_status, PostingStatus::POSTED);
+    assert!(promoted.hash.is_some());
+
+    // The statement closed after the posting time is closed again.
+    let stmts = AccountStmtRepository::find_by_account_and_stmt_status_and_pst_time_gte(
+        &mut conn,
+        CASH_ACCOUNT,
+        StmtStatus::CLOSED,
+        closed.pst_time,
+    )
+    .unwrap();
+    assert_eq!(stmts.len(), 2);
+    let reclosed = &stmts[1];
+    assert_eq!(reclosed.user_details.as_deref(), Some(SCHEDULER_USER));
+    assert_eq!(reclosed.pst_time, closed.pst_time);
+    assert_eq!(reclosed.stmt_seq_nbr, closed.stmt_seq_nbr + 1);
+    assert_eq!(reclosed.total_debit, Decimal::new(120, 0));
+    let traces = posting_trace_repository::find_by_tgt_pst_id(&mut conn, reclosed.posting_id.as_deref().unwrap()).unwrap();
+    let mut sources: Vec<&str> = traces.iter().map(|trace| trace.src_pst_id.as_str()).collect();
+    sources.sort();
+    assert_eq!(sources, ["def_001", "pst_001"]);
+
+    let notes = OpNoteRepository::find_by_rec_id(&mut conn, "def_001").unwrap();
+    assert_eq!(notes.len(), 1);
+    assert_eq!(notes[0].note_type.as_deref(), Some(POSTING_PROMOTED_NOTE));
+    assert_eq!(notes[0].content.as_deref(), Some(SCHEDULER_USER));
+
+    let report = stmt_service.read_stmt(&mut conn, CASH_ACCOUNT, time("2018-01-31 23:59:59")).unwrap();
+    assert_eq!(report.base_stmt_id.as_deref(), Some(reclosed.id.as_str()));
+    assert_eq!(report.balance, Decimal::new(120, 0));
+
+    // A second run, say after a restart, finds nothing to promote.
+    let run = scheduler.run_due(&mut conn).unwrap();
+    assert!(run.promoted.is_empty() && run.failed.is_empty());
+    let posting = posting_repository::find_by_id(&mut conn, "def_001").unwrap().unwrap();
+    assert_eq!(posting.record_time, promoted.record_time);
+    assert_eq!(JournalVerifier::new().verify_ledger(&mut conn, LEDGER_ID).unwrap(), None);
+}
+
+#[test]
+#[serial]
+fn test_due_posting_before_closed_stmt_is_cancelled_by_default() {
+    let mut conn = establish_connection();
+    seed_database(&mut conn, "tests/fixtures/posting_service_dataset.sql");
+    let _guard = TestDatabaseGuard::new();
+    let clock = Arc::new(FixedClock::new(time("2018-01-09 00:00:00")));
+    let scheduler = DeferredPostingScheduler::with_clock(clock.clone());
+
+    post(&mut conn, "pst_001", LEDGER_ID, "2018-01-01 10:00:00", &[(CASH_ACCOUNT, 100, 0), (EQUITY_ACCOUNT, 0, 100)]);
+    let mut deferred =
+        new_posting("def_001", LEDGER_ID, "2018-01-02 10:00:00", PostingType::BusiTx, PostingStatus::DEFERRED, TEST_USER);
+    deferred.pst_time = time("2018-01-10 10:00:00");
+    PostingService::new()
+        .new_posting(&mut conn, deferred, posting_lines("def_001", &[(CASH_ACCOUNT, 20, 0), (EQUITY_ACCOUNT, 0, 20)]))
+        .unwrap();
+    AccountStmtService::new().close_account_stmt(&mut conn, CASH_ACCOUNT, time("2018-01-15 23:59:59"), "Test User").unwrap();
+
+    // The promotion would fail on every run, so the posting is cancelled.
+    clock.set(time("2018-01-20 00:00:00"));
+    let run = scheduler.run_due(&mut conn).unwrap();
+    assert!(run.promoted.is_empty());
+    assert!(matches!(run.failed.as_slice(), [(id, ServiceError::AccountStmtClosed { .. })] if id == "def_001"));
+    let posting = posting_repository::find_by_id(&mut conn, "def_001").unwrap().unwrap();
+    assert_eq!(posting.pst_status, PostingStatus::CANCELLED);
+    assert!(posting.hash.is_none());
+    let notes = OpNoteRepository::find_by_rec_id(&mut conn, "def_001").unwrap();
+    assert_eq!(notes.len(), 1);
+    assert_eq!(notes[0].note_type.as_deref(), Some(POSTING_PROMOTION_FAILED_NOTE));
+    assert!(notes[0].content.as_deref().unwrap().contains(CASH_ACCOUNT));
+    assert_eq!(notes[0].rec_time, time("2018-01-20 00:00:00"));
+
+    // It is not tried again and does not count in the balance.
+    let run = scheduler.run_due(&mut conn).unwrap();
+    assert!(run.promoted.is_empty() && run.failed.is_empty());
+    let report = AccountStmtService::new().read_stmt(&mut conn, CASH_ACCOUNT, time("2018-01-31 23:59:59")).unwrap();
+    assert_eq!(report.balance, Decimal::new(100, 0));
+    assert_eq!(JournalVerifier::new().verify_ledger(&mut conn, LEDGER_ID).unwrap(), None);
+}
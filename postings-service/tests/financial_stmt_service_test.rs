@@ -0,0 +1,155 @@
+// tests/financial_stmt_service_test.rs
+//
+// Copyright (c) 2018-2024 adorsys GmbH and Co. KG
+// All rights are reserved.
+
+mod common;
+
+use common::{establish_connection, post, seed_database, time, TestDatabaseGuard};
+use diesel::prelude::*;
+use postings_repository::models::enums::{AccountCategory, PostingType};
+use postings_repository::repository::posting_line_repository;
+use postings_service::{AccountStmtService, FinancialStmtService, ServiceError};
+use rust_decimal::Decimal;
+use serial_test::serial;
+
+const LEDGER_ID: &str = "Zd0ND5YwSzGwIfZilhumPg";
+const ASSETS: &str = "xVgaTPMcRty9ik3BTQDh1Q_BS_1_0_0";
+const CASH: &str = "xVgaTPMcRty9ik3BTQDh1Q_BS_1_1_0";
+const BANK: &str = "xVgaTPMcRty9ik3BTQDh1Q_BS_1_2_0";
+const EQUITY: &str = "xVgaTPMcRty9ik3BTQDh1Q_BS_2_0_0";
+const LIABILITY: &str = "xVgaTPMcRty9ik3BTQDh1Q_BS_3_0_0";
+const SALES: &str = "xVgaTPMcRty9ik3BTQDh1Q_PL_4_1_0";
+const EXPENSE: &str = "xVgaTPMcRty9ik3BTQDh1Q_PL_5_0_0";
+const NON_OPERATING_REVENUE: &str = "xVgaTPMcRty9ik3BTQDh1Q_PL_6_0_0";
+const NON_OPERATING_EXPENSE: &str = "xVgaTPMcRty9ik3BTQDh1Q_PL_7_0_0";
+
+fn record_january(conn: &mut PgConnection) {
+    seed_database(conn, "tests/fixtures/financial_stmt_service_dataset.sql");
+    post(conn, "pst_001", LEDGER_ID, "2018-01-01 10:00:00", &[(CASH, 1000, 0), (EQUITY, 0, 1000)]);
+    post(conn, "pst_002", LEDGER_ID, "2018-01-02 10:00:00", &[(BANK, 500, 0), (LIABILITY, 0, 500)]);
+    post(conn, "pst_003", LEDGER_ID, "2018-01-03 10:00:00", &[(CASH, 300, 0), (SALES, 0, 300)]);
+    post(conn, "pst_004", LEDGER_ID, "2018-01-04 10:00:00", &[(EXPENSE, 120, 0), (BANK, 0, 120)]);
+    post(conn, "pst_005", LEDGER_ID, "2018-01-05 10:00:00", &[(BANK, 40, 0), (NON_OPERATING_REVENUE, 0, 40)]);
+    post(conn, "pst_006", LEDGER_ID, "2018-01-06 10:00:00", &[(NON_OPERATING_EXPENSE, 10, 0), (CASH, 0, 10)]);
+}
+
+#[test]
+#[serial]
+fn test_profit_and_loss() {
+    let mut conn = establish_connection();
+    let _guard = TestDatabaseGuard::new();
+    record_january(&mut conn);
+
+    let report = FinancialStmtService::new()
+        .profit_and_loss(&mut conn, LEDGER_ID, time("2018-01-31 23:59:59"))
+        .expect("Failed to build profit and loss report");
+
+    assert_eq!(report.operating_result, Decimal::new(180, 0));
+    assert_eq!(report.non_operating_result, Decimal::new(30, 0));
+    assert_eq!(report.net_result, Decimal::new(210, 0));
+
+    // The sales account is presented under its parent revenue account.
+    let revenue = report.sections.iter().find(|section| section.category == AccountCategory::RE).unwrap();
+    assert_eq!(revenue.total, Decimal::new(300, 0));
+    assert_eq!(revenue.accounts.len(), 1);
+    assert_eq!(revenue.accounts[0].balance, Decimal::new(300, 0));
+    assert_eq!(revenue.accounts[0].children[0].account_id, SALES);
+}
+
+#[test]
+#[serial]
+fn test_balance_sheet() {
+    let mut conn = establish_connection();
+    let _guard = TestDatabaseGuard::new();
+    record_january(&mut conn);
+
+    let report = FinancialStmtService::new()
+        .balance_sheet(&mut conn, LEDGER_ID, time("2018-01-31 23:59:59"))
+        .expect("Failed to build balance sheet");
+
+    assert_eq!(report.total_assets, Decimal::new(1710, 0));
+    assert_eq!(report.total_liabilities, Decimal::new(500, 0));
+    assert_eq!(report.total_equity, Decimal::new(1000, 0));
+    assert_eq!(report.net_result, Decimal::new(210, 0));
+    assert!(report.is_balanced());
+
+    let assets = report.sections.iter().find(|section| section.category == AccountCategory::AS).unwrap();
+    assert_eq!(assets.accounts.len(), 1);
+    assert_eq!(assets.accounts[0].account_id, ASSETS);
+    assert_eq!(assets.accounts[0].balance, Decimal::new(1710, 0));
+    assert_eq!(assets.accounts[0].children.len(), 2);
+}
+
+#[test]
+#[serial]
+fn test_record_balance_sheet() {
+    let mut conn = establish_connection();
+    let _guard = TestDatabaseGuard::new();
+    record_january(&mut conn);
+    let ref_time = time("2018-01-31 23:59:59");
+
+    let (posting, _) = FinancialStmtService::new()
+        .record_balance_sheet(&mut conn, LEDGER_ID, ref_time, "Test User")
+        .expect("Failed to record balance sheet");
+    let posting = posting.expect("Balance sheet posting is recorded");
+    assert_eq!(posting.pst_type, PostingType::BsStmt);
+    assert_eq!(posting.pst_time, ref_time);
+
+    // One pair of lines per booked balance sheet account.
+    let lines = posting_line_repository::find_by_opr_id_and_record_time(&mut conn, &posting.opr_id, posting.record_time)
+        .unwrap();
+    assert_eq!(lines.len(), 8);
+
+    // The snapshot does not change the balances.
+    let cash = AccountStmtService::new().read_stmt(&mut conn, CASH, ref_time).unwrap();
+    assert_eq!(cash.balance, Decimal::new(1290, 0));
+}
+
+#[test]
+#[serial]
+fn test_record_profit_and_loss() {
+    let mut conn = establish_connection();
+    let _guard = TestDatabaseGuard::new();
+    record_january(&mut conn);
+
+    let (posting, report) = FinancialStmtService::new()
+        .record_profit_and_loss(&mut conn, LEDGER_ID, time("2018-01-31 23:59:59"), "Test User")
+        .expect("Failed to record profit and loss report");
+    let posting = posting.expect("Profit and loss posting is recorded");
+    assert_eq!(posting.pst_type, PostingType::PnLStmt);
+    assert_eq!(posting.opr_type.as_deref(), Some("PnL_STMT"));
+    assert_eq!(report.net_result, Decimal::new(210, 0));
+}
+
+#[test]
+#[serial]
+fn test_record_reports_of_unbooked_ledger() {
+    let mut conn = establish_connection();
+    let _guard = TestDatabaseGuard::new();
+    seed_database(&mut conn, "tests/fixtures/financial_stmt_service_dataset.sql");
+    let ref_time = time("2018-01-31 23:59:59");
+    let service = FinancialStmtService::new();
+
+    let (posting, report) = service.record_balance_sheet(&mut conn, LEDGER_ID, ref_time, "Test User").unwrap();
+    assert!(posting.is_none());
+    assert_eq!(report.total_assets, Decimal::ZERO);
+    assert!(report.is_balanced());
+    let (posting, report) = service.record_profit_and_loss(&mut conn, LEDGER_ID, ref_time, "Test User").unwrap();
+    assert!(posting.is_none());
+    assert_eq!(report.net_result, Decimal::ZERO);
+}
+
+#[test]
+#[serial]
+fn test_report_of_cyclic_account_hierarchy() {
+    let mut conn = establish_connection();
+    let _guard = TestDatabaseGuard::new();
+    record_january(&mut conn);
+    diesel::sql_query(format!("UPDATE ledger_account SET parent_id = '{}' WHERE id = '{}'", CASH, ASSETS))
+        .execute(&mut conn)
+        .unwrap();
+
+    let result = FinancialStmtService::new().balance_sheet(&mut conn, LEDGER_ID, time("2018-01-31 23:59:59"));
+    assert!(matches!(result, Err(ServiceError::CyclicAccountHierarchy { .. })), "{:?}", result);
+}
@@ -0,0 +1,45 @@
+-- tests/fixtures/account_stmt_service_dataset.sql
+-- 
+-- Copyright (c) 2018-2024 adorsys GmbH and Co. KG
+-- All rights are reserved.
+--
+
+INSERT INTO chart_of_account (id, created, user_details, name, short_desc)
+VALUES 
+  ('ci8k8PDcTrCsi-F3sT3i-g', '2018-08-07 20:58:24.232', 'Francis', 'IFRS', 'Sample chart of account');
+
+INSERT INTO ledger (id, created, user_details, name, coa_id)
+VALUES 
+  ('Zd0ND5YwSzGwIfZilhumPg', '2018-08-07 20:58:24.232', 'Sample User', 'GL', 'ci8k8PDcTrCsi-F3sT3i-g');
+
+INSERT INTO ledger_account (id, created, user_details, ledger_id, coa_id, balance_side, category, name, short_desc)
+VALUES 
+  ('xVgaTPMcRty9ik3BTQDh1Q_BS_1_0_0', '2018-08-07 23:50:41.231', 'Sample User', 'Zd0ND5YwSzGwIfZilhumPg', 'ci8k8PDcTrCsi-F3sT3i-g', 'Dr', 'AS', '1.0.0', 'Asset Accounts'),
+  ('xVgaTPMcRty9ik3BTQDh1Q_BS_2_0_0', '2018-08-07 23:50:41.231', 'Sample User', 'Zd0ND5YwSzGwIfZilhumPg', 'ci8k8PDcTrCsi-F3sT3i-g', 'Cr', 'EQ', '2.0.0', 'Equity Accounts');
+
+INSERT INTO posting (id, hash, record_user, record_time, opr_id, pst_time, pst_type, pst_status, ledger_id, discarding_id)
+VALUES 
+  ('pst_001', 'hash_001', 'Sample User', '2018-01-15 10:00:00', 'opr_001', '2018-01-15 10:00:00', 'BUSI_TX', 'POSTED', 'Zd0ND5YwSzGwIfZilhumPg', NULL),
+  ('pst_002', 'hash_002', 'Sample User', '2018-02-10 10:00:00', 'opr_002', '2018-02-10 10:00:00', 'BUSI_TX', 'POSTED', 'Zd0ND5YwSzGwIfZilhumPg', NULL),
+  ('pst_003', 'hash_003', 'Sample User', '2018-02-12 10:00:00', 'opr_003', '2018-02-12 10:00:00', 'BUSI_TX', 'POSTED', 'Zd0ND5YwSzGwIfZilhumPg', NULL),
+  ('pst_004', 'hash_004', 'Sample User', '2018-02-15 10:00:00', 'opr_004', '2018-02-15 10:00:00', 'BUSI_TX', 'POSTED', 'Zd0ND5YwSzGwIfZilhumPg', 'pst_004_discarding'),
+  ('pst_005', 'hash_005', 'Sample User', '2018-03-10 10:00:00', 'opr_005', '2018-03-10 10:00:00', 'BUSI_TX', 'POSTED', 'Zd0ND5YwSzGwIfZilhumPg', NULL);
+
+-- The asset account was closed at the end of January.
+INSERT INTO account_stmt (id, pst_time, stmt_status, stmt_seq_nbr, account_id, total_debit, total_credit)
+VALUES 
+  ('stmt_001', '2018-01-31 23:59:59', 'CLOSED', 0, 'xVgaTPMcRty9ik3BTQDh1Q_BS_1_0_0', 1000.00, 200.00);
+
+INSERT INTO posting_line (id, account_id, debit_amount, credit_amount, record_time, opr_id, pst_time, pst_type, pst_status, hash, discarded_time)
+VALUES 
+  -- included in stmt_001
+  ('line_001', 'xVgaTPMcRty9ik3BTQDh1Q_BS_1_0_0', 1000.00, 0.00, '2018-01-15 10:00:00', 'opr_001', '2018-01-15 10:00:00', 'BUSI_TX', 'POSTED', 'hash', NULL),
+  -- posted after stmt_001
+  ('line_002', 'xVgaTPMcRty9ik3BTQDh1Q_BS_1_0_0', 300.00, 0.00, '2018-02-10 10:00:00', 'opr_002', '2018-02-10 10:00:00', 'BUSI_TX', 'POSTED', 'hash', NULL),
+  ('line_003', 'xVgaTPMcRty9ik3BTQDh1Q_BS_1_0_0', 0.00, 50.00, '2018-02-12 10:00:00', 'opr_003', '2018-02-12 10:00:00', 'BUSI_TX', 'POSTED', 'hash', NULL),
+  -- discarded
+  ('line_004', 'xVgaTPMcRty9ik3BTQDh1Q_BS_1_0_0', 999.00, 0.00, '2018-02-15 10:00:00', 'opr_004', '2018-02-15 10:00:00', 'BUSI_TX', 'POSTED', 'hash', '2018-02-16 10:00:00'),
+  -- posted after the reference time of the tests
+  ('line_005', 'xVgaTPMcRty9ik3BTQDh1Q_BS_1_0_0', 77.00, 0.00, '2018-03-10 10:00:00', 'opr_005', '2018-03-10 10:00:00', 'BUSI_TX', 'POSTED', 'hash', NULL),
+  ('line_006', 'xVgaTPMcRty9ik3BTQDh1Q_BS_2_0_0', 0.00, 300.00, '2018-02-10 10:00:00', 'opr_002', '2018-02-10 10:00:00', 'BUSI_TX', 'POSTED', 'hash', NULL),
+  ('line_007', 'xVgaTPMcRty9ik3BTQDh1Q_BS_2_0_0', 50.00, 0.00, '2018-02-12 10:00:00', 'opr_003', '2018-02-12 10:00:00', 'BUSI_TX', 'POSTED', 'hash', NULL);
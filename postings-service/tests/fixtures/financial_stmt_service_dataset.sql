@@ -0,0 +1,26 @@
+-- tests/fixtures/financial_stmt_service_dataset.sql
+-- 
+-- Copyright (c) 2018-2024 adorsys GmbH and Co. KG
+-- All rights are reserved.
+--
+
+INSERT INTO chart_of_account (id, created, user_details, name, short_desc)
+VALUES 
+  ('ci8k8PDcTrCsi-F3sT3i-g', '2018-08-07 20:58:24.232', 'Francis', 'IFRS', 'Sample chart of account');
+
+INSERT INTO ledger (id, created, user_details, name, coa_id)
+VALUES 
+  ('Zd0ND5YwSzGwIfZilhumPg', '2018-08-07 20:58:24.232', 'Sample User', 'GL', 'ci8k8PDcTrCsi-F3sT3i-g');
+
+INSERT INTO ledger_account (id, created, user_details, ledger_id, parent_id, coa_id, balance_side, category, name, short_desc)
+VALUES 
+  ('xVgaTPMcRty9ik3BTQDh1Q_BS_1_0_0', '2018-08-07 23:50:41.231', 'Sample User', 'Zd0ND5YwSzGwIfZilhumPg', NULL, 'ci8k8PDcTrCsi-F3sT3i-g', 'Dr', 'AS', '1.0.0', 'Asset Accounts'),
+  ('xVgaTPMcRty9ik3BTQDh1Q_BS_1_1_0', '2018-08-07 23:50:41.231', 'Sample User', 'Zd0ND5YwSzGwIfZilhumPg', 'xVgaTPMcRty9ik3BTQDh1Q_BS_1_0_0', 'ci8k8PDcTrCsi-F3sT3i-g', 'Dr', 'AS', '1.1.0', 'Cash'),
+  ('xVgaTPMcRty9ik3BTQDh1Q_BS_1_2_0', '2018-08-07 23:50:41.231', 'Sample User', 'Zd0ND5YwSzGwIfZilhumPg', 'xVgaTPMcRty9ik3BTQDh1Q_BS_1_0_0', 'ci8k8PDcTrCsi-F3sT3i-g', 'Dr', 'AS', '1.2.0', 'Bank'),
+  ('xVgaTPMcRty9ik3BTQDh1Q_BS_2_0_0', '2018-08-07 23:50:41.231', 'Sample User', 'Zd0ND5YwSzGwIfZilhumPg', NULL, 'ci8k8PDcTrCsi-F3sT3i-g', 'Cr', 'EQ', '2.0.0', 'Equity Accounts'),
+  ('xVgaTPMcRty9ik3BTQDh1Q_BS_3_0_0', '2018-08-07 23:50:41.231', 'Sample User', 'Zd0ND5YwSzGwIfZilhumPg', NULL, 'ci8k8PDcTrCsi-F3sT3i-g', 'Cr', 'LI', '3.0.0', 'Liability Accounts'),
+  ('xVgaTPMcRty9ik3BTQDh1Q_PL_4_0_0', '2018-08-07 23:50:41.231', 'Sample User', 'Zd0ND5YwSzGwIfZilhumPg', NULL, 'ci8k8PDcTrCsi-F3sT3i-g', 'Cr', 'RE', '4.0.0', 'Revenue Accounts'),
+  ('xVgaTPMcRty9ik3BTQDh1Q_PL_4_1_0', '2018-08-07 23:50:41.231', 'Sample User', 'Zd0ND5YwSzGwIfZilhumPg', 'xVgaTPMcRty9ik3BTQDh1Q_PL_4_0_0', 'ci8k8PDcTrCsi-F3sT3i-g', 'Cr', 'RE', '4.1.0', 'Sales'),
+  ('xVgaTPMcRty9ik3BTQDh1Q_PL_5_0_0', '2018-08-07 23:50:41.231', 'Sample User', 'Zd0ND5YwSzGwIfZilhumPg', NULL, 'ci8k8PDcTrCsi-F3sT3i-g', 'Dr', 'EX', '5.0.0', 'Operating Expense Accounts'),
+  ('xVgaTPMcRty9ik3BTQDh1Q_PL_6_0_0', '2018-08-07 23:50:41.231', 'Sample User', 'Zd0ND5YwSzGwIfZilhumPg', NULL, 'ci8k8PDcTrCsi-F3sT3i-g', 'Cr', 'NORE', '6.0.0', 'Non-Operating Revenue Accounts'),
+  ('xVgaTPMcRty9ik3BTQDh1Q_PL_7_0_0', '2018-08-07 23:50:41.231', 'Sample User', 'Zd0ND5YwSzGwIfZilhumPg', NULL, 'ci8k8PDcTrCsi-F3sT3i-g', 'Dr', 'NOEX', '7.0.0', 'Non-Operating Expense Accounts');
@@ -0,0 +1,205 @@
+// tests/in_memory_service_test.rs
+//
+// Copyright (c) 2018-2024 adorsys GmbH and Co. KG
+// All rights are reserved.
+//
+// The services running on the in-memory backend, without a database.
+
+mod common;
+
+use std::sync::Arc;
+
+use common::{business_posting, new_posting, post, posting_lines, time, TEST_USER};
+use postings_repository::models::enums::{AccountCategory, BalanceSide, PostingStatus, PostingType};
+use postings_repository::models::{NewChartOfAccount, NewLedger, NewLedgerAccount};
+use postings_repository::repository::{
+    ChartOfAccountRepository, InMemoryRepositories, LedgerAccountRepository, LedgerRepository,
+};
+use postings_service::{
+    AccountStmtService, DeferredPostingScheduler, FixedClock, JournalVerifier, LedgerStmtService, PostingService,
+    ServiceError, SimulationService,
+};
+use rust_decimal::Decimal;
+
+const LEDGER_ID: &str = "ledger";
+
+fn account(id: &str, category: AccountCategory, balance_side: BalanceSide) -> NewLedgerAccount {
+    NewLedgerAccount {
+        id: id.to_string(),
+        created: time("2018-01-01 00:00:00"),
+        user_details: "Francis".to_string(),
+        short_desc: None,
+        long_desc: None,
+        name: id.to_string(),
+        ledger_id: LEDGER_ID.to_string(),
+        parent_id: None,
+        coa_id: "coa".to_string(),
+        balance_side,
+        category,
+    }
+}
+
+/// A ledger with cash, equity, expense and revenue accounts and the
+/// postings of January.
+fn january() -> InMemoryRepositories {
+    let mut store = InMemoryRepositories::new();
+    ChartOfAccountRepository::save(
+        &mut store,
+        NewChartOfAccount {
+            id: "coa".to_string(),
+            created: time("2018-01-01 00:00:00"),
+            user_details: "Francis".to_string(),
+            short_desc: None,
+            long_desc: None,
+            name: "IFRS".to_string(),
+        },
+    )
+    .unwrap();
+    LedgerRepository::save(
+        &mut store,
+        NewLedger {
+            id: LEDGER_ID.to_string(),
+            created: time("2018-01-01 00:00:00"),
+            user_details: "Francis".to_string(),
+            short_desc: None,
+            long_desc: None,
+            name: "Sample Ledger".to_string(),
+            coa_id: "coa".to_string(),
+        },
+    )
+    .unwrap();
+    for new_account in [
+        account("cash", AccountCategory::AS, BalanceSide::Dr),
+        account("equity", AccountCategory::EQ, BalanceSide::Cr),
+        account("expense", AccountCategory::EX, BalanceSide::Dr),
+        account("revenue", AccountCategory::RE, BalanceSide::Cr),
+    ] {
+        LedgerAccountRepository::save(&mut store, new_account).unwrap();
+    }
+
+    post(&mut store, "pst_001", LEDGER_ID, "2018-01-05 10:00:00", &[("cash", 100, 0), ("equity", 0, 100)]);
+    post(&mut store, "pst_002", LEDGER_ID, "2018-01-10 10:00:00", &[("expense", 30, 0), ("cash", 0, 30)]);
+    post(&mut store, "pst_003", LEDGER_ID, "2018-01-20 10:00:00", &[("cash", 50, 0), ("revenue", 0, 50)]);
+    store
+}
+
+#[test]
+fn test_balances_and_statements_in_memory() {
+    let mut store = january();
+    let service = AccountStmtService::new();
+
+    let stmt = service.close_account_stmt(&mut store, "cash", time("2018-01-15 23:59:59"), "Test User").unwrap();
+    assert_eq!(stmt.total_debit, Decimal::new(100, 0));
+    assert_eq!(stmt.total_credit, Decimal::new(30, 0));
+
+    let report = service.read_stmt(&mut store, "cash", time("2018-01-31 23:59:59")).unwrap();
+    assert_eq!(report.base_stmt_id, Some(stmt.id.clone()));
+    assert_eq!(report.balance, Decimal::new(120, 0));
+
+    let result = service.close_account_stmt(&mut store, "cash", time("2018-01-10 23:59:59"), "Test User");
+    assert!(matches!(result, Err(ServiceError::AccountStmtClosed { .. })));
+}
+
+#[test]
+fn test_trial_balance_and_journal_in_memory() {
+    let mut store = january();
+
+    let trial_balance =
+        LedgerStmtService::new().trial_balance(&mut store, LEDGER_ID, time("2018-01-31 23:59:59")).unwrap();
+    assert!(trial_balance.is_balanced());
+    assert_eq!(trial_balance.total_debit, Decimal::new(180, 0));
+
+    assert!(JournalVerifier::new().verify_ledger(&mut store, LEDGER_ID).unwrap().is_none());
+}
+
+#[test]
+fn test_posting_approval_in_memory() {
+    let mut store = january();
+    let service = PostingService::new();
+    let proposal =
+        new_posting("pst_004", LEDGER_ID, "2018-01-25 10:00:00", PostingType::BusiTx, PostingStatus::PROPOSED, TEST_USER);
+    service.new_posting(&mut store, proposal, posting_lines("pst_004", &[("expense", 20, 0), ("cash", 0, 20)])).unwrap();
+
+    let balance = |store: &mut InMemoryRepositories| {
+        AccountStmtService::new().read_stmt(store, "cash", time("2018-01-31 23:59:59")).unwrap().balance
+    };
+    assert_eq!(balance(&mut store), Decimal::new(120, 0));
+
+    let result = service.approve(&mut store, "pst_004", "Test User", time("2018-01-26 10:00:00"));
+    assert!(matches!(result, Err(ServiceError::CheckerIsMaker { .. })));
+    let (approved, _) = service.approve(&mut store, "pst_004", "Checker", time("2018-01-26 10:00:00")).unwrap();
+    assert_eq!(approved.antecedent_id.as_deref(), Some("pst_003"));
+    assert_eq!(balance(&mut store), Decimal::new(100, 0));
+    assert!(JournalVerifier::new().verify_ledger(&mut store, LEDGER_ID).unwrap().is_none());
+}
+
+#[test]
+fn test_simulation_in_memory() {
+    let mut store = january();
+    let service = SimulationService::new();
+    let fee = business_posting("fee_001", LEDGER_ID, "2018-01-25 10:00:00");
+    service.post(&mut store, fee, posting_lines("fee_001", &[("expense", 5, 0), ("cash", 0, 5)])).unwrap();
+
+    let ref_time = time("2018-01-31 23:59:59");
+    assert_eq!(AccountStmtService::new().read_stmt(&mut store, "cash", ref_time).unwrap().balance, Decimal::new(120, 0));
+    assert_eq!(service.read_stmt(&mut store, "cash", ref_time).unwrap().balance, Decimal::new(115, 0));
+    let stmt = service.simulate_account_stmt(&mut store, "cash", ref_time, "Test User").unwrap();
+    assert_eq!(stmt.total_credit - stmt.total_debit, Decimal::new(-115, 0));
+    assert!(JournalVerifier::new().verify_ledger(&mut store, LEDGER_ID).unwrap().is_none());
+
+    let purge = service.purge(&mut store, LEDGER_ID).unwrap();
+    assert_eq!((purge.account_stmts, purge.postings, purge.posting_lines), (1, 2, 4));
+    assert_eq!(service.read_stmt(&mut store, "cash", ref_time).unwrap().balance, Decimal::new(120, 0));
+}
+
+#[test]
+fn test_deferred_posting_in_memory() {
+    let mut store = january();
+    let clock = Arc::new(FixedClock::new(time("2018-01-24 10:00:00")));
+    let scheduler = DeferredPostingScheduler::with_clock(clock.clone());
+    let mut deferred =
+        new_posting("pst_004", LEDGER_ID, "2018-01-21 10:00:00", PostingType::BusiTx, PostingStatus::DEFERRED, TEST_USER);
+    deferred.pst_time = time("2018-01-25 10:00:00");
+    PostingService::new()
+        .new_posting(&mut store, deferred, posting_lines("pst_004", &[("expense", 20, 0), ("cash", 0, 20)]))
+        .unwrap();
+
+    let balance = |store: &mut InMemoryRepositories| {
+        AccountStmtService::new().read_stmt(store, "cash", time("2018-01-31 23:59:59")).unwrap().balance
+    };
+    assert!(scheduler.run_due(&mut store).unwrap().promoted.is_empty());
+    assert_eq!(balance(&mut store), Decimal::new(120, 0));
+
+    clock.set(time("2018-01-25 10:00:00"));
+    let run = scheduler.run_due(&mut store).unwrap();
+    assert_eq!(run.promoted.len(), 1);
+    assert_eq!(run.promoted[0].antecedent_id.as_deref(), Some("pst_003"));
+    assert_eq!(run.promoted[0].record_time, time("2018-01-25 10:00:00"));
+    assert_eq!(balance(&mut store), Decimal::new(100, 0));
+    assert!(scheduler.run_due(&mut store).unwrap().promoted.is_empty());
+    assert!(JournalVerifier::new().verify_ledger(&mut store, LEDGER_ID).unwrap().is_none());
+}
+
+#[test]
+fn test_as_of_queries_in_memory() {
+    let mut store = january();
+    let service = AccountStmtService::new();
+    let mut correction = business_posting("pst_004", LEDGER_ID, "2018-01-20 10:00:00");
+    correction.opr_id = "opr_of_pst_003".to_string();
+    correction.record_time = time("2018-01-25 10:00:00");
+    PostingService::new()
+        .repost(&mut store, correction, posting_lines("pst_004", &[("cash", 60, 0), ("revenue", 0, 60)]))
+        .unwrap();
+
+    let ref_time = time("2018-01-31 23:59:59");
+    let report = service.read_stmt_as_of(&mut store, "cash", ref_time, time("2018-01-21 00:00:00")).unwrap();
+    assert_eq!(report.balance, Decimal::new(120, 0));
+    let report = service.read_stmt_as_of(&mut store, "cash", ref_time, time("2018-01-25 10:00:00")).unwrap();
+    assert_eq!(report.balance, Decimal::new(130, 0));
+
+    let lines = service
+        .find_lines_as_of(&mut store, "cash", time("2018-01-15 00:00:00"), ref_time, time("2018-01-21 00:00:00"))
+        .unwrap();
+    assert_eq!(lines.len(), 1);
+    assert_eq!(lines[0].debit_amount, Decimal::new(50, 0));
+}
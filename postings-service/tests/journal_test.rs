@@ -0,0 +1,196 @@
+// tests/journal_test.rs
+//
+// Copyright (c) 2018-2024 adorsys GmbH and Co. KG
+// All rights are reserved.
+
+mod common;
+
+use common::{business_posting, establish_connection, post, posting_lines, seed_database, TestDatabaseGuard};
+use diesel::prelude::*;
+use postings_repository::repository::posting_line_repository;
+use postings_service::journal::{BrokenLink, BrokenLinkReason};
+use postings_service::{JournalVerifier, PostingService, ServiceError};
+use serial_test::serial;
+
+const LEDGER_ID: &str = "Zd0ND5YwSzGwIfZilhumPg";
+const CASH_ACCOUNT: &str = "xVgaTPMcRty9ik3BTQDh1Q_BS_1_0_0";
+const EQUITY_ACCOUNT: &str = "xVgaTPMcRty9ik3BTQDh1Q_BS_2_0_0";
+
+/// Records three postings of 100 in the ledger.
+fn record_postings(conn: &mut PgConnection) {
+    for (idx, at) in ["2018-01-01 10:00:00", "2018-01-02 10:00:00", "2018-01-03 10:00:00"].iter().enumerate() {
+        post(conn, &format!("pst_00{}", idx + 1), LEDGER_ID, at, &[(CASH_ACCOUNT, 100, 0), (EQUITY_ACCOUNT, 0, 100)]);
+    }
+}
+
+#[test]
+#[serial]
+fn test_postings_are_chained() {
+    let mut conn = establish_connection();
+    seed_database(&mut conn, "tests/fixtures/posting_service_dataset.sql");
+    let _guard = TestDatabaseGuard::new();
+    let service = PostingService::new();
+
+    let (first, first_lines) = service
+        .new_posting(
+            &mut conn,
+            business_posting("pst_001", LEDGER_ID, "2018-01-01 10:00:00"),
+            posting_lines("pst_001", &[(CASH_ACCOUNT, 100, 0), (EQUITY_ACCOUNT, 0, 100)]),
+        )
+        .expect("Failed to create posting");
+    let second = post(&mut conn, "pst_002", LEDGER_ID, "2018-01-02 10:00:00", &[(CASH_ACCOUNT, 50, 0), (EQUITY_ACCOUNT, 0, 50)]);
+
+    assert_eq!(first.antecedent_id, None);
+    assert_eq!(first.hash_alg.as_deref(), Some("SHA-256"));
+    assert_eq!(first.hash.as_ref().map(String::len), Some(64));
+    assert!(first_lines.iter().all(|line| line.hash.len() == 64));
+    assert_eq!(second.antecedent_id.as_deref(), Some("pst_001"));
+    assert_eq!(second.antecedent_hash, first.hash);
+
+    let broken = JournalVerifier::new().verify_ledger(&mut conn, LEDGER_ID).unwrap();
+    assert_eq!(broken, None);
+}
+
+#[test]
+#[serial]
+fn test_record_time_before_antecedent() {
+    let mut conn = establish_connection();
+    seed_database(&mut conn, "tests/fixtures/posting_service_dataset.sql");
+    let _guard = TestDatabaseGuard::new();
+    record_postings(&mut conn);
+
+    let result = PostingService::new().new_posting(
+        &mut conn,
+        business_posting("pst_004", LEDGER_ID, "2018-01-02 12:00:00"),
+        posting_lines("pst_004", &[(CASH_ACCOUNT, 100, 0), (EQUITY_ACCOUNT, 0, 100)]),
+    );
+    assert!(matches!(result, Err(ServiceError::RecordTimeNotAfterAntecedent { .. })));
+}
+
+#[test]
+#[serial]
+fn test_verify_detects_edited_posting_line() {
+    let mut conn = establish_connection();
+    seed_database(&mut conn, "tests/fixtures/posting_service_dataset.sql");
+    let _guard = TestDatabaseGuard::new();
+    record_postings(&mut conn);
+
+    diesel::sql_query("UPDATE posting_line SET debit_amount = 1000 WHERE id = 'pst_002_line_0'")
+        .execute(&mut conn)
+        .unwrap();
+
+    let broken = JournalVerifier::new().verify_ledger(&mut conn, LEDGER_ID).unwrap();
+    assert_eq!(
+        broken,
+        Some(BrokenLink { posting_id: "pst_002".to_string(), reason: BrokenLinkReason::HashMismatch })
+    );
+}
+
+#[test]
+#[serial]
+fn test_verify_detects_edited_line_pst_time() {
+    let mut conn = establish_connection();
+    seed_database(&mut conn, "tests/fixtures/posting_service_dataset.sql");
+    let _guard = TestDatabaseGuard::new();
+    record_postings(&mut conn);
+
+    diesel::sql_query("UPDATE posting_line SET pst_time = '2017-12-31 10:00:00' WHERE id = 'pst_002_line_0'")
+        .execute(&mut conn)
+        .unwrap();
+
+    let broken = JournalVerifier::new().verify_ledger(&mut conn, LEDGER_ID).unwrap();
+    assert_eq!(
+        broken,
+        Some(BrokenLink { posting_id: "pst_002".to_string(), reason: BrokenLinkReason::HashMismatch })
+    );
+}
+
+#[test]
+#[serial]
+fn test_verify_detects_edited_discarded_time() {
+    let mut conn = establish_connection();
+    seed_database(&mut conn, "tests/fixtures/posting_service_dataset.sql");
+    let _guard = TestDatabaseGuard::new();
+    record_postings(&mut conn);
+
+    diesel::sql_query("UPDATE posting_line SET discarded_time = '2018-01-02 10:00:00' WHERE id = 'pst_001_line_0'")
+        .execute(&mut conn)
+        .unwrap();
+
+    let broken = JournalVerifier::new().verify_ledger(&mut conn, LEDGER_ID).unwrap();
+    assert_eq!(
+        broken,
+        Some(BrokenLink {
+            posting_id: "pst_001".to_string(),
+            reason: BrokenLinkReason::DiscardedTimeMismatch("pst_001_line_0".to_string()),
+        })
+    );
+}
+
+#[test]
+#[serial]
+fn test_verify_detects_edited_line_hash() {
+    let mut conn = establish_connection();
+    seed_database(&mut conn, "tests/fixtures/posting_service_dataset.sql");
+    let _guard = TestDatabaseGuard::new();
+    record_postings(&mut conn);
+
+    diesel::sql_query("UPDATE posting_line SET hash = 'forged' WHERE id = 'pst_001_line_1'")
+        .execute(&mut conn)
+        .unwrap();
+
+    let broken = JournalVerifier::new().verify_ledger(&mut conn, LEDGER_ID).unwrap();
+    assert_eq!(
+        broken,
+        Some(BrokenLink {
+            posting_id: "pst_001".to_string(),
+            reason: BrokenLinkReason::LineHashMismatch("pst_001_line_1".to_string()),
+        })
+    );
+}
+
+#[test]
+#[serial]
+fn test_verify_detects_removed_posting() {
+    let mut conn = establish_connection();
+    seed_database(&mut conn, "tests/fixtures/posting_service_dataset.sql");
+    let _guard = TestDatabaseGuard::new();
+    record_postings(&mut conn);
+
+    diesel::sql_query("DELETE FROM posting WHERE id = 'pst_002'")
+        .execute(&mut conn)
+        .unwrap();
+
+    let broken = JournalVerifier::new().verify_ledger(&mut conn, LEDGER_ID).unwrap();
+    assert_eq!(
+        broken,
+        Some(BrokenLink { posting_id: "pst_003".to_string(), reason: BrokenLinkReason::Unreachable })
+    );
+}
+
+#[test]
+#[serial]
+fn test_verify_survives_discarding() {
+    let mut conn = establish_connection();
+    seed_database(&mut conn, "tests/fixtures/posting_service_dataset.sql");
+    let _guard = TestDatabaseGuard::new();
+    record_postings(&mut conn);
+
+    // Re-posting an operation stamps the old posting and lines, which must not break the chain.
+    let mut correction = business_posting("pst_004", LEDGER_ID, "2018-01-04 10:00:00");
+    correction.opr_id = "opr_of_pst_001".to_string();
+    PostingService::new()
+        .repost(
+            &mut conn,
+            correction,
+            posting_lines("pst_004", &[(CASH_ACCOUNT, 90, 0), (EQUITY_ACCOUNT, 0, 90)]),
+        )
+        .expect("Failed to repost operation");
+    let old_line = posting_line_repository::find_first_by_id_and_account(&mut conn, "pst_001_line_0", CASH_ACCOUNT)
+        .unwrap()
+        .unwrap();
+    assert!(old_line.discarded_time.is_some());
+
+    let broken = JournalVerifier::new().verify_ledger(&mut conn, LEDGER_ID).unwrap();
+    assert_eq!(broken, None);
+}
@@ -0,0 +1,176 @@
+// tests/ledger_stmt_service_test.rs
+//
+// Copyright (c) 2018-2024 adorsys GmbH and Co. KG
+// All rights are reserved.
+
+mod common;
+
+use chrono::Utc;
+use common::{business_posting, establish_connection, post, posting_lines, seed_database, time, TestDatabaseGuard};
+use diesel::prelude::*;
+use postings_repository::models::enums::{PostingType, StmtStatus};
+use postings_repository::repository::posting_repository;
+use postings_service::{LedgerStmtService, PostingService, ServiceError};
+use rust_decimal::Decimal;
+use serial_test::serial;
+
+const LEDGER_ID: &str = "Zd0ND5YwSzGwIfZilhumPg";
+const CASH: &str = "xVgaTPMcRty9ik3BTQDh1Q_BS_1_0_0";
+const EQUITY: &str = "xVgaTPMcRty9ik3BTQDh1Q_BS_2_0_0";
+const REVENUE: &str = "xVgaTPMcRty9ik3BTQDh1Q_PL_4_0_0";
+const EXPENSE: &str = "xVgaTPMcRty9ik3BTQDh1Q_PL_5_0_0";
+
+fn record_january(conn: &mut PgConnection) {
+    post(conn, "pst_001", LEDGER_ID, "2018-01-01 10:00:00", &[(CASH, 100, 0), (EQUITY, 0, 100)]);
+    post(conn, "pst_002", LEDGER_ID, "2018-01-05 10:00:00", &[(EXPENSE, 30, 0), (CASH, 0, 30)]);
+    post(conn, "pst_003", LEDGER_ID, "2018-01-10 10:00:00", &[(CASH, 50, 0), (REVENUE, 0, 50)]);
+}
+
+#[test]
+#[serial]
+fn test_trial_balance() {
+    let mut conn = establish_connection();
+    seed_database(&mut conn, "tests/fixtures/posting_service_dataset.sql");
+    let _guard = TestDatabaseGuard::new();
+    record_january(&mut conn);
+
+    let trial_balance = LedgerStmtService::new()
+        .trial_balance(&mut conn, LEDGER_ID, time("2018-01-31 23:59:59"))
+        .expect("Failed to build trial balance");
+
+    // Every account of the ledger is listed, including the unused liability account.
+    assert_eq!(trial_balance.lines.len(), 5);
+    assert!(trial_balance.is_balanced());
+    assert_eq!(trial_balance.total_debit, Decimal::new(180, 0));
+
+    let cash = trial_balance.lines.iter().find(|line| line.account_id == CASH).unwrap();
+    assert_eq!(cash.total_debit, Decimal::new(150, 0));
+    assert_eq!(cash.total_credit, Decimal::new(30, 0));
+    assert_eq!(cash.balance, Decimal::new(120, 0));
+}
+
+#[test]
+#[serial]
+fn test_create_ledger_stmt() {
+    let mut conn = establish_connection();
+    seed_database(&mut conn, "tests/fixtures/posting_service_dataset.sql");
+    let _guard = TestDatabaseGuard::new();
+    record_january(&mut conn);
+    let service = LedgerStmtService::new();
+
+    let (simulated, _) = service
+        .create_ledger_stmt(&mut conn, LEDGER_ID, time("2018-01-15 23:59:59"), StmtStatus::SIMULATED, "Test User")
+        .expect("Failed to simulate ledger statement");
+    let (simulated_again, _) = service
+        .create_ledger_stmt(&mut conn, LEDGER_ID, time("2018-01-15 23:59:59"), StmtStatus::SIMULATED, "Test User")
+        .expect("Failed to simulate ledger statement");
+    assert_eq!(simulated.stmt_seq_nbr, 0);
+    assert_eq!(simulated_again.stmt_seq_nbr, 1);
+
+    let (closed, trial_balance) = service
+        .create_ledger_stmt(&mut conn, LEDGER_ID, time("2018-01-31 23:59:59"), StmtStatus::CLOSED, "Test User")
+        .expect("Failed to close ledger statement");
+    assert_eq!(closed.stmt_status, StmtStatus::CLOSED);
+    assert_eq!(closed.stmt_seq_nbr, 0);
+    assert_eq!(trial_balance.total_credit, Decimal::new(180, 0));
+
+    let result = service.create_ledger_stmt(&mut conn, LEDGER_ID, time("2018-01-20 00:00:00"), StmtStatus::CLOSED, "Test User");
+    assert!(matches!(result, Err(ServiceError::LedgerClosed { .. })));
+}
+
+#[test]
+#[serial]
+fn test_create_ledger_stmt_unbalanced() {
+    let mut conn = establish_connection();
+    seed_database(&mut conn, "tests/fixtures/posting_service_dataset.sql");
+    let _guard = TestDatabaseGuard::new();
+    record_january(&mut conn);
+
+    diesel::sql_query("UPDATE posting_line SET debit_amount = 99 WHERE id = 'pst_001_line_0'")
+        .execute(&mut conn)
+        .unwrap();
+
+    let result = LedgerStmtService::new().create_ledger_stmt(
+        &mut conn,
+        LEDGER_ID,
+        time("2018-01-31 23:59:59"),
+        StmtStatus::CLOSED,
+        "Test User",
+    );
+    assert!(matches!(result, Err(ServiceError::UnbalancedLedger { .. })));
+}
+
+#[test]
+#[serial]
+fn test_close_ledger() {
+    let mut conn = establish_connection();
+    seed_database(&mut conn, "tests/fixtures/posting_service_dataset.sql");
+    let _guard = TestDatabaseGuard::new();
+    record_january(&mut conn);
+    let closing_time = time("2018-01-31 23:59:59");
+
+    let (stmt, trial_balance) = LedgerStmtService::new()
+        .close_ledger(&mut conn, LEDGER_ID, closing_time, EQUITY, "Test User")
+        .expect("Failed to close ledger");
+    assert_eq!(stmt.stmt_status, StmtStatus::CLOSED);
+    assert_eq!(stmt.pst_time, closing_time);
+
+    let posting = posting_repository::find_by_id(&mut conn, stmt.posting_id.as_deref().unwrap()).unwrap().unwrap();
+    assert_eq!(posting.pst_type, PostingType::LdgClsng);
+
+    // Revenue and expense accounts are zeroed, the result of 20 moved to equity.
+    let balance_of = |account_id: &str| {
+        trial_balance.lines.iter().find(|line| line.account_id == account_id).unwrap().balance
+    };
+    assert_eq!(balance_of(REVENUE), Decimal::ZERO);
+    assert_eq!(balance_of(EXPENSE), Decimal::ZERO);
+    assert_eq!(balance_of(EQUITY), Decimal::new(120, 0));
+    assert_eq!(balance_of(CASH), Decimal::new(120, 0));
+}
+
+#[test]
+#[serial]
+fn test_close_ledger_refuses_later_changes() {
+    let mut conn = establish_connection();
+    seed_database(&mut conn, "tests/fixtures/posting_service_dataset.sql");
+    let _guard = TestDatabaseGuard::new();
+    record_january(&mut conn);
+    let service = LedgerStmtService::new();
+    service
+        .close_ledger(&mut conn, LEDGER_ID, time("2018-01-31 23:59:59"), EQUITY, "Test User")
+        .expect("Failed to close ledger");
+
+    let result = service.close_ledger(&mut conn, LEDGER_ID, time("2018-01-31 23:59:59"), EQUITY, "Test User");
+    assert!(matches!(result, Err(ServiceError::LedgerClosed { .. })));
+
+    let now = Utc::now().naive_utc();
+    let mut backdated = business_posting("pst_004", LEDGER_ID, "2018-01-31 23:59:59");
+    backdated.record_time = now;
+    let result = PostingService::new().new_posting(&mut conn, backdated, posting_lines("pst_004", &[(CASH, 10, 0), (EQUITY, 0, 10)]));
+    assert!(matches!(result, Err(ServiceError::LedgerClosed { .. })));
+
+    // Re-posting an operation of the closed period is refused as well.
+    let mut correction = business_posting("pst_005", LEDGER_ID, "2018-02-01 10:00:00");
+    correction.opr_id = "opr_of_pst_001".to_string();
+    correction.record_time = now;
+    let result = PostingService::new().repost(&mut conn, correction, posting_lines("pst_005", &[(CASH, 90, 0), (EQUITY, 0, 90)]));
+    assert!(matches!(result, Err(ServiceError::LedgerClosed { .. })));
+
+    let mut next_year = business_posting("pst_006", LEDGER_ID, "2018-02-01 10:00:00");
+    next_year.record_time = now;
+    PostingService::new()
+        .new_posting(&mut conn, next_year, posting_lines("pst_006", &[(CASH, 10, 0), (EQUITY, 0, 10)]))
+        .expect("Postings after the closing time are accepted");
+}
+
+#[test]
+#[serial]
+fn test_close_ledger_into_non_equity_account() {
+    let mut conn = establish_connection();
+    seed_database(&mut conn, "tests/fixtures/posting_service_dataset.sql");
+    let _guard = TestDatabaseGuard::new();
+    record_january(&mut conn);
+
+    let result = LedgerStmtService::new().close_ledger(&mut conn, LEDGER_ID, time("2018-01-31 23:59:59"), CASH, "Test User");
+    assert!(matches!(result, Err(ServiceError::NotAnEquityAccount(_))));
+}
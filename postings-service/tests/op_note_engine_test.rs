@@ -0,0 +1,183 @@
+// tests/op_note_engine_test.rs
+//
+// Copyright (c) 2018-2024 adorsys GmbH and Co. KG
+// All rights are reserved.
+
+mod common;
+
+use std::sync::Arc;
+
+use common::{establish_connection, seed_database, time, TestDatabaseGuard};
+use diesel::{Connection, PgConnection};
+use postings_repository::models::{NewOpNote, OpNote};
+use postings_repository::repository::{op_note_repository, OpNoteRepository};
+use postings_service::{FixedClock, OpNoteEngine, ServiceError};
+use serial_test::serial;
+
+const POSTING_ID: &str = "pst_001";
+
+fn note(id: &str, note_type: &str, exec_time: &str, premature_exc: bool, repeated_exec: bool) -> NewOpNote {
+    NewOpNote {
+        id: id.to_string(),
+        rec_id: POSTING_ID.to_string(),
+        note_type: Some(note_type.to_string()),
+        content: Some("Check the counterparty".to_string()),
+        rec_time: time("2018-01-01 10:00:00"),
+        exec_time: Some(time(exec_time)),
+        premature_exc: Some(premature_exc),
+        repeated_exec: Some(repeated_exec),
+        exec_status: None,
+    }
+}
+
+/// An engine reminding of notes by recording a `REMINDED` note on their record,
+/// and failing on `BROKEN` notes after recording one.
+fn engine(clock: Arc<FixedClock>) -> OpNoteEngine<PgConnection> {
+    let mut engine = OpNoteEngine::with_clock(clock);
+    let remind = |conn: &mut PgConnection, note: &OpNote| {
+        OpNoteRepository::save(
+            conn,
+            NewOpNote {
+                id: format!("{}_reminded", note.id),
+                note_type: Some("REMINDED".to_string()),
+                exec_time: None,
+                ..self::note(&note.id, "REMINDED", "2018-01-01 10:00:00", false, false)
+            },
+        )?;
+        Ok(())
+    };
+    engine.register("REMINDER", remind).register("BROKEN", move |conn, note| {
+        remind(conn, note)?;
+        Err(ServiceError::OpNoteHandlerFailed("broken".to_string()))
+    });
+    engine
+}
+
+fn reminded(conn: &mut PgConnection) -> Vec<String> {
+    OpNoteRepository::find_by_rec_id(conn, POSTING_ID)
+        .unwrap()
+        .into_iter()
+        .filter(|note| note.note_type.as_deref() == Some("REMINDED"))
+        .map(|note| note.id)
+        .collect()
+}
+
+#[test]
+#[serial]
+fn test_due_notes_are_executed_once() {
+    let mut conn = establish_connection();
+    seed_database(&mut conn, "tests/fixtures/posting_service_dataset.sql");
+    let _guard = TestDatabaseGuard::new();
+    let clock = Arc::new(FixedClock::new(time("2018-01-10 00:00:00")));
+    let engine = engine(clock.clone());
+    OpNoteRepository::save_all(
+        &mut conn,
+        vec![
+            note("reminder", "REMINDER", "2018-01-05 10:00:00", false, false),
+            note("follow_up", "REMINDER", "2018-01-15 10:00:00", false, false),
+            note("unhandled", "UNKNOWN", "2018-01-05 10:00:00", false, false),
+        ],
+    )
+    .unwrap();
+
+    let executions = engine.poll(&mut conn).unwrap();
+    assert_eq!(executions.len(), 1);
+    assert_eq!(executions[0].note.id, "reminder");
+    assert_eq!(executions[0].note.exec_status.as_deref(), Some(OpNote::EXEC_DONE));
+    assert!(executions[0].error.is_none());
+    assert_eq!(reminded(&mut conn), ["reminder_reminded"]);
+    let unhandled = op_note_repository::find_by_id(&mut conn, "unhandled").unwrap().unwrap();
+    assert_eq!(unhandled.exec_status, None);
+
+    assert!(engine.poll(&mut conn).unwrap().is_empty());
+    clock.set(time("2018-01-20 00:00:00"));
+    let executions = engine.poll(&mut conn).unwrap();
+    assert_eq!(executions.len(), 1);
+    assert_eq!(executions[0].note.id, "follow_up");
+}
+
+#[test]
+#[serial]
+fn test_failed_notes_are_rolled_back_and_repeated_if_allowed() {
+    let mut conn = establish_connection();
+    seed_database(&mut conn, "tests/fixtures/posting_service_dataset.sql");
+    let _guard = TestDatabaseGuard::new();
+    let engine = engine(Arc::new(FixedClock::new(time("2018-01-10 00:00:00"))));
+    OpNoteRepository::save_all(
+        &mut conn,
+        vec![
+            note("broken", "BROKEN", "2018-01-05 10:00:00", false, false),
+            note("retried", "BROKEN", "2018-01-06 10:00:00", false, true),
+        ],
+    )
+    .unwrap();
+
+    let executions = engine.poll(&mut conn).unwrap();
+    assert_eq!(executions.len(), 2);
+    assert!(executions.iter().all(|execution| execution.note.exec_status.as_deref() == Some(OpNote::EXEC_FAILED)));
+    assert!(matches!(executions[0].error, Some(ServiceError::OpNoteHandlerFailed(_))));
+    // The changes of the failed handlers are rolled back.
+    assert!(reminded(&mut conn).is_empty());
+
+    let executions = engine.poll(&mut conn).unwrap();
+    assert_eq!(executions.len(), 1);
+    assert_eq!(executions[0].note.id, "retried");
+}
+
+#[test]
+#[serial]
+fn test_notes_executed_on_demand() {
+    let mut conn = establish_connection();
+    seed_database(&mut conn, "tests/fixtures/posting_service_dataset.sql");
+    let _guard = TestDatabaseGuard::new();
+    let engine = engine(Arc::new(FixedClock::new(time("2018-01-10 00:00:00"))));
+    OpNoteRepository::save_all(
+        &mut conn,
+        vec![
+            note("later", "REMINDER", "2018-01-15 10:00:00", false, false),
+            note("early", "REMINDER", "2018-01-15 10:00:00", true, false),
+            note("repeated", "REMINDER", "2018-01-05 10:00:00", false, true),
+        ],
+    )
+    .unwrap();
+
+    let error = engine.execute(&mut conn, "later").unwrap_err();
+    assert!(matches!(error, ServiceError::OpNoteNotDue { .. }), "{:?}", error);
+    let error = engine.execute(&mut conn, "missing").unwrap_err();
+    assert!(matches!(error, ServiceError::OpNoteNotFound(_)), "{:?}", error);
+
+    let execution = engine.execute(&mut conn, "early").unwrap();
+    assert_eq!(execution.note.exec_status.as_deref(), Some(OpNote::EXEC_DONE));
+    let error = engine.execute(&mut conn, "early").unwrap_err();
+    assert!(matches!(error, ServiceError::OpNoteExecuted { .. }), "{:?}", error);
+
+    engine.execute(&mut conn, "repeated").unwrap();
+    // Run again, the handler fails to record its note twice.
+    let execution = engine.execute(&mut conn, "repeated").unwrap();
+    assert_eq!(execution.note.exec_status.as_deref(), Some(OpNote::EXEC_FAILED));
+    assert!(matches!(execution.error, Some(ServiceError::Repository(_))), "{:?}", execution.error);
+}
+
+#[test]
+#[serial]
+fn test_notes_locked_by_another_worker_are_skipped() {
+    let mut conn = establish_connection();
+    seed_database(&mut conn, "tests/fixtures/posting_service_dataset.sql");
+    let _guard = TestDatabaseGuard::new();
+    let engine = engine(Arc::new(FixedClock::new(time("2018-01-10 00:00:00"))));
+    OpNoteRepository::save(&mut conn, note("reminder", "REMINDER", "2018-01-05 10:00:00", false, false)).unwrap();
+
+    let mut worker = establish_connection();
+    worker
+        .transaction::<_, ServiceError, _>(|worker| {
+            assert!(OpNoteRepository::find_by_id_for_update_skip_locked(worker, "reminder")?.is_some());
+
+            assert!(engine.poll(&mut conn)?.is_empty());
+            let error = engine.execute(&mut conn, "reminder").unwrap_err();
+            assert!(matches!(error, ServiceError::OpNoteLocked(_)), "{:?}", error);
+            Ok(())
+        })
+        .unwrap();
+
+    assert_eq!(engine.poll(&mut conn).unwrap().len(), 1);
+}
@@ -0,0 +1,147 @@
+// tests/posting_approval_test.rs
+//
+// Copyright (c) 2018-2024 adorsys GmbH and Co. KG
+// All rights are reserved.
+
+mod common;
+
+use common::{establish_connection, new_posting, record, seed_database, time, TestDatabaseGuard};
+use diesel::PgConnection;
+use postings_repository::models::enums::{PostingStatus, PostingType};
+use postings_repository::models::{NewPosting, Posting};
+use postings_repository::repository::{op_note_repository, posting_repository};
+use postings_service::{AccountStmtService, JournalVerifier, PostingService, ServiceError};
+use rust_decimal::Decimal;
+use serial_test::serial;
+
+const LEDGER_ID: &str = "Zd0ND5YwSzGwIfZilhumPg";
+const CASH_ACCOUNT: &str = "xVgaTPMcRty9ik3BTQDh1Q_BS_1_0_0";
+const EQUITY_ACCOUNT: &str = "xVgaTPMcRty9ik3BTQDh1Q_BS_2_0_0";
+const MAKER: &str = "Maker";
+const CHECKER: &str = "Checker";
+
+/// Records an adjustment of `MAKER` in operation `opr_id`, moving `amount`
+/// from equity to cash.
+fn record_adjustment(
+    conn: &mut PgConnection,
+    id: &str,
+    opr_id: &str,
+    at: &str,
+    pst_status: PostingStatus,
+    amount: i64,
+) -> Posting {
+    let posting =
+        NewPosting { opr_id: opr_id.to_string(), ..new_posting(id, LEDGER_ID, at, PostingType::AdjTx, pst_status, MAKER) };
+    record(conn, posting, &[(CASH_ACCOUNT, amount, 0), (EQUITY_ACCOUNT, 0, amount)])
+}
+
+fn cash_balance(conn: &mut PgConnection) -> Decimal {
+    AccountStmtService::new().read_stmt(conn, CASH_ACCOUNT, time("2018-12-31 00:00:00")).unwrap().balance
+}
+
+#[test]
+#[serial]
+fn test_proposed_posting_takes_effect_once_approved() {
+    let mut conn = establish_connection();
+    seed_database(&mut conn, "tests/fixtures/posting_service_dataset.sql");
+    let _guard = TestDatabaseGuard::new();
+    let service = PostingService::new();
+
+    record_adjustment(&mut conn, "pst_001", "opr_001", "2018-01-01 10:00:00", PostingStatus::POSTED, 100);
+    let proposal = record_adjustment(&mut conn, "pst_002", "opr_002", "2018-01-01 11:00:00", PostingStatus::PROPOSED, 50);
+    assert_eq!(proposal.pst_status, PostingStatus::PROPOSED);
+    assert!(proposal.hash.is_none() && proposal.antecedent_id.is_none());
+    assert_eq!(cash_balance(&mut conn), Decimal::new(100, 0));
+    assert_eq!(JournalVerifier::new().verify_ledger(&mut conn, LEDGER_ID).unwrap(), None);
+
+    // Postings recorded meanwhile do not chain to the proposal.
+    let posted = record_adjustment(&mut conn, "pst_003", "opr_003", "2018-01-01 12:00:00", PostingStatus::POSTED, 10);
+    assert_eq!(posted.antecedent_id.as_deref(), Some("pst_001"));
+
+    let error = service.approve(&mut conn, "pst_002", MAKER, time("2018-01-01 13:00:00")).unwrap_err();
+    assert!(matches!(error, ServiceError::CheckerIsMaker { .. }), "{:?}", error);
+
+    let (approved, lines) = service.approve(&mut conn, "pst_002", CHECKER, time("2018-01-01 13:00:00")).unwrap();
+    assert_eq!(approved.id, "pst_002");
+    assert_eq!(approved.pst_status, PostingStatus::POSTED);
+    assert_eq!(approved.record_user, MAKER);
+    assert_eq!(approved.record_time, time("2018-01-01 13:00:00"));
+    assert_eq!(approved.antecedent_id.as_deref(), Some("pst_003"));
+    assert!(approved.hash.is_some());
+    assert_eq!(lines.len(), 2);
+    assert!(lines.iter().all(|line| line.pst_status == PostingStatus::POSTED && !line.hash.is_empty()));
+
+    assert_eq!(cash_balance(&mut conn), Decimal::new(160, 0));
+    assert_eq!(JournalVerifier::new().verify_ledger(&mut conn, LEDGER_ID).unwrap(), None);
+
+    let error = service.approve(&mut conn, "pst_002", CHECKER, time("2018-01-01 14:00:00")).unwrap_err();
+    assert!(matches!(error, ServiceError::PostingNotProposed { pst_status: PostingStatus::POSTED, .. }), "{:?}", error);
+}
+
+#[test]
+#[serial]
+fn test_approval_is_recorded_after_youngest_posting() {
+    let mut conn = establish_connection();
+    seed_database(&mut conn, "tests/fixtures/posting_service_dataset.sql");
+    let _guard = TestDatabaseGuard::new();
+
+    record_adjustment(&mut conn, "pst_001", "opr_001", "2018-01-01 11:00:00", PostingStatus::PROPOSED, 50);
+    record_adjustment(&mut conn, "pst_002", "opr_002", "2018-01-01 12:00:00", PostingStatus::POSTED, 100);
+
+    let (approved, lines) =
+        PostingService::new().approve(&mut conn, "pst_001", CHECKER, time("2018-01-01 12:00:00")).unwrap();
+    assert_eq!(approved.record_time, time("2018-01-01 12:00:00") + chrono::Duration::microseconds(1));
+    assert_eq!(approved.antecedent_id.as_deref(), Some("pst_002"));
+    assert!(lines.iter().all(|line| line.record_time == approved.record_time));
+    assert_eq!(JournalVerifier::new().verify_ledger(&mut conn, LEDGER_ID).unwrap(), None);
+}
+
+#[test]
+#[serial]
+fn test_approved_proposal_discards_effective_posting() {
+    let mut conn = establish_connection();
+    seed_database(&mut conn, "tests/fixtures/posting_service_dataset.sql");
+    let _guard = TestDatabaseGuard::new();
+
+    record_adjustment(&mut conn, "pst_001", "opr_001", "2018-01-01 10:00:00", PostingStatus::POSTED, 100);
+    record_adjustment(&mut conn, "pst_002", "opr_001", "2018-01-01 11:00:00", PostingStatus::PROPOSED, 80);
+    let effective = posting_repository::find_by_opr_id_and_discarding_id_is_null(&mut conn, "opr_001").unwrap().unwrap();
+    assert_eq!(effective.id, "pst_001");
+
+    let (approved, _) =
+        PostingService::new().approve(&mut conn, "pst_002", CHECKER, time("2018-01-01 12:00:00")).unwrap();
+    assert_eq!(approved.discarded_id.as_deref(), Some("pst_001"));
+    let discarded = posting_repository::find_by_id(&mut conn, "pst_001").unwrap().unwrap();
+    assert_eq!(discarded.discarding_id.as_deref(), Some("pst_002"));
+    assert_eq!(cash_balance(&mut conn), Decimal::new(80, 0));
+    assert_eq!(JournalVerifier::new().verify_ledger(&mut conn, LEDGER_ID).unwrap(), None);
+}
+
+#[test]
+#[serial]
+fn test_rejected_proposal_is_cancelled() {
+    let mut conn = establish_connection();
+    seed_database(&mut conn, "tests/fixtures/posting_service_dataset.sql");
+    let _guard = TestDatabaseGuard::new();
+    let service = PostingService::new();
+
+    record_adjustment(&mut conn, "pst_001", "opr_001", "2018-01-01 11:00:00", PostingStatus::PROPOSED, 50);
+    let error = service.reject(&mut conn, "pst_001", MAKER, time("2018-01-01 12:00:00")).unwrap_err();
+    assert!(matches!(error, ServiceError::CheckerIsMaker { .. }), "{:?}", error);
+
+    let rejected = service.reject(&mut conn, "pst_001", CHECKER, time("2018-01-01 12:00:00")).unwrap();
+    assert_eq!(rejected.pst_status, PostingStatus::CANCELLED);
+    let notes = op_note_repository::find_by_rec_id(&mut conn, "pst_001").unwrap();
+    assert_eq!(notes.len(), 1);
+    assert_eq!(notes[0].rec_time, time("2018-01-01 12:00:00"));
+    assert_eq!(cash_balance(&mut conn), Decimal::ZERO);
+
+    let error = service.approve(&mut conn, "pst_001", CHECKER, time("2018-01-01 12:00:00")).unwrap_err();
+    assert!(matches!(error, ServiceError::PostingNotProposed { pst_status: PostingStatus::CANCELLED, .. }), "{:?}", error);
+    let error = service.reject(&mut conn, "missing", CHECKER, time("2018-01-01 12:00:00")).unwrap_err();
+    assert!(matches!(error, ServiceError::PostingNotFound(_)), "{:?}", error);
+
+    // A cancelled proposal does not block posting the operation.
+    let posted = record_adjustment(&mut conn, "pst_002", "opr_001", "2018-01-01 12:00:00", PostingStatus::POSTED, 50);
+    assert!(posted.discarded_id.is_none());
+}
@@ -0,0 +1,191 @@
+// tests/posting_service_test.rs
+//
+// Copyright (c) 2018-2024 adorsys GmbH and Co. KG
+// All rights are reserved.
+
+mod common;
+
+use common::{business_posting, establish_connection, posting_lines, seed_database, time, TestDatabaseGuard};
+use postings_repository::models::enums::{PostingStatus, PostingType};
+use postings_repository::models::NewPosting;
+use postings_repository::repository::{posting_line_repository, posting_repository};
+use postings_service::{PostingService, ServiceError};
+use rust_decimal::Decimal;
+use serial_test::serial;
+
+const LEDGER_ID: &str = "Zd0ND5YwSzGwIfZilhumPg";
+const CASH_ACCOUNT: &str = "xVgaTPMcRty9ik3BTQDh1Q_BS_1_0_0";
+const EQUITY_ACCOUNT: &str = "xVgaTPMcRty9ik3BTQDh1Q_BS_2_0_0";
+const OTHER_LEDGER_ACCOUNT: &str = "Other0YwSzGwIfZilhumPg_BS_1_0_0";
+
+/// A deposit of operation `opr_id`, recorded at 10:00 and posted at 09:30.
+fn deposit(id: &str, opr_id: &str) -> NewPosting {
+    NewPosting {
+        opr_id: opr_id.to_string(),
+        opr_time: Some(time("2018-01-01 09:00:00")),
+        opr_type: Some("Deposit".to_string()),
+        pst_time: time("2018-01-01 09:30:00"),
+        ..business_posting(id, LEDGER_ID, "2018-01-01 10:00:00")
+    }
+}
+
+#[test]
+#[serial]
+fn test_new_posting_ok() {
+    let mut conn = establish_connection();
+    seed_database(&mut conn, "tests/fixtures/posting_service_dataset.sql");
+    let _guard = TestDatabaseGuard::new();
+
+    let lines = posting_lines("pst_001", &[(CASH_ACCOUNT, 100, 0), (EQUITY_ACCOUNT, 0, 100)]);
+    let (posting, lines) = PostingService::new()
+        .new_posting(&mut conn, deposit("pst_001", "opr_001"), lines)
+        .expect("Failed to create posting");
+
+    assert_eq!(posting.id, "pst_001");
+    assert_eq!(lines.len(), 2);
+    for line in &lines {
+        assert_eq!(line.opr_id, "opr_001");
+        assert_eq!(line.record_time, posting.record_time);
+        assert_eq!(line.pst_time, posting.pst_time);
+        assert_eq!(line.pst_type, PostingType::BusiTx);
+        assert_eq!(line.pst_status, PostingStatus::POSTED);
+    }
+
+    let stored = posting_line_repository::find_first_by_id_and_account(&mut conn, "pst_001_line_1", EQUITY_ACCOUNT)
+        .expect("Error fetching PostingLine")
+        .expect("PostingLine pst_001_line_1 should have been saved");
+    assert_eq!(stored.credit_amount, Decimal::new(10000, 2));
+}
+
+#[test]
+#[serial]
+fn test_new_posting_unbalanced() {
+    let mut conn = establish_connection();
+    seed_database(&mut conn, "tests/fixtures/posting_service_dataset.sql");
+    let _guard = TestDatabaseGuard::new();
+
+    let lines = posting_lines("pst_001", &[(CASH_ACCOUNT, 100, 0), (EQUITY_ACCOUNT, 0, 99)]);
+    let result = PostingService::new().new_posting(&mut conn, deposit("pst_001", "opr_001"), lines);
+
+    assert!(matches!(result, Err(ServiceError::UnbalancedPosting { .. })));
+    assert!(posting_repository::find_by_id(&mut conn, "pst_001").unwrap().is_none());
+}
+
+#[test]
+#[serial]
+fn test_new_posting_negative_amount() {
+    let mut conn = establish_connection();
+    seed_database(&mut conn, "tests/fixtures/posting_service_dataset.sql");
+    let _guard = TestDatabaseGuard::new();
+
+    // Balanced, but the negative credit is a debit in disguise.
+    let lines = posting_lines("pst_001", &[(CASH_ACCOUNT, 100, 0), (EQUITY_ACCOUNT, 0, 300), (EQUITY_ACCOUNT, 0, -200)]);
+    let result = PostingService::new().new_posting(&mut conn, deposit("pst_001", "opr_001"), lines);
+
+    assert!(matches!(result, Err(ServiceError::NegativeAmount { ref line_id }) if line_id == "pst_001_line_2"), "{:?}", result);
+    assert!(posting_repository::find_by_id(&mut conn, "pst_001").unwrap().is_none());
+}
+
+#[test]
+#[serial]
+fn test_new_posting_debit_and_credit_line() {
+    let mut conn = establish_connection();
+    seed_database(&mut conn, "tests/fixtures/posting_service_dataset.sql");
+    let _guard = TestDatabaseGuard::new();
+
+    let lines = posting_lines("pst_001", &[(CASH_ACCOUNT, 100, 50), (EQUITY_ACCOUNT, 50, 100)]);
+    let result = PostingService::new().new_posting(&mut conn, deposit("pst_001", "opr_001"), lines);
+
+    assert!(matches!(result, Err(ServiceError::DebitAndCreditLine { ref line_id }) if line_id == "pst_001_line_0"), "{:?}", result);
+    assert!(posting_repository::find_by_id(&mut conn, "pst_001").unwrap().is_none());
+}
+
+#[test]
+#[serial]
+fn test_new_posting_no_lines() {
+    let mut conn = establish_connection();
+    seed_database(&mut conn, "tests/fixtures/posting_service_dataset.sql");
+    let _guard = TestDatabaseGuard::new();
+
+    let result = PostingService::new().new_posting(&mut conn, deposit("pst_001", "opr_001"), vec![]);
+
+    assert!(matches!(result, Err(ServiceError::NoPostingLines)));
+}
+
+#[test]
+#[serial]
+fn test_new_posting_account_of_other_ledger() {
+    let mut conn = establish_connection();
+    seed_database(&mut conn, "tests/fixtures/posting_service_dataset.sql");
+    let _guard = TestDatabaseGuard::new();
+
+    let lines = posting_lines("pst_001", &[(OTHER_LEDGER_ACCOUNT, 100, 0), (EQUITY_ACCOUNT, 0, 100)]);
+    let result = PostingService::new().new_posting(&mut conn, deposit("pst_001", "opr_001"), lines);
+
+    assert!(matches!(result, Err(ServiceError::AccountNotInLedger { .. })));
+    assert!(posting_repository::find_by_id(&mut conn, "pst_001").unwrap().is_none());
+}
+
+#[test]
+#[serial]
+fn test_repost_discards_effective_posting() {
+    let mut conn = establish_connection();
+    seed_database(&mut conn, "tests/fixtures/posting_service_dataset.sql");
+    let _guard = TestDatabaseGuard::new();
+    let service = PostingService::new();
+
+    service
+        .new_posting(
+            &mut conn,
+            deposit("pst_001", "opr_001"),
+            posting_lines("pst_001", &[(CASH_ACCOUNT, 100, 0), (EQUITY_ACCOUNT, 0, 100)]),
+        )
+        .expect("Failed to create posting");
+
+    // The amount of the operation changed: post it again.
+    let mut correction = deposit("pst_002", "opr_001");
+    correction.record_time = time("2018-01-02 10:00:00");
+    let (posting, _) = service
+        .repost(
+            &mut conn,
+            correction,
+            posting_lines("pst_002", &[(CASH_ACCOUNT, 120, 0), (EQUITY_ACCOUNT, 0, 120)]),
+        )
+        .expect("Failed to repost operation");
+
+    assert_eq!(posting.discarded_id.as_deref(), Some("pst_001"));
+    assert_eq!(posting.discarded_time, Some(time("2018-01-01 10:00:00")));
+
+    let discarded = posting_repository::find_by_id(&mut conn, "pst_001").unwrap().unwrap();
+    assert_eq!(discarded.discarding_id.as_deref(), Some("pst_002"));
+
+    let effective = posting_repository::find_by_opr_id_and_discarding_id_is_null(&mut conn, "opr_001")
+        .unwrap()
+        .expect("Operation opr_001 should have an effective posting");
+    assert_eq!(effective.id, "pst_002");
+
+    let old_line = posting_line_repository::find_first_by_id_and_account(&mut conn, "pst_001_line_0", CASH_ACCOUNT)
+        .unwrap()
+        .unwrap();
+    assert_eq!(old_line.discarded_time, Some(time("2018-01-02 10:00:00")));
+
+    let lines = posting_line_repository::find_by_account_and_pst_time_lte_and_discarded_is_null_order_by_record_time_desc(
+        &mut conn, CASH_ACCOUNT, time("2018-12-31 00:00:00"),
+    )
+    .unwrap();
+    assert_eq!(lines.len(), 1);
+    assert_eq!(lines[0].id, "pst_002_line_0");
+}
+
+#[test]
+#[serial]
+fn test_repost_unknown_operation() {
+    let mut conn = establish_connection();
+    seed_database(&mut conn, "tests/fixtures/posting_service_dataset.sql");
+    let _guard = TestDatabaseGuard::new();
+
+    let lines = posting_lines("pst_001", &[(CASH_ACCOUNT, 100, 0), (EQUITY_ACCOUNT, 0, 100)]);
+    let result = PostingService::new().repost(&mut conn, deposit("pst_001", "opr_001"), lines);
+
+    assert!(matches!(result, Err(ServiceError::OperationNotFound(_))));
+}
@@ -0,0 +1,87 @@
+// tests/simulation_service_test.rs
+//
+// Copyright (c) 2018-2024 adorsys GmbH and Co. KG
+// All rights are reserved.
+
+mod common;
+
+use common::{business_posting, establish_connection, post, posting_lines, seed_database, time, TestDatabaseGuard};
+use postings_repository::models::enums::{PostingStatus, StmtStatus};
+use postings_repository::repository::{posting_repository, posting_trace_repository};
+use postings_service::{AccountStmtService, JournalVerifier, ServiceError, SimulationService};
+use rust_decimal::Decimal;
+use serial_test::serial;
+
+const LEDGER_ID: &str = "Zd0ND5YwSzGwIfZilhumPg";
+const CASH_ACCOUNT: &str = "xVgaTPMcRty9ik3BTQDh1Q_BS_1_0_0";
+const EQUITY_ACCOUNT: &str = "xVgaTPMcRty9ik3BTQDh1Q_BS_2_0_0";
+
+/// The amounts moving `amount` from equity to cash.
+fn amounts(amount: i64) -> [(&'static str, i64, i64); 2] {
+    [(CASH_ACCOUNT, amount, 0), (EQUITY_ACCOUNT, 0, amount)]
+}
+
+#[test]
+#[serial]
+fn test_simulated_postings_only_count_in_what_if_views() {
+    let mut conn = establish_connection();
+    seed_database(&mut conn, "tests/fixtures/posting_service_dataset.sql");
+    let _guard = TestDatabaseGuard::new();
+    let service = SimulationService::new();
+    let ref_time = time("2018-01-31 23:59:59");
+
+    post(&mut conn, "pst_001", LEDGER_ID, "2018-01-01 10:00:00", &amounts(100));
+    let fee = business_posting("fee_001", LEDGER_ID, "2018-01-02 10:00:00");
+    let (fee, lines) = service.post(&mut conn, fee, posting_lines("fee_001", &amounts(5))).unwrap();
+    assert_eq!(fee.pst_status, PostingStatus::SIMULATED);
+    assert!(fee.hash.is_none() && fee.antecedent_id.is_none());
+    assert!(lines.iter().all(|line| line.pst_status == PostingStatus::SIMULATED));
+
+    let real = AccountStmtService::new().read_stmt(&mut conn, CASH_ACCOUNT, ref_time).unwrap();
+    assert_eq!(real.balance, Decimal::new(100, 0));
+    let what_if = service.read_stmt(&mut conn, CASH_ACCOUNT, ref_time).unwrap();
+    assert_eq!(what_if.balance, Decimal::new(105, 0));
+
+    // The journal ignores simulated postings.
+    let posted = post(&mut conn, "pst_002", LEDGER_ID, "2018-01-03 10:00:00", &amounts(10));
+    assert_eq!(posted.antecedent_id.as_deref(), Some("pst_001"));
+    assert_eq!(JournalVerifier::new().verify_ledger(&mut conn, LEDGER_ID).unwrap(), None);
+
+    let stmt = service.simulate_account_stmt(&mut conn, CASH_ACCOUNT, ref_time, "Test User").unwrap();
+    assert_eq!(stmt.stmt_status, StmtStatus::SIMULATED);
+    assert_eq!(stmt.total_debit, Decimal::new(115, 0));
+    let stmt_posting = posting_repository::find_by_id(&mut conn, stmt.posting_id.as_deref().unwrap()).unwrap().unwrap();
+    assert_eq!(stmt_posting.pst_status, PostingStatus::SIMULATED);
+    let traces = posting_trace_repository::find_by_tgt_pst_id(&mut conn, &stmt_posting.id).unwrap();
+    let mut sources: Vec<&str> = traces.iter().map(|trace| trace.src_pst_id.as_str()).collect();
+    sources.sort();
+    assert_eq!(sources, ["fee_001", "pst_001", "pst_002"]);
+
+    // Simulated statements are no base of closed ones.
+    let closed = AccountStmtService::new().close_account_stmt(&mut conn, CASH_ACCOUNT, ref_time, "Test User").unwrap();
+    assert_eq!(closed.total_debit, Decimal::new(110, 0));
+    assert_eq!(closed.stmt_seq_nbr, 0);
+
+    let purge = service.purge(&mut conn, LEDGER_ID).unwrap();
+    assert_eq!(purge.account_stmts, 1);
+    assert_eq!(purge.posting_traces, 3);
+    assert_eq!(purge.posting_lines, 4);
+    assert_eq!(purge.postings, 2);
+    assert!(posting_repository::find_by_id(&mut conn, "fee_001").unwrap().is_none());
+
+    let what_if = service.read_stmt(&mut conn, CASH_ACCOUNT, ref_time).unwrap();
+    assert_eq!(what_if.balance, Decimal::new(110, 0));
+    assert_eq!(what_if.base_stmt_id, None);
+    assert_eq!(JournalVerifier::new().verify_ledger(&mut conn, LEDGER_ID).unwrap(), None);
+}
+
+#[test]
+#[serial]
+fn test_purge_of_unknown_ledger() {
+    let mut conn = establish_connection();
+    seed_database(&mut conn, "tests/fixtures/posting_service_dataset.sql");
+    let _guard = TestDatabaseGuard::new();
+
+    let error = SimulationService::new().purge(&mut conn, "missing").unwrap_err();
+    assert!(matches!(error, ServiceError::LedgerNotFound(_)), "{:?}", error);
+}